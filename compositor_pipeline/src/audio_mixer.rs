@@ -1,20 +1,38 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, VecDeque},
     sync::{Arc, Mutex},
+    time::Duration,
 };
 
-use compositor_render::{error::UpdateSceneError, OutputId};
+use compositor_render::{error::UpdateSceneError, event_handler::emit_event, InputId, OutputId};
 use tracing::trace;
 
+use crate::event::Event;
+
+pub(crate) mod channel_layout;
+mod ducking;
+mod effects;
+mod levels;
+mod loudness;
 mod mix;
+pub(crate) mod pcm;
 mod prepare_inputs;
 mod types;
+mod vad;
+mod volume_ramp;
 
+pub use levels::AudioLevels;
 pub use types::*;
 
 use self::{
+    ducking::Ducking,
+    effects::EffectsChain,
+    levels::LevelsMetering,
+    loudness::LoudnessNormalizer,
     mix::mix_samples,
     prepare_inputs::{expected_samples_count, prepare_input_samples},
+    vad::VoiceActivityDetector,
+    volume_ramp::VolumeRamp,
 };
 
 #[derive(Debug)]
@@ -22,15 +40,127 @@ struct OutputInfo {
     audio: AudioMixingParams,
     mixing_strategy: MixingStrategy,
     channels: AudioChannels,
+    /// State of the effects applied to each input, in the same order as in `audio.inputs`.
+    effects: Vec<EffectsChain>,
+    /// Set if the last update had a transition that is not finished yet.
+    volume_ramp: Option<VolumeRamp>,
+    /// State of the ducking rules, in the same order as in `audio.ducking`.
+    ducking: Vec<Ducking>,
+    /// Created on the first mixed batch if the output uses the loudness normalization.
+    loudness_normalizer: Option<LoudnessNormalizer>,
+}
+
+impl OutputInfo {
+    fn new(
+        audio: AudioMixingParams,
+        mixing_strategy: MixingStrategy,
+        channels: AudioChannels,
+        sample_rate: u32,
+    ) -> Self {
+        let mut output_info = Self {
+            audio: AudioMixingParams {
+                inputs: Vec::new(),
+                transition: None,
+                ducking: Vec::new(),
+            },
+            mixing_strategy,
+            channels,
+            effects: Vec::new(),
+            volume_ramp: None,
+            ducking: Vec::new(),
+            loudness_normalizer: None,
+        };
+        output_info.update(audio, sample_rate);
+        output_info
+    }
+
+    /// Replaces mixing params. State of the effects of inputs present before and after
+    /// the update is preserved. If the update has a transition, volume of the inputs is
+    /// interpolated from the current values (also in the middle of a previous transition).
+    fn update(&mut self, audio: AudioMixingParams, sample_rate: u32) {
+        let previous_entries = match_input_entries(&self.audio.inputs, &audio.inputs);
+
+        self.volume_ramp = audio.transition.map(|transition| {
+            let start_volumes = previous_entries
+                .iter()
+                .map(|previous_entry| {
+                    let Some(index) = *previous_entry else {
+                        return 0.0;
+                    };
+                    let input = &self.audio.inputs[index];
+                    match &self.volume_ramp {
+                        Some(ramp) => ramp.current_volume(index, input.volume),
+                        None => input.volume,
+                    }
+                })
+                .collect();
+            VolumeRamp::new(start_volumes, transition)
+        });
+
+        let mut old_effects: Vec<Option<EffectsChain>> = std::mem::take(&mut self.effects)
+            .into_iter()
+            .map(Some)
+            .collect();
+        self.effects = audio
+            .inputs
+            .iter()
+            .zip(&previous_entries)
+            .map(|(input, previous_entry)| {
+                match previous_entry.and_then(|index| old_effects[index].take()) {
+                    Some(mut chain) => {
+                        chain.update(&input.effects, sample_rate);
+                        chain
+                    }
+                    None => EffectsChain::new(&input.effects, sample_rate),
+                }
+            })
+            .collect();
+
+        let mut old_ducking = std::mem::take(&mut self.ducking).into_iter();
+        self.ducking = audio
+            .ducking
+            .iter()
+            .map(|rule| match old_ducking.next() {
+                Some(ducking) => ducking.update(rule.clone(), sample_rate),
+                None => Ducking::new(rule.clone(), sample_rate),
+            })
+            .collect();
+        self.audio = audio;
+    }
+}
+
+/// For each entry of `new_inputs` returns the index of the matching entry of `old_inputs`.
+/// The same input can be listed more than once (e.g. to mix each channel of a dual mono
+/// input separately), so entries of the same input are matched in order.
+fn match_input_entries(
+    old_inputs: &[InputParams],
+    new_inputs: &[InputParams],
+) -> Vec<Option<usize>> {
+    let mut old_entries: HashMap<&InputId, VecDeque<usize>> = HashMap::new();
+    for (index, input) in old_inputs.iter().enumerate() {
+        old_entries
+            .entry(&input.input_id)
+            .or_default()
+            .push_back(index);
+    }
+    new_inputs
+        .iter()
+        .map(|input| {
+            old_entries
+                .get_mut(&input.input_id)
+                .and_then(VecDeque::pop_front)
+        })
+        .collect()
 }
 
 #[derive(Debug, Clone)]
 pub(super) struct AudioMixer(Arc<Mutex<InternalAudioMixer>>);
 
 impl AudioMixer {
-    pub fn new(output_sample_rate: u32) -> Self {
+    pub fn new(output_sample_rate: u32, audio_levels_interval: Option<Duration>) -> Self {
         Self(Arc::new(Mutex::new(InternalAudioMixer::new(
             output_sample_rate,
+            audio_levels_interval,
         ))))
     }
 
@@ -46,14 +176,10 @@ impl AudioMixer {
         mixing_strategy: MixingStrategy,
         channels: AudioChannels,
     ) {
-        self.0.lock().unwrap().outputs.insert(
-            output_id,
-            OutputInfo {
-                audio,
-                channels,
-                mixing_strategy,
-            },
-        );
+        let mut mixer = self.0.lock().unwrap();
+        let output_info =
+            OutputInfo::new(audio, mixing_strategy, channels, mixer.output_sample_rate);
+        mixer.outputs.insert(output_id, output_info);
     }
 
     pub fn unregister_output(&self, output_id: &OutputId) {
@@ -73,13 +199,18 @@ impl AudioMixer {
 pub(super) struct InternalAudioMixer {
     outputs: HashMap<OutputId, OutputInfo>,
     output_sample_rate: u32,
+    /// Set if the `AUDIO_LEVELS` events are enabled.
+    levels_metering: Option<LevelsMetering>,
+    voice_activity: HashMap<InputId, VoiceActivityDetector>,
 }
 
 impl InternalAudioMixer {
-    pub fn new(output_sample_rate: u32) -> Self {
+    pub fn new(output_sample_rate: u32, audio_levels_interval: Option<Duration>) -> Self {
         Self {
             outputs: HashMap::new(),
             output_sample_rate,
+            levels_metering: audio_levels_interval.map(LevelsMetering::new),
+            voice_activity: HashMap::new(),
         }
     }
 
@@ -90,7 +221,7 @@ impl InternalAudioMixer {
     ) -> Result<(), UpdateSceneError> {
         match self.outputs.get_mut(output_id) {
             Some(output_info) => {
-                output_info.audio = audio;
+                output_info.update(audio, self.output_sample_rate);
                 Ok(())
             }
             None => Err(UpdateSceneError::OutputNotRegistered(output_id.clone())),
@@ -99,21 +230,65 @@ impl InternalAudioMixer {
 
     pub fn mix_samples(&mut self, samples_set: InputSamplesSet) -> OutputSamplesSet {
         let start_pts = samples_set.start_pts;
+        let end_pts = samples_set.end_pts;
         let samples_count = expected_samples_count(
             samples_set.start_pts,
             samples_set.end_pts,
             self.output_sample_rate,
         );
         let input_samples = prepare_input_samples(samples_set, self.output_sample_rate);
+        self.detect_voice_activity(&input_samples);
 
-        OutputSamplesSet(
+        let output_samples = OutputSamplesSet(
             self.outputs
-                .iter()
+                .iter_mut()
                 .map(|(output_id, output_info)| {
-                    let samples = mix_samples(&input_samples, output_info, samples_count);
+                    let samples = mix_samples(
+                        &input_samples,
+                        output_info,
+                        start_pts,
+                        samples_count,
+                        self.output_sample_rate,
+                    );
                     (output_id.clone(), OutputSamples { samples, start_pts })
                 })
                 .collect(),
-        )
+        );
+
+        if let Some(metering) = &mut self.levels_metering {
+            for (input_id, samples) in &input_samples {
+                metering.add_input(input_id, samples);
+            }
+            for (output_id, output) in &output_samples.0 {
+                metering.add_output(output_id, &output.samples);
+            }
+            metering.finish_batch(start_pts, end_pts);
+        }
+
+        output_samples
+    }
+
+    /// Emits `INPUT_SPEAKING_STARTED` and `INPUT_SPEAKING_STOPPED` events. Inputs that are
+    /// no longer present (removed or finished) stop speaking.
+    fn detect_voice_activity(&mut self, input_samples: &HashMap<InputId, AudioSamples>) {
+        self.voice_activity.retain(|input_id, vad| {
+            let is_present = input_samples.contains_key(input_id);
+            if !is_present && vad.is_speaking() {
+                emit_event(Event::InputSpeakingStopped(input_id.clone()));
+            }
+            is_present
+        });
+
+        for (input_id, samples) in input_samples {
+            let vad = self
+                .voice_activity
+                .entry(input_id.clone())
+                .or_insert_with(|| VoiceActivityDetector::new(self.output_sample_rate));
+            match vad.process(samples) {
+                Some(true) => emit_event(Event::InputSpeakingStarted(input_id.clone())),
+                Some(false) => emit_event(Event::InputSpeakingStopped(input_id.clone())),
+                None => (),
+            }
+        }
     }
 }
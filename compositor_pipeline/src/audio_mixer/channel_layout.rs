@@ -0,0 +1,183 @@
+use std::{borrow::Cow, f32::consts::FRAC_1_SQRT_2, ops::Range};
+
+use super::types::{AudioChannels, AudioSamples};
+
+/// Order of the 5.1 channels in the `AudioSamples::Surround51` samples.
+pub(crate) mod surround51 {
+    pub const FRONT_LEFT: usize = 0;
+    pub const FRONT_RIGHT: usize = 1;
+    pub const CENTER: usize = 2;
+    pub const LFE: usize = 3;
+    pub const SURROUND_LEFT: usize = 4;
+    pub const SURROUND_RIGHT: usize = 5;
+}
+
+/// Downmix according to ITU-R BS.775. Center and surround channels are attenuated by 3 dB
+/// and LFE is dropped. Result is not clipped, it can exceed the `[-1, 1]` range.
+pub(crate) fn downmix_surround51(sample: [f32; 6]) -> (f32, f32) {
+    use surround51::*;
+
+    let channel = |front: usize, surround: usize| {
+        sample[front] + FRAC_1_SQRT_2 * (sample[CENTER] + sample[surround])
+    };
+    (
+        channel(FRONT_LEFT, SURROUND_LEFT),
+        channel(FRONT_RIGHT, SURROUND_RIGHT),
+    )
+}
+
+/// Stereo is mapped to the front channels, the remaining channels are silent.
+pub(crate) fn upmix_stereo((l, r): (f32, f32)) -> [f32; 6] {
+    [l, r, 0.0, 0.0, 0.0, 0.0]
+}
+
+/// Mono is mapped to the center channel, the remaining channels are silent.
+pub(crate) fn upmix_mono(sample: f32) -> [f32; 6] {
+    let mut result = [0.0; 6];
+    result[surround51::CENTER] = sample;
+    result
+}
+
+fn average((l, r): (f32, f32)) -> f32 {
+    (l + r) / 2.0
+}
+
+impl AudioSamples {
+    pub fn silence(channels: AudioChannels, count: usize) -> Self {
+        match channels {
+            AudioChannels::Mono => AudioSamples::Mono(vec![0.0; count]),
+            AudioChannels::Stereo => AudioSamples::Stereo(vec![(0.0, 0.0); count]),
+            AudioChannels::Surround51 => AudioSamples::Surround51(vec![[0.0; 6]; count]),
+        }
+    }
+
+    /// Samples with channels in the order defined by `AudioChannels`.
+    pub fn from_interleaved(channels: AudioChannels, samples: &[f32]) -> Self {
+        match channels {
+            AudioChannels::Mono => AudioSamples::Mono(samples.to_vec()),
+            AudioChannels::Stereo => AudioSamples::Stereo(
+                samples
+                    .chunks_exact(2)
+                    .map(|chunk| (chunk[0], chunk[1]))
+                    .collect(),
+            ),
+            AudioChannels::Surround51 => AudioSamples::Surround51(
+                samples
+                    .chunks_exact(6)
+                    .map(|chunk| chunk.try_into().unwrap())
+                    .collect(),
+            ),
+        }
+    }
+
+    pub fn interleaved(&self) -> Vec<f32> {
+        match self {
+            AudioSamples::Mono(samples) => samples.clone(),
+            AudioSamples::Stereo(samples) => samples.iter().flat_map(|(l, r)| [*l, *r]).collect(),
+            AudioSamples::Surround51(samples) => samples.iter().flatten().copied().collect(),
+        }
+    }
+
+    pub fn channels(&self) -> AudioChannels {
+        match self {
+            AudioSamples::Mono(_) => AudioChannels::Mono,
+            AudioSamples::Stereo(_) => AudioChannels::Stereo,
+            AudioSamples::Surround51(_) => AudioChannels::Surround51,
+        }
+    }
+
+    pub fn mono(&self) -> Cow<[f32]> {
+        match self {
+            AudioSamples::Mono(samples) => Cow::Borrowed(samples),
+            AudioSamples::Stereo(samples) => samples.iter().copied().map(average).collect(),
+            AudioSamples::Surround51(samples) => samples
+                .iter()
+                .map(|sample| average(downmix_surround51(*sample)))
+                .collect(),
+        }
+    }
+
+    pub fn stereo(&self) -> Cow<[(f32, f32)]> {
+        match self {
+            AudioSamples::Mono(samples) => samples.iter().map(|s| (*s, *s)).collect(),
+            AudioSamples::Stereo(samples) => Cow::Borrowed(samples),
+            AudioSamples::Surround51(samples) => {
+                samples.iter().copied().map(downmix_surround51).collect()
+            }
+        }
+    }
+
+    pub fn surround51(&self) -> Cow<[[f32; 6]]> {
+        match self {
+            AudioSamples::Mono(samples) => samples.iter().copied().map(upmix_mono).collect(),
+            AudioSamples::Stereo(samples) => samples.iter().copied().map(upmix_stereo).collect(),
+            AudioSamples::Surround51(samples) => Cow::Borrowed(samples),
+        }
+    }
+
+    pub fn to_channels(&self, channels: AudioChannels) -> Cow<AudioSamples> {
+        if self.channels() == channels {
+            return Cow::Borrowed(self);
+        }
+        Cow::Owned(match channels {
+            AudioChannels::Mono => AudioSamples::Mono(self.mono().into_owned()),
+            AudioChannels::Stereo => AudioSamples::Stereo(self.stereo().into_owned()),
+            AudioChannels::Surround51 => AudioSamples::Surround51(self.surround51().into_owned()),
+        })
+    }
+
+    /// Appends samples from the `range`, converted to the layout of `self` if needed.
+    pub(super) fn extend_from(&mut self, other: &AudioSamples, range: Range<usize>) {
+        match self {
+            AudioSamples::Mono(samples) => samples.extend_from_slice(&other.mono()[range]),
+            AudioSamples::Stereo(samples) => samples.extend_from_slice(&other.stereo()[range]),
+            AudioSamples::Surround51(samples) => {
+                samples.extend_from_slice(&other.surround51()[range])
+            }
+        }
+    }
+
+    pub(super) fn extend_silence(&mut self, count: usize) {
+        let silence = Self::silence(self.channels(), count);
+        self.extend_from(&silence, 0..count);
+    }
+
+    pub(super) fn truncate(&mut self, len: usize) {
+        match self {
+            AudioSamples::Mono(samples) => samples.truncate(len),
+            AudioSamples::Stereo(samples) => samples.truncate(len),
+            AudioSamples::Surround51(samples) => samples.truncate(len),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_samples(actual: &[f32], expected: &[f32]) {
+        for (actual, expected) in actual.iter().zip(expected) {
+            assert!((actual - expected).abs() < 1e-6, "{actual} != {expected}");
+        }
+    }
+
+    #[test]
+    fn surround51_downmix_and_upmix() {
+        let samples = AudioSamples::Surround51(vec![[0.1, 0.2, 0.1, 0.5, 0.0, 0.1]]);
+        let (l, r) = samples.stereo()[0];
+        assert_samples(&[l, r], &[0.170_710_7, 0.341_421_4]);
+        assert_samples(&samples.mono(), &[0.256_066]);
+
+        let samples = AudioSamples::Stereo(vec![(0.1, 0.2)]);
+        assert_eq!(
+            samples.surround51().as_ref(),
+            &[[0.1, 0.2, 0.0, 0.0, 0.0, 0.0]]
+        );
+
+        let samples = AudioSamples::Mono(vec![0.1]);
+        assert_eq!(
+            samples.surround51().as_ref(),
+            &[[0.0, 0.0, 0.1, 0.0, 0.0, 0.0]]
+        );
+    }
+}
@@ -0,0 +1,119 @@
+use std::time::Duration;
+
+use compositor_render::InputId;
+
+use super::{
+    effects::{db_to_amplitude, smoothing_coefficient},
+    types::DuckingRule,
+};
+
+/// Time after which the detected level of the trigger input drops by ~63% if it goes silent.
+/// It prevents the ducking from reacting to the zero crossings of the trigger signal.
+const LEVEL_DETECTOR_RELEASE: Duration = Duration::from_millis(50);
+
+/// State of a ducking rule. Gain is smoothed in the dB domain, similarly to the compressor.
+#[derive(Debug)]
+pub(super) struct Ducking {
+    rule: DuckingRule,
+    attack_coefficient: f64,
+    release_coefficient: f64,
+    level_coefficient: f64,
+    /// Detected peak level of the trigger input.
+    level: f64,
+    gain_db: f64,
+}
+
+impl Ducking {
+    pub fn new(rule: DuckingRule, sample_rate: u32) -> Self {
+        Self {
+            attack_coefficient: smoothing_coefficient(rule.attack, sample_rate),
+            release_coefficient: smoothing_coefficient(rule.release, sample_rate),
+            level_coefficient: smoothing_coefficient(LEVEL_DETECTOR_RELEASE, sample_rate),
+            rule,
+            level: 0.0,
+            gain_db: 0.0,
+        }
+    }
+
+    /// Applies new parameters. Current gain is preserved if the trigger input did not change.
+    pub fn update(self, rule: DuckingRule, sample_rate: u32) -> Self {
+        let mut ducking = Self::new(rule, sample_rate);
+        if ducking.rule.trigger_input_id == self.rule.trigger_input_id {
+            ducking.level = self.level;
+            ducking.gain_db = self.gain_db;
+        }
+        ducking
+    }
+
+    pub fn trigger_input_id(&self) -> &InputId {
+        &self.rule.trigger_input_id
+    }
+
+    pub fn ducks(&self, input_id: &InputId) -> bool {
+        self.rule.ducked_input_ids.contains(input_id)
+    }
+
+    /// Returns gain that should be applied to the ducked inputs for each sample. If there
+    /// are no trigger samples, the trigger input is treated as silent.
+    pub fn gains(
+        &mut self,
+        trigger_samples: Option<&[(f32, f32)]>,
+        samples_count: usize,
+    ) -> Vec<f64> {
+        let threshold = db_to_amplitude(self.rule.threshold_db as f64);
+        let ducked_gain_db = -self.rule.amount_db as f64;
+
+        (0..samples_count)
+            .map(|index| {
+                let peak = trigger_samples
+                    .and_then(|samples| samples.get(index))
+                    .map_or(0.0, |(l, r)| f32::max(l.abs(), r.abs()) as f64);
+                self.level = f64::max(peak, self.level * self.level_coefficient);
+
+                let target_db = match self.level >= threshold {
+                    true => ducked_gain_db,
+                    false => 0.0,
+                };
+                let coefficient = match target_db < self.gain_db {
+                    true => self.attack_coefficient,
+                    false => self.release_coefficient,
+                };
+                self.gain_db = coefficient * self.gain_db + (1.0 - coefficient) * target_db;
+                db_to_amplitude(self.gain_db)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+
+    const SAMPLE_RATE: u32 = 48_000;
+
+    #[test]
+    fn ducks_while_trigger_is_active() {
+        let mut ducking = Ducking::new(
+            DuckingRule {
+                trigger_input_id: InputId(Arc::from("commentator")),
+                ducked_input_ids: vec![InputId(Arc::from("music"))],
+                threshold_db: -40.0,
+                amount_db: 12.0,
+                attack: Duration::from_millis(10),
+                release: Duration::from_millis(100),
+            },
+            SAMPLE_RATE,
+        );
+
+        let gains = ducking.gains(Some(&[(0.25, 0.25); 4800]), 4800);
+        let gain_db = 20.0 * gains.last().unwrap().log10();
+        assert!((gain_db - -12.0).abs() < 0.01, "{gain_db}");
+
+        // Silence for 1 second releases the ducking.
+        let gains = ducking.gains(None, SAMPLE_RATE as usize);
+        let gain_db = 20.0 * gains.last().unwrap().log10();
+        assert!(gain_db.abs() < 0.01, "{gain_db}");
+    }
+}
@@ -0,0 +1,435 @@
+use std::{f64::consts::PI, time::Duration};
+
+use super::types::{AudioEffect, EqBand, EqBandKind};
+
+/// Minimal level used when converting to dB, to avoid `-inf` for silence.
+const MIN_LEVEL_DB: f64 = -120.0;
+
+/// State of the effects applied to a single input of an output.
+#[derive(Debug, Default)]
+pub(super) struct EffectsChain {
+    effects: Vec<Effect>,
+}
+
+impl EffectsChain {
+    pub fn new(effects: &[AudioEffect], sample_rate: u32) -> Self {
+        let mut chain = Self::default();
+        chain.update(effects, sample_rate);
+        chain
+    }
+
+    /// Applies new parameters. If an effect on the same position has the same type,
+    /// its state (e.g. filter memory or current gain) is preserved, so parameter changes
+    /// do not produce audible clicks.
+    pub fn update(&mut self, effects: &[AudioEffect], sample_rate: u32) {
+        let mut old_effects = std::mem::take(&mut self.effects).into_iter();
+        self.effects = effects
+            .iter()
+            .map(|params| {
+                old_effects
+                    .next()
+                    .and_then(|mut effect| effect.update(params, sample_rate).then_some(effect))
+                    .unwrap_or_else(|| Effect::new(params, sample_rate))
+            })
+            .collect();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.effects.is_empty()
+    }
+
+    /// Samples are processed with double precision, thresholds are in dBFS.
+    pub fn process(&mut self, samples: &[(f32, f32)]) -> Vec<(f32, f32)> {
+        samples
+            .iter()
+            .map(|(l, r)| {
+                let sample = (*l as f64, *r as f64);
+                let (l, r) = self
+                    .effects
+                    .iter_mut()
+                    .fold(sample, |sample, effect| effect.process(sample));
+                (l as f32, r as f32)
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug)]
+enum Effect {
+    /// High-pass filter and parametric EQ are both implemented as a cascade of biquad filters.
+    Filters(Vec<Biquad>),
+    Compressor(Compressor),
+    NoiseGate(NoiseGate),
+}
+
+impl Effect {
+    fn new(params: &AudioEffect, sample_rate: u32) -> Self {
+        match params {
+            AudioEffect::HighPassFilter { .. } | AudioEffect::ParametricEq { .. } => Self::Filters(
+                filter_coefficients(params, sample_rate)
+                    .into_iter()
+                    .map(Biquad::new)
+                    .collect(),
+            ),
+            AudioEffect::Compressor { .. } => {
+                let mut compressor = Compressor::default();
+                compressor.set_params(params, sample_rate);
+                Self::Compressor(compressor)
+            }
+            AudioEffect::NoiseGate { .. } => {
+                let mut gate = NoiseGate::default();
+                gate.set_params(params, sample_rate);
+                Self::NoiseGate(gate)
+            }
+        }
+    }
+
+    /// Returns `false` if the effect can not be updated in place and has to be recreated.
+    fn update(&mut self, params: &AudioEffect, sample_rate: u32) -> bool {
+        match (self, params) {
+            (
+                Effect::Filters(filters),
+                AudioEffect::HighPassFilter { .. } | AudioEffect::ParametricEq { .. },
+            ) => {
+                let coefficients = filter_coefficients(params, sample_rate);
+                if coefficients.len() != filters.len() {
+                    return false;
+                }
+                for (filter, coefficients) in filters.iter_mut().zip(coefficients) {
+                    filter.coefficients = coefficients;
+                }
+                true
+            }
+            (Effect::Compressor(compressor), AudioEffect::Compressor { .. }) => {
+                compressor.set_params(params, sample_rate);
+                true
+            }
+            (Effect::NoiseGate(gate), AudioEffect::NoiseGate { .. }) => {
+                gate.set_params(params, sample_rate);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn process(&mut self, sample: (f64, f64)) -> (f64, f64) {
+        match self {
+            Effect::Filters(filters) => filters
+                .iter_mut()
+                .fold(sample, |sample, filter| filter.process(sample)),
+            Effect::Compressor(compressor) => compressor.process(sample),
+            Effect::NoiseGate(gate) => gate.process(sample),
+        }
+    }
+}
+
+fn filter_coefficients(params: &AudioEffect, sample_rate: u32) -> Vec<BiquadCoefficients> {
+    match params {
+        AudioEffect::HighPassFilter { cutoff_frequency } => vec![BiquadCoefficients::high_pass(
+            *cutoff_frequency as f64,
+            sample_rate,
+        )],
+        AudioEffect::ParametricEq { bands } => bands
+            .iter()
+            .map(|band| BiquadCoefficients::eq_band(band, sample_rate))
+            .collect(),
+        AudioEffect::Compressor { .. } | AudioEffect::NoiseGate { .. } => Vec::new(),
+    }
+}
+
+/// Coefficients normalized by `a0`, calculated based on
+/// [Audio EQ Cookbook](https://www.w3.org/TR/audio-eq-cookbook/).
+#[derive(Debug, Clone, Copy)]
+pub(super) struct BiquadCoefficients {
+    b0: f64,
+    b1: f64,
+    b2: f64,
+    a1: f64,
+    a2: f64,
+}
+
+impl BiquadCoefficients {
+    pub fn high_pass(frequency: f64, sample_rate: u32) -> Self {
+        let (cos, alpha) =
+            Self::cos_and_alpha(frequency, std::f64::consts::FRAC_1_SQRT_2, sample_rate);
+        Self::normalized(
+            [(1.0 + cos) / 2.0, -(1.0 + cos), (1.0 + cos) / 2.0],
+            [1.0 + alpha, -2.0 * cos, 1.0 - alpha],
+        )
+    }
+
+    pub fn low_pass(frequency: f64, sample_rate: u32) -> Self {
+        let (cos, alpha) =
+            Self::cos_and_alpha(frequency, std::f64::consts::FRAC_1_SQRT_2, sample_rate);
+        Self::normalized(
+            [(1.0 - cos) / 2.0, 1.0 - cos, (1.0 - cos) / 2.0],
+            [1.0 + alpha, -2.0 * cos, 1.0 - alpha],
+        )
+    }
+
+    fn eq_band(band: &EqBand, sample_rate: u32) -> Self {
+        let (cos, alpha) = Self::cos_and_alpha(band.frequency as f64, band.q as f64, sample_rate);
+        let a = 10f64.powf(band.gain_db as f64 / 40.0);
+        let sqrt_a_alpha = 2.0 * a.sqrt() * alpha;
+
+        match band.kind {
+            EqBandKind::Peaking => Self::normalized(
+                [1.0 + alpha * a, -2.0 * cos, 1.0 - alpha * a],
+                [1.0 + alpha / a, -2.0 * cos, 1.0 - alpha / a],
+            ),
+            EqBandKind::LowShelf => Self::normalized(
+                [
+                    a * ((a + 1.0) - (a - 1.0) * cos + sqrt_a_alpha),
+                    2.0 * a * ((a - 1.0) - (a + 1.0) * cos),
+                    a * ((a + 1.0) - (a - 1.0) * cos - sqrt_a_alpha),
+                ],
+                [
+                    (a + 1.0) + (a - 1.0) * cos + sqrt_a_alpha,
+                    -2.0 * ((a - 1.0) + (a + 1.0) * cos),
+                    (a + 1.0) + (a - 1.0) * cos - sqrt_a_alpha,
+                ],
+            ),
+            EqBandKind::HighShelf => Self::normalized(
+                [
+                    a * ((a + 1.0) + (a - 1.0) * cos + sqrt_a_alpha),
+                    -2.0 * a * ((a - 1.0) + (a + 1.0) * cos),
+                    a * ((a + 1.0) + (a - 1.0) * cos - sqrt_a_alpha),
+                ],
+                [
+                    (a + 1.0) - (a - 1.0) * cos + sqrt_a_alpha,
+                    2.0 * ((a - 1.0) - (a + 1.0) * cos),
+                    (a + 1.0) - (a - 1.0) * cos - sqrt_a_alpha,
+                ],
+            ),
+        }
+    }
+
+    fn cos_and_alpha(frequency: f64, q: f64, sample_rate: u32) -> (f64, f64) {
+        // Filters are unstable for frequencies close to or above the Nyquist frequency.
+        let frequency = frequency.clamp(1.0, sample_rate as f64 * 0.49);
+        let w0 = 2.0 * PI * frequency / sample_rate as f64;
+        (w0.cos(), w0.sin() / (2.0 * q))
+    }
+
+    pub fn normalized([b0, b1, b2]: [f64; 3], [a0, a1, a2]: [f64; 3]) -> Self {
+        Self {
+            b0: b0 / a0,
+            b1: b1 / a0,
+            b2: b2 / a0,
+            a1: a1 / a0,
+            a2: a2 / a0,
+        }
+    }
+}
+
+/// Biquad filter in the transposed direct form II with a separate state for each channel.
+#[derive(Debug)]
+pub(super) struct Biquad {
+    coefficients: BiquadCoefficients,
+    state: [[f64; 2]; 2],
+}
+
+impl Biquad {
+    pub fn new(coefficients: BiquadCoefficients) -> Self {
+        Self {
+            coefficients,
+            state: [[0.0; 2]; 2],
+        }
+    }
+
+    pub fn process(&mut self, (l, r): (f64, f64)) -> (f64, f64) {
+        (self.process_channel(0, l), self.process_channel(1, r))
+    }
+
+    fn process_channel(&mut self, channel: usize, x: f64) -> f64 {
+        let BiquadCoefficients { b0, b1, b2, a1, a2 } = self.coefficients;
+        let [z1, z2] = &mut self.state[channel];
+        let y = b0 * x + *z1;
+        *z1 = b1 * x - a1 * y + *z2;
+        *z2 = b2 * x - a2 * y;
+        y
+    }
+}
+
+/// Feed-forward compressor with the gain reduction smoothed in the dB domain.
+/// Both channels are reduced by the same amount to preserve the stereo image.
+#[derive(Debug, Default)]
+struct Compressor {
+    threshold_db: f64,
+    ratio: f64,
+    makeup_gain_db: f64,
+    attack_coefficient: f64,
+    release_coefficient: f64,
+    gain_reduction_db: f64,
+}
+
+impl Compressor {
+    fn set_params(&mut self, params: &AudioEffect, sample_rate: u32) {
+        let AudioEffect::Compressor {
+            threshold_db,
+            ratio,
+            attack,
+            release,
+            makeup_gain_db,
+        } = params
+        else {
+            return;
+        };
+        self.threshold_db = *threshold_db as f64;
+        self.ratio = *ratio as f64;
+        self.makeup_gain_db = *makeup_gain_db as f64;
+        self.attack_coefficient = smoothing_coefficient(*attack, sample_rate);
+        self.release_coefficient = smoothing_coefficient(*release, sample_rate);
+    }
+
+    fn process(&mut self, (l, r): (f64, f64)) -> (f64, f64) {
+        let level_db = amplitude_to_db(f64::max(l.abs(), r.abs()));
+        let target_db = match level_db > self.threshold_db {
+            true => (self.threshold_db - level_db) * (1.0 - 1.0 / self.ratio),
+            false => 0.0,
+        };
+        let coefficient = match target_db < self.gain_reduction_db {
+            true => self.attack_coefficient,
+            false => self.release_coefficient,
+        };
+        self.gain_reduction_db =
+            coefficient * self.gain_reduction_db + (1.0 - coefficient) * target_db;
+
+        let gain = db_to_amplitude(self.gain_reduction_db + self.makeup_gain_db);
+        (l * gain, r * gain)
+    }
+}
+
+/// Mutes the input when its level stays below the threshold for longer than the hold time.
+#[derive(Debug, Default)]
+struct NoiseGate {
+    threshold_db: f64,
+    attack_coefficient: f64,
+    release_coefficient: f64,
+    hold_samples: u64,
+    /// Number of samples left before the gate starts closing.
+    remaining_hold_samples: u64,
+    gain: f64,
+}
+
+impl NoiseGate {
+    fn set_params(&mut self, params: &AudioEffect, sample_rate: u32) {
+        let AudioEffect::NoiseGate {
+            threshold_db,
+            attack,
+            hold,
+            release,
+        } = params
+        else {
+            return;
+        };
+        self.threshold_db = *threshold_db as f64;
+        self.attack_coefficient = smoothing_coefficient(*attack, sample_rate);
+        self.release_coefficient = smoothing_coefficient(*release, sample_rate);
+        self.hold_samples = (hold.as_secs_f64() * sample_rate as f64) as u64;
+        self.remaining_hold_samples = u64::min(self.remaining_hold_samples, self.hold_samples);
+    }
+
+    fn process(&mut self, (l, r): (f64, f64)) -> (f64, f64) {
+        let level_db = amplitude_to_db(f64::max(l.abs(), r.abs()));
+        let target = if level_db >= self.threshold_db {
+            self.remaining_hold_samples = self.hold_samples;
+            1.0
+        } else if self.remaining_hold_samples > 0 {
+            self.remaining_hold_samples -= 1;
+            1.0
+        } else {
+            0.0
+        };
+        let coefficient = match target > self.gain {
+            true => self.attack_coefficient,
+            false => self.release_coefficient,
+        };
+        self.gain = coefficient * self.gain + (1.0 - coefficient) * target;
+
+        (l * self.gain, r * self.gain)
+    }
+}
+
+/// Coefficient of a one-pole smoothing filter that reaches ~63% of the target after `time`.
+pub(super) fn smoothing_coefficient(time: Duration, sample_rate: u32) -> f64 {
+    let samples = time.as_secs_f64() * sample_rate as f64;
+    match samples > 0.0 {
+        true => (-1.0 / samples).exp(),
+        false => 0.0,
+    }
+}
+
+pub(super) fn amplitude_to_db(amplitude: f64) -> f64 {
+    f64::max(20.0 * amplitude.log10(), MIN_LEVEL_DB)
+}
+
+pub(super) fn db_to_amplitude(db: f64) -> f64 {
+    10f64.powf(db / 20.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: u32 = 48_000;
+
+    fn compressor(threshold_db: f32, ratio: f32) -> AudioEffect {
+        AudioEffect::Compressor {
+            threshold_db,
+            ratio,
+            attack: Duration::from_millis(5),
+            release: Duration::from_millis(50),
+            makeup_gain_db: 0.0,
+        }
+    }
+
+    #[test]
+    fn high_pass_filter_removes_dc_offset() {
+        let mut chain = EffectsChain::new(
+            &[AudioEffect::HighPassFilter {
+                cutoff_frequency: 100.0,
+            }],
+            SAMPLE_RATE,
+        );
+
+        let output = chain.process(&vec![(0.3, -0.3); SAMPLE_RATE as usize]);
+
+        let (l, r) = *output.last().unwrap();
+        assert!(l.abs() < 3e-4 && r.abs() < 3e-4, "{l} {r}");
+    }
+
+    #[test]
+    fn compressor_reduces_level_above_threshold() {
+        let mut chain = EffectsChain::new(&[compressor(-20.0, 4.0)], SAMPLE_RATE);
+
+        // -6 dBFS is 14 dB above the threshold, so the gain is reduced by 10.5 dB.
+        let output = chain.process(&vec![(0.5, 0.5); SAMPLE_RATE as usize]);
+
+        let (l, _) = *output.last().unwrap();
+        assert!((l - 0.1493).abs() < 3e-4, "{l}");
+    }
+
+    #[test]
+    fn update_preserves_state_of_the_same_effect() {
+        let gate = |threshold_db| AudioEffect::NoiseGate {
+            threshold_db,
+            attack: Duration::ZERO,
+            hold: Duration::ZERO,
+            release: Duration::from_millis(100),
+        };
+        let mut chain = EffectsChain::new(&[gate(-40.0)], SAMPLE_RATE);
+        // Open the gate.
+        chain.process(&[(0.5, 0.5)]);
+
+        // Gate with a new threshold continues releasing instead of starting closed.
+        chain.update(&[gate(-30.0)], SAMPLE_RATE);
+        let (l, _) = chain.process(&[(0.003, 0.003)])[0];
+        assert!((l - 0.003).abs() < 1e-5, "{l}");
+
+        // Different effect on the same position is created from scratch.
+        chain.update(&[compressor(-20.0, 2.0)], SAMPLE_RATE);
+        let output = chain.process(&[(0.003, 0.003)]);
+        assert_eq!(output, vec![(0.003, 0.003)]);
+    }
+}
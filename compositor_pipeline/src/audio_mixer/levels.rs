@@ -0,0 +1,149 @@
+use std::{collections::HashMap, time::Duration};
+
+use compositor_render::{event_handler::emit_event, InputId, OutputId};
+
+use crate::event::Event;
+
+use super::types::AudioSamples;
+
+/// Levels below this value (including digital silence) are reported as this value.
+const MIN_LEVEL_DBFS: f64 = -100.0;
+
+/// Per-channel levels in dBFS, channels are in the order defined by `AudioChannels`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudioLevels {
+    pub peak_dbfs: Vec<f64>,
+    pub rms_dbfs: Vec<f64>,
+}
+
+#[derive(Debug)]
+struct LevelMeter {
+    peak: Vec<f32>,
+    sum_squares: Vec<f64>,
+    samples_count: usize,
+}
+
+impl LevelMeter {
+    fn new(channels: usize) -> Self {
+        Self {
+            peak: vec![0.0; channels],
+            sum_squares: vec![0.0; channels],
+            samples_count: 0,
+        }
+    }
+
+    /// Accumulates samples. If the channel layout changes, previous measurements are discarded.
+    fn add(&mut self, samples: &AudioSamples) {
+        let channels = samples.channels().count();
+        if channels != self.peak.len() {
+            *self = Self::new(channels);
+        }
+
+        for frame in samples.interleaved().chunks_exact(channels) {
+            for (channel, sample) in frame.iter().enumerate() {
+                self.peak[channel] = f32::max(self.peak[channel], sample.abs());
+                self.sum_squares[channel] += (*sample as f64).powi(2);
+            }
+        }
+        self.samples_count += samples.len();
+    }
+
+    fn levels(&self) -> AudioLevels {
+        let samples_count = usize::max(self.samples_count, 1) as f64;
+        AudioLevels {
+            peak_dbfs: self.peak.iter().map(|peak| to_dbfs(*peak as f64)).collect(),
+            rms_dbfs: self
+                .sum_squares
+                .iter()
+                .map(|sum| to_dbfs(f64::sqrt(sum / samples_count)))
+                .collect(),
+        }
+    }
+}
+
+fn to_dbfs(amplitude: f64) -> f64 {
+    f64::max(20.0 * amplitude.log10(), MIN_LEVEL_DBFS)
+}
+
+/// Measures peak and RMS levels of the mixer inputs (before volume and effects are applied)
+/// and outputs, and emits `AUDIO_LEVELS` events once per `interval`.
+#[derive(Debug)]
+pub(super) struct LevelsMetering {
+    interval: Duration,
+    /// PTS of the first batch measured since the last emitted events.
+    period_start_pts: Option<Duration>,
+    inputs: HashMap<InputId, LevelMeter>,
+    outputs: HashMap<OutputId, LevelMeter>,
+}
+
+impl LevelsMetering {
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            period_start_pts: None,
+            inputs: HashMap::new(),
+            outputs: HashMap::new(),
+        }
+    }
+
+    pub fn add_input(&mut self, input_id: &InputId, samples: &AudioSamples) {
+        self.inputs
+            .entry(input_id.clone())
+            .or_insert_with(|| LevelMeter::new(0))
+            .add(samples);
+    }
+
+    pub fn add_output(&mut self, output_id: &OutputId, samples: &AudioSamples) {
+        self.outputs
+            .entry(output_id.clone())
+            .or_insert_with(|| LevelMeter::new(0))
+            .add(samples);
+    }
+
+    /// Emits levels measured since the last emitted events if the batch ending at `end_pts`
+    /// completes the interval. Inputs and outputs that were not measured in that period
+    /// (e.g. removed ones) are not reported.
+    pub fn finish_batch(&mut self, start_pts: Duration, end_pts: Duration) {
+        let period_start_pts = *self.period_start_pts.get_or_insert(start_pts);
+        if end_pts.saturating_sub(period_start_pts) < self.interval {
+            return;
+        }
+
+        for (input_id, meter) in self.inputs.drain() {
+            emit_event(Event::InputAudioLevels(input_id, meter.levels()));
+        }
+        for (output_id, meter) in self.outputs.drain() {
+            emit_event(Event::OutputAudioLevels(output_id, meter.levels()));
+        }
+        self.period_start_pts = Some(end_pts);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn peak_and_rms_levels() {
+        let mut meter = LevelMeter::new(0);
+        meter.add(&AudioSamples::Stereo(vec![(0.5, 0.0), (-0.5, 0.0)]));
+        meter.add(&AudioSamples::Stereo(vec![(0.5, 0.0), (-0.5, 0.0)]));
+
+        let levels = meter.levels();
+        assert!((levels.peak_dbfs[0] - -6.0206).abs() < 1e-3);
+        assert!((levels.rms_dbfs[0] - -6.0206).abs() < 1e-3);
+        assert_eq!(levels.peak_dbfs[1], MIN_LEVEL_DBFS);
+        assert_eq!(levels.rms_dbfs[1], MIN_LEVEL_DBFS);
+    }
+
+    #[test]
+    fn layout_change_resets_meter() {
+        let mut meter = LevelMeter::new(0);
+        meter.add(&AudioSamples::Stereo(vec![(1.0, 1.0)]));
+        meter.add(&AudioSamples::Mono(vec![0.1, 0.1]));
+
+        let levels = meter.levels();
+        assert_eq!(levels.peak_dbfs.len(), 1);
+        assert!((levels.peak_dbfs[0] - -20.0).abs() < 1e-3);
+    }
+}
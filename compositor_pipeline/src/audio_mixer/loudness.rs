@@ -0,0 +1,409 @@
+use std::{collections::VecDeque, f64::consts::PI, time::Duration};
+
+use tracing::trace;
+
+use super::effects::{db_to_amplitude, smoothing_coefficient, Biquad, BiquadCoefficients};
+
+/// Loudness is measured in blocks of 100ms.
+const BLOCKS_PER_SECOND: u32 = 10;
+const MOMENTARY_BLOCKS: usize = 4;
+const SHORT_TERM_BLOCKS: usize = 30;
+
+const ABSOLUTE_GATE_LUFS: f64 = -70.0;
+const RELATIVE_GATE_LU: f64 = -10.0;
+
+/// Gating blocks are stored in a histogram with 0.1 LU bins from the absolute gate up to +10 LUFS,
+/// so the memory usage does not grow with the duration of the stream.
+const HISTOGRAM_BINS_PER_LU: f64 = 10.0;
+const HISTOGRAM_BINS: usize = 800;
+
+/// Normalization never changes the level by more than this value.
+const MAX_GAIN_DB: f64 = 20.0;
+const GAIN_SMOOTHING_TIME: Duration = Duration::from_secs(2);
+
+const LIMITER_LOOKAHEAD: Duration = Duration::from_micros(1500);
+const LIMITER_RELEASE_TIME: Duration = Duration::from_millis(100);
+
+/// Number of taps of the interpolation filter used for the true-peak estimation.
+const INTERPOLATION_TAPS: usize = 12;
+/// True-peak is estimated by 4x oversampling, each phase is an inter-sample position.
+const OVERSAMPLING_PHASES: usize = 4;
+
+/// Normalizes summed samples of an output to the target loudness and limits their true-peak.
+///
+/// Loudness is measured according to [ITU-R BS.1770](https://www.itu.int/rec/R-REC-BS.1770).
+/// Gain is calculated from the short-term loudness of the summed samples and smoothed over
+/// a few seconds, so it does not change with every batch.
+#[derive(Debug)]
+pub(super) struct LoudnessNormalizer {
+    meter: LoudnessMeter,
+    limiter: TruePeakLimiter,
+    target_lufs: f64,
+    gain_db: f64,
+    target_gain_db: f64,
+    gain_smoothing_coefficient: f64,
+}
+
+impl LoudnessNormalizer {
+    pub fn new(target_lufs: f64, true_peak_limit_dbtp: f64, sample_rate: u32) -> Self {
+        Self {
+            meter: LoudnessMeter::new(sample_rate),
+            limiter: TruePeakLimiter::new(true_peak_limit_dbtp, sample_rate),
+            target_lufs,
+            gain_db: 0.0,
+            target_gain_db: 0.0,
+            gain_smoothing_coefficient: smoothing_coefficient(GAIN_SMOOTHING_TIME, sample_rate),
+        }
+    }
+
+    pub fn process(&mut self, summed_samples: Vec<(f32, f32)>) -> Vec<(f32, f32)> {
+        summed_samples
+            .into_iter()
+            .map(|(l, r)| {
+                let sample = (l as f64, r as f64);
+                if self.meter.process(sample) {
+                    self.on_block_measured();
+                }
+
+                self.gain_db = self.gain_smoothing_coefficient * self.gain_db
+                    + (1.0 - self.gain_smoothing_coefficient) * self.target_gain_db;
+                let gain = db_to_amplitude(self.gain_db);
+
+                let (l, r) = self.limiter.process((sample.0 * gain, sample.1 * gain));
+                (l as f32, r as f32)
+            })
+            .collect()
+    }
+
+    fn on_block_measured(&mut self) {
+        let short_term = self.meter.short_term();
+        // Silence is not amplified, the last gain is kept instead.
+        if short_term > ABSOLUTE_GATE_LUFS {
+            self.target_gain_db = (self.target_lufs - short_term).clamp(-MAX_GAIN_DB, MAX_GAIN_DB);
+        }
+        trace!(
+            momentary = self.meter.momentary(),
+            short_term,
+            integrated = self.meter.integrated(),
+            gain_db = self.gain_db,
+            "Measured output loudness"
+        );
+    }
+}
+
+#[derive(Debug)]
+struct LoudnessMeter {
+    k_weighting: [Biquad; 2],
+    block_size: usize,
+    /// Sum of squares of K-weighted samples in the current block.
+    block_energy: f64,
+    block_samples: usize,
+    /// Mean square of the last blocks, the newest at the back.
+    blocks: VecDeque<f64>,
+    histogram: Vec<HistogramBin>,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct HistogramBin {
+    count: u64,
+    energy: f64,
+}
+
+impl LoudnessMeter {
+    fn new(sample_rate: u32) -> Self {
+        let (pre_filter, rlb_filter) = k_weighting_coefficients(sample_rate);
+        Self {
+            k_weighting: [Biquad::new(pre_filter), Biquad::new(rlb_filter)],
+            block_size: (sample_rate / BLOCKS_PER_SECOND) as usize,
+            block_energy: 0.0,
+            block_samples: 0,
+            blocks: VecDeque::with_capacity(SHORT_TERM_BLOCKS + 1),
+            histogram: vec![HistogramBin::default(); HISTOGRAM_BINS],
+        }
+    }
+
+    /// Returns `true` if the sample completed a block.
+    fn process(&mut self, sample: (f64, f64)) -> bool {
+        let (l, r) = self
+            .k_weighting
+            .iter_mut()
+            .fold(sample, |sample, filter| filter.process(sample));
+        // Left and right channels have weight 1.0.
+        self.block_energy += l * l + r * r;
+        self.block_samples += 1;
+        if self.block_samples < self.block_size {
+            return false;
+        }
+
+        self.blocks
+            .push_back(self.block_energy / self.block_size as f64);
+        if self.blocks.len() > SHORT_TERM_BLOCKS {
+            self.blocks.pop_front();
+        }
+        self.block_energy = 0.0;
+        self.block_samples = 0;
+
+        // Gating blocks are 400ms long with 75% overlap, so each of them has the momentary loudness.
+        if self.blocks.len() >= MOMENTARY_BLOCKS {
+            self.add_gating_block(self.energy(MOMENTARY_BLOCKS));
+        }
+        true
+    }
+
+    fn momentary(&self) -> f64 {
+        loudness(self.energy(MOMENTARY_BLOCKS))
+    }
+
+    fn short_term(&self) -> f64 {
+        loudness(self.energy(SHORT_TERM_BLOCKS))
+    }
+
+    /// Returns `None` if no block above the absolute gate was measured yet.
+    fn integrated(&self) -> Option<f64> {
+        let (count, energy) = self.gated_energy(0);
+        if count == 0 {
+            return None;
+        }
+        let relative_gate = loudness(energy / count as f64) + RELATIVE_GATE_LU;
+        let first_bin = ((relative_gate - ABSOLUTE_GATE_LUFS) * HISTOGRAM_BINS_PER_LU).max(0.0);
+        let (count, energy) = self.gated_energy(first_bin.ceil() as usize);
+        Some(loudness(energy / count as f64))
+    }
+
+    /// Mean square of the last `blocks` blocks.
+    fn energy(&self, blocks: usize) -> f64 {
+        let blocks = self.blocks.iter().rev().take(blocks);
+        let count = blocks.len();
+        match count {
+            0 => 0.0,
+            _ => blocks.sum::<f64>() / count as f64,
+        }
+    }
+
+    fn add_gating_block(&mut self, energy: f64) {
+        let block_loudness = loudness(energy);
+        if block_loudness < ABSOLUTE_GATE_LUFS {
+            return;
+        }
+        let bin = ((block_loudness - ABSOLUTE_GATE_LUFS) * HISTOGRAM_BINS_PER_LU) as usize;
+        let bin = &mut self.histogram[usize::min(bin, HISTOGRAM_BINS - 1)];
+        bin.count += 1;
+        bin.energy += energy;
+    }
+
+    fn gated_energy(&self, first_bin: usize) -> (u64, f64) {
+        self.histogram
+            .iter()
+            .skip(first_bin)
+            .fold((0, 0.0), |(count, energy), bin| {
+                (count + bin.count, energy + bin.energy)
+            })
+    }
+}
+
+fn loudness(energy: f64) -> f64 {
+    -0.691 + 10.0 * energy.log10()
+}
+
+/// Coefficients of the K-weighting filters (a high shelf followed by a high-pass),
+/// recalculated for the sample rate as they are specified only for 48kHz.
+fn k_weighting_coefficients(sample_rate: u32) -> (BiquadCoefficients, BiquadCoefficients) {
+    let sample_rate = sample_rate as f64;
+
+    let frequency = 1681.974450955533;
+    let gain_db = 3.999843853973347;
+    let q = 0.7071752369554196;
+    let k = f64::tan(PI * frequency / sample_rate);
+    let vh = 10f64.powf(gain_db / 20.0);
+    let vb = vh.powf(0.4996667741545416);
+    let pre_filter = BiquadCoefficients::normalized(
+        [
+            vh + vb * k / q + k * k,
+            2.0 * (k * k - vh),
+            vh - vb * k / q + k * k,
+        ],
+        [
+            1.0 + k / q + k * k,
+            2.0 * (k * k - 1.0),
+            1.0 - k / q + k * k,
+        ],
+    );
+
+    let frequency = 38.13547087602444;
+    let q = 0.5003270373238773;
+    let k = f64::tan(PI * frequency / sample_rate);
+    let rlb_filter = BiquadCoefficients::normalized(
+        [1.0, -2.0, 1.0],
+        [
+            1.0 + k / q + k * k,
+            2.0 * (k * k - 1.0),
+            1.0 - k / q + k * k,
+        ],
+    );
+
+    (pre_filter, rlb_filter)
+}
+
+/// Lookahead limiter that keeps the true-peak (estimated with 4x oversampling) of the
+/// output below the limit. Output is delayed by the lookahead and the interpolation filter.
+///
+/// Gain required for each sample is held for the lookahead duration and then averaged over
+/// the same duration, so gain is reduced gradually and is low enough when the peak is played.
+#[derive(Debug)]
+struct TruePeakLimiter {
+    limit: f64,
+    interpolation: [[f64; INTERPOLATION_TAPS]; OVERSAMPLING_PHASES - 1],
+    /// The newest sample is at the end.
+    history: [[f64; INTERPOLATION_TAPS]; 2],
+    lookahead: usize,
+    /// Monotonic queue of (sample index, gain) used to find the minimal gain in the lookahead window.
+    min_gains: VecDeque<(u64, f64)>,
+    sample_index: u64,
+    envelope: f64,
+    release_coefficient: f64,
+    /// Last gains from the envelope, averaged to get the applied gain.
+    envelope_window: VecDeque<f64>,
+    envelope_window_sum: f64,
+    delayed_samples: VecDeque<(f64, f64)>,
+}
+
+impl TruePeakLimiter {
+    fn new(limit_db: f64, sample_rate: u32) -> Self {
+        let lookahead = usize::max(
+            (LIMITER_LOOKAHEAD.as_secs_f64() * sample_rate as f64) as usize,
+            1,
+        );
+        let interpolation = std::array::from_fn(|phase| {
+            let fraction = (phase + 1) as f64 / OVERSAMPLING_PHASES as f64;
+            let center = (INTERPOLATION_TAPS / 2 - 1) as f64 + fraction;
+            std::array::from_fn(|tap| windowed_sinc(center - tap as f64))
+        });
+        // Sample in the middle of the interpolation filter is delayed by half of its taps.
+        let delay = INTERPOLATION_TAPS / 2 + lookahead - 1;
+        Self {
+            limit: db_to_amplitude(limit_db),
+            interpolation,
+            history: [[0.0; INTERPOLATION_TAPS]; 2],
+            lookahead,
+            min_gains: VecDeque::with_capacity(lookahead + 1),
+            sample_index: 0,
+            envelope: 1.0,
+            release_coefficient: smoothing_coefficient(LIMITER_RELEASE_TIME, sample_rate),
+            envelope_window: VecDeque::from(vec![1.0; lookahead]),
+            envelope_window_sum: lookahead as f64,
+            delayed_samples: VecDeque::from(vec![(0.0, 0.0); delay]),
+        }
+    }
+
+    fn process(&mut self, (l, r): (f64, f64)) -> (f64, f64) {
+        for (history, sample) in self.history.iter_mut().zip([l, r]) {
+            history.rotate_left(1);
+            history[INTERPOLATION_TAPS - 1] = sample;
+        }
+        let required_gain = match self.true_peak() {
+            peak if peak > self.limit => self.limit / peak,
+            _ => 1.0,
+        };
+
+        while self
+            .min_gains
+            .back()
+            .is_some_and(|(_, gain)| *gain >= required_gain)
+        {
+            self.min_gains.pop_back();
+        }
+        self.min_gains.push_back((self.sample_index, required_gain));
+        while self
+            .min_gains
+            .front()
+            .is_some_and(|(index, _)| index + (self.lookahead as u64) <= self.sample_index)
+        {
+            self.min_gains.pop_front();
+        }
+        self.sample_index += 1;
+        let min_gain = self.min_gains.front().map_or(1.0, |(_, gain)| *gain);
+
+        self.envelope = match min_gain < self.envelope {
+            true => min_gain,
+            false => {
+                self.release_coefficient * self.envelope
+                    + (1.0 - self.release_coefficient) * min_gain
+            }
+        };
+        self.envelope_window.push_back(self.envelope);
+        self.envelope_window_sum += self.envelope;
+        self.envelope_window_sum -= self.envelope_window.pop_front().unwrap_or(1.0);
+        let gain = self.envelope_window_sum / self.lookahead as f64;
+
+        self.delayed_samples.push_back((l, r));
+        let (l, r) = self.delayed_samples.pop_front().unwrap_or((l, r));
+        (l * gain, r * gain)
+    }
+
+    /// True-peak of the sample in the middle of the history and the inter-sample
+    /// values that follow it.
+    fn true_peak(&self) -> f64 {
+        let middle = INTERPOLATION_TAPS / 2 - 1;
+        self.history
+            .iter()
+            .flat_map(|history| {
+                let inter_sample_peaks = self.interpolation.iter().map(move |coefficients| {
+                    let value: f64 = coefficients.iter().zip(history).map(|(c, s)| c * s).sum();
+                    value.abs()
+                });
+                inter_sample_peaks.chain([history[middle].abs()])
+            })
+            .fold(0.0, f64::max)
+    }
+}
+
+/// Sinc with the Hann window spanning the whole interpolation filter.
+fn windowed_sinc(x: f64) -> f64 {
+    let half_width = (INTERPOLATION_TAPS / 2) as f64;
+    if x.abs() >= half_width {
+        return 0.0;
+    }
+    let window = 0.5 * (1.0 + f64::cos(PI * x / half_width));
+    let sinc = match x == 0.0 {
+        true => 1.0,
+        false => f64::sin(PI * x) / (PI * x),
+    };
+    sinc * window
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::TAU;
+
+    use super::*;
+
+    const SAMPLE_RATE: u32 = 48_000;
+
+    fn sine(amplitude: f64, seconds: u32) -> impl Iterator<Item = f64> {
+        (0..SAMPLE_RATE * seconds)
+            .map(move |i| amplitude * f64::sin(TAU * 1000.0 * i as f64 / SAMPLE_RATE as f64))
+    }
+
+    #[test]
+    fn integrated_loudness_of_sine() {
+        let mut meter = LoudnessMeter::new(SAMPLE_RATE);
+        for sample in sine(0.1, 5) {
+            meter.process((sample, sample));
+        }
+
+        // 1kHz sine at -20 dBFS in both channels.
+        let integrated = meter.integrated().unwrap();
+        assert!((integrated - -20.0).abs() < 0.1, "{integrated}");
+    }
+
+    #[test]
+    fn limiter_keeps_peaks_below_limit() {
+        let mut limiter = TruePeakLimiter::new(-6.0, SAMPLE_RATE);
+
+        let peak = sine(1.0, 1)
+            .map(|sample| limiter.process((sample, sample)).0.abs())
+            .fold(0.0, f64::max);
+
+        assert!(peak <= db_to_amplitude(-6.0) + 1e-6, "{peak}");
+    }
+}
@@ -1,89 +1,570 @@
-use std::collections::HashMap;
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    f64::consts::{FRAC_PI_4, SQRT_2},
+    time::Duration,
+};
 
 use compositor_render::InputId;
 
-use crate::audio_mixer::{InputParams, MixingStrategy};
+use crate::audio_mixer::{ChannelRouting, InputParams, MixingStrategy};
 
 use super::{
+    channel_layout::{downmix_surround51, surround51},
+    effects::EffectsChain,
+    loudness::LoudnessNormalizer,
     types::{AudioChannels, AudioSamples},
+    volume_ramp::VolumeRamp,
     OutputInfo,
 };
 
 /// Mix input samples accordingly to provided specification.
 pub(super) fn mix_samples(
-    input_samples: &HashMap<InputId, Vec<(i16, i16)>>,
-    output_info: &OutputInfo,
+    input_samples: &HashMap<InputId, AudioSamples>,
+    output_info: &mut OutputInfo,
+    start_pts: Duration,
     samples_count: usize,
+    sample_rate: u32,
 ) -> AudioSamples {
-    /// Clips sample to i16 PCM range
-    fn clip_to_i16(sample: i64) -> i16 {
-        sample.min(i16::MAX as i64).max(i16::MIN as i64) as i16
-    }
-
-    let summed_samples = sum_samples(
+    let ramp_states = output_info
+        .volume_ramp
+        .as_mut()
+        .map(|ramp| ramp.states(start_pts, samples_count, sample_rate));
+    let channels = output_info.channels;
+    let inputs = process_inputs(
         input_samples,
         samples_count,
-        output_info.audio.inputs.iter(),
+        output_info,
+        ramp_states.as_deref(),
     );
+    if channels == AudioChannels::Surround51 {
+        let summed_samples = sum_surround51_samples(&inputs, samples_count);
+        finish_volume_ramp(output_info);
+        return AudioSamples::Surround51(scale_surround51_samples(
+            summed_samples,
+            &output_info.mixing_strategy,
+        ));
+    }
+    let summed_samples = sum_samples(&inputs, samples_count);
+    finish_volume_ramp(output_info);
 
-    let mixed: Vec<(i16, i16)> = match output_info.mixing_strategy {
+    let mixed: Vec<(f32, f32)> = match output_info.mixing_strategy {
+        MixingStrategy::LoudnessNormalization {
+            target_lufs,
+            true_peak_limit_dbtp,
+        } => output_info
+            .loudness_normalizer
+            .get_or_insert_with(|| {
+                LoudnessNormalizer::new(target_lufs, true_peak_limit_dbtp, sample_rate)
+            })
+            .process(summed_samples),
         MixingStrategy::SumClip => summed_samples
             .into_iter()
-            .map(|(l, r)| (clip_to_i16(l), clip_to_i16(r)))
+            .map(|(l, r)| (clip(l), clip(r)))
             .collect(),
         MixingStrategy::SumScale => {
-            let scaling_factor = {
-                // abs panics in debug if val = i64::MIN, but it would require summing so many i16 samples, that it'll never happen.
-                // Assumes that summed samples is not empty (therefore unwrap is safe)
-                let max_abs = summed_samples
-                    .iter()
-                    .map(|(l, r)| i64::max(l.abs(), r.abs()))
-                    .max()
-                    .unwrap();
-                f64::max(max_abs as f64 / i16::MAX as f64, 1.0)
-            };
+            let scaling_factor = summed_samples
+                .iter()
+                .map(|(l, r)| f32::max(l.abs(), r.abs()))
+                .fold(1.0, f32::max);
 
             summed_samples
                 .into_iter()
-                .map(|(l, r)| {
-                    (
-                        clip_to_i16((l as f64 * scaling_factor) as i64),
-                        clip_to_i16((r as f64 * scaling_factor) as i64),
-                    )
-                })
+                .map(|(l, r)| (l / scaling_factor, r / scaling_factor))
                 .collect()
         }
     };
 
     match output_info.channels {
-        AudioChannels::Mono => AudioSamples::Mono(
-            mixed
-                .into_iter()
-                // Convert to i32 to avoid additions overflows
-                .map(|(l, r)| ((l as i32 + r as i32) / 2) as i16)
-                .collect(),
-        ),
-        AudioChannels::Stereo => AudioSamples::Stereo(mixed),
+        AudioChannels::Mono => {
+            AudioSamples::Mono(mixed.into_iter().map(|(l, r)| (l + r) / 2.0).collect())
+        }
+        AudioChannels::Stereo | AudioChannels::Surround51 => AudioSamples::Stereo(mixed),
     }
 }
 
-/// Sums samples from inputs
-fn sum_samples<'a, I: Iterator<Item = &'a InputParams>>(
-    input_samples: &HashMap<InputId, Vec<(i16, i16)>>,
+fn clip(sample: f32) -> f32 {
+    sample.clamp(-1.0, 1.0)
+}
+
+fn finish_volume_ramp(output_info: &mut OutputInfo) {
+    if output_info
+        .volume_ramp
+        .as_ref()
+        .is_some_and(VolumeRamp::is_finished)
+    {
+        output_info.volume_ramp = None;
+    }
+}
+
+/// Input samples prepared for summing.
+struct ProcessedInput<'a> {
+    params: &'a InputParams,
+    samples: ProcessedSamples<'a>,
+    /// Volume for each sample, including volume ramp and ducking.
+    volumes: Vec<f64>,
+}
+
+enum ProcessedSamples<'a> {
+    /// Channel routing and effects are applied. Mono and 5.1 inputs are converted to
+    /// stereo, unless 5.1 channels are kept (see `keeps_surround51_channels`).
+    Stereo(Cow<'a, [(f32, f32)]>),
+    /// 5.1 input mixed into a 5.1 output, it is summed without processing.
+    Surround51(&'a [[f32; 6]]),
+}
+
+impl ProcessedSamples<'_> {
+    fn stereo(&self) -> Cow<[(f32, f32)]> {
+        match self {
+            ProcessedSamples::Stereo(samples) => Cow::Borrowed(samples),
+            ProcessedSamples::Surround51(samples) => {
+                samples.iter().copied().map(downmix_surround51).collect()
+            }
+        }
+    }
+}
+
+/// Applies channel routing and effects, and calculates volume of the inputs. During
+/// a volume ramp, volume is interpolated for each sample.
+fn process_inputs<'a>(
+    input_samples: &'a HashMap<InputId, AudioSamples>,
     samples_count: usize,
-    inputs: I,
-) -> Vec<(i64, i64)> {
-    let mut summed_samples = vec![(0i64, 0i64); samples_count];
+    output_info: &'a mut OutputInfo,
+    ramp_states: Option<&[f64]>,
+) -> Vec<ProcessedInput<'a>> {
+    let OutputInfo {
+        audio,
+        channels,
+        effects,
+        volume_ramp,
+        ducking,
+        ..
+    } = output_info;
 
-    for input_params in inputs {
-        let Some(input_samples) = input_samples.get(&input_params.input_id) else {
-            continue;
-        };
-        for (sum, sample) in summed_samples.iter_mut().zip(input_samples.iter()) {
-            sum.0 += (sample.0 as f64 * input_params.volume as f64) as i64;
-            sum.1 += (sample.1 as f64 * input_params.volume as f64) as i64;
+    // Inputs are identified by the index of the entry, because the same input can be
+    // listed more than once, e.g. to mix each channel of a dual mono input separately.
+    let processed_inputs: Vec<(usize, &InputParams, ProcessedSamples)> = audio
+        .inputs
+        .iter()
+        .zip(effects.iter_mut())
+        .enumerate()
+        .filter_map(|(index, (input_params, effects))| {
+            let samples = match (input_samples.get(&input_params.input_id)?, *channels) {
+                (AudioSamples::Surround51(samples), AudioChannels::Surround51)
+                    if keeps_surround51_channels(input_params) =>
+                {
+                    ProcessedSamples::Surround51(samples)
+                }
+                (samples, _) => {
+                    ProcessedSamples::Stereo(process_input(input_params, samples.stereo(), effects))
+                }
+            };
+            Some((index, input_params, samples))
+        })
+        .collect();
+
+    // Trigger inputs that are not mixed into the output are also detected.
+    let ducking_gains: Vec<Vec<f64>> = ducking
+        .iter_mut()
+        .map(|ducking| {
+            let trigger_samples = processed_inputs
+                .iter()
+                .find(|(_, input_params, _)| &input_params.input_id == ducking.trigger_input_id())
+                .map(|(_, _, samples)| samples.stereo())
+                .or_else(|| {
+                    input_samples
+                        .get(ducking.trigger_input_id())
+                        .map(AudioSamples::stereo)
+                });
+            ducking.gains(trigger_samples.as_deref(), samples_count)
+        })
+        .collect();
+
+    processed_inputs
+        .into_iter()
+        .map(|(index, input_params, samples)| {
+            let ducking_gains: Vec<&[f64]> = ducking
+                .iter()
+                .zip(&ducking_gains)
+                .filter(|(ducking, _)| ducking.ducks(&input_params.input_id))
+                .map(|(_, gains)| gains.as_slice())
+                .collect();
+            let volumes = (0..samples_count)
+                .map(|sample_index| {
+                    let volume = match (&*volume_ramp, ramp_states) {
+                        (Some(ramp), Some(states)) => {
+                            ramp.volume(index, input_params.volume, states[sample_index])
+                        }
+                        _ => input_params.volume as f64,
+                    };
+                    ducking_gains
+                        .iter()
+                        .fold(volume, |volume, gains| volume * gains[sample_index])
+                })
+                .collect();
+            ProcessedInput {
+                params: input_params,
+                samples,
+                volumes,
+            }
+        })
+        .collect()
+}
+
+/// Sums processed samples, applying volume and pan.
+fn sum_samples(inputs: &[ProcessedInput], samples_count: usize) -> Vec<(f32, f32)> {
+    let mut summed_samples = vec![(0.0, 0.0); samples_count];
+    for input in inputs {
+        let (left_gain, right_gain) = pan_gains(input.params.pan);
+        for ((sum, sample), volume) in summed_samples
+            .iter_mut()
+            .zip(input.samples.stereo().iter())
+            .zip(&input.volumes)
+        {
+            sum.0 += (sample.0 as f64 * volume * left_gain) as f32;
+            sum.1 += (sample.1 as f64 * volume * right_gain) as f32;
+        }
+    }
+
+    summed_samples
+}
+
+/// Sums processed samples into 5.1 channels. Stereo samples are panned and mixed into
+/// the front channels, 5.1 samples are mixed into the matching channels.
+fn sum_surround51_samples(inputs: &[ProcessedInput], samples_count: usize) -> Vec<[f32; 6]> {
+    let mut summed_samples = vec![[0.0; 6]; samples_count];
+    for input in inputs {
+        match &input.samples {
+            ProcessedSamples::Stereo(samples) => {
+                let (left_gain, right_gain) = pan_gains(input.params.pan);
+                for ((sum, (l, r)), volume) in summed_samples
+                    .iter_mut()
+                    .zip(samples.iter())
+                    .zip(&input.volumes)
+                {
+                    sum[surround51::FRONT_LEFT] += (*l as f64 * volume * left_gain) as f32;
+                    sum[surround51::FRONT_RIGHT] += (*r as f64 * volume * right_gain) as f32;
+                }
+            }
+            ProcessedSamples::Surround51(samples) => {
+                for ((sum, sample), volume) in summed_samples
+                    .iter_mut()
+                    .zip(samples.iter())
+                    .zip(&input.volumes)
+                {
+                    for (sum, sample) in sum.iter_mut().zip(sample) {
+                        *sum += (*sample as f64 * volume) as f32;
+                    }
+                }
+            }
         }
     }
 
     summed_samples
 }
+
+fn scale_surround51_samples(
+    summed_samples: Vec<[f32; 6]>,
+    mixing_strategy: &MixingStrategy,
+) -> Vec<[f32; 6]> {
+    let scaling_factor = match mixing_strategy {
+        MixingStrategy::SumScale => summed_samples
+            .iter()
+            .flatten()
+            .map(|sample| sample.abs())
+            .fold(1.0, f32::max),
+        // Loudness normalization of 5.1 audio is not supported, such outputs
+        // are rejected by the API and fall back to clipping.
+        MixingStrategy::SumClip | MixingStrategy::LoudnessNormalization { .. } => 1.0,
+    };
+
+    summed_samples
+        .into_iter()
+        .map(|sample| sample.map(|sample| clip(sample / scaling_factor)))
+        .collect()
+}
+
+/// Pan, channel routing and effects are defined for stereo, so a 5.1 input mixed into
+/// a 5.1 output keeps its channels only if none of them is used. Otherwise, it is
+/// downmixed to stereo and processed like a stereo input.
+fn keeps_surround51_channels(input_params: &InputParams) -> bool {
+    input_params.pan == 0.0
+        && input_params.channel_routing == ChannelRouting::Stereo
+        && input_params.effects.is_empty()
+}
+
+/// Applies channel routing and effects of the input.
+fn process_input<'a>(
+    input_params: &InputParams,
+    samples: Cow<'a, [(f32, f32)]>,
+    effects: &mut EffectsChain,
+) -> Cow<'a, [(f32, f32)]> {
+    let samples: Cow<[(f32, f32)]> = match input_params.channel_routing {
+        ChannelRouting::Stereo => samples,
+        ChannelRouting::Left => samples.iter().map(|(l, _)| (*l, *l)).collect(),
+        ChannelRouting::Right => samples.iter().map(|(_, r)| (*r, *r)).collect(),
+    };
+    if effects.is_empty() {
+        return samples;
+    }
+    Cow::Owned(effects.process(&samples))
+}
+
+/// Constant-power pan law, scaled so that centered input keeps its original level.
+/// Fully panned input is louder by 3 dB on one channel and silent on the other.
+fn pan_gains(pan: f32) -> (f64, f64) {
+    if pan == 0.0 {
+        // Avoids rounding errors, so centered inputs are mixed exactly as without panning.
+        return (1.0, 1.0);
+    }
+    let angle = (pan.clamp(-1.0, 1.0) as f64 + 1.0) * FRAC_PI_4;
+    (angle.cos() * SQRT_2, angle.sin() * SQRT_2)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::audio_mixer::{AudioEffect, AudioMixingParams};
+
+    use super::*;
+
+    fn input_params(pan: f32, channel_routing: ChannelRouting) -> InputParams {
+        InputParams {
+            input_id: InputId(Arc::from("input_1")),
+            volume: 1.0,
+            pan,
+            channel_routing,
+            effects: vec![],
+        }
+    }
+
+    fn assert_gains(pan: f32, expected: (f64, f64)) {
+        let (left, right) = pan_gains(pan);
+        assert!(
+            (left - expected.0).abs() < 1e-9 && (right - expected.1).abs() < 1e-9,
+            "pan {pan}: ({left}, {right})"
+        );
+    }
+
+    #[test]
+    fn pan_law() {
+        assert_eq!(pan_gains(0.0), (1.0, 1.0));
+        assert_gains(-1.0, (SQRT_2, 0.0));
+        assert_gains(1.0, (0.0, SQRT_2));
+        assert_gains(5.0, (0.0, SQRT_2));
+
+        // Total power is the same for every pan position.
+        for pan in [-0.75, -0.3, 0.2, 0.5, 0.9] {
+            let (left, right) = pan_gains(pan);
+            assert!(
+                (left * left + right * right - 2.0).abs() < 1e-9,
+                "pan {pan}"
+            );
+        }
+        let (left, right) = pan_gains(0.5);
+        assert!(left < 1.0 && right > 1.0);
+    }
+
+    #[test]
+    fn channel_routing() {
+        let samples = [(0.25, -0.5), (0.75, 0.1)];
+        let mut effects = EffectsChain::default();
+
+        let stereo = process_input(
+            &input_params(0.0, ChannelRouting::Stereo),
+            Cow::Borrowed(&samples),
+            &mut effects,
+        );
+        assert!(matches!(stereo, Cow::Borrowed(_)));
+        assert_eq!(*stereo, samples);
+
+        let left = process_input(
+            &input_params(0.0, ChannelRouting::Left),
+            Cow::Borrowed(&samples),
+            &mut effects,
+        );
+        assert_eq!(*left, [(0.25, 0.25), (0.75, 0.75)]);
+
+        let right = process_input(
+            &input_params(0.0, ChannelRouting::Right),
+            Cow::Borrowed(&samples),
+            &mut effects,
+        );
+        assert_eq!(*right, [(-0.5, -0.5), (0.1, 0.1)]);
+    }
+
+    #[test]
+    fn dual_mono_input_split_into_panned_sources() {
+        // Each channel of the input carries a different source, e.g. two microphones.
+        let samples = [(0.5, 0.0), (0.0, 0.25)];
+        let left_params = input_params(-1.0, ChannelRouting::Left);
+        let right_params = input_params(1.0, ChannelRouting::Right);
+
+        let inputs: Vec<ProcessedInput> = [&left_params, &right_params]
+            .into_iter()
+            .map(|params| ProcessedInput {
+                params,
+                samples: ProcessedSamples::Stereo(process_input(
+                    params,
+                    Cow::Borrowed(&samples),
+                    &mut EffectsChain::default(),
+                )),
+                volumes: vec![1.0; samples.len()],
+            })
+            .collect();
+        let mixed = sum_samples(&inputs, samples.len());
+
+        let expected = [(0.5 * SQRT_2 as f32, 0.0), (0.0, 0.25 * SQRT_2 as f32)];
+        for ((l, r), (expected_l, expected_r)) in mixed.iter().zip(expected) {
+            assert!(
+                (l - expected_l).abs() < 1e-6 && (r - expected_r).abs() < 1e-6,
+                "({l}, {r})"
+            );
+        }
+    }
+
+    #[test]
+    fn same_input_listed_twice_has_separate_effects_state() {
+        let input_id = InputId(Arc::from("input_1"));
+        let high_pass = InputParams {
+            effects: vec![AudioEffect::HighPassFilter {
+                cutoff_frequency: 1000.0,
+            }],
+            ..input_params(0.0, ChannelRouting::Stereo)
+        };
+        // Output of the filter for a constant signal depends on its state.
+        let input_samples =
+            HashMap::from([(input_id, AudioSamples::Stereo(vec![(0.25, 0.25); 48]))]);
+        let mix = |inputs: Vec<InputParams>| {
+            let mut output_info = OutputInfo::new(
+                AudioMixingParams {
+                    inputs,
+                    transition: None,
+                    ducking: vec![],
+                },
+                MixingStrategy::SumClip,
+                AudioChannels::Stereo,
+                48_000,
+            );
+            (0..2)
+                .flat_map(|batch| {
+                    let start_pts = Duration::from_millis(batch);
+                    match mix_samples(&input_samples, &mut output_info, start_pts, 48, 48_000) {
+                        AudioSamples::Stereo(samples) => samples,
+                        samples => panic!("Unexpected samples: {samples:?}"),
+                    }
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let single = mix(vec![high_pass.clone()]);
+        let doubled = mix(vec![high_pass.clone(), high_pass]);
+        for ((l, r), (doubled_l, doubled_r)) in single.iter().zip(&doubled) {
+            assert!(
+                (doubled_l - 2.0 * l).abs() < 1e-6 && (doubled_r - 2.0 * r).abs() < 1e-6,
+                "({l}, {r}) mixed twice: ({doubled_l}, {doubled_r})"
+            );
+        }
+    }
+
+    #[test]
+    fn surround51_input_is_downmixed_if_panned() {
+        let input_id = InputId(Arc::from("input_1"));
+        let input_samples = HashMap::from([(
+            input_id,
+            AudioSamples::Surround51(vec![[0.1, 0.2, 0.0, 0.3, 0.0, 0.0]; 4]),
+        )]);
+        let mix = |input: InputParams| {
+            let mut output_info = OutputInfo::new(
+                AudioMixingParams {
+                    inputs: vec![input],
+                    transition: None,
+                    ducking: vec![],
+                },
+                MixingStrategy::SumClip,
+                AudioChannels::Surround51,
+                48_000,
+            );
+            match mix_samples(&input_samples, &mut output_info, Duration::ZERO, 4, 48_000) {
+                AudioSamples::Surround51(samples) => samples[0],
+                samples => panic!("Unexpected samples: {samples:?}"),
+            }
+        };
+
+        assert_eq!(
+            mix(input_params(0.0, ChannelRouting::Stereo)),
+            [0.1, 0.2, 0.0, 0.3, 0.0, 0.0]
+        );
+
+        // Downmixed to stereo (LFE is dropped) and panned to the front left channel.
+        let [front_left, rest @ ..] = mix(input_params(-1.0, ChannelRouting::Stereo));
+        assert!(
+            (front_left - 0.1 * SQRT_2 as f32).abs() < 1e-6,
+            "{front_left}"
+        );
+        assert_eq!(rest, [0.0; 5]);
+
+        let [front_left, front_right, rest @ ..] = mix(input_params(0.0, ChannelRouting::Right));
+        assert_eq!((front_left, front_right), (0.2, 0.2));
+        assert_eq!(rest, [0.0; 4]);
+    }
+
+    #[test]
+    fn sum_scale_scales_down_batch_with_peak_above_full_scale() {
+        let input_samples = HashMap::from([
+            (
+                InputId(Arc::from("input_1")),
+                AudioSamples::Stereo(vec![(0.75, 0.25), (0.5, -0.25)]),
+            ),
+            (
+                InputId(Arc::from("input_2")),
+                AudioSamples::Stereo(vec![(0.75, 0.25), (0.25, 0.0)]),
+            ),
+        ]);
+        let mut output_info = OutputInfo::new(
+            AudioMixingParams {
+                inputs: ["input_1", "input_2"]
+                    .into_iter()
+                    .map(|input_id| InputParams {
+                        input_id: InputId(Arc::from(input_id)),
+                        ..input_params(0.0, ChannelRouting::Stereo)
+                    })
+                    .collect(),
+                transition: None,
+                ducking: vec![],
+            },
+            MixingStrategy::SumScale,
+            AudioChannels::Stereo,
+            48_000,
+        );
+        let mut mix = |input_samples: &HashMap<InputId, AudioSamples>| match mix_samples(
+            input_samples,
+            &mut output_info,
+            Duration::ZERO,
+            2,
+            48_000,
+        ) {
+            AudioSamples::Stereo(samples) => samples,
+            samples => panic!("Unexpected samples: {samples:?}"),
+        };
+
+        // Sum is `[(1.5, 0.5), (0.75, -0.25)]`, the whole batch is divided by its peak.
+        assert_eq!(
+            mix(&input_samples),
+            [(1.0, 0.5 / 1.5), (0.75 / 1.5, -0.25 / 1.5)]
+        );
+
+        // Batches within the full scale are not amplified.
+        let quiet_samples = input_samples
+            .keys()
+            .map(|input_id| {
+                (
+                    input_id.clone(),
+                    AudioSamples::Stereo(vec![(0.25, -0.125); 2]),
+                )
+            })
+            .collect();
+        assert_eq!(mix(&quiet_samples), [(0.5, -0.25); 2]);
+    }
+}
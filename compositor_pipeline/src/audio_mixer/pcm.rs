@@ -0,0 +1,82 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use super::types::AudioSamples;
+
+/// Float samples in `[-1, 1]` range are mapped to the full 16-bit range.
+const I16_SCALE: f32 = 32768.0;
+
+pub fn i16_to_f32(sample: i16) -> f32 {
+    sample as f32 / I16_SCALE
+}
+
+/// TPDF (triangular probability density function) dither. Noise with amplitude of up to
+/// 1 LSB is added before rounding, so the quantization error is not correlated with the signal
+/// and is heard as a constant noise floor instead of a distortion of quiet sounds.
+pub struct Dither {
+    rng: StdRng,
+}
+
+impl Dither {
+    pub fn new() -> Self {
+        Self {
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    /// Difference of two uniform random values is triangularly distributed in `(-1, 1)`.
+    fn noise(&mut self) -> f32 {
+        self.rng.gen::<f32>() - self.rng.gen::<f32>()
+    }
+}
+
+impl Default for Dither {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Converts sample to 16-bit PCM. Values outside of `[-1, 1]` range are clipped.
+pub fn f32_to_i16(sample: f32, dither: Option<&mut Dither>) -> i16 {
+    let noise = dither.map_or(0.0, Dither::noise);
+    (sample * I16_SCALE + noise)
+        .round()
+        .clamp(i16::MIN as f32, i16::MAX as f32) as i16
+}
+
+impl AudioSamples {
+    /// Interleaved 16-bit PCM samples, with channels in the order defined by `AudioChannels`.
+    pub fn to_i16_interleaved(&self, mut dither: Option<&mut Dither>) -> Vec<i16> {
+        self.interleaved()
+            .into_iter()
+            .map(|sample| f32_to_i16(sample, dither.as_deref_mut()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conversion_without_dither_is_lossless_for_i16() {
+        for sample in [i16::MIN, -1, 0, 1, 12345, i16::MAX] {
+            assert_eq!(f32_to_i16(i16_to_f32(sample), None), sample);
+        }
+        assert_eq!(f32_to_i16(1.5, None), i16::MAX);
+        assert_eq!(f32_to_i16(-1.5, None), i16::MIN);
+    }
+
+    #[test]
+    fn dither_error_is_below_one_lsb_and_unbiased() {
+        let mut dither = Dither::new();
+        let sample = 0.25 / I16_SCALE;
+
+        let errors: Vec<f32> = (0..100_000)
+            .map(|_| f32_to_i16(sample, Some(&mut dither)) as f32 - sample * I16_SCALE)
+            .collect();
+
+        assert!(errors.iter().all(|error| error.abs() <= 1.5));
+        let mean = errors.iter().sum::<f32>() / errors.len() as f32;
+        assert!(mean.abs() < 0.01, "{mean}");
+    }
+}
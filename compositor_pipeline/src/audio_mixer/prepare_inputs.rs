@@ -3,7 +3,7 @@ use std::{collections::HashMap, time::Duration};
 use compositor_render::InputId;
 use tracing::warn;
 
-use super::{InputSamples, InputSamplesSet};
+use super::{AudioChannels, AudioSamples, InputSamples, InputSamplesSet};
 
 #[cfg(test)]
 mod consecutive_frames_tests;
@@ -16,7 +16,7 @@ pub(super) fn expected_samples_count(start: Duration, end: Duration, sample_rate
 pub(super) fn prepare_input_samples(
     input_samples_set: InputSamplesSet,
     output_sample_rate: u32,
-) -> HashMap<InputId, Vec<(i16, i16)>> {
+) -> HashMap<InputId, AudioSamples> {
     input_samples_set
         .samples
         .into_iter()
@@ -48,13 +48,19 @@ pub(super) fn prepare_input_samples(
 /// - start_pts of a sample >= start_pts of an output batch (after applying `sample_offset`).
 /// - end_pts of a sample <= end_pts of an output batch (after applying `sample_offset`).
 /// - `=` in above cases means close enough to be a precision related error.
+///
+/// Samples are in the channel layout of the first batch (stereo if there are no batches).
 fn frame_input_samples(
     start_pts: Duration,
     end_pts: Duration,
     samples: Vec<InputSamples>,
     sample_rate: u32,
-) -> Vec<(i16, i16)> {
-    let mut samples_in_frame = Vec::new();
+) -> AudioSamples {
+    let channels = samples
+        .first()
+        .map(|batch| batch.samples.channels())
+        .unwrap_or(AudioChannels::Stereo);
+    let mut samples_in_frame = AudioSamples::silence(channels, 0);
 
     // Real numerical errors are a lot smaller, but taking max error as 1% of a sample duration
     // seems to be safe enough.
@@ -108,7 +114,7 @@ fn frame_input_samples(
                     "Distance between samples is higher than expected."
                 )
             }
-            samples_in_frame.extend((0..missing_samples_count).map(|_| (0i16, 0i16)))
+            samples_in_frame.extend_silence(missing_samples_count)
         }
 
         let sample_count = samples_in_frame.len();
@@ -139,7 +145,7 @@ fn frame_input_samples(
             end_range = start_range + desired_sample_count;
         }
 
-        samples_in_frame.extend(input_samples.samples[start_range..end_range].iter());
+        samples_in_frame.extend_from(&input_samples.samples, start_range..end_range);
     }
 
     // Fill at the end only if last batch is ending to quickly
@@ -160,7 +166,7 @@ fn check_frame_samples(
     start_pts: Duration,
     end_pts: Duration,
     sample_rate: u32,
-    samples: &[(i16, i16)],
+    samples: &AudioSamples,
 ) {
     let samples_count_times_1e9 =
         end_pts.saturating_sub(start_pts).as_nanos() * sample_rate as u128;
@@ -184,15 +190,13 @@ fn ensure_correct_amount_of_samples(
     start: Duration,
     end: Duration,
     sample_rate: u32,
-    samples_buffer: &mut Vec<(i16, i16)>,
+    samples_buffer: &mut AudioSamples,
 ) {
     // This is precise as long as (end - start) is divisible by `1/sample_rate`
     let expected_samples_count = expected_samples_count(start, end, sample_rate);
     if expected_samples_count > samples_buffer.len() {
-        let missing_samples_count = expected_samples_count - samples_buffer.len();
-        let missing_samples = (0..missing_samples_count).map(|_| (0i16, 0i16));
-        samples_buffer.extend(missing_samples);
+        samples_buffer.extend_silence(expected_samples_count - samples_buffer.len());
     } else {
-        samples_buffer.drain(expected_samples_count..samples_buffer.len());
+        samples_buffer.truncate(expected_samples_count);
     }
 }
@@ -1,6 +1,6 @@
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
 
-use crate::audio_mixer::InputSamples;
+use crate::audio_mixer::{AudioSamples, InputSamples};
 
 use super::frame_input_samples;
 
@@ -17,7 +17,14 @@ fn test_prepare_inputs() {
 
     assert_eq!(
         frame_input_samples(start, end, vec![], sample_rate),
-        vec![(0, 0), (0, 0), (0, 0), (0, 0), (0, 0), (0, 0)]
+        AudioSamples::Stereo(vec![
+            (0.0, 0.0),
+            (0.0, 0.0),
+            (0.0, 0.0),
+            (0.0, 0.0),
+            (0.0, 0.0),
+            (0.0, 0.0)
+        ])
     );
 
     let first_batch_start = start - small_error;
@@ -28,19 +35,36 @@ fn test_prepare_inputs() {
             end,
             vec![
                 InputSamples {
-                    samples: vec![(1, 1), (2, 2), (3, 3), (4, 4)].into(),
+                    samples: Arc::new(AudioSamples::Stereo(vec![
+                        (1.0, 1.0),
+                        (2.0, 2.0),
+                        (3.0, 3.0),
+                        (4.0, 4.0)
+                    ])),
                     start_pts: first_batch_start,
                     end_pts: first_batch_start + (4 * sample_duration)
                 },
                 InputSamples {
-                    samples: vec![(5, 5), (6, 6), (7, 7), (8, 8)].into(),
+                    samples: Arc::new(AudioSamples::Stereo(vec![
+                        (5.0, 5.0),
+                        (6.0, 6.0),
+                        (7.0, 7.0),
+                        (8.0, 8.0)
+                    ])),
                     start_pts: second_batch_start,
                     end_pts: second_batch_start + (4 * sample_duration)
                 },
             ],
             sample_rate
         ),
-        vec![(1, 1), (2, 2), (3, 3), (4, 4), (5, 5), (6, 6)]
+        AudioSamples::Stereo(vec![
+            (1.0, 1.0),
+            (2.0, 2.0),
+            (3.0, 3.0),
+            (4.0, 4.0),
+            (5.0, 5.0),
+            (6.0, 6.0)
+        ])
     );
 
     let first_batch_start = start - half_sample;
@@ -51,19 +75,36 @@ fn test_prepare_inputs() {
             end,
             vec![
                 InputSamples {
-                    samples: vec![(1, 1), (2, 2), (3, 3), (4, 4)].into(),
+                    samples: Arc::new(AudioSamples::Stereo(vec![
+                        (1.0, 1.0),
+                        (2.0, 2.0),
+                        (3.0, 3.0),
+                        (4.0, 4.0)
+                    ])),
                     start_pts: first_batch_start,
                     end_pts: first_batch_start + (4 * sample_duration)
                 },
                 InputSamples {
-                    samples: vec![(5, 5), (6, 6), (7, 7), (8, 8)].into(),
+                    samples: Arc::new(AudioSamples::Stereo(vec![
+                        (5.0, 5.0),
+                        (6.0, 6.0),
+                        (7.0, 7.0),
+                        (8.0, 8.0)
+                    ])),
                     start_pts: second_batch_start,
                     end_pts: second_batch_start + (4 * sample_duration)
                 },
             ],
             sample_rate
         ),
-        vec![(1, 1), (2, 2), (3, 3), (4, 4), (5, 5), (6, 6)]
+        AudioSamples::Stereo(vec![
+            (1.0, 1.0),
+            (2.0, 2.0),
+            (3.0, 3.0),
+            (4.0, 4.0),
+            (5.0, 5.0),
+            (6.0, 6.0)
+        ])
     );
 
     let first_batch_start = start + small_error;
@@ -74,19 +115,36 @@ fn test_prepare_inputs() {
             end,
             vec![
                 InputSamples {
-                    samples: vec![(1, 1), (2, 2), (3, 3), (4, 4)].into(),
+                    samples: Arc::new(AudioSamples::Stereo(vec![
+                        (1.0, 1.0),
+                        (2.0, 2.0),
+                        (3.0, 3.0),
+                        (4.0, 4.0)
+                    ])),
                     start_pts: first_batch_start,
                     end_pts: first_batch_start + (4 * sample_duration)
                 },
                 InputSamples {
-                    samples: vec![(5, 5), (6, 6), (7, 7), (8, 8)].into(),
+                    samples: Arc::new(AudioSamples::Stereo(vec![
+                        (5.0, 5.0),
+                        (6.0, 6.0),
+                        (7.0, 7.0),
+                        (8.0, 8.0)
+                    ])),
                     start_pts: second_batch_start,
                     end_pts: second_batch_start + (4 * sample_duration)
                 },
             ],
             sample_rate
         ),
-        vec![(0, 0), (1, 1), (2, 2), (3, 3), (4, 4), (5, 5)]
+        AudioSamples::Stereo(vec![
+            (0.0, 0.0),
+            (1.0, 1.0),
+            (2.0, 2.0),
+            (3.0, 3.0),
+            (4.0, 4.0),
+            (5.0, 5.0)
+        ])
     );
 
     let first_batch_start = start - sample_duration + small_error;
@@ -97,19 +155,36 @@ fn test_prepare_inputs() {
             end,
             vec![
                 InputSamples {
-                    samples: vec![(1, 1), (2, 2), (3, 3), (4, 4)].into(),
+                    samples: Arc::new(AudioSamples::Stereo(vec![
+                        (1.0, 1.0),
+                        (2.0, 2.0),
+                        (3.0, 3.0),
+                        (4.0, 4.0)
+                    ])),
                     start_pts: first_batch_start,
                     end_pts: first_batch_start + (4 * sample_duration)
                 },
                 InputSamples {
-                    samples: vec![(5, 5), (6, 6), (7, 7), (8, 8)].into(),
+                    samples: Arc::new(AudioSamples::Stereo(vec![
+                        (5.0, 5.0),
+                        (6.0, 6.0),
+                        (7.0, 7.0),
+                        (8.0, 8.0)
+                    ])),
                     start_pts: second_batch_start,
                     end_pts: second_batch_start + (4 * sample_duration)
                 },
             ],
             sample_rate
         ),
-        vec![(1, 1), (2, 2), (3, 3), (4, 4), (5, 5), (6, 6)]
+        AudioSamples::Stereo(vec![
+            (1.0, 1.0),
+            (2.0, 2.0),
+            (3.0, 3.0),
+            (4.0, 4.0),
+            (5.0, 5.0),
+            (6.0, 6.0)
+        ])
     );
 
     let first_batch_start = start - sample_duration - small_error;
@@ -120,19 +195,36 @@ fn test_prepare_inputs() {
             end,
             vec![
                 InputSamples {
-                    samples: vec![(1, 1), (2, 2), (3, 3), (4, 4)].into(),
+                    samples: Arc::new(AudioSamples::Stereo(vec![
+                        (1.0, 1.0),
+                        (2.0, 2.0),
+                        (3.0, 3.0),
+                        (4.0, 4.0)
+                    ])),
                     start_pts: first_batch_start,
                     end_pts: first_batch_start + (4 * sample_duration)
                 },
                 InputSamples {
-                    samples: vec![(5, 5), (6, 6), (7, 7), (8, 8)].into(),
+                    samples: Arc::new(AudioSamples::Stereo(vec![
+                        (5.0, 5.0),
+                        (6.0, 6.0),
+                        (7.0, 7.0),
+                        (8.0, 8.0)
+                    ])),
                     start_pts: second_batch_start,
                     end_pts: second_batch_start + (4 * sample_duration)
                 },
             ],
             sample_rate
         ),
-        vec![(2, 2), (3, 3), (4, 4), (5, 5), (6, 6), (7, 7)]
+        AudioSamples::Stereo(vec![
+            (2.0, 2.0),
+            (3.0, 3.0),
+            (4.0, 4.0),
+            (5.0, 5.0),
+            (6.0, 6.0),
+            (7.0, 7.0)
+        ])
     );
 
     //slightly overlapping batches
@@ -144,19 +236,36 @@ fn test_prepare_inputs() {
             end,
             vec![
                 InputSamples {
-                    samples: vec![(1, 1), (2, 2), (3, 3), (4, 4)].into(),
+                    samples: Arc::new(AudioSamples::Stereo(vec![
+                        (1.0, 1.0),
+                        (2.0, 2.0),
+                        (3.0, 3.0),
+                        (4.0, 4.0)
+                    ])),
                     start_pts: first_batch_start,
                     end_pts: first_batch_start + (4 * sample_duration)
                 },
                 InputSamples {
-                    samples: vec![(5, 5), (6, 6), (7, 7), (8, 8)].into(),
+                    samples: Arc::new(AudioSamples::Stereo(vec![
+                        (5.0, 5.0),
+                        (6.0, 6.0),
+                        (7.0, 7.0),
+                        (8.0, 8.0)
+                    ])),
                     start_pts: second_batch_start,
                     end_pts: second_batch_start + (4 * sample_duration)
                 },
             ],
             sample_rate
         ),
-        vec![(1, 1), (2, 2), (3, 3), (4, 4), (5, 5), (6, 6)]
+        AudioSamples::Stereo(vec![
+            (1.0, 1.0),
+            (2.0, 2.0),
+            (3.0, 3.0),
+            (4.0, 4.0),
+            (5.0, 5.0),
+            (6.0, 6.0)
+        ])
     );
 
     // batches with small gap (small error)
@@ -168,19 +277,36 @@ fn test_prepare_inputs() {
             end,
             vec![
                 InputSamples {
-                    samples: vec![(1, 1), (2, 2), (3, 3), (4, 4)].into(),
+                    samples: Arc::new(AudioSamples::Stereo(vec![
+                        (1.0, 1.0),
+                        (2.0, 2.0),
+                        (3.0, 3.0),
+                        (4.0, 4.0)
+                    ])),
                     start_pts: first_batch_start,
                     end_pts: first_batch_start + (4 * sample_duration)
                 },
                 InputSamples {
-                    samples: vec![(5, 5), (6, 6), (7, 7), (8, 8)].into(),
+                    samples: Arc::new(AudioSamples::Stereo(vec![
+                        (5.0, 5.0),
+                        (6.0, 6.0),
+                        (7.0, 7.0),
+                        (8.0, 8.0)
+                    ])),
                     start_pts: second_batch_start,
                     end_pts: second_batch_start + (4 * sample_duration)
                 },
             ],
             sample_rate
         ),
-        vec![(1, 1), (2, 2), (3, 3), (4, 4), (5, 5), (6, 6)]
+        AudioSamples::Stereo(vec![
+            (1.0, 1.0),
+            (2.0, 2.0),
+            (3.0, 3.0),
+            (4.0, 4.0),
+            (5.0, 5.0),
+            (6.0, 6.0)
+        ])
     );
 
     //slightly overlapping batches (more than half sample)
@@ -192,19 +318,36 @@ fn test_prepare_inputs() {
             end,
             vec![
                 InputSamples {
-                    samples: vec![(1, 1), (2, 2), (3, 3), (4, 4)].into(),
+                    samples: Arc::new(AudioSamples::Stereo(vec![
+                        (1.0, 1.0),
+                        (2.0, 2.0),
+                        (3.0, 3.0),
+                        (4.0, 4.0)
+                    ])),
                     start_pts: first_batch_start,
                     end_pts: first_batch_start + (4 * sample_duration)
                 },
                 InputSamples {
-                    samples: vec![(5, 5), (6, 6), (7, 7), (8, 8)].into(),
+                    samples: Arc::new(AudioSamples::Stereo(vec![
+                        (5.0, 5.0),
+                        (6.0, 6.0),
+                        (7.0, 7.0),
+                        (8.0, 8.0)
+                    ])),
                     start_pts: second_batch_start,
                     end_pts: second_batch_start + (4 * sample_duration)
                 },
             ],
             sample_rate
         ),
-        vec![(1, 1), (2, 2), (3, 3), (4, 4), (5, 5), (6, 6)]
+        AudioSamples::Stereo(vec![
+            (1.0, 1.0),
+            (2.0, 2.0),
+            (3.0, 3.0),
+            (4.0, 4.0),
+            (5.0, 5.0),
+            (6.0, 6.0)
+        ])
     );
 
     // batches with small gap (more than half sample)
@@ -216,19 +359,36 @@ fn test_prepare_inputs() {
             end,
             vec![
                 InputSamples {
-                    samples: vec![(1, 1), (2, 2), (3, 3), (4, 4)].into(),
+                    samples: Arc::new(AudioSamples::Stereo(vec![
+                        (1.0, 1.0),
+                        (2.0, 2.0),
+                        (3.0, 3.0),
+                        (4.0, 4.0)
+                    ])),
                     start_pts: first_batch_start,
                     end_pts: first_batch_start + (4 * sample_duration)
                 },
                 InputSamples {
-                    samples: vec![(5, 5), (6, 6), (7, 7), (8, 8)].into(),
+                    samples: Arc::new(AudioSamples::Stereo(vec![
+                        (5.0, 5.0),
+                        (6.0, 6.0),
+                        (7.0, 7.0),
+                        (8.0, 8.0)
+                    ])),
                     start_pts: second_batch_start,
                     end_pts: second_batch_start + (4 * sample_duration)
                 },
             ],
             sample_rate
         ),
-        vec![(1, 1), (2, 2), (3, 3), (4, 4), (5, 5), (6, 6)]
+        AudioSamples::Stereo(vec![
+            (1.0, 1.0),
+            (2.0, 2.0),
+            (3.0, 3.0),
+            (4.0, 4.0),
+            (5.0, 5.0),
+            (6.0, 6.0)
+        ])
     );
 
     //slightly overlapping batches (more than a sample)
@@ -241,19 +401,36 @@ fn test_prepare_inputs() {
             end,
             vec![
                 InputSamples {
-                    samples: vec![(1, 1), (2, 2), (3, 3), (4, 4)].into(),
+                    samples: Arc::new(AudioSamples::Stereo(vec![
+                        (1.0, 1.0),
+                        (2.0, 2.0),
+                        (3.0, 3.0),
+                        (4.0, 4.0)
+                    ])),
                     start_pts: first_batch_start,
                     end_pts: first_batch_start + (4 * sample_duration)
                 },
                 InputSamples {
-                    samples: vec![(5, 5), (6, 6), (7, 7), (8, 8)].into(),
+                    samples: Arc::new(AudioSamples::Stereo(vec![
+                        (5.0, 5.0),
+                        (6.0, 6.0),
+                        (7.0, 7.0),
+                        (8.0, 8.0)
+                    ])),
                     start_pts: second_batch_start,
                     end_pts: second_batch_start + (4 * sample_duration)
                 },
             ],
             sample_rate
         ),
-        vec![(1, 1), (2, 2), (3, 3), (4, 4), (6, 6), (7, 7)]
+        AudioSamples::Stereo(vec![
+            (1.0, 1.0),
+            (2.0, 2.0),
+            (3.0, 3.0),
+            (4.0, 4.0),
+            (6.0, 6.0),
+            (7.0, 7.0)
+        ])
     );
 
     // batches with small gap (more than half sample)
@@ -266,18 +443,82 @@ fn test_prepare_inputs() {
             end,
             vec![
                 InputSamples {
-                    samples: vec![(1, 1), (2, 2), (3, 3), (4, 4)].into(),
+                    samples: Arc::new(AudioSamples::Stereo(vec![
+                        (1.0, 1.0),
+                        (2.0, 2.0),
+                        (3.0, 3.0),
+                        (4.0, 4.0)
+                    ])),
                     start_pts: first_batch_start,
                     end_pts: first_batch_start + (4 * sample_duration)
                 },
                 InputSamples {
-                    samples: vec![(5, 5), (6, 6), (7, 7), (8, 8)].into(),
+                    samples: Arc::new(AudioSamples::Stereo(vec![
+                        (5.0, 5.0),
+                        (6.0, 6.0),
+                        (7.0, 7.0),
+                        (8.0, 8.0)
+                    ])),
                     start_pts: second_batch_start,
                     end_pts: second_batch_start + (4 * sample_duration)
                 },
             ],
             sample_rate
         ),
-        vec![(1, 1), (2, 2), (3, 3), (4, 4), (0, 0), (5, 5)]
+        AudioSamples::Stereo(vec![
+            (1.0, 1.0),
+            (2.0, 2.0),
+            (3.0, 3.0),
+            (4.0, 4.0),
+            (0.0, 0.0),
+            (5.0, 5.0)
+        ])
+    );
+}
+
+#[test]
+fn test_prepare_inputs_with_layout_change() {
+    // 6 samples at sample rate 48000
+    let batch_duration = Duration::from_micros(125);
+    let start = Duration::from_millis(20);
+    let end = start + batch_duration;
+    let sample_rate = 48000;
+    let sample_duration = Duration::from_secs_f64(1.0 / sample_rate as f64);
+
+    // Samples are converted to the layout of the first batch.
+    let second_batch_start = start + (3 * sample_duration);
+    assert_eq!(
+        frame_input_samples(
+            start,
+            end,
+            vec![
+                InputSamples {
+                    samples: Arc::new(AudioSamples::Surround51(vec![
+                        [1.0, 1.0, 0.0, 0.0, 0.0, 0.0];
+                        3
+                    ])),
+                    start_pts: start,
+                    end_pts: second_batch_start
+                },
+                InputSamples {
+                    samples: Arc::new(AudioSamples::Stereo(vec![
+                        (2.0, 3.0),
+                        (4.0, 5.0),
+                        (6.0, 7.0)
+                    ])),
+                    start_pts: second_batch_start,
+                    end_pts: second_batch_start + (3 * sample_duration)
+                },
+            ],
+            sample_rate
+        ),
+        AudioSamples::Surround51(vec![
+            [1.0, 1.0, 0.0, 0.0, 0.0, 0.0],
+            [1.0, 1.0, 0.0, 0.0, 0.0, 0.0],
+            [1.0, 1.0, 0.0, 0.0, 0.0, 0.0],
+            [2.0, 3.0, 0.0, 0.0, 0.0, 0.0],
+            [4.0, 5.0, 0.0, 0.0, 0.0, 0.0],
+            [6.0, 7.0, 0.0, 0.0, 0.0, 0.0],
+        ])
     );
 }
@@ -1,16 +1,37 @@
 use std::{collections::HashMap, fmt::Debug, sync::Arc, time::Duration};
 
-use compositor_render::{InputId, OutputId};
+use compositor_render::{scene::Transition, InputId, OutputId};
 
 #[derive(Debug, Clone)]
 pub struct AudioMixingParams {
     pub inputs: Vec<InputParams>,
+    /// Interpolates volume of the inputs from the values before the update.
+    pub transition: Option<Transition>,
+    pub ducking: Vec<DuckingRule>,
+}
+
+/// Lowers the volume of the ducked inputs while the level of the trigger input
+/// is above the threshold.
+#[derive(Debug, Clone)]
+pub struct DuckingRule {
+    pub trigger_input_id: InputId,
+    pub ducked_input_ids: Vec<InputId>,
+    pub threshold_db: f32,
+    /// Gain reduction in dB, positive value.
+    pub amount_db: f32,
+    pub attack: Duration,
+    pub release: Duration,
 }
 
 #[derive(Debug, Clone)]
 pub enum MixingStrategy {
     SumClip,
     SumScale,
+    /// Normalizes loudness of the summed samples (ITU-R BS.1770) and limits their true-peak.
+    LoudnessNormalization {
+        target_lufs: f64,
+        true_peak_limit_dbtp: f64,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -18,12 +39,69 @@ pub struct InputParams {
     pub input_id: InputId,
     // [0, 1] range of input volume
     pub volume: f32,
+    /// [-1, 1] range, -1 is full left, 1 is full right
+    pub pan: f32,
+    pub channel_routing: ChannelRouting,
+    /// Effects applied to input samples before mixing, in order.
+    pub effects: Vec<AudioEffect>,
+}
+
+/// Selects which channels of an input are mixed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChannelRouting {
+    Stereo,
+    /// Only the left channel is mixed, as a mono source.
+    Left,
+    /// Only the right channel is mixed, as a mono source.
+    Right,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AudioEffect {
+    /// Second order Butterworth high-pass filter.
+    HighPassFilter {
+        cutoff_frequency: f32,
+    },
+    ParametricEq {
+        bands: Vec<EqBand>,
+    },
+    Compressor {
+        threshold_db: f32,
+        ratio: f32,
+        attack: Duration,
+        release: Duration,
+        makeup_gain_db: f32,
+    },
+    NoiseGate {
+        threshold_db: f32,
+        attack: Duration,
+        hold: Duration,
+        release: Duration,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EqBand {
+    pub kind: EqBandKind,
+    pub frequency: f32,
+    pub gain_db: f32,
+    pub q: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EqBandKind {
+    Peaking,
+    LowShelf,
+    HighShelf,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AudioChannels {
     Mono,
     Stereo,
+    /// 5.1 surround, samples are in the WAV channel order: front left, front right,
+    /// center, LFE, surround left, surround right.
+    Surround51,
 }
 
 #[derive(Debug, Clone)]
@@ -38,7 +116,7 @@ pub struct OutputSamplesSet(pub HashMap<OutputId, OutputSamples>);
 
 #[derive(Clone)]
 pub struct InputSamples {
-    pub samples: Arc<Vec<(i16, i16)>>,
+    pub samples: Arc<AudioSamples>,
     pub start_pts: Duration,
     pub end_pts: Duration,
 }
@@ -49,10 +127,23 @@ pub struct OutputSamples {
     pub start_pts: Duration,
 }
 
-#[derive(Clone)]
+/// Samples are in `[-1, 1]` range. Values outside of it can occur before the final mixing
+/// step and are clipped when converted to integers.
+#[derive(Clone, PartialEq)]
 pub enum AudioSamples {
-    Mono(Vec<i16>),
-    Stereo(Vec<(i16, i16)>),
+    Mono(Vec<f32>),
+    Stereo(Vec<(f32, f32)>),
+    Surround51(Vec<[f32; 6]>),
+}
+
+impl AudioChannels {
+    pub fn count(&self) -> usize {
+        match self {
+            AudioChannels::Mono => 1,
+            AudioChannels::Stereo => 2,
+            AudioChannels::Surround51 => 6,
+        }
+    }
 }
 
 impl InputSamplesSet {
@@ -62,11 +153,7 @@ impl InputSamplesSet {
 }
 
 impl InputSamples {
-    pub fn new(
-        samples: Arc<Vec<(i16, i16)>>,
-        start_pts: Duration,
-        output_sample_rate: u32,
-    ) -> Self {
+    pub fn new(samples: Arc<AudioSamples>, start_pts: Duration, output_sample_rate: u32) -> Self {
         let end_pts =
             start_pts + Duration::from_secs_f64(samples.len() as f64 / output_sample_rate as f64);
 
@@ -91,6 +178,7 @@ impl AudioSamples {
         match self {
             AudioSamples::Mono(samples) => samples.len(),
             AudioSamples::Stereo(samples) => samples.len(),
+            AudioSamples::Surround51(samples) => samples.len(),
         }
     }
 
@@ -117,15 +205,9 @@ impl Debug for AudioSamples {
             AudioSamples::Stereo(samples) => {
                 write!(f, "AudioSamples::Stereo(len={})", samples.len())
             }
-        }
-    }
-}
-
-impl From<AudioChannels> for opus::Channels {
-    fn from(value: AudioChannels) -> Self {
-        match value {
-            AudioChannels::Mono => opus::Channels::Mono,
-            AudioChannels::Stereo => opus::Channels::Stereo,
+            AudioSamples::Surround51(samples) => {
+                write!(f, "AudioSamples::Surround51(len={})", samples.len())
+            }
         }
     }
 }
@@ -0,0 +1,177 @@
+use std::time::Duration;
+
+use super::{
+    effects::{amplitude_to_db, Biquad, BiquadCoefficients},
+    types::AudioSamples,
+};
+
+/// Level of the signal is computed over frames of this duration.
+const FRAME_DURATION: Duration = Duration::from_millis(10);
+/// Speech band, frequencies outside of it are filtered out before computing the level.
+const SPEECH_BAND_HZ: (f64, f64) = (200.0, 4000.0);
+
+/// Frame is considered speech if it is louder than the noise floor by this amount.
+const START_THRESHOLD_DB: f64 = 12.0;
+/// While speaking, frames louder than the noise floor by this amount keep the speech active.
+const STOP_THRESHOLD_DB: f64 = 6.0;
+/// Frames quieter than this level are never considered speech.
+const MIN_SPEECH_LEVEL_DB: f64 = -55.0;
+/// Speech starts after this many consecutive frames above the start threshold.
+const START_FRAMES: u32 = 5;
+/// Speech stops after this many consecutive frames below the stop threshold.
+const STOP_FRAMES: u32 = 50;
+
+const INITIAL_NOISE_FLOOR_DB: f64 = -70.0;
+const MIN_NOISE_FLOOR_DB: f64 = -90.0;
+/// Noise floor follows quieter frames immediately and rises slowly (1 dB per second)
+/// otherwise, so it is not affected by speech.
+const NOISE_FLOOR_RISE_DB_PER_FRAME: f64 = 0.01;
+
+/// Energy based voice activity detector with hysteresis. Signal is band-pass filtered
+/// to the speech band and its level is compared with the tracked noise floor.
+#[derive(Debug)]
+pub(super) struct VoiceActivityDetector {
+    filters: [Biquad; 2],
+    frame_length: usize,
+    frame_energy: f64,
+    frame_samples: usize,
+    noise_floor_db: f64,
+    is_speaking: bool,
+    /// Consecutive frames above the start threshold (while not speaking)
+    /// or below the stop threshold (while speaking).
+    transition_frames: u32,
+}
+
+impl VoiceActivityDetector {
+    pub fn new(sample_rate: u32) -> Self {
+        let (low, high) = SPEECH_BAND_HZ;
+        Self {
+            filters: [
+                Biquad::new(BiquadCoefficients::high_pass(low, sample_rate)),
+                Biquad::new(BiquadCoefficients::low_pass(high, sample_rate)),
+            ],
+            frame_length: usize::max(
+                (FRAME_DURATION.as_secs_f64() * sample_rate as f64) as usize,
+                1,
+            ),
+            frame_energy: 0.0,
+            frame_samples: 0,
+            noise_floor_db: INITIAL_NOISE_FLOOR_DB,
+            is_speaking: false,
+            transition_frames: 0,
+        }
+    }
+
+    pub fn is_speaking(&self) -> bool {
+        self.is_speaking
+    }
+
+    /// Processes samples, returns `Some` with the new state if speech started or stopped.
+    pub fn process(&mut self, samples: &AudioSamples) -> Option<bool> {
+        let was_speaking = self.is_speaking;
+        for (l, r) in samples.stereo().iter() {
+            let sample = self
+                .filters
+                .iter_mut()
+                .fold((*l as f64, *r as f64), |sample, filter| {
+                    filter.process(sample)
+                });
+            self.frame_energy += (sample.0.powi(2) + sample.1.powi(2)) / 2.0;
+            self.frame_samples += 1;
+
+            if self.frame_samples == self.frame_length {
+                let level_db =
+                    amplitude_to_db(f64::sqrt(self.frame_energy / self.frame_length as f64));
+                self.process_frame(level_db);
+                self.frame_energy = 0.0;
+                self.frame_samples = 0;
+            }
+        }
+
+        match self.is_speaking != was_speaking {
+            true => Some(self.is_speaking),
+            false => None,
+        }
+    }
+
+    fn process_frame(&mut self, level_db: f64) {
+        let threshold_db = match self.is_speaking {
+            true => self.noise_floor_db + STOP_THRESHOLD_DB,
+            false => self.noise_floor_db + START_THRESHOLD_DB,
+        };
+        let is_speech = level_db > f64::max(threshold_db, MIN_SPEECH_LEVEL_DB);
+
+        // Frames that keep the current state reset the counter.
+        if is_speech == self.is_speaking {
+            self.transition_frames = 0;
+        } else {
+            self.transition_frames += 1;
+            let required_frames = match self.is_speaking {
+                true => STOP_FRAMES,
+                false => START_FRAMES,
+            };
+            if self.transition_frames >= required_frames {
+                self.is_speaking = is_speech;
+                self.transition_frames = 0;
+            }
+        }
+
+        self.noise_floor_db = match level_db < self.noise_floor_db {
+            true => f64::max(level_db, MIN_NOISE_FLOOR_DB),
+            false => self.noise_floor_db + NOISE_FLOOR_RISE_DB_PER_FRAME,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+
+    use super::*;
+
+    const SAMPLE_RATE: u32 = 48_000;
+
+    fn tone(amplitude: f32, duration: Duration) -> AudioSamples {
+        let count = (duration.as_secs_f64() * SAMPLE_RATE as f64) as usize;
+        AudioSamples::Mono(
+            (0..count)
+                .map(|i| amplitude * f32::sin(2.0 * PI * 440.0 * i as f32 / SAMPLE_RATE as f32))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn detects_speech_with_hysteresis() {
+        let mut vad = VoiceActivityDetector::new(SAMPLE_RATE);
+        let quiet = tone(0.0001, Duration::from_millis(100));
+
+        assert_eq!(vad.process(&quiet), None);
+        assert_eq!(vad.process(&tone(0.3, Duration::from_millis(30))), None);
+        assert_eq!(
+            vad.process(&tone(0.3, Duration::from_millis(100))),
+            Some(true)
+        );
+
+        // Short pause does not stop the speech.
+        assert_eq!(vad.process(&quiet), None);
+        assert!(vad.is_speaking());
+        assert_eq!(vad.process(&tone(0.3, Duration::from_millis(100))), None);
+
+        assert_eq!(
+            vad.process(&tone(0.0001, Duration::from_millis(600))),
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn low_frequency_noise_is_not_speech() {
+        let mut vad = VoiceActivityDetector::new(SAMPLE_RATE);
+        let hum = AudioSamples::Mono(
+            (0..SAMPLE_RATE)
+                .map(|i| 0.1 * f32::sin(2.0 * PI * 20.0 * i as f32 / SAMPLE_RATE as f32))
+                .collect(),
+        );
+        assert_eq!(vad.process(&hum), None);
+        assert!(!vad.is_speaking());
+    }
+}
@@ -0,0 +1,107 @@
+use std::time::Duration;
+
+use compositor_render::scene::Transition;
+
+/// Interpolates volume of the inputs from the values before an update to the new ones.
+#[derive(Debug)]
+pub(super) struct VolumeRamp {
+    /// Volume of each input entry before the update, in the same order as in the new
+    /// `AudioMixingParams::inputs`.
+    start_volumes: Vec<f32>,
+    /// PTS of the first sample mixed after the update.
+    start_pts: Option<Duration>,
+    transition: Transition,
+    /// Value of the easing function for the last mixed sample.
+    last_state: f64,
+    finished: bool,
+}
+
+impl VolumeRamp {
+    /// Inputs missing in `start_volumes` fade in from silence.
+    pub fn new(start_volumes: Vec<f32>, transition: Transition) -> Self {
+        Self {
+            start_volumes,
+            start_pts: None,
+            transition,
+            last_state: 0.0,
+            finished: false,
+        }
+    }
+
+    /// Returns the value of the easing function for each sample of the batch.
+    pub fn states(
+        &mut self,
+        batch_start_pts: Duration,
+        samples_count: usize,
+        sample_rate: u32,
+    ) -> Vec<f64> {
+        let start_pts = *self.start_pts.get_or_insert(batch_start_pts);
+        let offset = batch_start_pts.as_secs_f64() - start_pts.as_secs_f64();
+        let duration = self.transition.duration.as_secs_f64();
+
+        let progress = |index: usize| {
+            let elapsed = offset + index as f64 / sample_rate as f64;
+            match duration > 0.0 {
+                true => f64::clamp(elapsed / duration, 0.0, 1.0),
+                false => 1.0,
+            }
+        };
+        let states: Vec<f64> = (0..samples_count)
+            .map(|index| self.transition.interpolation_kind.ease(progress(index)))
+            .collect();
+
+        self.last_state = states.last().copied().unwrap_or(self.last_state);
+        self.finished = progress(samples_count) >= 1.0;
+        states
+    }
+
+    /// * `index` - index of the input entry in `AudioMixingParams::inputs`.
+    pub fn volume(&self, index: usize, target_volume: f32, state: f64) -> f64 {
+        let start_volume = self.start_volumes.get(index).copied().unwrap_or(0.0) as f64;
+        start_volume + (target_volume as f64 - start_volume) * state
+    }
+
+    /// Volume of the input at the last mixed sample.
+    pub fn current_volume(&self, index: usize, target_volume: f32) -> f32 {
+        self.volume(index, target_volume, self.last_state) as f32
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use compositor_render::scene::InterpolationKind;
+
+    use super::*;
+
+    fn assert_volume(volume: f64, expected: f64) {
+        assert!((volume - expected).abs() < 1e-9, "{volume} != {expected}");
+    }
+
+    #[test]
+    fn linear_ramp_across_batches() {
+        let mut ramp = VolumeRamp::new(
+            vec![1.0],
+            Transition {
+                duration: Duration::from_millis(20),
+                interpolation_kind: InterpolationKind::Linear,
+            },
+        );
+
+        // 10ms batches at 1kHz sample rate.
+        let states = ramp.states(Duration::from_secs(1), 10, 1000);
+        assert_volume(ramp.volume(0, 0.0, states[0]), 1.0);
+        assert_volume(ramp.volume(0, 0.0, states[5]), 0.75);
+        assert!(!ramp.is_finished());
+
+        let states = ramp.states(Duration::from_millis(1010), 10, 1000);
+        assert_volume(ramp.volume(0, 0.0, states[0]), 0.5);
+        assert!(ramp.is_finished());
+
+        // Inputs without a start volume fade in from silence.
+        assert_volume(ramp.volume(1, 1.0, states[0]), 0.5);
+    }
+}
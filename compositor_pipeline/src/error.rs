@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use compositor_render::{
     error::{
         InitRendererEngineError, RegisterError, RegisterRendererError, UnregisterRendererError,
@@ -6,7 +8,9 @@ use compositor_render::{
     InputId, OutputId,
 };
 
-use crate::pipeline::{decoder::AacDecoderError, VideoCodec};
+use crate::pipeline::{
+    decoder::AacDecoderError, encoder::fdk_aac::AacEncoderError, AudioCodec, VideoCodec,
+};
 
 #[derive(Debug, thiserror::Error)]
 pub enum RegisterInputError {
@@ -68,9 +72,30 @@ pub enum OutputInitError {
     #[error("An unsupported codec was requested: {0:?}.")]
     UnsupportedVideoCodec(VideoCodec),
 
+    #[error("An unsupported codec was requested: {0:?}.")]
+    UnsupportedAudioCodec(AudioCodec),
+
     #[error(transparent)]
     SocketError(#[from] std::io::Error),
 
+    #[error("Failed to register output. File {0:?} already exists.")]
+    Mp4FileAlreadyExists(PathBuf),
+
+    #[error(transparent)]
+    FfmpegMp4Error(ffmpeg_next::Error),
+
+    #[error("Failed to register output. HLS playlist {0:?} already exists.")]
+    HlsPlaylistAlreadyExists(PathBuf),
+
+    #[error("Failed to create HLS output directory {0:?}.")]
+    HlsDirectoryError(PathBuf, #[source] std::io::Error),
+
+    #[error(transparent)]
+    FfmpegHlsError(ffmpeg_next::Error),
+
+    #[error("Invalid RTMP URL \"{0}\". URL has to start with \"rtmp://\" or \"rtmps://\".")]
+    InvalidRtmpUrl(String),
+
     #[error("Failed to register output. Port: {0} is already used or not available.")]
     PortAlreadyInUse(u16),
 
@@ -88,10 +113,18 @@ pub enum EncoderInitError {
 
     #[error(transparent)]
     OpusError(#[from] opus::Error),
+
+    #[error("Failed to initialize the Opus multistream encoder (error code {0}).")]
+    OpusMultistreamError(i32),
+
+    #[error(transparent)]
+    AacError(#[from] AacEncoderError),
 }
 
 #[derive(Debug, thiserror::Error)]
 pub enum DecoderInitError {
+    #[error("Could not find an ffmpeg codec")]
+    NoCodec,
     #[error(transparent)]
     FfmpegError(#[from] ffmpeg_next::Error),
     #[error(transparent)]
@@ -111,6 +144,12 @@ pub enum InputInitError {
 
     #[error(transparent)]
     Mp4(#[from] crate::pipeline::input::mp4::Mp4Error),
+
+    #[error(transparent)]
+    Rtmp(#[from] crate::pipeline::input::rtmp::RtmpReceiverError),
+
+    #[error(transparent)]
+    Whip(#[from] crate::pipeline::input::whip::WhipReceiverError),
 }
 
 pub enum ErrorType {
@@ -1,4 +1,6 @@
-use compositor_render::{event_handler, InputId};
+use compositor_render::{event_handler, InputId, OutputId};
+
+use crate::audio_mixer::AudioLevels;
 
 pub(crate) enum Event {
     AudioInputStreamDelivered(InputId),
@@ -7,12 +9,27 @@ pub(crate) enum Event {
     VideoInputStreamPlaying(InputId),
     AudioInputStreamEos(InputId),
     VideoInputStreamEos(InputId),
+    InputSpeakingStarted(InputId),
+    InputSpeakingStopped(InputId),
+    InputAudioLevels(InputId, AudioLevels),
+    OutputAudioLevels(OutputId, AudioLevels),
 }
 
 fn input_event(kind: &str, input_id: InputId) -> event_handler::Event {
     event_handler::Event {
         kind: kind.to_string(),
-        properties: vec![("input_id".to_string(), input_id.to_string())],
+        properties: vec![("input_id".to_string(), input_id.to_string().into())],
+    }
+}
+
+fn audio_levels_event(id_property: &str, id: String, levels: AudioLevels) -> event_handler::Event {
+    event_handler::Event {
+        kind: "AUDIO_LEVELS".to_string(),
+        properties: vec![
+            (id_property.to_string(), id.into()),
+            ("peak_dbfs".to_string(), levels.peak_dbfs.into()),
+            ("rms_dbfs".to_string(), levels.rms_dbfs.into()),
+        ],
     }
 }
 
@@ -25,6 +42,14 @@ impl From<Event> for event_handler::Event {
             Event::VideoInputStreamPlaying(id) => input_event("VIDEO_INPUT_PLAYING", id),
             Event::AudioInputStreamEos(id) => input_event("AUDIO_INPUT_EOS", id),
             Event::VideoInputStreamEos(id) => input_event("VIDEO_INPUT_EOS", id),
+            Event::InputSpeakingStarted(id) => input_event("INPUT_SPEAKING_STARTED", id),
+            Event::InputSpeakingStopped(id) => input_event("INPUT_SPEAKING_STOPPED", id),
+            Event::InputAudioLevels(id, levels) => {
+                audio_levels_event("input_id", id.to_string(), levels)
+            }
+            Event::OutputAudioLevels(id, levels) => {
+                audio_levels_event("output_id", id.to_string(), levels)
+            }
         }
     }
 }
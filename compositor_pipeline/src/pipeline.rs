@@ -20,8 +20,8 @@ use crossbeam_channel::{bounded, Receiver};
 use tracing::{error, info, trace, warn};
 
 use crate::audio_mixer::AudioMixer;
+use crate::audio_mixer::AudioMixingParams;
 use crate::audio_mixer::MixingStrategy;
-use crate::audio_mixer::{AudioChannels, AudioMixingParams};
 use crate::error::{
     RegisterInputError, RegisterOutputError, UnregisterInputError, UnregisterOutputError,
 };
@@ -31,7 +31,7 @@ use crate::queue::PipelineEvent;
 use crate::queue::QueueAudioOutput;
 use crate::queue::{self, Queue, QueueOptions, QueueVideoOutput};
 
-use self::encoder::{AudioEncoderPreset, VideoEncoderOptions};
+use self::encoder::{AudioEncoderOptions, VideoEncoderOptions};
 use self::input::InputOptions;
 use self::output::OutputOptions;
 
@@ -43,6 +43,7 @@ mod pipeline_input;
 mod pipeline_output;
 pub mod rtp;
 mod structs;
+mod webrtc_utils;
 
 use self::pipeline_input::register_pipeline_input;
 use self::pipeline_input::PipelineInput;
@@ -70,9 +71,7 @@ pub struct OutputVideoOptions {
 pub struct OutputAudioOptions {
     pub initial: AudioMixingParams,
     pub mixing_strategy: MixingStrategy,
-    pub channels: AudioChannels,
-    pub forward_error_correction: bool,
-    pub encoder_preset: AudioEncoderPreset,
+    pub encoder_opts: AudioEncoderOptions,
     pub end_condition: PipelineOutputEndCondition,
 }
 
@@ -108,6 +107,8 @@ pub struct Options {
     pub force_gpu: bool,
     pub download_root: PathBuf,
     pub output_sample_rate: u32,
+    /// Interval of the `AUDIO_LEVELS` events, they are not emitted if not set.
+    pub audio_levels_interval: Option<Duration>,
     pub wgpu_features: WgpuFeatures,
 }
 
@@ -130,7 +131,7 @@ impl Pipeline {
             inputs: HashMap::new(),
             queue: Queue::new(opts.queue_options),
             renderer,
-            audio_mixer: AudioMixer::new(opts.output_sample_rate),
+            audio_mixer: AudioMixer::new(opts.output_sample_rate, opts.audio_levels_interval),
             is_started: false,
             download_dir,
             output_sample_rate: opts.output_sample_rate,
@@ -7,7 +7,9 @@ use tracing::{span, trace, Level};
 
 extern crate opus as lib_opus;
 use crate::{
-    audio_mixer::InputSamples, error::DecoderInitError, pipeline::structs::EncodedChunk,
+    audio_mixer::{AudioChannels, AudioSamples, InputSamples},
+    error::DecoderInitError,
+    pipeline::structs::EncodedChunk,
     queue::PipelineEvent,
 };
 
@@ -21,7 +23,7 @@ mod opus;
 mod resampler;
 
 struct DecodedSamples {
-    samples: Arc<Vec<(i16, i16)>>,
+    samples: Arc<AudioSamples>,
     start_pts: Duration,
     sample_rate: u32,
 }
@@ -29,7 +31,7 @@ struct DecodedSamples {
 impl fmt::Debug for DecodedSamples {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("audio::DecodedSamples")
-            .field("samples", &format!("len={}", self.samples.len()))
+            .field("samples", &self.samples)
             .field("start_pts", &self.start_pts)
             .field("sample_rate", &self.sample_rate)
             .finish()
@@ -49,6 +51,8 @@ trait AudioDecoderExt {
         -> Result<Vec<DecodedSamples>, DecodingError>;
 
     fn decoded_sample_rate(&self) -> u32;
+
+    fn decoded_channels(&self) -> AudioChannels;
 }
 
 pub fn start_audio_decoder_thread(
@@ -178,8 +182,11 @@ fn run_decoding<F>(
             };
             let init_res = AacDecoder::new(aac_decoder_opts, &first_chunk)
                 .map(|decoder| {
-                    let resampler =
-                        Resampler::new(decoder.decoded_sample_rate(), output_sample_rate)?;
+                    let resampler = Resampler::new(
+                        decoder.decoded_sample_rate(),
+                        output_sample_rate,
+                        decoder.decoded_channels(),
+                    )?;
                     Ok((decoder, resampler))
                 })
                 .and_then(|res| res);
@@ -235,6 +242,10 @@ fn init_opus_decoder(
     output_sample_rate: u32,
 ) -> Result<(OpusDecoder, Resampler), DecoderInitError> {
     let decoder = OpusDecoder::new(opus_decoder_opts, output_sample_rate)?;
-    let resampler = Resampler::new(decoder.decoded_sample_rate(), output_sample_rate)?;
+    let resampler = Resampler::new(
+        decoder.decoded_sample_rate(),
+        output_sample_rate,
+        decoder.decoded_channels(),
+    )?;
     Ok((decoder, resampler))
 }
@@ -3,6 +3,7 @@ use std::sync::Arc;
 use tracing::error;
 
 use crate::{
+    audio_mixer::{pcm, AudioChannels, AudioSamples},
     error::DecoderInitError,
     pipeline::{
         decoder::AacDecoderOptions,
@@ -30,6 +31,7 @@ pub enum AacDecoderError {
 pub(super) struct AacDecoder {
     instance: *mut fdk::AAC_DECODER_INSTANCE,
     sample_rate: u32,
+    channels: AudioChannels,
 }
 
 impl AacDecoder {
@@ -48,7 +50,7 @@ impl AacDecoder {
 
         let instance = unsafe { fdk::aacDecoder_Open(transport, 1) };
 
-        if let Some(config) = options.asc {
+        if let Some(config) = &options.asc {
             let result = unsafe {
                 fdk::aacDecoder_ConfigRaw(
                     instance,
@@ -62,24 +64,58 @@ impl AacDecoder {
             }
         }
 
-        let info = unsafe { *fdk::aacDecoder_GetStreamInfo(instance) };
-        let aac_sample_rate = info.aacSampleRate;
+        // Without the ASC, the stream info is not available before the first frame
+        // is decoded. ADTS headers carry the same information, so it is read from there.
+        let (aac_sample_rate, channel_config) =
+            match (&options.asc, transport == fdk::TRANSPORT_TYPE_TT_MP4_ADTS) {
+                (None, true) => adts_stream_info(&first_chunk.data),
+                _ => {
+                    let info = unsafe { *fdk::aacDecoder_GetStreamInfo(instance) };
+                    (info.aacSampleRate, info.channelConfig)
+                }
+            };
         let sample_rate = if aac_sample_rate > 0 {
             aac_sample_rate as u32
         } else {
             return Err(AacDecoderError::UnsupportedSampleRate(aac_sample_rate).into());
         };
-        if info.channelConfig != 1 && info.channelConfig != 2 {
-            return Err(AacDecoderError::UnsupportedChannelConfig.into());
-        }
+        let channels = decoded_channels(channel_config)?;
 
         Ok(AacDecoder {
             instance,
             sample_rate,
+            channels,
         })
     }
 }
 
+/// Maps the channel configuration to the layout of the decoded samples. The decoder
+/// outputs 5.1 audio in the WAV channel order.
+fn decoded_channels(channel_config: i32) -> Result<AudioChannels, AacDecoderError> {
+    match channel_config {
+        1 => Ok(AudioChannels::Mono),
+        2 => Ok(AudioChannels::Stereo),
+        6 => Ok(AudioChannels::Surround51),
+        _ => Err(AacDecoderError::UnsupportedChannelConfig),
+    }
+}
+
+/// Returns sample rate and channel configuration from the ADTS header.
+/// Returns sample rate 0 if the header is truncated or uses a reserved frequency index.
+fn adts_stream_info(data: &[u8]) -> (i32, i32) {
+    const SAMPLE_RATES: [i32; 13] = [
+        96000, 88200, 64000, 48000, 44100, 32000, 24000, 22050, 16000, 12000, 11025, 8000, 7350,
+    ];
+
+    if data.len() < 4 {
+        return (0, 0);
+    }
+    let frequency_index = ((data[2] >> 2) & 0x0F) as usize;
+    let channel_config = (((data[2] & 0x01) << 2) | (data[3] >> 6)) as i32;
+    let sample_rate = SAMPLE_RATES.get(frequency_index).copied().unwrap_or(0);
+    (sample_rate, channel_config)
+}
+
 impl Drop for AacDecoder {
     fn drop(&mut self) {
         unsafe {
@@ -117,10 +153,11 @@ impl AudioDecoderExt for AacDecoder {
             }
 
             let info = unsafe { *fdk::aacDecoder_GetStreamInfo(self.instance) };
+            let channels = decoded_channels(info.channelConfig)?;
 
             // The decoder should output `info.aacSamplesPerFrame` for each channel
             let mut decoded_samples: Vec<fdk::INT_PCM> =
-                vec![0; (info.aacSamplesPerFrame * info.channelConfig) as usize];
+                vec![0; info.aacSamplesPerFrame as usize * channels.count()];
 
             let result = unsafe {
                 fdk::aacDecoder_DecodeFrame(
@@ -140,16 +177,10 @@ impl AudioDecoderExt for AacDecoder {
                 return Err(AacDecoderError::FdkDecoderError(result).into());
             }
 
-            let samples = match info.channelConfig {
-                1 => Arc::new(decoded_samples.iter().map(|s| (*s, *s)).collect()),
-                2 => Arc::new(
-                    decoded_samples
-                        .chunks_exact(2)
-                        .map(|c| (c[0], c[1]))
-                        .collect(),
-                ),
-                _ => return Err(AacDecoderError::UnsupportedChannelConfig.into()),
-            };
+            // The fdk library is built with 16-bit output samples.
+            let decoded_samples: Vec<f32> =
+                decoded_samples.into_iter().map(pcm::i16_to_f32).collect();
+            let samples = Arc::new(AudioSamples::from_interleaved(channels, &decoded_samples));
 
             // Sample rate can change after decoding
             let info = unsafe { *fdk::aacDecoder_GetStreamInfo(self.instance) };
@@ -176,4 +207,8 @@ impl AudioDecoderExt for AacDecoder {
     fn decoded_sample_rate(&self) -> u32 {
         self.sample_rate
     }
+
+    fn decoded_channels(&self) -> AudioChannels {
+        self.channels
+    }
 }
@@ -1,6 +1,7 @@
 use std::sync::Arc;
 
 use crate::{
+    audio_mixer::{AudioChannels, AudioSamples},
     error::DecoderInitError,
     pipeline::{decoder::OpusDecoderOptions, structs::EncodedChunk},
 };
@@ -9,7 +10,7 @@ use super::{AudioDecoderExt, DecodedSamples, DecodingError};
 
 pub(super) struct OpusDecoder {
     decoder: opus::Decoder,
-    decoded_samples_buffer: [i16; 100_000],
+    decoded_samples_buffer: [f32; 100_000],
     forward_error_correction: bool,
     decoded_sample_rate: u32,
 }
@@ -29,7 +30,7 @@ impl OpusDecoder {
         // Max sample rate for opus is 48kHz.
         // Usually packets contain 20ms audio chunks, but for safety we use buffer
         // that can hold >1s of 48kHz stereo audio (96k samples)
-        let decoded_samples_buffer = [0i16; 100_000];
+        let decoded_samples_buffer = [0.0; 100_000];
 
         Ok(Self {
             decoder,
@@ -40,13 +41,11 @@ impl OpusDecoder {
     }
 
     /// Panics if buffer.len() < 2 * decoded_samples_count
-    fn read_buffer(buffer: &[i16], decoded_samples_count: usize) -> Arc<Vec<(i16, i16)>> {
-        Arc::new(
-            buffer[0..(2 * decoded_samples_count)]
-                .chunks_exact(2)
-                .map(|c| (c[0], c[1]))
-                .collect(),
-        )
+    fn read_buffer(buffer: &[f32], decoded_samples_count: usize) -> Arc<AudioSamples> {
+        Arc::new(AudioSamples::from_interleaved(
+            AudioChannels::Stereo,
+            &buffer[0..(2 * decoded_samples_count)],
+        ))
     }
 }
 
@@ -55,7 +54,7 @@ impl AudioDecoderExt for OpusDecoder {
         &mut self,
         encoded_chunk: EncodedChunk,
     ) -> Result<Vec<DecodedSamples>, DecodingError> {
-        let decoded_samples_count = self.decoder.decode(
+        let decoded_samples_count = self.decoder.decode_float(
             &encoded_chunk.data,
             &mut self.decoded_samples_buffer,
             self.forward_error_correction,
@@ -73,4 +72,8 @@ impl AudioDecoderExt for OpusDecoder {
     fn decoded_sample_rate(&self) -> u32 {
         self.decoded_sample_rate
     }
+
+    fn decoded_channels(&self) -> AudioChannels {
+        AudioChannels::Stereo
+    }
 }
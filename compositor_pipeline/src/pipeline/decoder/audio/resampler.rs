@@ -5,7 +5,10 @@ use log::{debug, error};
 use rubato::{FftFixedOut, Resampler as _};
 use tracing::trace;
 
-use crate::{audio_mixer::InputSamples, error::DecoderInitError};
+use crate::{
+    audio_mixer::{AudioChannels, AudioSamples, InputSamples},
+    error::DecoderInitError,
+};
 
 use super::DecodedSamples;
 
@@ -17,14 +20,18 @@ pub(super) enum Resampler {
 }
 
 impl Resampler {
-    pub fn new(input_sample_rate: u32, output_sample_rate: u32) -> Result<Self, DecoderInitError> {
+    pub fn new(
+        input_sample_rate: u32,
+        output_sample_rate: u32,
+        channels: AudioChannels,
+    ) -> Result<Self, DecoderInitError> {
         if input_sample_rate == output_sample_rate {
             Ok(Self::Passthrough(PassthroughResampler::new(
                 input_sample_rate,
                 output_sample_rate,
             )))
         } else {
-            FftResampler::new(input_sample_rate, output_sample_rate)
+            FftResampler::new(input_sample_rate, output_sample_rate, channels)
                 .map(Box::new)
                 .map(Self::Fft)
         }
@@ -70,9 +77,12 @@ impl PassthroughResampler {
 pub(super) struct FftResampler {
     input_sample_rate: u32,
     output_sample_rate: u32,
-    input_buffer: [Vec<f64>; 2],
-    output_buffer: [Vec<f64>; 2],
-    resampler: FftFixedOut<f64>,
+    /// Layout of the resampled samples. Input samples with a different layout are converted.
+    channels: AudioChannels,
+    /// Samples of each channel.
+    input_buffer: Vec<Vec<f32>>,
+    output_buffer: Vec<Vec<f32>>,
+    resampler: FftFixedOut<f32>,
     first_batch_pts: Option<Duration>,
     resampler_input_samples: u64,
     resampler_output_samples: u64,
@@ -82,35 +92,35 @@ impl FftResampler {
     fn new(
         input_sample_rate: u32,
         output_sample_rate: u32,
+        channels: AudioChannels,
     ) -> Result<FftResampler, DecoderInitError> {
-        /// This part of pipeline use stereo
-        const CHANNELS: usize = 2;
         /// Not sure what should be here, but rubato example used 2
         /// https://github.com/HEnquist/rubato/blob/master/examples/process_f64.rs#L174
         const SUB_CHUNKS: usize = 2;
         let output_batch_size =
             (output_sample_rate as f64 * SAMPLE_BATCH_DURATION.as_secs_f64()).round() as usize;
 
-        let resampler = rubato::FftFixedOut::<f64>::new(
+        let resampler = rubato::FftFixedOut::<f32>::new(
             input_sample_rate as usize,
             output_sample_rate as usize,
             output_batch_size,
             SUB_CHUNKS,
-            CHANNELS,
+            channels.count(),
         )?;
 
         // Input buffer is preallocated, to push input samples and fill missing samples between them.
         // Reallocation happens per every output batch, due to drain from the begging,
         // but this shouldn't have a noticeable performance impact and reduce code complexity.
         // This could be done without allocations, but it would complicate this code substantially.
-        let input_buffer = [Vec::new(), Vec::new()];
+        let input_buffer = vec![Vec::new(); channels.count()];
 
         // Output buffer is preallocated to avoid allocating it on every output batch.
-        let output_buffer = [vec![0.0; output_batch_size], vec![0.0; output_batch_size]];
+        let output_buffer = vec![vec![0.0; output_batch_size]; channels.count()];
 
         Ok(Self {
             input_sample_rate,
             output_sample_rate,
+            channels,
             input_buffer,
             output_buffer,
             resampler,
@@ -173,31 +183,33 @@ impl FftResampler {
         if expected_samples > actual_samples + SAMPLES_COMPARE_ERROR_MARGIN {
             let filling_samples = expected_samples - actual_samples;
             debug!("Filling {} missing samples in resampler", filling_samples);
-            for _ in 0..filling_samples {
-                self.input_buffer[0].push(0.0);
-                self.input_buffer[1].push(0.0);
+            for channel in self.input_buffer.iter_mut() {
+                channel.extend((0..filling_samples).map(|_| 0.0));
             }
         }
 
-        for (l, r) in decoded_samples.samples.iter().cloned() {
-            self.input_buffer[0].push(pcm_i16_to_f64(l));
-            self.input_buffer[1].push(pcm_i16_to_f64(r));
+        let samples = decoded_samples
+            .samples
+            .to_channels(self.channels)
+            .interleaved();
+        for frame in samples.chunks_exact(self.channels.count()) {
+            for (channel, sample) in self.input_buffer.iter_mut().zip(frame) {
+                channel.push(*sample);
+            }
         }
     }
 
-    fn read_output_buffer(&mut self, output_samples: usize) -> Vec<(i16, i16)> {
-        let left_channel_iter = self.output_buffer[0][0..output_samples].iter().cloned();
-        let right_channel_iter = self.output_buffer[1][0..output_samples].iter().cloned();
-
-        left_channel_iter
-            .zip(right_channel_iter)
-            .map(|(l, r)| (pcm_f64_to_i16(l), pcm_f64_to_i16(r)))
-            .collect()
+    fn read_output_buffer(&mut self, output_samples: usize) -> AudioSamples {
+        let interleaved: Vec<f32> = (0..output_samples)
+            .flat_map(|index| self.output_buffer.iter().map(move |channel| channel[index]))
+            .collect();
+        AudioSamples::from_interleaved(self.channels, &interleaved)
     }
 
     fn drop_input_samples(&mut self, used_samples: usize) {
-        self.input_buffer[0].drain(0..used_samples);
-        self.input_buffer[1].drain(0..used_samples);
+        for channel in self.input_buffer.iter_mut() {
+            channel.drain(0..used_samples);
+        }
     }
 
     fn output_batch_pts(&mut self) -> Duration {
@@ -207,14 +219,3 @@ impl FftResampler {
         self.first_batch_pts.unwrap() + send_audio_duration
     }
 }
-
-fn pcm_i16_to_f64(val: i16) -> f64 {
-    val as f64 / i16::MAX as f64
-}
-
-fn pcm_f64_to_i16(val: f64) -> i16 {
-    let mapped_to_i16_range = val * i16::MAX as f64;
-    mapped_to_i16_range
-        .min(i16::MAX as f64)
-        .max(i16::MIN as f64) as i16
-}
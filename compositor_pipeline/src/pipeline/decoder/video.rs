@@ -9,7 +9,11 @@ use crate::{
 
 use super::VideoDecoderOptions;
 
+mod ffmpeg_av1;
 mod ffmpeg_h264;
+mod ffmpeg_h265;
+mod ffmpeg_utils;
+mod ffmpeg_vpx;
 
 pub fn start_video_decoder_thread(
     options: &VideoDecoderOptions,
@@ -21,5 +25,17 @@ pub fn start_video_decoder_thread(
         VideoCodec::H264 => {
             ffmpeg_h264::start_ffmpeg_decoder_thread(chunks_receiver, frame_sender, input_id)
         }
+        VideoCodec::H265 => {
+            ffmpeg_h265::start_ffmpeg_h265_decoder_thread(chunks_receiver, frame_sender, input_id)
+        }
+        VideoCodec::Vp8 | VideoCodec::Vp9 => ffmpeg_vpx::start_libvpx_decoder_thread(
+            options.codec,
+            chunks_receiver,
+            frame_sender,
+            input_id,
+        ),
+        VideoCodec::Av1 => {
+            ffmpeg_av1::start_dav1d_decoder_thread(chunks_receiver, frame_sender, input_id)
+        }
     }
 }
@@ -0,0 +1,28 @@
+use compositor_render::{Frame, InputId};
+use crossbeam_channel::{Receiver, Sender};
+use ffmpeg_next::codec::Id;
+
+use crate::{
+    error::DecoderInitError,
+    pipeline::structs::{EncodedChunk, VideoCodec},
+    queue::PipelineEvent,
+};
+
+use super::ffmpeg_utils::spawn_decoder_thread;
+
+/// Starts a decoder thread for AV1 video. Decoding is done by dav1d through ffmpeg.
+/// Chunks have to contain whole temporal units in the low overhead bitstream format.
+pub fn start_dav1d_decoder_thread(
+    chunks_receiver: Receiver<PipelineEvent<EncodedChunk>>,
+    frame_sender: Sender<PipelineEvent<Frame>>,
+    input_id: InputId,
+) -> Result<(), DecoderInitError> {
+    spawn_decoder_thread(
+        VideoCodec::Av1,
+        Id::AV1,
+        "libdav1d",
+        chunks_receiver,
+        frame_sender,
+        input_id,
+    )
+}
@@ -164,14 +164,14 @@ fn chunk_to_av(chunk: EncodedChunk) -> Result<ffmpeg_next::Packet, DecoderChunkC
 }
 
 #[derive(Debug, thiserror::Error)]
-enum DecoderFrameConversionError {
+pub(super) enum DecoderFrameConversionError {
     #[error("Error converting frame: {0}")]
     FrameConversionError(String),
     #[error("Unsupported pixel format: {0:?}")]
     UnsupportedPixelFormat(ffmpeg_next::format::pixel::Pixel),
 }
 
-fn frame_from_av(
+pub(super) fn frame_from_av(
     decoded: &mut Video,
     pts_offset: &mut Option<i64>,
 ) -> Result<Frame, DecoderFrameConversionError> {
@@ -0,0 +1,28 @@
+use compositor_render::{Frame, InputId};
+use crossbeam_channel::{Receiver, Sender};
+use ffmpeg_next::codec::Id;
+
+use crate::{
+    error::DecoderInitError,
+    pipeline::structs::{EncodedChunk, VideoCodec},
+    queue::PipelineEvent,
+};
+
+use super::ffmpeg_utils::spawn_decoder_thread;
+
+/// Starts a decoder thread for H265 video. Chunks have to contain whole access units
+/// in the Annex B format. Only 8 bit streams (e.g. the Main profile) are supported.
+pub fn start_ffmpeg_h265_decoder_thread(
+    chunks_receiver: Receiver<PipelineEvent<EncodedChunk>>,
+    frame_sender: Sender<PipelineEvent<Frame>>,
+    input_id: InputId,
+) -> Result<(), DecoderInitError> {
+    spawn_decoder_thread(
+        VideoCodec::H265,
+        Id::HEVC,
+        "hevc",
+        chunks_receiver,
+        frame_sender,
+        input_id,
+    )
+}
@@ -0,0 +1,145 @@
+use crate::{
+    error::DecoderInitError,
+    pipeline::structs::{EncodedChunk, EncodedChunkKind, VideoCodec},
+    queue::PipelineEvent,
+};
+
+use compositor_render::{Frame, InputId};
+use crossbeam_channel::{Receiver, Sender};
+use ffmpeg_next::{
+    codec::{Context, Id},
+    media::Type,
+    Rational,
+};
+use tracing::{debug, error, span, trace, warn, Level};
+
+use super::ffmpeg_h264::frame_from_av;
+
+/// Starts a thread that decodes chunks with ffmpeg decoder of the provided name.
+pub(super) fn spawn_decoder_thread(
+    codec: VideoCodec,
+    codec_id: Id,
+    decoder_name: &'static str,
+    chunks_receiver: Receiver<PipelineEvent<EncodedChunk>>,
+    frame_sender: Sender<PipelineEvent<Frame>>,
+    input_id: InputId,
+) -> Result<(), DecoderInitError> {
+    let decoder_codec =
+        ffmpeg_next::codec::decoder::find_by_name(decoder_name).ok_or(DecoderInitError::NoCodec)?;
+
+    let (init_result_sender, init_result_receiver) = crossbeam_channel::bounded(0);
+
+    let mut parameters = ffmpeg_next::codec::Parameters::new();
+    unsafe {
+        let parameters = &mut *parameters.as_mut_ptr();
+
+        parameters.codec_type = Type::Video.into();
+        parameters.codec_id = codec_id.into();
+    };
+
+    std::thread::Builder::new()
+        .name(format!("{decoder_name} decoder {}", input_id.0))
+        .spawn(move || {
+            let _span = span!(
+                Level::INFO,
+                "ffmpeg decoder",
+                decoder = decoder_name,
+                codec = ?codec,
+                input_id = input_id.to_string()
+            )
+            .entered();
+
+            let decoder = Context::from_parameters(parameters)
+                .and_then(|mut decoder| {
+                    unsafe {
+                        // This is because we use microseconds as pts and dts in the packets.
+                        // See `chunk_to_av` and `frame_from_av`.
+                        (*decoder.as_mut_ptr()).pkt_timebase = Rational::new(1, 1_000_000).into();
+                    }
+                    decoder.decoder().open_as(decoder_codec)
+                })
+                .map_err(DecoderInitError::FfmpegError);
+
+            let decoder = match decoder {
+                Ok(decoder) => {
+                    init_result_sender.send(Ok(())).unwrap();
+                    decoder
+                }
+                Err(err) => {
+                    init_result_sender.send(Err(err)).unwrap();
+                    return;
+                }
+            };
+            run_decoder_thread(decoder, codec, chunks_receiver, frame_sender)
+        })
+        .unwrap();
+
+    init_result_receiver.recv().unwrap()?;
+
+    Ok(())
+}
+
+fn run_decoder_thread(
+    mut decoder: ffmpeg_next::decoder::Opened,
+    codec: VideoCodec,
+    chunks_receiver: Receiver<PipelineEvent<EncodedChunk>>,
+    frame_sender: Sender<PipelineEvent<Frame>>,
+) {
+    let mut decoded_frame = ffmpeg_next::frame::Video::empty();
+    let mut pts_offset = None;
+    for chunk in chunks_receiver {
+        let chunk = match chunk {
+            PipelineEvent::Data(chunk) => chunk,
+            PipelineEvent::EOS => {
+                break;
+            }
+        };
+        if chunk.kind != EncodedChunkKind::Video(codec) {
+            error!(
+                "{:?} decoder received chunk of wrong kind: {:?}",
+                codec, chunk.kind
+            );
+            continue;
+        }
+
+        if let Err(err) = decoder.send_packet(&chunk_to_av(chunk)) {
+            warn!("Failed to send a packet to decoder: {}", err);
+            continue;
+        }
+
+        while decoder.receive_frame(&mut decoded_frame).is_ok() {
+            let frame = match frame_from_av(&mut decoded_frame, &mut pts_offset) {
+                Ok(frame) => frame,
+                Err(err) => {
+                    warn!("Dropping frame: {}", err);
+                    continue;
+                }
+            };
+
+            trace!(pts=?frame.pts, "{:?} decoder produced a frame.", codec);
+            if frame_sender.send(PipelineEvent::Data(frame)).is_err() {
+                debug!(
+                    "Failed to send frame from {:?} decoder. Channel closed.",
+                    codec
+                );
+                return;
+            }
+        }
+    }
+    if frame_sender.send(PipelineEvent::EOS).is_err() {
+        debug!(
+            "Failed to send EOS from {:?} decoder. Channel closed.",
+            codec
+        )
+    }
+}
+
+fn chunk_to_av(chunk: EncodedChunk) -> ffmpeg_next::Packet {
+    let mut packet = ffmpeg_next::Packet::new(chunk.data.len());
+
+    packet.data_mut().unwrap().copy_from_slice(&chunk.data);
+    packet.set_pts(Some(chunk.pts.as_micros() as i64));
+    packet.set_dts(chunk.dts.map(|dts| dts.as_micros() as i64));
+
+    packet
+}
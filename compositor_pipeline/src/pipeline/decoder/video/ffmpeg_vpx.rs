@@ -0,0 +1,36 @@
+use crate::{
+    error::DecoderInitError,
+    pipeline::structs::{EncodedChunk, VideoCodec},
+    queue::PipelineEvent,
+};
+
+use compositor_render::{Frame, InputId};
+use crossbeam_channel::{Receiver, Sender};
+use ffmpeg_next::codec::Id;
+
+use super::ffmpeg_utils::spawn_decoder_thread;
+
+/// Starts a decoder thread for VP8 or VP9 video. Decoding is done by libvpx
+/// through ffmpeg.
+pub fn start_libvpx_decoder_thread(
+    codec: VideoCodec,
+    chunks_receiver: Receiver<PipelineEvent<EncodedChunk>>,
+    frame_sender: Sender<PipelineEvent<Frame>>,
+    input_id: InputId,
+) -> Result<(), DecoderInitError> {
+    let (codec_id, decoder_name) = match codec {
+        VideoCodec::Vp8 => (Id::VP8, "libvpx"),
+        VideoCodec::Vp9 => (Id::VP9, "libvpx-vp9"),
+        VideoCodec::H264 | VideoCodec::H265 | VideoCodec::Av1 => {
+            return Err(DecoderInitError::NoCodec)
+        }
+    };
+    spawn_decoder_thread(
+        codec,
+        codec_id,
+        decoder_name,
+        chunks_receiver,
+        frame_sender,
+        input_id,
+    )
+}
@@ -1,14 +1,26 @@
+use bytes::Bytes;
 use compositor_render::{Frame, OutputId, Resolution};
 use crossbeam_channel::{bounded, Receiver, Sender};
 use log::error;
 
-use crate::{audio_mixer::OutputSamples, error::EncoderInitError, queue::PipelineEvent};
+use crate::{
+    audio_mixer::{AudioChannels, OutputSamples},
+    error::EncoderInitError,
+    queue::PipelineEvent,
+};
 
-use self::{ffmpeg_h264::LibavH264Encoder, opus::OpusEncoder};
+use self::{
+    fdk_aac::AacEncoder, ffmpeg_av1::LibaomAv1Encoder, ffmpeg_h264::LibavH264Encoder,
+    ffmpeg_vpx::LibvpxEncoder, opus::OpusEncoder,
+};
 
-use super::structs::EncoderOutputEvent;
+use super::structs::{EncoderOutputEvent, VideoCodec};
 
+pub mod fdk_aac;
+pub mod ffmpeg_av1;
 pub mod ffmpeg_h264;
+mod ffmpeg_utils;
+pub mod ffmpeg_vpx;
 pub mod opus;
 
 pub struct EncoderOptions {
@@ -19,11 +31,15 @@ pub struct EncoderOptions {
 #[derive(Debug, Clone)]
 pub enum VideoEncoderOptions {
     H264(ffmpeg_h264::Options),
+    Vp8(ffmpeg_vpx::Options),
+    Vp9(ffmpeg_vpx::Options),
+    Av1(ffmpeg_av1::Options),
 }
 
 #[derive(Debug, Clone)]
 pub enum AudioEncoderOptions {
     Opus(opus::Options),
+    Aac(fdk_aac::Options),
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -40,10 +56,32 @@ pub struct Encoder {
 
 pub enum VideoEncoder {
     H264(LibavH264Encoder),
+    Vpx(LibvpxEncoder),
+    Av1(LibaomAv1Encoder),
 }
 
 pub enum AudioEncoder {
     Opus(OpusEncoder),
+    Aac(AacEncoder),
+}
+
+/// Information about encoded streams that outputs need before the first chunk is produced.
+#[derive(Debug, Clone)]
+pub struct EncoderContext {
+    pub audio: Option<AudioEncoderContext>,
+    /// Forces the video encoder to produce a keyframe as soon as possible, e.g. when
+    /// a receiver reports picture loss. `None` if the encoder does not support it.
+    pub keyframe_request_sender: Option<Sender<()>>,
+}
+
+#[derive(Debug, Clone)]
+pub enum AudioEncoderContext {
+    Opus,
+    Aac {
+        /// AudioSpecificConfig as described in MPEG-4 part 3, section 1.6.2.1
+        asc: Bytes,
+        sample_rate: u32,
+    },
 }
 
 impl Encoder {
@@ -84,6 +122,8 @@ impl Encoder {
     pub fn frame_sender(&self) -> Option<&Sender<PipelineEvent<Frame>>> {
         match &self.video {
             Some(VideoEncoder::H264(encoder)) => Some(encoder.frame_sender()),
+            Some(VideoEncoder::Vpx(encoder)) => Some(encoder.frame_sender()),
+            Some(VideoEncoder::Av1(encoder)) => Some(encoder.frame_sender()),
             None => {
                 error!("Non video encoder received frame to send.");
                 None
@@ -94,18 +134,31 @@ impl Encoder {
     pub fn samples_batch_sender(&self) -> Option<&Sender<PipelineEvent<OutputSamples>>> {
         match &self.audio {
             Some(AudioEncoder::Opus(encoder)) => Some(encoder.samples_batch_sender()),
+            Some(AudioEncoder::Aac(encoder)) => Some(encoder.samples_batch_sender()),
             None => {
                 error!("Non audio encoder received samples to send.");
                 None
             }
         }
     }
+
+    pub fn context(&self) -> EncoderContext {
+        EncoderContext {
+            audio: self.audio.as_ref().map(AudioEncoder::context),
+            keyframe_request_sender: self
+                .video
+                .as_ref()
+                .and_then(VideoEncoder::keyframe_request_sender),
+        }
+    }
 }
 
 impl VideoEncoderOptions {
     pub fn resolution(&self) -> Resolution {
         match self {
             VideoEncoderOptions::H264(opt) => opt.resolution,
+            VideoEncoderOptions::Vp8(opt) | VideoEncoderOptions::Vp9(opt) => opt.resolution,
+            VideoEncoderOptions::Av1(opt) => opt.resolution,
         }
     }
 }
@@ -120,12 +173,44 @@ impl VideoEncoder {
             VideoEncoderOptions::H264(options) => Ok(Self::H264(LibavH264Encoder::new(
                 output_id, options, sender,
             )?)),
+            VideoEncoderOptions::Vp8(options) => Ok(Self::Vpx(LibvpxEncoder::new(
+                output_id,
+                VideoCodec::Vp8,
+                options,
+                sender,
+            )?)),
+            VideoEncoderOptions::Vp9(options) => Ok(Self::Vpx(LibvpxEncoder::new(
+                output_id,
+                VideoCodec::Vp9,
+                options,
+                sender,
+            )?)),
+            VideoEncoderOptions::Av1(options) => Ok(Self::Av1(LibaomAv1Encoder::new(
+                output_id, options, sender,
+            )?)),
         }
     }
 
     pub fn resolution(&self) -> Resolution {
         match self {
             Self::H264(encoder) => encoder.resolution(),
+            Self::Vpx(encoder) => encoder.resolution(),
+            Self::Av1(encoder) => encoder.resolution(),
+        }
+    }
+    fn keyframe_request_sender(&self) -> Option<Sender<()>> {
+        match self {
+            Self::H264(encoder) => Some(encoder.keyframe_request_sender()),
+            Self::Vpx(_) | Self::Av1(_) => None,
+        }
+    }
+}
+
+impl AudioEncoderOptions {
+    pub fn channels(&self) -> AudioChannels {
+        match self {
+            AudioEncoderOptions::Opus(opt) => opt.channels,
+            AudioEncoderOptions::Aac(opt) => opt.channels,
         }
     }
 }
@@ -140,6 +225,19 @@ impl AudioEncoder {
             AudioEncoderOptions::Opus(opus_encoder_options) => {
                 OpusEncoder::new(opus_encoder_options, sample_rate, sender).map(AudioEncoder::Opus)
             }
+            AudioEncoderOptions::Aac(aac_encoder_options) => {
+                AacEncoder::new(aac_encoder_options, sample_rate, sender).map(AudioEncoder::Aac)
+            }
+        }
+    }
+
+    fn context(&self) -> AudioEncoderContext {
+        match self {
+            AudioEncoder::Opus(_) => AudioEncoderContext::Opus,
+            AudioEncoder::Aac(encoder) => AudioEncoderContext::Aac {
+                asc: encoder.audio_specific_config(),
+                sample_rate: encoder.sample_rate(),
+            },
         }
     }
 }
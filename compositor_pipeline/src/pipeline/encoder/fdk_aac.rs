@@ -0,0 +1,295 @@
+use std::{os::raw::c_void, ptr, time::Duration};
+
+use bytes::Bytes;
+use crossbeam_channel::{bounded, Receiver, Sender};
+use fdk_aac_sys as fdk;
+use log::error;
+use tracing::{span, trace, warn, Level};
+
+use crate::{
+    audio_mixer::{pcm::Dither, AudioChannels, OutputSamples},
+    error::EncoderInitError,
+    pipeline::{
+        structs::{EncodedChunk, EncodedChunkKind, EncoderOutputEvent, IsKeyframe},
+        AudioCodec,
+    },
+    queue::PipelineEvent,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum AacEncoderError {
+    #[error("The internal fdk encoder returned an error: {0:?}.")]
+    FdkEncoderError(fdk::AACENC_ERROR),
+}
+
+#[derive(Debug, Clone)]
+pub struct Options {
+    pub channels: AudioChannels,
+    /// Apply TPDF dither when mixed samples are converted to 16-bit integers.
+    pub dither: bool,
+}
+
+pub struct AacEncoder {
+    samples_batch_sender: Sender<PipelineEvent<OutputSamples>>,
+    asc: Bytes,
+    sample_rate: u32,
+}
+
+impl AacEncoder {
+    pub fn new(
+        options: Options,
+        sample_rate: u32,
+        packets_sender: Sender<EncoderOutputEvent>,
+    ) -> Result<Self, EncoderInitError> {
+        let (samples_batch_sender, samples_batch_receiver) = bounded(2);
+
+        let encoder = FdkAacEncoder::new(options, sample_rate)?;
+        let asc = encoder.asc.clone();
+
+        std::thread::Builder::new()
+            .name("AAC encoder thread".to_string())
+            .spawn(move || {
+                let _span = span!(Level::INFO, "AAC encoder thread",).entered();
+                run_encoder_thread(encoder, sample_rate, samples_batch_receiver, packets_sender)
+            })
+            .unwrap();
+
+        Ok(Self {
+            samples_batch_sender,
+            asc,
+            sample_rate,
+        })
+    }
+
+    pub fn samples_batch_sender(&self) -> &Sender<PipelineEvent<OutputSamples>> {
+        &self.samples_batch_sender
+    }
+
+    /// AudioSpecificConfig (MPEG-4 part 3, 1.6.2.1) of the produced stream. Encoded chunks
+    /// are raw AAC frames, so muxers and receivers need it to decode them.
+    pub fn audio_specific_config(&self) -> Bytes {
+        self.asc.clone()
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+}
+
+struct FdkAacEncoder {
+    instance: fdk::HANDLE_AACENCODER,
+    asc: Bytes,
+    frame_length: u32,
+    output_buffer: Vec<u8>,
+    dither: Option<Dither>,
+}
+
+// Encoder instance is created on the pipeline thread, but after that it is only
+// used from the encoder thread.
+unsafe impl Send for FdkAacEncoder {}
+
+impl FdkAacEncoder {
+    fn new(options: Options, sample_rate: u32) -> Result<Self, AacEncoderError> {
+        let (channel_count, channel_mode) = match options.channels {
+            AudioChannels::Mono => (1, fdk::CHANNEL_MODE_MODE_1),
+            AudioChannels::Stereo => (2, fdk::CHANNEL_MODE_MODE_2),
+            AudioChannels::Surround51 => (6, fdk::CHANNEL_MODE_MODE_1_2_2_1),
+        };
+
+        let mut instance = ptr::null_mut();
+        check(unsafe { fdk::aacEncOpen(&mut instance, 0, channel_count) })?;
+        // Encoder is closed on drop if the initialization fails.
+        let mut encoder = Self {
+            instance,
+            asc: Bytes::new(),
+            frame_length: 0,
+            output_buffer: Vec::new(),
+            dither: options.dither.then(Dither::new),
+        };
+
+        let params = [
+            (
+                fdk::AACENC_PARAM_AACENC_AOT,
+                fdk::AUDIO_OBJECT_TYPE_AOT_AAC_LC as u32,
+            ),
+            (fdk::AACENC_PARAM_AACENC_SAMPLERATE, sample_rate),
+            (fdk::AACENC_PARAM_AACENC_CHANNELMODE, channel_mode as u32),
+            // Input samples are in the WAV channel order.
+            (fdk::AACENC_PARAM_AACENC_CHANNELORDER, 1),
+            (
+                fdk::AACENC_PARAM_AACENC_TRANSMUX,
+                fdk::TRANSPORT_TYPE_TT_MP4_RAW as u32,
+            ),
+            (fdk::AACENC_PARAM_AACENC_AFTERBURNER, 1),
+        ];
+        for (param, value) in params {
+            check(unsafe { fdk::aacEncoder_SetParam(encoder.instance, param, value) })?;
+        }
+
+        // Calling encode without any buffers initializes the encoder with the parameters set above.
+        check(unsafe {
+            fdk::aacEncEncode(
+                encoder.instance,
+                ptr::null(),
+                ptr::null(),
+                ptr::null(),
+                ptr::null_mut(),
+            )
+        })?;
+
+        let mut info: fdk::AACENC_InfoStruct = unsafe { std::mem::zeroed() };
+        check(unsafe { fdk::aacEncInfo(encoder.instance, &mut info) })?;
+
+        encoder.asc = Bytes::copy_from_slice(&info.confBuf[..info.confSize as usize]);
+        encoder.frame_length = info.frameLength;
+        encoder.output_buffer = vec![0; info.maxOutBufBytes as usize];
+        Ok(encoder)
+    }
+
+    /// Encodes interleaved samples. The encoder buffers samples internally, so it returns
+    /// as many AAC frames as could be completed so far. If `samples` is `None`, all buffered
+    /// samples are flushed.
+    fn encode(&mut self, samples: Option<&[i16]>) -> Result<Vec<Bytes>, AacEncoderError> {
+        let mut frames = Vec::new();
+        let mut remaining = samples.unwrap_or_default();
+
+        loop {
+            let mut input_buffer = remaining.as_ptr() as *mut c_void;
+            let mut input_identifier = fdk::AACENC_BufferIdentifier_IN_AUDIO_DATA as i32;
+            let mut input_size = std::mem::size_of_val(remaining) as i32;
+            let mut input_element_size = std::mem::size_of::<i16>() as i32;
+            let input_desc = fdk::AACENC_BufDesc {
+                numBufs: 1,
+                bufs: &mut input_buffer,
+                bufferIdentifiers: &mut input_identifier,
+                bufSizes: &mut input_size,
+                bufElSizes: &mut input_element_size,
+            };
+
+            let mut output_buffer = self.output_buffer.as_mut_ptr() as *mut c_void;
+            let mut output_identifier = fdk::AACENC_BufferIdentifier_OUT_BITSTREAM_DATA as i32;
+            let mut output_size = self.output_buffer.len() as i32;
+            let mut output_element_size = 1;
+            let output_desc = fdk::AACENC_BufDesc {
+                numBufs: 1,
+                bufs: &mut output_buffer,
+                bufferIdentifiers: &mut output_identifier,
+                bufSizes: &mut output_size,
+                bufElSizes: &mut output_element_size,
+            };
+
+            let in_args = fdk::AACENC_InArgs {
+                // -1 signals the end of the stream.
+                numInSamples: samples.map_or(-1, |_| remaining.len() as i32),
+                numAncBytes: 0,
+            };
+            let mut out_args: fdk::AACENC_OutArgs = unsafe { std::mem::zeroed() };
+
+            let result = unsafe {
+                fdk::aacEncEncode(
+                    self.instance,
+                    &input_desc,
+                    &output_desc,
+                    &in_args,
+                    &mut out_args,
+                )
+            };
+            if result == fdk::AACENC_ERROR_AACENC_ENCODE_EOF {
+                break;
+            }
+            check(result)?;
+
+            if out_args.numOutBytes > 0 {
+                frames.push(Bytes::copy_from_slice(
+                    &self.output_buffer[..out_args.numOutBytes as usize],
+                ));
+            }
+            remaining = &remaining[out_args.numInSamples as usize..];
+            if samples.is_some() && remaining.is_empty() {
+                break;
+            }
+        }
+
+        Ok(frames)
+    }
+}
+
+impl Drop for FdkAacEncoder {
+    fn drop(&mut self) {
+        unsafe {
+            fdk::aacEncClose(&mut self.instance);
+        }
+    }
+}
+
+fn check(result: fdk::AACENC_ERROR) -> Result<(), AacEncoderError> {
+    match result {
+        fdk::AACENC_ERROR_AACENC_OK => Ok(()),
+        err => Err(AacEncoderError::FdkEncoderError(err)),
+    }
+}
+
+fn run_encoder_thread(
+    mut encoder: FdkAacEncoder,
+    sample_rate: u32,
+    samples_batch_receiver: Receiver<PipelineEvent<OutputSamples>>,
+    packets_sender: Sender<EncoderOutputEvent>,
+) {
+    // Each AAC frame has a constant number of samples, so timestamps are
+    // calculated from the number of frames produced so far.
+    let mut first_pts = None;
+    let mut frame_count: u64 = 0;
+    let frame_duration = Duration::from_secs_f64(encoder.frame_length as f64 / sample_rate as f64);
+
+    let mut send_frames = |frames: Vec<Bytes>, first_pts: Duration| {
+        for data in frames {
+            let chunk = EncodedChunk {
+                data,
+                pts: first_pts + frame_duration.mul_f64(frame_count as f64),
+                dts: None,
+                is_keyframe: IsKeyframe::NoKeyframes,
+                kind: EncodedChunkKind::Audio(AudioCodec::Aac),
+            };
+            frame_count += 1;
+
+            trace!(pts=?chunk.pts, "AAC encoder produced an encoded chunk.");
+            if let Err(_err) = packets_sender.send(EncoderOutputEvent::Data(chunk)) {
+                warn!("Failed to send encoded audio from AAC encoder. Channel closed.");
+                return false;
+            }
+        }
+        true
+    };
+
+    for msg in samples_batch_receiver.iter() {
+        let batch = match msg {
+            PipelineEvent::Data(batch) => batch,
+            PipelineEvent::EOS => break,
+        };
+        let first_pts = *first_pts.get_or_insert(batch.start_pts);
+
+        let samples = batch.samples.to_i16_interleaved(encoder.dither.as_mut());
+        match encoder.encode(Some(&samples)) {
+            Ok(frames) => {
+                if !send_frames(frames, first_pts) {
+                    return;
+                }
+            }
+            Err(err) => error!("AAC encoding error: {}", err),
+        }
+    }
+
+    if let Some(first_pts) = first_pts {
+        match encoder.encode(None) {
+            Ok(frames) => {
+                if !send_frames(frames, first_pts) {
+                    return;
+                }
+            }
+            Err(err) => error!("Failed to flush AAC encoder: {}", err),
+        }
+    }
+    if let Err(_err) = packets_sender.send(EncoderOutputEvent::AudioEOS) {
+        warn!("Failed to send EOS from AAC encoder. Channel closed.")
+    }
+}
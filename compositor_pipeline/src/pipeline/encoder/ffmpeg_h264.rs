@@ -1,9 +1,11 @@
+use std::time::Duration;
+
 use compositor_render::{Frame, OutputId, Resolution};
 use crossbeam_channel::{Receiver, Sender};
 use ffmpeg_next::{
     codec::{Context, Id},
     format::Pixel,
-    frame, Dictionary, Packet, Rational,
+    frame, picture, Dictionary, Packet, Rational,
 };
 use tracing::{debug, error, span, trace, warn, Level};
 
@@ -79,11 +81,15 @@ pub struct Options {
     pub preset: EncoderPreset,
     pub resolution: Resolution,
     pub raw_options: Vec<(String, String)>,
+    /// If set, IDR frame is forced whenever this much time passed since the previous
+    /// forced keyframe. Used by outputs that split stream into segments on keyframes.
+    pub keyframe_interval: Option<Duration>,
 }
 
 pub struct LibavH264Encoder {
     resolution: Resolution,
     frame_sender: Sender<PipelineEvent<Frame>>,
+    keyframe_request_sender: Sender<()>,
 }
 
 impl LibavH264Encoder {
@@ -93,6 +99,7 @@ impl LibavH264Encoder {
         chunks_sender: Sender<EncoderOutputEvent>,
     ) -> Result<Self, EncoderInitError> {
         let (frame_sender, frame_receiver) = crossbeam_channel::bounded(5);
+        let (keyframe_request_sender, keyframe_request_receiver) = crossbeam_channel::unbounded();
         let (result_sender, result_receiver) = crossbeam_channel::bounded(0);
 
         let options_clone = options.clone();
@@ -110,6 +117,7 @@ impl LibavH264Encoder {
                 let encoder_result = run_encoder_thread(
                     options_clone,
                     frame_receiver,
+                    keyframe_request_receiver,
                     chunks_sender,
                     &result_sender,
                 );
@@ -128,6 +136,7 @@ impl LibavH264Encoder {
 
         Ok(Self {
             frame_sender,
+            keyframe_request_sender,
             resolution: options.resolution,
         })
     }
@@ -139,11 +148,16 @@ impl LibavH264Encoder {
     pub fn resolution(&self) -> Resolution {
         self.resolution
     }
+
+    pub fn keyframe_request_sender(&self) -> Sender<()> {
+        self.keyframe_request_sender.clone()
+    }
 }
 
 fn run_encoder_thread(
     options: Options,
     frame_receiver: Receiver<PipelineEvent<Frame>>,
+    keyframe_request_receiver: Receiver<()>,
     packet_sender: Sender<EncoderOutputEvent>,
     result_sender: &Sender<Result<(), EncoderInitError>>,
 ) -> Result<(), EncoderInitError> {
@@ -189,12 +203,18 @@ fn run_encoder_thread(
         ("subq", options.preset.default_subq_mode()),
     ];
 
-    let encoder_opts_iter = merge_options_with_defaults(&defaults, &options.raw_options);
+    let mut raw_options = options.raw_options.clone();
+    // Make libx264 encode forced keyframes as IDR frames, otherwise
+    // it is allowed to produce a non-IDR I frame.
+    raw_options.push(("forced-idr".to_string(), "1".to_string()));
+
+    let encoder_opts_iter = merge_options_with_defaults(&defaults, &raw_options);
     let mut encoder = encoder.open_as_with(codec, Dictionary::from_iter(encoder_opts_iter))?;
 
     result_sender.send(Ok(())).unwrap();
 
     let mut packet = Packet::empty();
+    let mut next_forced_keyframe_pts = options.keyframe_interval.map(|_| Duration::ZERO);
 
     loop {
         let frame = match frame_receiver.recv() {
@@ -209,6 +229,20 @@ fn run_encoder_thread(
             options.resolution.height as u32,
         );
 
+        // All pending requests are satisfied by a single keyframe.
+        let mut force_keyframe = keyframe_request_receiver.try_iter().count() > 0;
+        if let (Some(keyframe_pts), Some(interval)) =
+            (next_forced_keyframe_pts, options.keyframe_interval)
+        {
+            if frame.pts >= keyframe_pts {
+                force_keyframe = true;
+                next_forced_keyframe_pts = Some(frame.pts + interval);
+            }
+        }
+        if force_keyframe {
+            av_frame.set_kind(picture::Type::I);
+        }
+
         if let Err(e) = frame_into_av(frame, &mut av_frame) {
             error!(
                 "Failed to convert a frame to an ffmpeg frame: {}. Dropping",
@@ -263,9 +297,12 @@ fn run_encoder_thread(
 }
 
 #[derive(Debug)]
-struct FrameConversionError(String);
+pub(super) struct FrameConversionError(pub(super) String);
 
-fn frame_into_av(frame: Frame, av_frame: &mut frame::Video) -> Result<(), FrameConversionError> {
+pub(super) fn frame_into_av(
+    frame: Frame,
+    av_frame: &mut frame::Video,
+) -> Result<(), FrameConversionError> {
     let expected_y_plane_size = (av_frame.plane_width(0) * av_frame.plane_height(0)) as usize;
     let expected_u_plane_size = (av_frame.plane_width(1) * av_frame.plane_height(1)) as usize;
     let expected_v_plane_size = (av_frame.plane_width(2) * av_frame.plane_height(2)) as usize;
@@ -309,7 +346,7 @@ fn write_plane_to_av(frame: &mut frame::Video, plane: usize, data: &[u8]) {
         .for_each(|(data, target)| target[..width].copy_from_slice(data));
 }
 
-fn merge_options_with_defaults<'a>(
+pub(super) fn merge_options_with_defaults<'a>(
     defaults: &'a [(&str, &str)],
     overrides: &'a [(String, String)],
 ) -> impl Iterator<Item = (&'a str, &'a str)> {
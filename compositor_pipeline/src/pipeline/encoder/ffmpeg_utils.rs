@@ -0,0 +1,101 @@
+use std::time::Duration;
+
+use compositor_render::{Frame, Resolution};
+use crossbeam_channel::{Receiver, Sender};
+use ffmpeg_next::{format::Pixel, frame, picture, Packet};
+use tracing::{error, trace, warn};
+
+use crate::{
+    pipeline::structs::{EncodedChunk, EncodedChunkKind, EncoderOutputEvent, VideoCodec},
+    queue::PipelineEvent,
+};
+
+use super::ffmpeg_h264::frame_into_av;
+
+/// Encodes frames with an already opened encoder until the frames channel is closed.
+pub(super) fn encode_frames(
+    mut encoder: ffmpeg_next::encoder::video::Encoder,
+    codec: VideoCodec,
+    resolution: Resolution,
+    keyframe_interval: Option<Duration>,
+    frame_receiver: Receiver<PipelineEvent<Frame>>,
+    packet_sender: Sender<EncoderOutputEvent>,
+) {
+    let mut packet = Packet::empty();
+    let mut next_forced_keyframe_pts = keyframe_interval.map(|_| Duration::ZERO);
+
+    loop {
+        let frame = match frame_receiver.recv() {
+            Ok(PipelineEvent::Data(f)) => f,
+            Ok(PipelineEvent::EOS) => break,
+            Err(_) => break,
+        };
+
+        let mut av_frame = frame::Video::new(
+            Pixel::YUV420P,
+            resolution.width as u32,
+            resolution.height as u32,
+        );
+
+        if let (Some(keyframe_pts), Some(interval)) = (next_forced_keyframe_pts, keyframe_interval)
+        {
+            if frame.pts >= keyframe_pts {
+                av_frame.set_kind(picture::Type::I);
+                next_forced_keyframe_pts = Some(frame.pts + interval);
+            }
+        }
+
+        if let Err(e) = frame_into_av(frame, &mut av_frame) {
+            error!(
+                "Failed to convert a frame to an ffmpeg frame: {}. Dropping",
+                e.0
+            );
+            continue;
+        }
+
+        if let Err(e) = encoder.send_frame(&av_frame) {
+            error!("Encoder error: {e}.");
+            continue;
+        }
+
+        loop {
+            match encoder.receive_packet(&mut packet) {
+                Ok(_) => {
+                    match EncodedChunk::from_av_packet(
+                        &packet,
+                        EncodedChunkKind::Video(codec),
+                        1_000_000,
+                    ) {
+                        Ok(chunk) => {
+                            trace!(pts=?packet.pts(), "{:?} encoder produced an encoded packet.", codec);
+                            if packet_sender.send(EncoderOutputEvent::Data(chunk)).is_err() {
+                                warn!("Failed to send encoded video from {:?} encoder. Channel closed.", codec);
+                                return;
+                            }
+                        }
+                        Err(e) => {
+                            warn!("failed to parse an ffmpeg packet received from encoder: {e}",);
+                            break;
+                        }
+                    }
+                }
+
+                Err(ffmpeg_next::Error::Other {
+                    errno: ffmpeg_next::error::EAGAIN,
+                }) => break, // encoder needs more frames to produce a packet
+
+                Err(e) => {
+                    error!("Encoder error: {e}.");
+                    break;
+                }
+            }
+        }
+    }
+
+    if let Err(_err) = packet_sender.send(EncoderOutputEvent::VideoEOS) {
+        warn!(
+            "Failed to send EOS from {:?} encoder. Channel closed.",
+            codec
+        )
+    }
+}
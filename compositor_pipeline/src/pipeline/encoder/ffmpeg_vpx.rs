@@ -0,0 +1,146 @@
+use std::time::Duration;
+
+use compositor_render::{Frame, OutputId, Resolution};
+use crossbeam_channel::{Receiver, Sender};
+use ffmpeg_next::{codec::Context, format::Pixel, Dictionary, Rational};
+use tracing::{debug, span, warn, Level};
+
+use crate::{
+    error::EncoderInitError,
+    pipeline::structs::{EncoderOutputEvent, VideoCodec},
+    queue::PipelineEvent,
+};
+
+use super::{ffmpeg_h264::merge_options_with_defaults, ffmpeg_utils::encode_frames};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Options {
+    pub resolution: Resolution,
+    pub raw_options: Vec<(String, String)>,
+    /// If set, keyframe is forced whenever this much time passed since the previous
+    /// forced keyframe.
+    pub keyframe_interval: Option<Duration>,
+}
+
+/// VP8 or VP9 encoder. Encoding is done by libvpx through ffmpeg.
+pub struct LibvpxEncoder {
+    resolution: Resolution,
+    frame_sender: Sender<PipelineEvent<Frame>>,
+}
+
+impl LibvpxEncoder {
+    pub fn new(
+        output_id: &OutputId,
+        codec: VideoCodec,
+        options: Options,
+        chunks_sender: Sender<EncoderOutputEvent>,
+    ) -> Result<Self, EncoderInitError> {
+        let (frame_sender, frame_receiver) = crossbeam_channel::bounded(5);
+        let (result_sender, result_receiver) = crossbeam_channel::bounded(0);
+
+        let options_clone = options.clone();
+        let output_id = output_id.clone();
+
+        std::thread::Builder::new()
+            .name(format!("Encoder thread for output {}", output_id))
+            .spawn(move || {
+                let _span = span!(
+                    Level::INFO,
+                    "libvpx encoder",
+                    codec = ?codec,
+                    output_id = output_id.to_string()
+                )
+                .entered();
+                let encoder_result = run_encoder_thread(
+                    codec,
+                    options_clone,
+                    frame_receiver,
+                    chunks_sender,
+                    &result_sender,
+                );
+
+                if let Err(err) = encoder_result {
+                    warn!(%err, "Encoder thread finished with an error.");
+                    if let Err(err) = result_sender.send(Err(err)) {
+                        warn!(%err, "Failed to send error info. Result channel already closed.");
+                    }
+                }
+                debug!("Encoder thread finished.");
+            })
+            .unwrap();
+
+        result_receiver.recv().unwrap()?;
+
+        Ok(Self {
+            frame_sender,
+            resolution: options.resolution,
+        })
+    }
+
+    pub fn frame_sender(&self) -> &Sender<PipelineEvent<Frame>> {
+        &self.frame_sender
+    }
+
+    pub fn resolution(&self) -> Resolution {
+        self.resolution
+    }
+}
+
+fn run_encoder_thread(
+    codec: VideoCodec,
+    options: Options,
+    frame_receiver: Receiver<PipelineEvent<Frame>>,
+    packet_sender: Sender<EncoderOutputEvent>,
+    result_sender: &Sender<Result<(), EncoderInitError>>,
+) -> Result<(), EncoderInitError> {
+    // Range of "cpu-used" for realtime deadline is 4-16 for VP8 and 5-8 for VP9.
+    let (encoder_name, cpu_used) = match codec {
+        VideoCodec::Vp8 => ("libvpx", "8"),
+        VideoCodec::Vp9 => ("libvpx-vp9", "7"),
+        VideoCodec::H264 | VideoCodec::H265 | VideoCodec::Av1 => {
+            return Err(EncoderInitError::NoCodec)
+        }
+    };
+    let av_codec =
+        ffmpeg_next::codec::encoder::find_by_name(encoder_name).ok_or(EncoderInitError::NoCodec)?;
+
+    let mut encoder = Context::new().encoder().video()?;
+
+    // We set this to 1 / 1_000_000, bc we use `as_micros` to convert frames to AV packets.
+    let pts_unit_secs = Rational::new(1, 1_000_000);
+    encoder.set_time_base(pts_unit_secs);
+    encoder.set_format(Pixel::YUV420P);
+    encoder.set_width(options.resolution.width as u32);
+    encoder.set_height(options.resolution.height as u32);
+
+    // Target around 3 bits per pixel per second, e.g. ~2.7Mbps for 720p.
+    let bitrate = (options.resolution.width * options.resolution.height * 3).to_string();
+    let mut defaults = vec![
+        // Encode without look-ahead, so each frame is produced without delay.
+        ("deadline", "realtime"),
+        ("lag-in-frames", "0"),
+        ("cpu-used", cpu_used),
+        ("b", bitrate.as_str()),
+        // Maximum GOP (Group of Pictures) size
+        ("g", "250"),
+    ];
+    if codec == VideoCodec::Vp9 {
+        // Encode rows in parallel
+        defaults.push(("row-mt", "1"));
+    }
+
+    let encoder_opts_iter = merge_options_with_defaults(&defaults, &options.raw_options);
+    let encoder = encoder.open_as_with(av_codec, Dictionary::from_iter(encoder_opts_iter))?;
+
+    result_sender.send(Ok(())).unwrap();
+
+    encode_frames(
+        encoder,
+        codec,
+        options.resolution,
+        options.keyframe_interval,
+        frame_receiver,
+        packet_sender,
+    );
+    Ok(())
+}
@@ -6,14 +6,18 @@ use crate::{
     audio_mixer::{AudioChannels, AudioSamples, OutputSamples},
     error::EncoderInitError,
     pipeline::{
-        structs::{EncodedChunk, EncodedChunkKind, EncoderOutputEvent},
+        structs::{EncodedChunk, EncodedChunkKind, EncoderOutputEvent, IsKeyframe},
         AudioCodec,
     },
     queue::PipelineEvent,
 };
 
+use self::multistream::MultistreamEncoder;
+
 use super::AudioEncoderPreset;
 
+pub(crate) mod multistream;
+
 #[derive(Debug, Clone)]
 pub struct Options {
     pub channels: AudioChannels,
@@ -24,6 +28,12 @@ pub struct OpusEncoder {
     samples_batch_sender: Sender<PipelineEvent<OutputSamples>>,
 }
 
+enum Encoder {
+    Opus(opus::Encoder),
+    /// Used for 5.1 audio.
+    Multistream(MultistreamEncoder),
+}
+
 impl OpusEncoder {
     pub fn new(
         options: Options,
@@ -32,8 +42,21 @@ impl OpusEncoder {
     ) -> Result<Self, EncoderInitError> {
         let (samples_batch_sender, samples_batch_receiver) = bounded(2);
 
-        let encoder =
-            opus::Encoder::new(sample_rate, options.channels.into(), options.preset.into())?;
+        let opus_channels = match options.channels {
+            AudioChannels::Mono => Some(opus::Channels::Mono),
+            AudioChannels::Stereo => Some(opus::Channels::Stereo),
+            AudioChannels::Surround51 => None,
+        };
+        let encoder = match opus_channels {
+            Some(channels) => Encoder::Opus(opus::Encoder::new(
+                sample_rate,
+                channels,
+                options.preset.into(),
+            )?),
+            None => {
+                Encoder::Multistream(MultistreamEncoder::new(sample_rate, options.preset.into())?)
+            }
+        };
 
         std::thread::Builder::new()
             .name("Opus encoder thread".to_string())
@@ -54,17 +77,27 @@ impl OpusEncoder {
 }
 
 fn run_encoder_thread(
-    mut encoder: opus::Encoder,
+    mut encoder: Encoder,
     samples_batch_receiver: Receiver<PipelineEvent<OutputSamples>>,
     packets_sender: Sender<EncoderOutputEvent>,
 ) {
     let mut output_buffer = [0u8; 1024 * 1024];
 
-    let mut encode = |samples: &[i16]| match encoder.encode(samples, &mut output_buffer) {
-        Ok(len) => Some(bytes::Bytes::copy_from_slice(&output_buffer[..len])),
-        Err(err) => {
-            error!("Opus encoding error: {}", err);
-            None
+    let mut encode = |samples: &AudioSamples| {
+        let result = match &mut encoder {
+            Encoder::Multistream(encoder) => encoder
+                .encode(&samples.surround51(), &mut output_buffer)
+                .map_err(|err| format!("multistream error code {err}")),
+            Encoder::Opus(encoder) => encoder
+                .encode_float(&samples.interleaved(), &mut output_buffer)
+                .map_err(|err| err.to_string()),
+        };
+        match result {
+            Ok(len) => Some(bytes::Bytes::copy_from_slice(&output_buffer[..len])),
+            Err(err) => {
+                error!("Opus encoding error: {}", err);
+                None
+            }
         }
     };
 
@@ -74,26 +107,14 @@ fn run_encoder_thread(
             PipelineEvent::EOS => break,
         };
 
-        let data = match batch.samples {
-            AudioSamples::Mono(mono_samples) => {
-                let Some(data) = encode(&mono_samples) else {
-                    continue;
-                };
-                data
-            }
-            AudioSamples::Stereo(stereo_samples) => {
-                let flatten_samples: Vec<i16> =
-                    stereo_samples.iter().flat_map(|(l, r)| [*l, *r]).collect();
-                let Some(data) = encode(&flatten_samples) else {
-                    continue;
-                };
-                data
-            }
+        let Some(data) = encode(&batch.samples) else {
+            continue;
         };
         let chunk = EncodedChunk {
             data,
             pts: batch.start_pts,
             dts: None,
+            is_keyframe: IsKeyframe::NoKeyframes,
             kind: EncodedChunkKind::Audio(AudioCodec::Opus),
         };
 
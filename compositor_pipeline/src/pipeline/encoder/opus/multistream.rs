@@ -0,0 +1,94 @@
+use std::os::raw::c_int;
+
+use audiopus_sys as ffi;
+use tracing::warn;
+
+use crate::{audio_mixer::channel_layout::surround51, error::EncoderInitError};
+
+/// Streams produced by the libopus surround encoder for 5.1 audio with the channel
+/// mapping family 1. Muxers need them in the identification header.
+pub(crate) const SURROUND51_STREAMS: u8 = 4;
+pub(crate) const SURROUND51_COUPLED_STREAMS: u8 = 2;
+pub(crate) const SURROUND51_MAPPING: [u8; 6] = [0, 4, 1, 2, 3, 5];
+
+/// Channels of the 5.1 samples in the Vorbis channel order, expected by the encoder.
+const VORBIS_ORDER: [usize; 6] = [
+    surround51::FRONT_LEFT,
+    surround51::CENTER,
+    surround51::FRONT_RIGHT,
+    surround51::SURROUND_LEFT,
+    surround51::SURROUND_RIGHT,
+    surround51::LFE,
+];
+
+/// Opus multistream encoder for 5.1 audio. The `opus` crate supports only mono and stereo.
+pub(super) struct MultistreamEncoder {
+    instance: *mut ffi::OpusMSEncoder,
+}
+
+// Encoder is created on the pipeline thread, but after that it is only
+// used from the encoder thread.
+unsafe impl Send for MultistreamEncoder {}
+
+impl MultistreamEncoder {
+    pub fn new(sample_rate: u32, application: opus::Application) -> Result<Self, EncoderInitError> {
+        let mut streams: c_int = 0;
+        let mut coupled_streams: c_int = 0;
+        let mut mapping = [0u8; 6];
+        let mut error: c_int = 0;
+        let instance = unsafe {
+            ffi::opus_multistream_surround_encoder_create(
+                sample_rate as ffi::opus_int32,
+                6,
+                1,
+                &mut streams,
+                &mut coupled_streams,
+                mapping.as_mut_ptr(),
+                application as c_int,
+                &mut error,
+            )
+        };
+        if instance.is_null() || error != ffi::OPUS_OK as c_int {
+            return Err(EncoderInitError::OpusMultistreamError(error));
+        }
+        if streams != SURROUND51_STREAMS as c_int
+            || coupled_streams != SURROUND51_COUPLED_STREAMS as c_int
+            || mapping != SURROUND51_MAPPING
+        {
+            warn!(
+                streams,
+                coupled_streams,
+                ?mapping,
+                "Unexpected stream layout of the Opus surround encoder."
+            );
+        }
+
+        Ok(Self { instance })
+    }
+
+    pub fn encode(&mut self, samples: &[[f32; 6]], output: &mut [u8]) -> Result<usize, c_int> {
+        let input: Vec<f32> = samples
+            .iter()
+            .flat_map(|sample| VORBIS_ORDER.map(|channel| sample[channel]))
+            .collect();
+        let result = unsafe {
+            ffi::opus_multistream_encode_float(
+                self.instance,
+                input.as_ptr(),
+                samples.len() as c_int,
+                output.as_mut_ptr(),
+                output.len() as ffi::opus_int32,
+            )
+        };
+        match result {
+            len if len >= 0 => Ok(len as usize),
+            err => Err(err),
+        }
+    }
+}
+
+impl Drop for MultistreamEncoder {
+    fn drop(&mut self) {
+        unsafe { ffi::opus_multistream_encoder_destroy(self.instance) };
+    }
+}
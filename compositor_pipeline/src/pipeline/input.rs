@@ -4,18 +4,24 @@ use crate::{error::InputInitError, queue::PipelineEvent};
 
 use compositor_render::InputId;
 use crossbeam_channel::Receiver;
+use rtmp::{RtmpReceiver, RtmpReceiverOptions};
 use rtp::{RtpReceiver, RtpReceiverOptions};
+use whip::{WhipReceiver, WhipReceiverOptions};
 
 use self::mp4::{Mp4, Mp4Options};
 
 use super::{decoder::DecoderOptions, structs::EncodedChunk, Port};
 
 pub mod mp4;
+pub mod rtmp;
 pub mod rtp;
+pub mod whip;
 
 pub enum Input {
     Rtp(RtpReceiver),
     Mp4(Mp4),
+    Rtmp(RtmpReceiver),
+    Whip(WhipReceiver),
 }
 
 impl Input {
@@ -41,6 +47,23 @@ impl Input {
                     (Self::Mp4(mp4), chunks_receiver, decoder_options, None)
                 },
             )?),
+
+            InputOptions::Rtmp(opts) => Ok(RtmpReceiver::new(input_id, opts).map(
+                |(receiver, chunks_receiver, decoder_options, port)| {
+                    (
+                        Self::Rtmp(receiver),
+                        chunks_receiver,
+                        decoder_options,
+                        Some(port),
+                    )
+                },
+            )?),
+
+            InputOptions::Whip(opts) => Ok(WhipReceiver::new(input_id, opts).map(
+                |(receiver, chunks_receiver, decoder_options)| {
+                    (Self::Whip(receiver), chunks_receiver, decoder_options, None)
+                },
+            )?),
         }
     }
 }
@@ -48,6 +71,8 @@ impl Input {
 pub enum InputOptions {
     Rtp(RtpReceiverOptions),
     Mp4(Mp4Options),
+    Rtmp(RtmpReceiverOptions),
+    Whip(WhipReceiverOptions),
 }
 
 #[derive(Debug)]
@@ -2,7 +2,7 @@ use std::path::{Path, PathBuf};
 
 use bytes::Bytes;
 use compositor_render::InputId;
-use crossbeam_channel::Receiver;
+use crossbeam_channel::{Receiver, Sender};
 use tracing::error;
 
 use crate::{
@@ -15,6 +15,11 @@ use mp4_file_reader::Mp4FileReader;
 use super::ChunksReceiver;
 
 pub mod mp4_file_reader;
+mod sample_entry;
+
+/// Number of fragments that can be queued for each track before
+/// sending a new fragment blocks.
+const FRAGMENT_QUEUE_SIZE: usize = 5;
 
 pub struct Mp4Options {
     pub source: Source,
@@ -24,7 +29,6 @@ pub(crate) enum Mp4ReaderOptions {
     NonFragmented {
         file: PathBuf,
     },
-    #[allow(dead_code)]
     Fragmented {
         header: Bytes,
         fragment_receiver: Receiver<PipelineEvent<Bytes>>,
@@ -34,6 +38,39 @@ pub(crate) enum Mp4ReaderOptions {
 pub enum Source {
     Url(String),
     File(PathBuf),
+    /// Fragmented MP4 stream. Media fragments (`moof` and `mdat` boxes) are delivered
+    /// after registration with [`Mp4FragmentSender`].
+    Fragmented {
+        init_segment: Bytes,
+    },
+}
+
+/// Delivers media fragments of a fragmented MP4 input to the reader threads
+/// of all tracks.
+#[derive(Debug, Clone)]
+pub struct Mp4FragmentSender {
+    senders: Vec<Sender<PipelineEvent<Bytes>>>,
+}
+
+impl Mp4FragmentSender {
+    /// Blocks until all reader threads have space in their queues.
+    pub fn send_fragment(&self, fragment: Bytes) -> Result<(), Mp4Error> {
+        for sender in &self.senders {
+            sender
+                .send(PipelineEvent::Data(fragment.clone()))
+                .map_err(|_| Mp4Error::InputClosed)?;
+        }
+        Ok(())
+    }
+
+    pub fn send_eos(&self) -> Result<(), Mp4Error> {
+        for sender in &self.senders {
+            sender
+                .send(PipelineEvent::EOS)
+                .map_err(|_| Mp4Error::InputClosed)?;
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -49,14 +86,21 @@ pub enum Mp4Error {
 
     #[error("No suitable track in the mp4 file")]
     NoTrack,
+
+    #[error("MP4 input is not fragmented.")]
+    NotFragmented,
+
+    #[error("MP4 input already finished processing fragments.")]
+    InputClosed,
 }
 
 pub struct Mp4 {
     pub input_id: InputId,
     _video_thread: Option<Mp4FileReader<VideoDecoderOptions>>,
     _audio_thread: Option<Mp4FileReader<AudioDecoderOptions>>,
-    source: Source,
-    path_to_file: PathBuf,
+    fragment_sender: Option<Mp4FragmentSender>,
+    /// File downloaded from `Source::Url`, it is removed when the input is dropped.
+    downloaded_file: Option<PathBuf>,
 }
 
 impl Mp4 {
@@ -65,32 +109,45 @@ impl Mp4 {
         options: Mp4Options,
         download_dir: &Path,
     ) -> Result<(Self, ChunksReceiver, DecoderOptions), Mp4Error> {
-        let input_path = match options.source {
+        let (video_options, audio_options, downloaded_file, fragment_senders) = match options.source
+        {
             Source::Url(ref url) => {
-                let file_response = reqwest::blocking::get(url)?;
-                let mut file_response = file_response.error_for_status()?;
-
-                let mut path = download_dir.to_owned();
-                path.push(format!(
-                    "live-compositor-user-file-{}.mp4",
-                    rand::random::<u64>()
-                ));
-
-                let mut file = std::fs::File::create(&path)?;
-
-                std::io::copy(&mut file_response, &mut file)?;
-
-                path
+                let path = download_file(url, download_dir)?;
+                (
+                    Mp4ReaderOptions::NonFragmented { file: path.clone() },
+                    Mp4ReaderOptions::NonFragmented { file: path.clone() },
+                    Some(path),
+                    None,
+                )
+            }
+            Source::File(ref path) => (
+                Mp4ReaderOptions::NonFragmented { file: path.clone() },
+                Mp4ReaderOptions::NonFragmented { file: path.clone() },
+                None,
+                None,
+            ),
+            Source::Fragmented { ref init_segment } => {
+                // Each track is read by a separate thread, so every fragment is sent to both.
+                let (video_sender, video_receiver) =
+                    crossbeam_channel::bounded(FRAGMENT_QUEUE_SIZE);
+                let (audio_sender, audio_receiver) =
+                    crossbeam_channel::bounded(FRAGMENT_QUEUE_SIZE);
+                (
+                    Mp4ReaderOptions::Fragmented {
+                        header: init_segment.clone(),
+                        fragment_receiver: video_receiver,
+                    },
+                    Mp4ReaderOptions::Fragmented {
+                        header: init_segment.clone(),
+                        fragment_receiver: audio_receiver,
+                    },
+                    None,
+                    Some((video_sender, audio_sender)),
+                )
             }
-            Source::File(ref path) => path.clone(),
         };
 
-        let video = Mp4FileReader::new_video(
-            Mp4ReaderOptions::NonFragmented {
-                file: input_path.clone(),
-            },
-            input_id.clone(),
-        )?;
+        let video = Mp4FileReader::new_video(video_options, input_id.clone())?;
 
         let (video_reader, video_receiver, video_decoder_options) = match video {
             Some((reader, receiver)) => {
@@ -100,12 +157,7 @@ impl Mp4 {
             None => (None, None, None),
         };
 
-        let audio = Mp4FileReader::new_audio(
-            Mp4ReaderOptions::NonFragmented {
-                file: input_path.clone(),
-            },
-            input_id.clone(),
-        )?;
+        let audio = Mp4FileReader::new_audio(audio_options, input_id.clone())?;
 
         let (audio_reader, audio_receiver, audio_deocder_options) = match audio {
             Some((reader, receiver)) => {
@@ -115,13 +167,25 @@ impl Mp4 {
             None => (None, None, None),
         };
 
+        // Tracks that are not present in the init segment do not have a reader thread.
+        let fragment_sender =
+            fragment_senders.map(|(video_sender, audio_sender)| Mp4FragmentSender {
+                senders: [
+                    video_reader.as_ref().map(|_| video_sender),
+                    audio_reader.as_ref().map(|_| audio_sender),
+                ]
+                .into_iter()
+                .flatten()
+                .collect(),
+            });
+
         Ok((
             Self {
                 input_id: input_id.clone(),
                 _video_thread: video_reader,
                 _audio_thread: audio_reader,
-                source: options.source,
-                path_to_file: input_path,
+                fragment_sender,
+                downloaded_file,
             },
             ChunksReceiver {
                 video: video_receiver,
@@ -133,12 +197,34 @@ impl Mp4 {
             },
         ))
     }
+
+    /// Returns sender for media fragments if the input was registered with `Source::Fragmented`.
+    pub fn fragment_sender(&self) -> Result<Mp4FragmentSender, Mp4Error> {
+        self.fragment_sender.clone().ok_or(Mp4Error::NotFragmented)
+    }
+}
+
+fn download_file(url: &str, download_dir: &Path) -> Result<PathBuf, Mp4Error> {
+    let file_response = reqwest::blocking::get(url)?;
+    let mut file_response = file_response.error_for_status()?;
+
+    let mut path = download_dir.to_owned();
+    path.push(format!(
+        "live-compositor-user-file-{}.mp4",
+        rand::random::<u64>()
+    ));
+
+    let mut file = std::fs::File::create(&path)?;
+
+    std::io::copy(&mut file_response, &mut file)?;
+
+    Ok(path)
 }
 
 impl Drop for Mp4 {
     fn drop(&mut self) {
-        if let Source::Url(_) = self.source {
-            if let Err(e) = std::fs::remove_file(&self.path_to_file) {
+        if let Some(ref path) = self.downloaded_file {
+            if let Err(e) = std::fs::remove_file(path) {
                 error!(input_id=?self.input_id.0, "Error while removing the downloaded mp4 file: {e}");
             }
         }
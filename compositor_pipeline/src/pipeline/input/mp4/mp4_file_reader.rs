@@ -14,19 +14,22 @@ use tracing::{debug, span, trace, warn, Level, Span};
 use crate::{
     pipeline::{
         decoder::{AacDecoderOptions, AudioDecoderOptions, VideoDecoderOptions},
-        structs::{EncodedChunk, EncodedChunkKind},
+        structs::{EncodedChunk, EncodedChunkKind, IsKeyframe},
         AudioCodec, VideoCodec,
     },
     queue::PipelineEvent,
 };
 
-use super::{Mp4Error, Mp4ReaderOptions};
+use super::{
+    sample_entry::{read_video_sample_entries, VideoSampleEntry},
+    Mp4Error, Mp4ReaderOptions,
+};
 
 type ChunkReceiver = Receiver<PipelineEvent<EncodedChunk>>;
+type VideoSampleUnpacker = Box<dyn FnMut(mp4::Mp4Sample) -> Bytes + Send>;
 
 pub(crate) struct Mp4FileReader<DecoderOptions> {
     stop_thread: Arc<AtomicBool>,
-    fragment_sender: Option<Sender<PipelineEvent<Bytes>>>,
     decoder_options: DecoderOptions,
 }
 
@@ -128,12 +131,13 @@ impl Mp4FileReader<VideoDecoderOptions> {
 
         match options {
             Mp4ReaderOptions::NonFragmented { file } => {
-                let input_file = std::fs::File::open(file)?;
+                let mut input_file = std::fs::File::open(file)?;
                 let size = input_file.metadata()?.size();
+                let sample_entries = read_video_sample_entries(&mut input_file)?;
                 Self::new(
                     input_file,
                     size,
-                    Self::find_h264_info,
+                    |reader| Self::find_video_info(reader, &sample_entries),
                     None,
                     stop_thread,
                     span,
@@ -144,11 +148,12 @@ impl Mp4FileReader<VideoDecoderOptions> {
                 fragment_receiver,
             } => {
                 let size = header.len() as u64;
-                let reader = std::io::Cursor::new(header);
+                let mut reader = std::io::Cursor::new(header);
+                let sample_entries = read_video_sample_entries(&mut reader)?;
                 Self::new(
                     reader,
                     size,
-                    Self::find_h264_info,
+                    |reader| Self::find_video_info(reader, &sample_entries),
                     Some(fragment_receiver),
                     stop_thread,
                     span,
@@ -157,9 +162,26 @@ impl Mp4FileReader<VideoDecoderOptions> {
         }
     }
 
+    /// H264 tracks are read by the mp4 crate, sample entries of other codecs
+    /// are read directly from the file.
+    fn find_video_info<Reader: Read + Seek + Send + 'static>(
+        reader: &mp4::Mp4Reader<Reader>,
+        sample_entries: &[VideoSampleEntry],
+    ) -> Option<TrackInfo<VideoDecoderOptions, VideoSampleUnpacker>> {
+        Self::find_h264_info(reader).or_else(|| {
+            sample_entries
+                .iter()
+                .find_map(|sample_entry| match &sample_entry.entry_type {
+                    b"hvc1" | b"hev1" => Self::find_h265_info(reader, sample_entry),
+                    b"av01" => Self::find_av1_info(reader, sample_entry),
+                    _ => None,
+                })
+        })
+    }
+
     fn find_h264_info<Reader: Read + Seek + Send + 'static>(
         reader: &mp4::Mp4Reader<Reader>,
-    ) -> Option<TrackInfo<VideoDecoderOptions, impl FnMut(mp4::Mp4Sample) -> Bytes>> {
+    ) -> Option<TrackInfo<VideoDecoderOptions, VideoSampleUnpacker>> {
         let (&track_id, track, avc) = reader.tracks().iter().find_map(|(id, track)| {
             let track_type = track.track_type().ok()?;
             let media_type = track.media_type().ok()?;
@@ -189,43 +211,9 @@ impl Mp4FileReader<VideoDecoderOptions> {
             .iter()
             .flat_map(|s| [0, 0, 0, 1].iter().chain(s.bytes.iter()));
 
-        let mut sps_and_pps_payload = Some(sps.chain(pps).copied().collect::<Bytes>());
-
+        let sps_and_pps_payload = sps.chain(pps).copied().collect::<Bytes>();
         let length_size = avc.avcc.length_size_minus_one + 1;
-
-        let sample_unpacker = move |sample: mp4::Mp4Sample| {
-            let mut sample_data = sample.bytes.reader();
-            let mut data: BytesMut = Default::default();
-
-            if let Some(first_nal) = sps_and_pps_payload.take() {
-                data.extend_from_slice(&first_nal);
-            }
-
-            // the mp4 sample contains one h264 access unit (possibly more than one NAL).
-            // the NALs are stored as: <length_size bytes long big endian encoded length><the NAL>.
-            // we need to convert this into Annex B, in which NALs are separated by
-            // [0, 0, 0, 1]. `length_size` is at most 4 bytes long.
-            loop {
-                let mut len = [0u8; 4];
-
-                if sample_data
-                    .read_exact(&mut len[4 - length_size as usize..])
-                    .is_err()
-                {
-                    break;
-                }
-
-                let len = u32::from_be_bytes(len);
-
-                let mut nalu = bytes::BytesMut::zeroed(len as usize);
-                sample_data.read_exact(&mut nalu).unwrap();
-
-                data.extend_from_slice(&[0, 0, 0, 1]);
-                data.extend_from_slice(&nalu);
-            }
-
-            data.freeze()
-        };
+        let sample_unpacker = annex_b_sample_unpacker(sps_and_pps_payload, length_size);
 
         let decoder_options = VideoDecoderOptions {
             codec: VideoCodec::H264,
@@ -241,9 +229,60 @@ impl Mp4FileReader<VideoDecoderOptions> {
         })
     }
 
-    #[allow(dead_code)]
-    pub(crate) fn fragment_sender(&self) -> Option<Sender<PipelineEvent<Bytes>>> {
-        self.fragment_sender.clone()
+    fn find_h265_info<Reader: Read + Seek + Send + 'static>(
+        reader: &mp4::Mp4Reader<Reader>,
+        sample_entry: &VideoSampleEntry,
+    ) -> Option<TrackInfo<VideoDecoderOptions, VideoSampleUnpacker>> {
+        let track = reader.tracks().get(&sample_entry.track_id)?;
+        let hevc_config = sample_entry.hevc_config()?;
+
+        // VPS, SPS and PPS are stored in the container (always for `hvc1` and optionally
+        // for `hev1` tracks), they are prepended to the first frame just like for H264.
+        let parameter_sets = hevc_config
+            .nal_units
+            .iter()
+            .flat_map(|nal| [0, 0, 0, 1].iter().chain(nal.iter()))
+            .copied()
+            .collect::<Bytes>();
+
+        Some(TrackInfo {
+            sample_count: track.sample_count(),
+            timescale: track.timescale(),
+            decoder_options: VideoDecoderOptions {
+                codec: VideoCodec::H265,
+            },
+            track_id: sample_entry.track_id,
+            sample_unpacker: annex_b_sample_unpacker(parameter_sets, hevc_config.length_size),
+            chunk_kind: EncodedChunkKind::Video(VideoCodec::H265),
+        })
+    }
+
+    fn find_av1_info<Reader: Read + Seek + Send + 'static>(
+        reader: &mp4::Mp4Reader<Reader>,
+        sample_entry: &VideoSampleEntry,
+    ) -> Option<TrackInfo<VideoDecoderOptions, VideoSampleUnpacker>> {
+        let track = reader.tracks().get(&sample_entry.track_id)?;
+
+        // Samples are already in the low overhead bitstream format. Only the configuration OBUs
+        // (e.g. the sequence header) from the container have to be prepended to the first frame.
+        let mut config_obus = sample_entry
+            .av1_config_obus()
+            .filter(|obus| !obus.is_empty());
+        let sample_unpacker = move |sample: mp4::Mp4Sample| match config_obus.take() {
+            Some(config_obus) => [config_obus, sample.bytes].concat().into(),
+            None => sample.bytes,
+        };
+
+        Some(TrackInfo {
+            sample_count: track.sample_count(),
+            timescale: track.timescale(),
+            decoder_options: VideoDecoderOptions {
+                codec: VideoCodec::Av1,
+            },
+            track_id: sample_entry.track_id,
+            sample_unpacker: Box::new(sample_unpacker),
+            chunk_kind: EncodedChunkKind::Video(VideoCodec::Av1),
+        })
     }
 }
 
@@ -289,7 +328,6 @@ impl<DecoderOptions: Clone + Send + 'static> Mp4FileReader<DecoderOptions> {
         Ok(Some((
             Mp4FileReader {
                 stop_thread,
-                fragment_sender: None,
                 decoder_options,
             },
             receiver,
@@ -308,11 +346,50 @@ impl<D> Drop for Mp4FileReader<D> {
     }
 }
 
+/// Converts H264 or H265 samples into the Annex B format. Parameter sets (already in
+/// the Annex B format) are prepended to the first sample.
+fn annex_b_sample_unpacker(parameter_sets: Bytes, length_size: u8) -> VideoSampleUnpacker {
+    let mut parameter_sets = Some(parameter_sets);
+    Box::new(move |sample: mp4::Mp4Sample| {
+        let mut sample_data = sample.bytes.reader();
+        let mut data: BytesMut = Default::default();
+
+        if let Some(parameter_sets) = parameter_sets.take() {
+            data.extend_from_slice(&parameter_sets);
+        }
+
+        // the mp4 sample contains one access unit (possibly more than one NAL).
+        // the NALs are stored as: <length_size bytes long big endian encoded length><the NAL>.
+        // we need to convert this into Annex B, in which NALs are separated by
+        // [0, 0, 0, 1]. `length_size` is at most 4 bytes long.
+        loop {
+            let mut len = [0u8; 4];
+
+            if sample_data
+                .read_exact(&mut len[4 - length_size as usize..])
+                .is_err()
+            {
+                break;
+            }
+
+            let len = u32::from_be_bytes(len);
+
+            let mut nalu = bytes::BytesMut::zeroed(len as usize);
+            sample_data.read_exact(&mut nalu).unwrap();
+
+            data.extend_from_slice(&[0, 0, 0, 1]);
+            data.extend_from_slice(&nalu);
+        }
+
+        data.freeze()
+    })
+}
+
 fn run_reader_thread<Reader: Read + Seek, DecoderOptions>(
     mut reader: Mp4Reader<Reader>,
     sender: Sender<PipelineEvent<EncodedChunk>>,
     stop_thread: Arc<AtomicBool>,
-    _fragment_receiver: Option<Receiver<PipelineEvent<Bytes>>>,
+    fragment_receiver: Option<Receiver<PipelineEvent<Bytes>>>,
     track_info: TrackInfo<DecoderOptions, impl FnMut(mp4::Mp4Sample) -> Bytes>,
 ) {
     let mut sample_unpacker = track_info.sample_unpacker;
@@ -322,38 +399,106 @@ fn run_reader_thread<Reader: Read + Seek, DecoderOptions>(
             return;
         }
 
-        match reader.read_sample(track_info.track_id, i) {
-            Ok(Some(sample)) => {
-                let rendering_offset = sample.rendering_offset;
-                let start_time = sample.start_time;
-                let data = sample_unpacker(sample);
-
-                let dts = Duration::from_secs_f64(start_time as f64 / track_info.timescale as f64);
-                let chunk = EncodedChunk {
-                    data,
-                    pts: Duration::from_secs_f64(
-                        (start_time as f64 + rendering_offset as f64) / track_info.timescale as f64,
-                    ),
-                    dts: Some(dts),
-                    kind: track_info.chunk_kind,
-                };
-
-                trace!(pts=?chunk.pts, "MP4 reader produced a chunk.");
-                match sender.send(PipelineEvent::Data(chunk)) {
-                    Ok(_) => {}
-                    Err(_) => {
-                        debug!("Failed to send MP4 chunk. Channel closed.");
-                        return;
-                    }
+        let Some(chunk) = read_chunk(
+            &mut reader,
+            i,
+            track_info.track_id,
+            track_info.timescale,
+            track_info.chunk_kind,
+            &mut sample_unpacker,
+        ) else {
+            continue;
+        };
+        if sender.send(PipelineEvent::Data(chunk)).is_err() {
+            debug!("Failed to send MP4 chunk. Channel closed.");
+            return;
+        }
+    }
+
+    // Fragmented MP4 header does not contain any samples, they are delivered
+    // in fragments (`moof` and `mdat` boxes) after the reader is created.
+    for fragment in fragment_receiver.into_iter().flatten() {
+        let fragment = match fragment {
+            PipelineEvent::Data(fragment) => fragment,
+            PipelineEvent::EOS => break,
+        };
+        if stop_thread.load(std::sync::atomic::Ordering::Relaxed) {
+            return;
+        }
+
+        let size = fragment.len() as u64;
+        let mut fragment_reader =
+            match reader.read_fragment_header(std::io::Cursor::new(fragment), size) {
+                Ok(fragment_reader) => fragment_reader,
+                Err(err) => {
+                    warn!("Error while reading MP4 fragment: {:?}", err);
+                    continue;
                 }
+            };
+        let sample_count = fragment_reader
+            .sample_count(track_info.track_id)
+            .unwrap_or(0);
+
+        for i in 1..=sample_count {
+            let Some(chunk) = read_chunk(
+                &mut fragment_reader,
+                i,
+                track_info.track_id,
+                track_info.timescale,
+                track_info.chunk_kind,
+                &mut sample_unpacker,
+            ) else {
+                continue;
+            };
+            if sender.send(PipelineEvent::Data(chunk)).is_err() {
+                debug!("Failed to send MP4 chunk. Channel closed.");
+                return;
             }
-            Err(e) => {
-                warn!("Error while reading MP4 video sample: {:?}", e);
-            }
-            _ => {}
         }
     }
+
     if let Err(_err) = sender.send(PipelineEvent::EOS) {
         debug!("Failed to send EOS from MP4 video reader. Channel closed.");
     }
 }
+
+fn read_chunk<Reader: Read + Seek>(
+    reader: &mut Mp4Reader<Reader>,
+    sample_id: u32,
+    track_id: u32,
+    timescale: u32,
+    chunk_kind: EncodedChunkKind,
+    sample_unpacker: &mut impl FnMut(mp4::Mp4Sample) -> Bytes,
+) -> Option<EncodedChunk> {
+    let sample = match reader.read_sample(track_id, sample_id) {
+        Ok(Some(sample)) => sample,
+        Ok(None) => return None,
+        Err(e) => {
+            warn!("Error while reading MP4 video sample: {:?}", e);
+            return None;
+        }
+    };
+
+    let rendering_offset = sample.rendering_offset;
+    let start_time = sample.start_time;
+    let is_keyframe = match chunk_kind {
+        EncodedChunkKind::Video(_) if sample.is_sync => IsKeyframe::Yes,
+        EncodedChunkKind::Video(_) => IsKeyframe::No,
+        EncodedChunkKind::Audio(_) => IsKeyframe::NoKeyframes,
+    };
+    let data = sample_unpacker(sample);
+
+    let dts = Duration::from_secs_f64(start_time as f64 / timescale as f64);
+    let chunk = EncodedChunk {
+        data,
+        pts: Duration::from_secs_f64(
+            (start_time as f64 + rendering_offset as f64) / timescale as f64,
+        ),
+        dts: Some(dts),
+        is_keyframe,
+        kind: chunk_kind,
+    };
+
+    trace!(pts=?chunk.pts, "MP4 reader produced a chunk.");
+    Some(chunk)
+}
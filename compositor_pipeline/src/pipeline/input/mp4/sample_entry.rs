@@ -0,0 +1,244 @@
+//! The mp4 crate skips sample entries of some video codecs (e.g. `av01` or `hvc1`),
+//! so they are read directly from the `moov` box.
+
+use std::io::{Read, Seek, SeekFrom};
+
+use bytes::{Buf, Bytes};
+
+/// Size of `VisualSampleEntry` fields that precede child boxes of the sample entry.
+const VISUAL_SAMPLE_ENTRY_SIZE: usize = 78;
+/// Size of `AV1CodecConfigurationRecord` fields that precede the configuration OBUs.
+const AV1_CONFIG_RECORD_SIZE: usize = 4;
+/// Offset of the `lengthSizeMinusOne` field in `HEVCDecoderConfigurationRecord`.
+const HEVC_LENGTH_SIZE_OFFSET: usize = 21;
+/// Size of `HEVCDecoderConfigurationRecord` fields that precede the parameter set arrays.
+const HEVC_CONFIG_RECORD_SIZE: usize = 22;
+
+pub(super) struct VideoSampleEntry {
+    pub track_id: u32,
+    /// Four character code of the sample entry, e.g. `av01`.
+    pub entry_type: [u8; 4],
+    /// Boxes nested in the sample entry, e.g. the codec configuration box.
+    children: Bytes,
+}
+
+pub(super) struct HevcConfig {
+    /// Size of NAL unit length fields in samples.
+    pub length_size: u8,
+    /// VPS, SPS, PPS and SEI NAL units.
+    pub nal_units: Vec<Bytes>,
+}
+
+impl VideoSampleEntry {
+    /// OBUs (e.g. the sequence header) stored in the `av1C` box, with size fields. See
+    /// [AV1 Codec ISO Media File Format Binding](https://aomediacodec.github.io/av1-isobmff/).
+    pub fn av1_config_obus(&self) -> Option<Bytes> {
+        skip(&find_box(&self.children, b"av1C")?, AV1_CONFIG_RECORD_SIZE)
+    }
+
+    /// Configuration stored in the `hvcC` box, see ISO/IEC 14496-15, 8.3.3.
+    pub fn hevc_config(&self) -> Option<HevcConfig> {
+        let hvcc = find_box(&self.children, b"hvcC")?;
+        let length_size = (hvcc.get(HEVC_LENGTH_SIZE_OFFSET)? & 0b11) + 1;
+
+        let mut arrays = skip(&hvcc, HEVC_CONFIG_RECORD_SIZE)?;
+        let mut nal_units = Vec::new();
+        let arrays_count = get_u8(&mut arrays)?;
+        for _ in 0..arrays_count {
+            // Array completeness flag and the NAL unit type.
+            get_u8(&mut arrays)?;
+            let nal_units_count = get_u16(&mut arrays)?;
+            for _ in 0..nal_units_count {
+                let len = get_u16(&mut arrays)? as usize;
+                if arrays.len() < len {
+                    return None;
+                }
+                nal_units.push(arrays.split_to(len));
+            }
+        }
+
+        Some(HevcConfig {
+            length_size,
+            nal_units,
+        })
+    }
+}
+
+/// Reads sample entries of all video tracks from the `moov` box. Position of the reader
+/// is restored to the beginning of the file.
+pub(super) fn read_video_sample_entries<Reader: Read + Seek>(
+    reader: &mut Reader,
+) -> Result<Vec<VideoSampleEntry>, std::io::Error> {
+    let moov = read_moov(reader)?;
+    reader.seek(SeekFrom::Start(0))?;
+
+    let Some(moov) = moov else {
+        return Ok(Vec::new());
+    };
+    Ok(boxes(moov)
+        .filter(|(box_type, _)| box_type == b"trak")
+        .filter_map(|(_, trak)| read_video_sample_entry(trak))
+        .collect())
+}
+
+fn read_moov<Reader: Read + Seek>(reader: &mut Reader) -> Result<Option<Bytes>, std::io::Error> {
+    reader.seek(SeekFrom::Start(0))?;
+    loop {
+        let mut header = [0u8; 8];
+        match reader.read_exact(&mut header) {
+            Ok(()) => (),
+            Err(err) if err.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(err) => return Err(err),
+        }
+        let box_type = &header[4..8];
+        let (header_size, size) = match u32::from_be_bytes(header[0..4].try_into().unwrap()) {
+            // Box extends to the end of the file.
+            0 => {
+                let position = reader.stream_position()?;
+                let end = reader.seek(SeekFrom::End(0))?;
+                reader.seek(SeekFrom::Start(position))?;
+                (8, end - position + 8)
+            }
+            1 => {
+                let mut large_size = [0u8; 8];
+                reader.read_exact(&mut large_size)?;
+                (16, u64::from_be_bytes(large_size))
+            }
+            size => (8, size as u64),
+        };
+        if size < header_size {
+            return Ok(None);
+        }
+        let content_size = size - header_size;
+
+        if box_type == b"moov" {
+            // Box size is not trusted, reading through `take` limits the buffer to the
+            // actual length of the stream.
+            let mut content = Vec::new();
+            reader
+                .by_ref()
+                .take(content_size)
+                .read_to_end(&mut content)?;
+            if content.len() as u64 != content_size {
+                return Ok(None);
+            }
+            return Ok(Some(content.into()));
+        }
+        let Ok(offset) = i64::try_from(content_size) else {
+            return Ok(None);
+        };
+        reader.seek(SeekFrom::Current(offset))?;
+    }
+}
+
+fn read_video_sample_entry(trak: Bytes) -> Option<VideoSampleEntry> {
+    let tkhd = find_box(&trak, b"tkhd")?;
+    // Creation and modification times are 64 bit long in the version 1 of the box.
+    let track_id_offset = match tkhd.first()? {
+        1 => 20,
+        _ => 12,
+    };
+    let track_id = u32::from_be_bytes(
+        tkhd.get(track_id_offset..track_id_offset + 4)?
+            .try_into()
+            .ok()?,
+    );
+
+    let mdia = find_box(&trak, b"mdia")?;
+    // Version, flags and pre_defined fields precede the handler type.
+    if find_box(&mdia, b"hdlr")?.get(8..12)? != b"vide" {
+        return None;
+    }
+
+    let stsd = [b"minf", b"stbl", b"stsd"]
+        .into_iter()
+        .try_fold(mdia, |parent, box_type| find_box(&parent, box_type))?;
+    // Version, flags and entry count precede sample entries.
+    let (entry_type, entry) = boxes(skip(&stsd, 8)?).next()?;
+
+    Some(VideoSampleEntry {
+        track_id,
+        entry_type,
+        children: skip(&entry, VISUAL_SAMPLE_ENTRY_SIZE)?,
+    })
+}
+
+fn find_box(data: &Bytes, box_type: &[u8; 4]) -> Option<Bytes> {
+    boxes(data.clone())
+        .find_map(|(current_type, content)| (&current_type == box_type).then_some(content))
+}
+
+/// Iterates over boxes stored one after another in `data`. Returns box types and contents.
+fn boxes(mut data: Bytes) -> impl Iterator<Item = ([u8; 4], Bytes)> {
+    std::iter::from_fn(move || {
+        if data.len() < 8 {
+            return None;
+        }
+        let box_type: [u8; 4] = data[4..8].try_into().unwrap();
+        let (header_size, size) = match u32::from_be_bytes(data[0..4].try_into().unwrap()) {
+            // Box extends to the end of the parent box.
+            0 => (8, data.len()),
+            1 if data.len() >= 16 => (
+                16,
+                u64::from_be_bytes(data[8..16].try_into().unwrap()) as usize,
+            ),
+            1 => return None,
+            size => (8, size as usize),
+        };
+        if size < header_size || size > data.len() {
+            return None;
+        }
+
+        let mut content = data.split_to(size);
+        content.advance(header_size);
+        Some((box_type, content))
+    })
+}
+
+fn skip(data: &Bytes, count: usize) -> Option<Bytes> {
+    (data.len() >= count).then(|| data.slice(count..))
+}
+
+fn get_u8(data: &mut Bytes) -> Option<u8> {
+    data.has_remaining().then(|| data.get_u8())
+}
+
+fn get_u16(data: &mut Bytes) -> Option<u16> {
+    (data.remaining() >= 2).then(|| data.get_u16())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    fn mp4_box(size: u32, box_type: &[u8; 4], content: &[u8]) -> Vec<u8> {
+        [&size.to_be_bytes(), box_type.as_slice(), content].concat()
+    }
+
+    #[test]
+    fn read_moov_box() {
+        let data = [
+            mp4_box(12, b"ftyp", &[0; 4]),
+            mp4_box(12, b"moov", &[1, 2, 3, 4]),
+        ]
+        .concat();
+        let moov = read_moov(&mut Cursor::new(data)).unwrap();
+        assert_eq!(moov.as_deref(), Some([1, 2, 3, 4].as_slice()));
+    }
+
+    #[test]
+    fn read_moov_box_with_size_exceeding_stream() {
+        let data = mp4_box(u32::MAX, b"moov", &[1, 2, 3, 4]);
+        assert!(read_moov(&mut Cursor::new(data)).unwrap().is_none());
+
+        let large_size = [
+            &1u32.to_be_bytes(),
+            b"moov".as_slice(),
+            &u64::MAX.to_be_bytes(),
+        ]
+        .concat();
+        assert!(read_moov(&mut Cursor::new(large_size)).unwrap().is_none());
+    }
+}
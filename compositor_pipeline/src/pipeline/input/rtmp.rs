@@ -0,0 +1,109 @@
+use std::sync::{atomic::AtomicBool, Arc};
+
+use crate::pipeline::{
+    decoder::{AacDecoderOptions, AudioDecoderOptions, DecoderOptions, VideoDecoderOptions},
+    rtp::{BindToPortError, RequestedPort},
+    Port, VideoCodec,
+};
+use compositor_render::InputId;
+use crossbeam_channel::bounded;
+
+use self::server::start_rtmp_server_thread;
+
+use super::ChunksReceiver;
+
+mod amf0;
+mod chunk_stream;
+mod flv;
+mod server;
+
+#[derive(Debug, thiserror::Error)]
+pub enum RtmpReceiverError {
+    #[error("Error while setting socket options.")]
+    SocketOptions(#[source] std::io::Error),
+
+    #[error("Error while binding the socket.")]
+    SocketBind(#[source] std::io::Error),
+
+    #[error("Failed to register input. Port: {0} is already used or not available.")]
+    PortAlreadyInUse(u16),
+
+    #[error("Failed to register input. All ports in range {lower_bound} to {upper_bound} are already used or not available.")]
+    AllPortsAlreadyInUse { lower_bound: u16, upper_bound: u16 },
+}
+
+pub struct RtmpReceiverOptions {
+    pub port: RequestedPort,
+}
+
+/// RTMP server that accepts a single publisher, e.g. OBS. Stream key and
+/// application name are not validated.
+pub struct RtmpReceiver {
+    should_close: Arc<AtomicBool>,
+    pub port: u16,
+}
+
+impl RtmpReceiver {
+    pub fn new(
+        input_id: &InputId,
+        opts: RtmpReceiverOptions,
+    ) -> Result<(Self, ChunksReceiver, DecoderOptions, Port), RtmpReceiverError> {
+        let should_close = Arc::new(AtomicBool::new(false));
+        let (video_sender, video_receiver) = bounded(5);
+        let (audio_sender, audio_receiver) = bounded(5);
+
+        let port = start_rtmp_server_thread(
+            input_id,
+            &opts,
+            video_sender,
+            audio_sender,
+            should_close.clone(),
+        )?;
+
+        Ok((
+            Self {
+                port: port.0,
+                should_close,
+            },
+            ChunksReceiver {
+                video: Some(video_receiver),
+                audio: Some(audio_receiver),
+            },
+            DecoderOptions {
+                video: Some(VideoDecoderOptions {
+                    codec: VideoCodec::H264,
+                }),
+                // AudioSpecificConfig is not known until the publisher connects,
+                // so AAC frames are passed to the decoder with ADTS headers.
+                audio: Some(AudioDecoderOptions::Aac(AacDecoderOptions {
+                    depayloader_mode: None,
+                    asc: None,
+                })),
+            },
+            port,
+        ))
+    }
+}
+
+impl Drop for RtmpReceiver {
+    fn drop(&mut self) {
+        self.should_close
+            .store(true, std::sync::atomic::Ordering::Relaxed);
+    }
+}
+
+impl From<BindToPortError> for RtmpReceiverError {
+    fn from(value: BindToPortError) -> Self {
+        match value {
+            BindToPortError::SocketBind(err) => RtmpReceiverError::SocketBind(err),
+            BindToPortError::PortAlreadyInUse(port) => RtmpReceiverError::PortAlreadyInUse(port),
+            BindToPortError::AllPortsAlreadyInUse {
+                lower_bound,
+                upper_bound,
+            } => RtmpReceiverError::AllPortsAlreadyInUse {
+                lower_bound,
+                upper_bound,
+            },
+        }
+    }
+}
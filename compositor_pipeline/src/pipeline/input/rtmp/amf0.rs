@@ -0,0 +1,228 @@
+use bytes::{Buf, BufMut, BytesMut};
+
+const NUMBER_MARKER: u8 = 0x00;
+const BOOLEAN_MARKER: u8 = 0x01;
+const STRING_MARKER: u8 = 0x02;
+const OBJECT_MARKER: u8 = 0x03;
+const NULL_MARKER: u8 = 0x05;
+const UNDEFINED_MARKER: u8 = 0x06;
+const ECMA_ARRAY_MARKER: u8 = 0x08;
+const OBJECT_END_MARKER: u8 = 0x09;
+const STRICT_ARRAY_MARKER: u8 = 0x0A;
+const LONG_STRING_MARKER: u8 = 0x0C;
+
+/// Subset of AMF0 values used in RTMP commands.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum Amf0Value {
+    Number(f64),
+    Boolean(bool),
+    String(String),
+    Object(Vec<(String, Amf0Value)>),
+    Null,
+    Undefined,
+    EcmaArray(Vec<(String, Amf0Value)>),
+    StrictArray(Vec<Amf0Value>),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Amf0DecodeError {
+    #[error("Unexpected end of AMF0 data.")]
+    UnexpectedEnd,
+
+    #[error("Unsupported AMF0 type marker {0}.")]
+    UnsupportedMarker(u8),
+
+    #[error("AMF0 string is not valid UTF-8.")]
+    InvalidString,
+}
+
+impl Amf0Value {
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Amf0Value::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_number(&self) -> Option<f64> {
+        match self {
+            Amf0Value::Number(value) => Some(*value),
+            _ => None,
+        }
+    }
+}
+
+/// Decodes all values in the buffer, e.g. all arguments of an RTMP command.
+pub(super) fn decode(mut data: &[u8]) -> Result<Vec<Amf0Value>, Amf0DecodeError> {
+    let mut values = Vec::new();
+    while data.has_remaining() {
+        values.push(decode_value(&mut data)?);
+    }
+    Ok(values)
+}
+
+pub(super) fn encode(values: &[Amf0Value]) -> BytesMut {
+    let mut buf = BytesMut::new();
+    for value in values {
+        encode_value(value, &mut buf);
+    }
+    buf
+}
+
+fn decode_value(data: &mut &[u8]) -> Result<Amf0Value, Amf0DecodeError> {
+    let marker = read_u8(data)?;
+    let value = match marker {
+        NUMBER_MARKER => {
+            ensure_remaining(data, 8)?;
+            Amf0Value::Number(data.get_f64())
+        }
+        BOOLEAN_MARKER => Amf0Value::Boolean(read_u8(data)? != 0),
+        STRING_MARKER => {
+            ensure_remaining(data, 2)?;
+            let len = data.get_u16() as usize;
+            Amf0Value::String(read_string(data, len)?)
+        }
+        LONG_STRING_MARKER => {
+            ensure_remaining(data, 4)?;
+            let len = data.get_u32() as usize;
+            Amf0Value::String(read_string(data, len)?)
+        }
+        OBJECT_MARKER => Amf0Value::Object(decode_properties(data)?),
+        ECMA_ARRAY_MARKER => {
+            // Count is only a hint, properties are terminated with the end marker.
+            ensure_remaining(data, 4)?;
+            data.advance(4);
+            Amf0Value::EcmaArray(decode_properties(data)?)
+        }
+        STRICT_ARRAY_MARKER => {
+            ensure_remaining(data, 4)?;
+            let count = data.get_u32();
+            let values = (0..count)
+                .map(|_| decode_value(data))
+                .collect::<Result<_, _>>()?;
+            Amf0Value::StrictArray(values)
+        }
+        NULL_MARKER => Amf0Value::Null,
+        UNDEFINED_MARKER => Amf0Value::Undefined,
+        marker => return Err(Amf0DecodeError::UnsupportedMarker(marker)),
+    };
+    Ok(value)
+}
+
+fn decode_properties(data: &mut &[u8]) -> Result<Vec<(String, Amf0Value)>, Amf0DecodeError> {
+    let mut properties = Vec::new();
+    loop {
+        ensure_remaining(data, 2)?;
+        let key_len = data.get_u16() as usize;
+        if key_len == 0 {
+            if read_u8(data)? != OBJECT_END_MARKER {
+                return Err(Amf0DecodeError::UnexpectedEnd);
+            }
+            return Ok(properties);
+        }
+        let key = read_string(data, key_len)?;
+        properties.push((key, decode_value(data)?));
+    }
+}
+
+fn encode_value(value: &Amf0Value, buf: &mut BytesMut) {
+    match value {
+        Amf0Value::Number(value) => {
+            buf.put_u8(NUMBER_MARKER);
+            buf.put_f64(*value);
+        }
+        Amf0Value::Boolean(value) => {
+            buf.put_u8(BOOLEAN_MARKER);
+            buf.put_u8(*value as u8);
+        }
+        Amf0Value::String(value) if value.len() > u16::MAX as usize => {
+            buf.put_u8(LONG_STRING_MARKER);
+            buf.put_u32(value.len() as u32);
+            buf.put_slice(value.as_bytes());
+        }
+        Amf0Value::String(value) => {
+            buf.put_u8(STRING_MARKER);
+            buf.put_u16(value.len() as u16);
+            buf.put_slice(value.as_bytes());
+        }
+        Amf0Value::Object(properties) => {
+            buf.put_u8(OBJECT_MARKER);
+            encode_properties(properties, buf);
+        }
+        Amf0Value::EcmaArray(properties) => {
+            buf.put_u8(ECMA_ARRAY_MARKER);
+            buf.put_u32(properties.len() as u32);
+            encode_properties(properties, buf);
+        }
+        Amf0Value::StrictArray(values) => {
+            buf.put_u8(STRICT_ARRAY_MARKER);
+            buf.put_u32(values.len() as u32);
+            for value in values {
+                encode_value(value, buf);
+            }
+        }
+        Amf0Value::Null => buf.put_u8(NULL_MARKER),
+        Amf0Value::Undefined => buf.put_u8(UNDEFINED_MARKER),
+    }
+}
+
+fn encode_properties(properties: &[(String, Amf0Value)], buf: &mut BytesMut) {
+    for (key, value) in properties {
+        buf.put_u16(key.len() as u16);
+        buf.put_slice(key.as_bytes());
+        encode_value(value, buf);
+    }
+    buf.put_u16(0);
+    buf.put_u8(OBJECT_END_MARKER);
+}
+
+fn read_u8(data: &mut &[u8]) -> Result<u8, Amf0DecodeError> {
+    ensure_remaining(data, 1)?;
+    Ok(data.get_u8())
+}
+
+fn read_string(data: &mut &[u8], len: usize) -> Result<String, Amf0DecodeError> {
+    ensure_remaining(data, len)?;
+    let value =
+        String::from_utf8(data[..len].to_vec()).map_err(|_| Amf0DecodeError::InvalidString)?;
+    data.advance(len);
+    Ok(value)
+}
+
+fn ensure_remaining(data: &[u8], len: usize) -> Result<(), Amf0DecodeError> {
+    match data.remaining() >= len {
+        true => Ok(()),
+        false => Err(Amf0DecodeError::UnexpectedEnd),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_decode_command() {
+        let command = vec![
+            Amf0Value::String("connect".to_string()),
+            Amf0Value::Number(1.0),
+            Amf0Value::Object(vec![
+                ("app".to_string(), Amf0Value::String("live".to_string())),
+                ("fpad".to_string(), Amf0Value::Boolean(false)),
+                ("audioCodecs".to_string(), Amf0Value::Number(3575.0)),
+            ]),
+            Amf0Value::Null,
+        ];
+
+        let encoded = encode(&command);
+        assert_eq!(decode(&encoded).unwrap(), command);
+    }
+
+    #[test]
+    fn decode_truncated_data() {
+        let encoded = encode(&[Amf0Value::String("publish".to_string())]);
+        assert!(matches!(
+            decode(&encoded[..encoded.len() - 1]),
+            Err(Amf0DecodeError::UnexpectedEnd)
+        ));
+    }
+}
@@ -0,0 +1,240 @@
+use std::{
+    collections::HashMap,
+    io::{self, Read, Write},
+};
+
+use bytes::{BufMut, Bytes, BytesMut};
+
+/// Chunk size used before the peer sends the "Set Chunk Size" message.
+const DEFAULT_CHUNK_SIZE: usize = 128;
+const EXTENDED_TIMESTAMP: u32 = 0xFFFFFF;
+
+#[derive(Debug, Clone)]
+pub(super) struct RtmpMessage {
+    pub type_id: u8,
+    pub stream_id: u32,
+    /// Timestamp in milliseconds.
+    pub timestamp: u32,
+    pub payload: Bytes,
+}
+
+#[derive(Debug, Default)]
+struct ChunkStreamState {
+    timestamp: u32,
+    /// Timestamp delta, or absolute timestamp if the last header had type 0.
+    /// Chunks of type 3 that start a new message reuse this value.
+    timestamp_field: u32,
+    has_extended_timestamp: bool,
+    message_length: usize,
+    type_id: u8,
+    stream_id: u32,
+    payload: BytesMut,
+}
+
+/// Reassembles RTMP messages from chunks as described in
+/// [RTMP specification, section 5.3](https://rtmp.veriskope.com/docs/spec/#53-chunking).
+pub(super) struct ChunkReader {
+    chunk_size: usize,
+    chunk_streams: HashMap<u32, ChunkStreamState>,
+}
+
+impl ChunkReader {
+    pub fn new() -> Self {
+        Self {
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            chunk_streams: HashMap::new(),
+        }
+    }
+
+    pub fn set_chunk_size(&mut self, chunk_size: usize) {
+        self.chunk_size = chunk_size;
+    }
+
+    pub fn read_message(&mut self, reader: &mut impl Read) -> io::Result<RtmpMessage> {
+        loop {
+            if let Some(message) = self.read_chunk(reader)? {
+                return Ok(message);
+            }
+        }
+    }
+
+    fn read_chunk(&mut self, reader: &mut impl Read) -> io::Result<Option<RtmpMessage>> {
+        let first_byte = read_u8(reader)?;
+        let format = first_byte >> 6;
+        let chunk_stream_id = match first_byte & 0x3F {
+            0 => 64 + read_u8(reader)? as u32,
+            1 => 64 + read_u8(reader)? as u32 + 256 * read_u8(reader)? as u32,
+            id => id as u32,
+        };
+
+        let state = self.chunk_streams.entry(chunk_stream_id).or_default();
+        let is_new_message = state.payload.is_empty();
+
+        match format {
+            0 => {
+                let timestamp = read_u24(reader)?;
+                state.message_length = read_u24(reader)? as usize;
+                state.type_id = read_u8(reader)?;
+                state.stream_id = read_u32_le(reader)?;
+                state.has_extended_timestamp = timestamp == EXTENDED_TIMESTAMP;
+                state.timestamp_field = match state.has_extended_timestamp {
+                    true => read_u32(reader)?,
+                    false => timestamp,
+                };
+                state.timestamp = state.timestamp_field;
+            }
+            1 | 2 => {
+                let timestamp_delta = read_u24(reader)?;
+                if format == 1 {
+                    state.message_length = read_u24(reader)? as usize;
+                    state.type_id = read_u8(reader)?;
+                }
+                state.has_extended_timestamp = timestamp_delta == EXTENDED_TIMESTAMP;
+                state.timestamp_field = match state.has_extended_timestamp {
+                    true => read_u32(reader)?,
+                    false => timestamp_delta,
+                };
+                state.timestamp = state.timestamp.wrapping_add(state.timestamp_field);
+            }
+            _ => {
+                if state.has_extended_timestamp {
+                    read_u32(reader)?;
+                }
+                if is_new_message {
+                    state.timestamp = state.timestamp.wrapping_add(state.timestamp_field);
+                }
+            }
+        }
+
+        let chunk_len = usize::min(self.chunk_size, state.message_length - state.payload.len());
+        let start = state.payload.len();
+        state.payload.resize(start + chunk_len, 0);
+        reader.read_exact(&mut state.payload[start..])?;
+
+        if state.payload.len() < state.message_length {
+            return Ok(None);
+        }
+
+        Ok(Some(RtmpMessage {
+            type_id: state.type_id,
+            stream_id: state.stream_id,
+            timestamp: state.timestamp,
+            payload: state.payload.split().freeze(),
+        }))
+    }
+}
+
+/// Writes the message as a chunk with a full header followed by continuation chunks.
+pub(super) fn write_message(
+    writer: &mut impl Write,
+    chunk_stream_id: u8,
+    message: &RtmpMessage,
+    chunk_size: usize,
+) -> io::Result<()> {
+    let mut buf = BytesMut::with_capacity(message.payload.len() + 16);
+    let has_extended_timestamp = message.timestamp >= EXTENDED_TIMESTAMP;
+
+    buf.put_u8(chunk_stream_id & 0x3F);
+    put_u24(&mut buf, u32::min(message.timestamp, EXTENDED_TIMESTAMP));
+    put_u24(&mut buf, message.payload.len() as u32);
+    buf.put_u8(message.type_id);
+    buf.put_u32_le(message.stream_id);
+    if has_extended_timestamp {
+        buf.put_u32(message.timestamp);
+    }
+
+    for (i, chunk) in message.payload.chunks(chunk_size).enumerate() {
+        if i > 0 {
+            buf.put_u8(0xC0 | (chunk_stream_id & 0x3F));
+            if has_extended_timestamp {
+                buf.put_u32(message.timestamp);
+            }
+        }
+        buf.put_slice(chunk);
+    }
+
+    writer.write_all(&buf)
+}
+
+fn put_u24(buf: &mut BytesMut, value: u32) {
+    buf.put_slice(&value.to_be_bytes()[1..]);
+}
+
+fn read_u8(reader: &mut impl Read) -> io::Result<u8> {
+    let mut buf = [0u8; 1];
+    reader.read_exact(&mut buf)?;
+    Ok(buf[0])
+}
+
+fn read_u24(reader: &mut impl Read) -> io::Result<u32> {
+    let mut buf = [0u8; 4];
+    reader.read_exact(&mut buf[1..])?;
+    Ok(u32::from_be_bytes(buf))
+}
+
+fn read_u32(reader: &mut impl Read) -> io::Result<u32> {
+    let mut buf = [0u8; 4];
+    reader.read_exact(&mut buf)?;
+    Ok(u32::from_be_bytes(buf))
+}
+
+fn read_u32_le(reader: &mut impl Read) -> io::Result<u32> {
+    let mut buf = [0u8; 4];
+    reader.read_exact(&mut buf)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_and_read_chunked_message() {
+        let message = RtmpMessage {
+            type_id: 9,
+            stream_id: 1,
+            timestamp: 1234,
+            payload: (0..300).map(|i| i as u8).collect(),
+        };
+
+        let mut buf = Vec::new();
+        write_message(&mut buf, 6, &message, DEFAULT_CHUNK_SIZE).unwrap();
+        // 12 bytes of the first header, 2 continuation headers
+        assert_eq!(buf.len(), 300 + 12 + 2);
+
+        let mut reader = ChunkReader::new();
+        let read_message = reader.read_message(&mut buf.as_slice()).unwrap();
+        assert_eq!(read_message.type_id, message.type_id);
+        assert_eq!(read_message.stream_id, message.stream_id);
+        assert_eq!(read_message.timestamp, message.timestamp);
+        assert_eq!(read_message.payload, message.payload);
+    }
+
+    #[test]
+    fn timestamp_delta_for_new_message() {
+        let mut buf = Vec::new();
+        write_message(
+            &mut buf,
+            4,
+            &RtmpMessage {
+                type_id: 8,
+                stream_id: 1,
+                timestamp: 100,
+                payload: Bytes::from_static(&[1, 2]),
+            },
+            DEFAULT_CHUNK_SIZE,
+        )
+        .unwrap();
+        // Type 2 header: 20ms delta, same length and type
+        buf.extend_from_slice(&[0x80 | 4, 0, 0, 20, 3, 4]);
+        // Type 3 header: new message reuses the 20ms delta
+        buf.extend_from_slice(&[0xC0 | 4, 5, 6]);
+
+        let mut reader = ChunkReader::new();
+        let mut data = buf.as_slice();
+        let timestamps: Vec<u32> = (0..3)
+            .map(|_| reader.read_message(&mut data).unwrap().timestamp)
+            .collect();
+        assert_eq!(timestamps, vec![100, 120, 140]);
+    }
+}
@@ -0,0 +1,307 @@
+use std::time::Duration;
+
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+
+use crate::pipeline::{
+    structs::{EncodedChunk, EncodedChunkKind, IsKeyframe},
+    AudioCodec, VideoCodec,
+};
+
+const AVC_CODEC_ID: u8 = 7;
+const AAC_SOUND_FORMAT: u8 = 10;
+const KEYFRAME_FRAME_TYPE: u8 = 1;
+
+const SEQUENCE_HEADER_PACKET_TYPE: u8 = 0;
+const DATA_PACKET_TYPE: u8 = 1;
+
+const ANNEX_B_START_CODE: [u8; 4] = [0, 0, 0, 1];
+
+#[derive(Debug, thiserror::Error)]
+pub enum FlvDemuxError {
+    #[error("Unsupported FLV video codec id {0}. Only H264 is supported.")]
+    UnsupportedVideoCodec(u8),
+
+    #[error("Unsupported FLV sound format {0}. Only AAC is supported.")]
+    UnsupportedAudioCodec(u8),
+
+    #[error("Malformed FLV {0} tag.")]
+    MalformedTag(&'static str),
+
+    #[error("Received {0} data before the sequence header.")]
+    MissingSequenceHeader(&'static str),
+
+    #[error("AAC object type {0} can not be described with an ADTS header.")]
+    UnsupportedAacObjectType(u8),
+}
+
+/// Parameter sets from AVCDecoderConfigurationRecord.
+struct AvcConfig {
+    nal_length_size: usize,
+    /// SPS and PPS in Annex B format.
+    parameter_sets: Bytes,
+}
+
+/// Fields from AudioSpecificConfig needed to build ADTS headers.
+struct AacConfig {
+    object_type: u8,
+    frequency_index: u8,
+    channel_config: u8,
+}
+
+/// Converts payloads of FLV video and audio tags into chunks that can be passed to decoders.
+///
+/// H264 is converted from AVCC to Annex B format, with parameter sets prepended to each keyframe.
+/// AAC frames are prefixed with ADTS headers, so the decoder can be configured without the
+/// AudioSpecificConfig, which is only known after the publisher connects.
+#[derive(Default)]
+pub(super) struct FlvDemuxer {
+    avc_config: Option<AvcConfig>,
+    aac_config: Option<AacConfig>,
+}
+
+impl FlvDemuxer {
+    pub fn demux_video(
+        &mut self,
+        timestamp_ms: u32,
+        mut data: Bytes,
+    ) -> Result<Option<EncodedChunk>, FlvDemuxError> {
+        if data.remaining() < 5 {
+            return Err(FlvDemuxError::MalformedTag("video"));
+        }
+        let frame_type = data[0] >> 4;
+        let codec_id = data[0] & 0x0F;
+        if codec_id != AVC_CODEC_ID {
+            return Err(FlvDemuxError::UnsupportedVideoCodec(codec_id));
+        }
+        let packet_type = data[1];
+        // Composition time offset is a signed 24-bit integer.
+        let composition_time = ((data.slice(2..5).get_uint(3) as i32) << 8) >> 8;
+        data.advance(5);
+
+        match packet_type {
+            SEQUENCE_HEADER_PACKET_TYPE => {
+                self.avc_config = Some(parse_avc_config(data)?);
+                Ok(None)
+            }
+            DATA_PACKET_TYPE => {
+                let config = self
+                    .avc_config
+                    .as_ref()
+                    .ok_or(FlvDemuxError::MissingSequenceHeader("H264"))?;
+                let is_keyframe = frame_type == KEYFRAME_FRAME_TYPE;
+
+                let mut annex_b = BytesMut::with_capacity(data.len() + 64);
+                if is_keyframe {
+                    annex_b.put_slice(&config.parameter_sets);
+                }
+                while data.has_remaining() {
+                    if data.remaining() < config.nal_length_size {
+                        return Err(FlvDemuxError::MalformedTag("video"));
+                    }
+                    let nal_length = data.get_uint(config.nal_length_size) as usize;
+                    if data.remaining() < nal_length {
+                        return Err(FlvDemuxError::MalformedTag("video"));
+                    }
+                    annex_b.put_slice(&ANNEX_B_START_CODE);
+                    annex_b.put_slice(&data.split_to(nal_length));
+                }
+
+                let dts = timestamp_ms as i64;
+                let pts = i64::max(dts + composition_time as i64, 0);
+                Ok(Some(EncodedChunk {
+                    data: annex_b.freeze(),
+                    pts: Duration::from_millis(pts as u64),
+                    dts: Some(Duration::from_millis(dts as u64)),
+                    is_keyframe: match is_keyframe {
+                        true => IsKeyframe::Yes,
+                        false => IsKeyframe::No,
+                    },
+                    kind: EncodedChunkKind::Video(VideoCodec::H264),
+                }))
+            }
+            // End of sequence
+            _ => Ok(None),
+        }
+    }
+
+    pub fn demux_audio(
+        &mut self,
+        timestamp_ms: u32,
+        mut data: Bytes,
+    ) -> Result<Option<EncodedChunk>, FlvDemuxError> {
+        if data.remaining() < 2 {
+            return Err(FlvDemuxError::MalformedTag("audio"));
+        }
+        let sound_format = data[0] >> 4;
+        if sound_format != AAC_SOUND_FORMAT {
+            return Err(FlvDemuxError::UnsupportedAudioCodec(sound_format));
+        }
+        let packet_type = data[1];
+        data.advance(2);
+
+        match packet_type {
+            SEQUENCE_HEADER_PACKET_TYPE => {
+                self.aac_config = Some(parse_aac_config(&data)?);
+                Ok(None)
+            }
+            DATA_PACKET_TYPE => {
+                let config = self
+                    .aac_config
+                    .as_ref()
+                    .ok_or(FlvDemuxError::MissingSequenceHeader("AAC"))?;
+
+                let mut frame = BytesMut::with_capacity(data.len() + 7);
+                put_adts_header(&mut frame, config, data.len());
+                frame.put_slice(&data);
+
+                Ok(Some(EncodedChunk {
+                    data: frame.freeze(),
+                    pts: Duration::from_millis(timestamp_ms as u64),
+                    dts: None,
+                    is_keyframe: IsKeyframe::NoKeyframes,
+                    kind: EncodedChunkKind::Audio(AudioCodec::Aac),
+                }))
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+fn parse_avc_config(mut data: Bytes) -> Result<AvcConfig, FlvDemuxError> {
+    let malformed = || FlvDemuxError::MalformedTag("video");
+
+    if data.remaining() < 6 {
+        return Err(malformed());
+    }
+    let nal_length_size = (data[4] & 0x03) as usize + 1;
+    let sps_count = data[5] & 0x1F;
+    data.advance(6);
+
+    let mut parameter_sets = BytesMut::new();
+    let mut read_parameter_sets = |data: &mut Bytes, count: u8| {
+        for _ in 0..count {
+            if data.remaining() < 2 {
+                return Err(malformed());
+            }
+            let len = data.get_u16() as usize;
+            if data.remaining() < len {
+                return Err(malformed());
+            }
+            parameter_sets.put_slice(&ANNEX_B_START_CODE);
+            parameter_sets.put_slice(&data.split_to(len));
+        }
+        Ok(())
+    };
+
+    read_parameter_sets(&mut data, sps_count)?;
+    if !data.has_remaining() {
+        return Err(malformed());
+    }
+    let pps_count = data.get_u8();
+    read_parameter_sets(&mut data, pps_count)?;
+
+    Ok(AvcConfig {
+        nal_length_size,
+        parameter_sets: parameter_sets.freeze(),
+    })
+}
+
+fn parse_aac_config(data: &[u8]) -> Result<AacConfig, FlvDemuxError> {
+    if data.len() < 2 {
+        return Err(FlvDemuxError::MalformedTag("audio"));
+    }
+    let object_type = data[0] >> 3;
+    let frequency_index = ((data[0] & 0x07) << 1) | (data[1] >> 7);
+    let channel_config = (data[1] >> 3) & 0x0F;
+
+    // ADTS profile field is 2 bits wide and stores object type minus one.
+    // Explicit frequency (index 15) can not be expressed either.
+    if !(1..=4).contains(&object_type) || frequency_index > 12 {
+        return Err(FlvDemuxError::UnsupportedAacObjectType(object_type));
+    }
+
+    Ok(AacConfig {
+        object_type,
+        frequency_index,
+        channel_config,
+    })
+}
+
+fn put_adts_header(buf: &mut BytesMut, config: &AacConfig, payload_len: usize) {
+    let frame_len = payload_len + 7;
+    buf.put_u8(0xFF);
+    // MPEG-4, layer 0, no CRC
+    buf.put_u8(0xF1);
+    buf.put_u8(
+        ((config.object_type - 1) << 6)
+            | (config.frequency_index << 2)
+            | ((config.channel_config >> 2) & 0x01),
+    );
+    buf.put_u8(((config.channel_config & 0x03) << 6) | ((frame_len >> 11) & 0x03) as u8);
+    buf.put_u8(((frame_len >> 3) & 0xFF) as u8);
+    // Buffer fullness is set to 0x7FF (variable bitrate)
+    buf.put_u8((((frame_len & 0x07) << 5) as u8) | 0x1F);
+    buf.put_u8(0xFC);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn avcc_to_annex_b() {
+        let mut demuxer = FlvDemuxer::default();
+        let sequence_header = Bytes::from_static(&[
+            0x17, 0, 0, 0, 0, // tag header
+            1, 0x64, 0, 0x1F,
+            0xFF, // avcC version, profile, compatibility, level, length size
+            0xE1, 0, 2, 0x67, 1, // SPS
+            1, 0, 1, 0x68, // PPS
+        ]);
+        assert!(demuxer.demux_video(0, sequence_header).unwrap().is_none());
+
+        let keyframe = Bytes::from_static(&[
+            0x17, 1, 0, 0, 40, // tag header, composition time 40ms
+            0, 0, 0, 2, 0x65, 2, // IDR slice
+        ]);
+        let chunk = demuxer.demux_video(100, keyframe).unwrap().unwrap();
+        assert_eq!(
+            chunk.data,
+            Bytes::from_static(&[0, 0, 0, 1, 0x67, 1, 0, 0, 0, 1, 0x68, 0, 0, 0, 1, 0x65, 2])
+        );
+        assert_eq!(chunk.pts, Duration::from_millis(140));
+        assert_eq!(chunk.dts, Some(Duration::from_millis(100)));
+        assert_eq!(chunk.is_keyframe, IsKeyframe::Yes);
+
+        let frame = Bytes::from_static(&[0x27, 1, 0, 0, 0, 0, 0, 0, 1, 0x41]);
+        let chunk = demuxer.demux_video(133, frame).unwrap().unwrap();
+        assert_eq!(chunk.data, Bytes::from_static(&[0, 0, 0, 1, 0x41]));
+        assert_eq!(chunk.is_keyframe, IsKeyframe::No);
+    }
+
+    #[test]
+    fn aac_with_adts_header() {
+        let mut demuxer = FlvDemuxer::default();
+        // AAC-LC, 48kHz, stereo
+        let sequence_header = Bytes::from_static(&[0xAF, 0, 0x11, 0x90]);
+        assert!(demuxer.demux_audio(0, sequence_header).unwrap().is_none());
+
+        let frame = Bytes::from_static(&[0xAF, 1, 0xAA, 0xBB]);
+        let chunk = demuxer.demux_audio(21, frame).unwrap().unwrap();
+        assert_eq!(
+            chunk.data,
+            Bytes::from_static(&[0xFF, 0xF1, 0x4C, 0x80, 0x01, 0x3F, 0xFC, 0xAA, 0xBB])
+        );
+        assert_eq!(chunk.pts, Duration::from_millis(21));
+    }
+
+    #[test]
+    fn data_before_sequence_header() {
+        let mut demuxer = FlvDemuxer::default();
+        let frame = Bytes::from_static(&[0xAF, 1, 0xAA]);
+        assert!(matches!(
+            demuxer.demux_audio(0, frame),
+            Err(FlvDemuxError::MissingSequenceHeader(_))
+        ));
+    }
+}
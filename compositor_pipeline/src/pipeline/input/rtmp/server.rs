@@ -0,0 +1,455 @@
+use std::{
+    io::{self, Read, Write},
+    net::{TcpListener, TcpStream},
+    sync::{atomic::AtomicBool, Arc},
+    thread,
+    time::Duration,
+};
+
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use compositor_render::{error::ErrorStack, InputId};
+use crossbeam_channel::Sender;
+use tracing::{debug, info, span, trace, warn, Level};
+
+use crate::{
+    pipeline::{rtp::bind_to_requested_port, structs::EncodedChunk, Port},
+    queue::PipelineEvent,
+};
+
+use super::{
+    amf0::{self, Amf0Value},
+    chunk_stream::{write_message, ChunkReader, RtmpMessage},
+    flv::FlvDemuxer,
+    RtmpReceiverError, RtmpReceiverOptions,
+};
+
+const HANDSHAKE_SIZE: usize = 1536;
+const RTMP_VERSION: u8 = 3;
+
+const SERVER_CHUNK_SIZE: usize = 4096;
+const WINDOW_ACK_SIZE: u32 = 2_500_000;
+
+/// Chunk stream used for protocol control messages.
+const CONTROL_CHUNK_STREAM_ID: u8 = 2;
+/// Chunk stream used for command responses.
+const COMMAND_CHUNK_STREAM_ID: u8 = 3;
+/// Stream id assigned to the stream created with the `createStream` command.
+const PUBLISH_STREAM_ID: u32 = 1;
+
+mod message_type {
+    pub const SET_CHUNK_SIZE: u8 = 1;
+    pub const ACKNOWLEDGEMENT: u8 = 3;
+    pub const WINDOW_ACK_SIZE: u8 = 5;
+    pub const SET_PEER_BANDWIDTH: u8 = 6;
+    pub const AUDIO: u8 = 8;
+    pub const VIDEO: u8 = 9;
+    pub const AMF3_COMMAND: u8 = 17;
+    pub const AMF0_COMMAND: u8 = 20;
+}
+
+pub(super) fn start_rtmp_server_thread(
+    input_id: &InputId,
+    opts: &RtmpReceiverOptions,
+    video_sender: Sender<PipelineEvent<EncodedChunk>>,
+    audio_sender: Sender<PipelineEvent<EncodedChunk>>,
+    should_close: Arc<AtomicBool>,
+) -> Result<Port, RtmpReceiverError> {
+    let input_id = input_id.clone();
+    info!(?input_id, "Starting RTMP server");
+
+    let socket = socket2::Socket::new(
+        socket2::Domain::IPV4,
+        socket2::Type::STREAM,
+        Some(socket2::Protocol::TCP),
+    )
+    .map_err(RtmpReceiverError::SocketOptions)?;
+
+    let port = bind_to_requested_port(opts.port, &socket)?;
+
+    socket.listen(1).map_err(RtmpReceiverError::SocketBind)?;
+
+    let listener = TcpListener::from(socket);
+
+    thread::Builder::new()
+        .name(format!("RTMP server {}", input_id))
+        .spawn(move || {
+            let _span =
+                span!(Level::INFO, "RTMP server", input_id = input_id.to_string()).entered();
+            run_rtmp_server_thread(listener, video_sender, audio_sender, should_close);
+            debug!("Closing RTMP server thread.");
+        })
+        .unwrap();
+
+    Ok(port)
+}
+
+fn run_rtmp_server_thread(
+    listener: TcpListener,
+    video_sender: Sender<PipelineEvent<EncodedChunk>>,
+    audio_sender: Sender<PipelineEvent<EncodedChunk>>,
+    should_close: Arc<AtomicBool>,
+) {
+    // make accept non blocking so we have a chance to handle should_close value
+    listener
+        .set_nonblocking(true)
+        .expect("Cannot set non-blocking");
+
+    let mut connected_socket = None;
+    while !should_close.load(std::sync::atomic::Ordering::Relaxed) && connected_socket.is_none() {
+        // accept only one publisher
+        let Ok((socket, addr)) = listener.accept() else {
+            thread::sleep(Duration::from_millis(50));
+            continue;
+        };
+        info!(%addr, "RTMP publisher connected.");
+        connected_socket = Some(socket);
+    }
+
+    let Some(socket) = connected_socket else {
+        return;
+    };
+
+    let mut session = match RtmpSession::new(socket, should_close, video_sender, audio_sender) {
+        Ok(session) => session,
+        Err(err) => {
+            warn!(
+                "Failed to start RTMP session: {}",
+                ErrorStack::new(&err).into_string()
+            );
+            return;
+        }
+    };
+    match session.run() {
+        Ok(()) => info!("RTMP publisher finished the stream."),
+        Err(err) if session.should_close() => {
+            debug!(
+                "RTMP session closed: {}",
+                ErrorStack::new(&err).into_string()
+            )
+        }
+        Err(err) => warn!(
+            "RTMP publisher disconnected: {}",
+            ErrorStack::new(&err).into_string()
+        ),
+    }
+    session.send_eos();
+}
+
+#[derive(Debug, thiserror::Error)]
+enum RtmpSessionError {
+    #[error("Failed to read from or write to the TCP socket.")]
+    Io(#[from] io::Error),
+
+    #[error("Unsupported RTMP version {0}.")]
+    UnsupportedVersion(u8),
+
+    #[error("Failed to decode RTMP command.")]
+    InvalidCommand(#[from] amf0::Amf0DecodeError),
+
+    #[error("Input was closed.")]
+    InputClosed,
+}
+
+struct RtmpSession {
+    reader: SocketReader,
+    writer: TcpStream,
+    chunk_reader: ChunkReader,
+    demuxer: FlvDemuxer,
+    last_acknowledged_bytes: u64,
+    video_sender: Sender<PipelineEvent<EncodedChunk>>,
+    audio_sender: Sender<PipelineEvent<EncodedChunk>>,
+}
+
+impl RtmpSession {
+    fn new(
+        socket: TcpStream,
+        should_close: Arc<AtomicBool>,
+        video_sender: Sender<PipelineEvent<EncodedChunk>>,
+        audio_sender: Sender<PipelineEvent<EncodedChunk>>,
+    ) -> io::Result<Self> {
+        socket.set_nonblocking(false)?;
+        socket.set_read_timeout(Some(Duration::from_millis(50)))?;
+        socket.set_nodelay(true)?;
+        let writer = socket.try_clone()?;
+
+        Ok(Self {
+            reader: SocketReader {
+                socket,
+                should_close,
+                bytes_read: 0,
+            },
+            writer,
+            chunk_reader: ChunkReader::new(),
+            demuxer: FlvDemuxer::default(),
+            last_acknowledged_bytes: 0,
+            video_sender,
+            audio_sender,
+        })
+    }
+
+    fn should_close(&self) -> bool {
+        self.reader
+            .should_close
+            .load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// Returns when the publisher ends the stream or an error occurs.
+    fn run(&mut self) -> Result<(), RtmpSessionError> {
+        self.handshake()?;
+
+        loop {
+            let message = self.chunk_reader.read_message(&mut self.reader)?;
+            self.maybe_send_acknowledgement()?;
+
+            match message.type_id {
+                message_type::SET_CHUNK_SIZE if message.payload.len() >= 4 => {
+                    let chunk_size = message.payload.clone().get_u32() & 0x7FFFFFFF;
+                    self.chunk_reader.set_chunk_size(chunk_size as usize);
+                }
+                message_type::VIDEO => {
+                    match self.demuxer.demux_video(message.timestamp, message.payload) {
+                        Ok(Some(chunk)) => self.send_chunk(&self.video_sender, chunk)?,
+                        Ok(None) => (),
+                        Err(err) => warn!("Failed to demux FLV video: {err}"),
+                    }
+                }
+                message_type::AUDIO => {
+                    match self.demuxer.demux_audio(message.timestamp, message.payload) {
+                        Ok(Some(chunk)) => self.send_chunk(&self.audio_sender, chunk)?,
+                        Ok(None) => (),
+                        Err(err) => warn!("Failed to demux FLV audio: {err}"),
+                    }
+                }
+                message_type::AMF0_COMMAND => {
+                    if !self.handle_command(&message, message.payload.clone())? {
+                        return Ok(());
+                    }
+                }
+                message_type::AMF3_COMMAND if !message.payload.is_empty() => {
+                    // AMF3 command messages start with a format byte followed by AMF0 data.
+                    if !self.handle_command(&message, message.payload.slice(1..))? {
+                        return Ok(());
+                    }
+                }
+                type_id => trace!(type_id, "Ignoring RTMP message."),
+            }
+        }
+    }
+
+    fn handshake(&mut self) -> Result<(), RtmpSessionError> {
+        let mut c0_c1 = [0u8; HANDSHAKE_SIZE + 1];
+        self.reader.read_exact(&mut c0_c1)?;
+        if c0_c1[0] != RTMP_VERSION {
+            return Err(RtmpSessionError::UnsupportedVersion(c0_c1[0]));
+        }
+
+        // S0, S1 with zero time and version, S2 echoing C1
+        let mut response = BytesMut::with_capacity(2 * HANDSHAKE_SIZE + 1);
+        response.put_u8(RTMP_VERSION);
+        response.put_bytes(0, HANDSHAKE_SIZE);
+        response.put_slice(&c0_c1[1..]);
+        self.writer.write_all(&response)?;
+
+        let mut c2 = [0u8; HANDSHAKE_SIZE];
+        self.reader.read_exact(&mut c2)?;
+        Ok(())
+    }
+
+    /// Returns `false` if the publisher ended the stream.
+    fn handle_command(
+        &mut self,
+        message: &RtmpMessage,
+        payload: Bytes,
+    ) -> Result<bool, RtmpSessionError> {
+        let command = amf0::decode(&payload)?;
+        let name = command.first().and_then(Amf0Value::as_str).unwrap_or("");
+        let transaction_id = command.get(1).and_then(Amf0Value::as_number).unwrap_or(0.0);
+        debug!(name, transaction_id, "Received RTMP command.");
+
+        match name {
+            "connect" => {
+                self.send_control(
+                    message_type::WINDOW_ACK_SIZE,
+                    &WINDOW_ACK_SIZE.to_be_bytes(),
+                )?;
+                // Dynamic limit type
+                let mut peer_bandwidth = WINDOW_ACK_SIZE.to_be_bytes().to_vec();
+                peer_bandwidth.push(2);
+                self.send_control(message_type::SET_PEER_BANDWIDTH, &peer_bandwidth)?;
+                self.send_control(
+                    message_type::SET_CHUNK_SIZE,
+                    &(SERVER_CHUNK_SIZE as u32).to_be_bytes(),
+                )?;
+
+                self.send_command(
+                    0,
+                    &[
+                        Amf0Value::String("_result".to_string()),
+                        Amf0Value::Number(transaction_id),
+                        object(&[
+                            ("fmsVer", Amf0Value::String("FMS/3,0,1,123".to_string())),
+                            ("capabilities", Amf0Value::Number(31.0)),
+                        ]),
+                        status_object("NetConnection.Connect.Success", "Connection succeeded."),
+                    ],
+                )?;
+            }
+            "createStream" => {
+                self.send_command(
+                    0,
+                    &[
+                        Amf0Value::String("_result".to_string()),
+                        Amf0Value::Number(transaction_id),
+                        Amf0Value::Null,
+                        Amf0Value::Number(PUBLISH_STREAM_ID as f64),
+                    ],
+                )?;
+            }
+            "publish" => {
+                self.send_command(
+                    message.stream_id,
+                    &[
+                        Amf0Value::String("onStatus".to_string()),
+                        Amf0Value::Number(0.0),
+                        Amf0Value::Null,
+                        status_object("NetStream.Publish.Start", "Publishing stream."),
+                    ],
+                )?;
+            }
+            "FCUnpublish" | "deleteStream" | "closeStream" => return Ok(false),
+            // Commands like "releaseStream" or "FCPublish" do not require any action,
+            // but some clients wait for the response.
+            _ if transaction_id > 0.0 => {
+                self.send_command(
+                    0,
+                    &[
+                        Amf0Value::String("_result".to_string()),
+                        Amf0Value::Number(transaction_id),
+                        Amf0Value::Null,
+                        Amf0Value::Undefined,
+                    ],
+                )?;
+            }
+            _ => (),
+        }
+        Ok(true)
+    }
+
+    fn send_chunk(
+        &self,
+        sender: &Sender<PipelineEvent<EncodedChunk>>,
+        chunk: EncodedChunk,
+    ) -> Result<(), RtmpSessionError> {
+        trace!(pts=?chunk.pts, kind=?chunk.kind, "Received RTMP chunk.");
+        sender
+            .send(PipelineEvent::Data(chunk))
+            .map_err(|_| RtmpSessionError::InputClosed)
+    }
+
+    fn send_eos(&self) {
+        if self.video_sender.send(PipelineEvent::EOS).is_err() {
+            debug!("Failed to send EOS from RTMP video receiver. Channel closed.");
+        }
+        if self.audio_sender.send(PipelineEvent::EOS).is_err() {
+            debug!("Failed to send EOS from RTMP audio receiver. Channel closed.");
+        }
+    }
+
+    fn maybe_send_acknowledgement(&mut self) -> io::Result<()> {
+        let bytes_read = self.reader.bytes_read;
+        if bytes_read - self.last_acknowledged_bytes < WINDOW_ACK_SIZE as u64 {
+            return Ok(());
+        }
+        self.last_acknowledged_bytes = bytes_read;
+        // Sequence number wraps around after 4GB
+        self.send_control(
+            message_type::ACKNOWLEDGEMENT,
+            &(bytes_read as u32).to_be_bytes(),
+        )
+    }
+
+    fn send_control(&mut self, type_id: u8, payload: &[u8]) -> io::Result<()> {
+        let message = RtmpMessage {
+            type_id,
+            stream_id: 0,
+            timestamp: 0,
+            payload: Bytes::copy_from_slice(payload),
+        };
+        write_message(
+            &mut self.writer,
+            CONTROL_CHUNK_STREAM_ID,
+            &message,
+            SERVER_CHUNK_SIZE,
+        )
+    }
+
+    fn send_command(&mut self, stream_id: u32, values: &[Amf0Value]) -> io::Result<()> {
+        let message = RtmpMessage {
+            type_id: message_type::AMF0_COMMAND,
+            stream_id,
+            timestamp: 0,
+            payload: amf0::encode(values).freeze(),
+        };
+        write_message(
+            &mut self.writer,
+            COMMAND_CHUNK_STREAM_ID,
+            &message,
+            SERVER_CHUNK_SIZE,
+        )
+    }
+}
+
+fn object(properties: &[(&str, Amf0Value)]) -> Amf0Value {
+    Amf0Value::Object(
+        properties
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.clone()))
+            .collect(),
+    )
+}
+
+fn status_object(code: &str, description: &str) -> Amf0Value {
+    object(&[
+        ("level", Amf0Value::String("status".to_string())),
+        ("code", Amf0Value::String(code.to_string())),
+        ("description", Amf0Value::String(description.to_string())),
+        ("objectEncoding", Amf0Value::Number(0.0)),
+    ])
+}
+
+/// Blocking reader that retries on read timeouts until the input is closed.
+struct SocketReader {
+    socket: TcpStream,
+    should_close: Arc<AtomicBool>,
+    bytes_read: u64,
+}
+
+impl Read for SocketReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            match self.socket.read(buf) {
+                Ok(0) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        "Connection closed by the publisher.",
+                    ))
+                }
+                Ok(read_bytes) => {
+                    self.bytes_read += read_bytes as u64;
+                    return Ok(read_bytes);
+                }
+                Err(err)
+                    if matches!(
+                        err.kind(),
+                        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                    ) =>
+                {
+                    if self.should_close.load(std::sync::atomic::Ordering::Relaxed) {
+                        return Err(err);
+                    }
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+}
@@ -24,7 +24,7 @@ use self::{
 
 use super::ChunksReceiver;
 
-mod depayloader;
+pub(super) mod depayloader;
 mod tcp_server;
 mod udp;
 
@@ -112,7 +112,7 @@ impl RtpReceiver {
         ))
     }
 
-    fn start_depayloader_thread(
+    pub(super) fn start_depayloader_thread(
         input_id: &InputId,
         receiver: Receiver<bytes::Bytes>,
         depayloader: Depayloader,
@@ -268,6 +268,10 @@ pub enum DepayloadingError {
     Rtp(#[from] rtp::Error),
     #[error("AAC depayoading error")]
     Aac(#[from] depayloader::AacDepayloadingError),
+    #[error("H265 depayloading error")]
+    H265(#[from] depayloader::H265DepayloadingError),
+    #[error("AV1 depayloading error")]
+    Av1(#[from] depayloader::Av1DepayloadingError),
 }
 
 impl From<BindToPortError> for RtpReceiverError {
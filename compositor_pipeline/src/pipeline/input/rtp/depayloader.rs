@@ -3,14 +3,14 @@ use std::{mem, time::Duration};
 use bytes::Bytes;
 use log::error;
 use rtp::{
-    codecs::{h264::H264Packet, opus::OpusPacket},
+    codecs::{h264::H264Packet, opus::OpusPacket, vp8::Vp8Packet, vp9::Vp9Packet},
     packetizer::Depacketizer,
 };
 
 use crate::pipeline::{
     decoder::{self, AacDecoderOptions},
     rtp::{AUDIO_PAYLOAD_TYPE, VIDEO_PAYLOAD_TYPE},
-    structs::{AudioCodec, EncodedChunk, EncodedChunkKind, VideoCodec},
+    structs::{AudioCodec, EncodedChunk, EncodedChunkKind, IsKeyframe, VideoCodec},
 };
 
 use self::aac::AacDepayloaderNewError;
@@ -18,8 +18,12 @@ use self::aac::AacDepayloaderNewError;
 use super::{DepayloadingError, RtpStream};
 
 pub use aac::{AacDepayloader, AacDepayloadingError};
+pub use av1::{Av1Depayloader, Av1DepayloadingError};
+pub use h265::{H265Depayloader, H265DepayloadingError};
 
 mod aac;
+mod av1;
+mod h265;
 
 #[derive(Debug, thiserror::Error)]
 pub enum DepayloaderNewError {
@@ -77,6 +81,18 @@ pub enum VideoDepayloader {
         buffer: Vec<Bytes>,
         rollover_state: RolloverState,
     },
+    H265(H265Depayloader),
+    Vp8 {
+        depayloader: Vp8Packet,
+        buffer: Vec<Bytes>,
+        rollover_state: RolloverState,
+    },
+    Vp9 {
+        depayloader: Vp9Packet,
+        buffer: Vec<Bytes>,
+        rollover_state: RolloverState,
+    },
+    Av1(Av1Depayloader),
 }
 
 impl VideoDepayloader {
@@ -87,6 +103,18 @@ impl VideoDepayloader {
                 buffer: vec![],
                 rollover_state: RolloverState::default(),
             },
+            VideoCodec::H265 => VideoDepayloader::H265(H265Depayloader::default()),
+            VideoCodec::Vp8 => VideoDepayloader::Vp8 {
+                depayloader: Vp8Packet::default(),
+                buffer: vec![],
+                rollover_state: RolloverState::default(),
+            },
+            VideoCodec::Vp9 => VideoDepayloader::Vp9 {
+                depayloader: Vp9Packet::default(),
+                buffer: vec![],
+                rollover_state: RolloverState::default(),
+            },
+            VideoCodec::Av1 => VideoDepayloader::Av1(Av1Depayloader::default()),
         }
     }
 
@@ -99,32 +127,64 @@ impl VideoDepayloader {
                 depayloader,
                 buffer,
                 rollover_state,
+            } => depayload_video_frame(
+                depayloader,
+                buffer,
+                rollover_state,
+                packet,
+                VideoCodec::H264,
+            ),
+            VideoDepayloader::H265(h265) => Ok(h265.depayload(packet)?),
+            VideoDepayloader::Vp8 {
+                depayloader,
+                buffer,
+                rollover_state,
             } => {
-                let kind = EncodedChunkKind::Video(VideoCodec::H264);
-                let h264_chunk = depayloader.depacketize(&packet.payload)?;
+                depayload_video_frame(depayloader, buffer, rollover_state, packet, VideoCodec::Vp8)
+            }
+            VideoDepayloader::Vp9 {
+                depayloader,
+                buffer,
+                rollover_state,
+            } => {
+                depayload_video_frame(depayloader, buffer, rollover_state, packet, VideoCodec::Vp9)
+            }
+            VideoDepayloader::Av1(av1) => Ok(av1.depayload(packet)?),
+        }
+    }
+}
 
-                if h264_chunk.is_empty() {
-                    return Ok(Vec::new());
-                }
+/// Buffers depacketized payloads until the last packet of a frame is received.
+/// H264, VP8 and VP9 payload formats use a 90kHz clock and set the marker bit
+/// on the last packet of a frame.
+fn depayload_video_frame<T: Depacketizer>(
+    depayloader: &mut T,
+    buffer: &mut Vec<Bytes>,
+    rollover_state: &mut RolloverState,
+    packet: rtp::packet::Packet,
+    codec: VideoCodec,
+) -> Result<Vec<EncodedChunk>, DepayloadingError> {
+    let chunk = depayloader.depacketize(&packet.payload)?;
+
+    if chunk.is_empty() {
+        return Ok(Vec::new());
+    }
 
-                buffer.push(h264_chunk);
-                if !packet.header.marker {
-                    // the marker bit is set on the last packet of an access unit
-                    return Ok(Vec::new());
-                }
+    buffer.push(chunk);
+    if !packet.header.marker {
+        return Ok(Vec::new());
+    }
 
-                let timestamp = rollover_state.timestamp(packet.header.timestamp);
-                let new_chunk = EncodedChunk {
-                    data: mem::take(buffer).concat().into(),
-                    pts: Duration::from_secs_f64(timestamp as f64 / 90000.0),
-                    dts: None,
-                    kind,
-                };
+    let timestamp = rollover_state.timestamp(packet.header.timestamp);
+    let new_chunk = EncodedChunk {
+        data: mem::take(buffer).concat().into(),
+        pts: Duration::from_secs_f64(timestamp as f64 / 90000.0),
+        dts: None,
+        is_keyframe: IsKeyframe::Unknown,
+        kind: EncodedChunkKind::Video(codec),
+    };
 
-                Ok(vec![new_chunk])
-            }
-        }
-    }
+    Ok(vec![new_chunk])
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -186,6 +246,7 @@ impl AudioDepayloader {
                     data: opus_packet,
                     pts: Duration::from_secs_f64(timestamp as f64 / 48000.0),
                     dts: None,
+                    is_keyframe: IsKeyframe::NoKeyframes,
                     kind,
                 }])
             }
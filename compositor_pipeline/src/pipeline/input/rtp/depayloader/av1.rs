@@ -0,0 +1,167 @@
+use std::time::Duration;
+
+use bytes::{Buf, Bytes, BytesMut};
+use tracing::debug;
+
+use crate::pipeline::{
+    rtp::av1::{
+        read_leb128, Obu, ObuParseError, AGGREGATION_HEADER_N, AGGREGATION_HEADER_W_SHIFT,
+        AGGREGATION_HEADER_Y, AGGREGATION_HEADER_Z, OBU_TYPE_TEMPORAL_DELIMITER,
+    },
+    structs::{EncodedChunk, EncodedChunkKind, IsKeyframe},
+    VideoCodec,
+};
+
+use super::RolloverState;
+
+/// Temporal delimiter OBU with the size field, it starts every temporal unit.
+const TEMPORAL_DELIMITER: [u8; 2] = [0b0001_0010, 0];
+
+#[derive(Debug, thiserror::Error)]
+pub enum Av1DepayloadingError {
+    #[error("Packet too short")]
+    PacketTooShort,
+
+    #[error("Invalid OBU element")]
+    InvalidObu(#[from] ObuParseError),
+}
+
+/// Depayloader for the [AV1 RTP payload format](https://aomediacodec.github.io/av1-rtp-spec/).
+/// Produces temporal units in the low overhead bitstream format, i.e. OBUs with size fields.
+#[derive(Default)]
+pub struct Av1Depayloader {
+    /// OBUs of the temporal unit that is currently received.
+    temporal_unit: BytesMut,
+    /// Beginning of the OBU that is fragmented over multiple packets.
+    fragment: Option<BytesMut>,
+    is_keyframe: bool,
+    rollover_state: RolloverState,
+}
+
+impl Av1Depayloader {
+    pub fn depayload(
+        &mut self,
+        packet: rtp::packet::Packet,
+    ) -> Result<Vec<EncodedChunk>, Av1DepayloadingError> {
+        let mut payload = packet.payload;
+        if !payload.has_remaining() {
+            return Err(Av1DepayloadingError::PacketTooShort);
+        }
+        let aggregation_header = payload.get_u8();
+        let starts_with_fragment = aggregation_header & AGGREGATION_HEADER_Z != 0;
+        let ends_with_fragment = aggregation_header & AGGREGATION_HEADER_Y != 0;
+        let elements_count = ((aggregation_header >> AGGREGATION_HEADER_W_SHIFT) & 0b11) as usize;
+        if aggregation_header & AGGREGATION_HEADER_N != 0 {
+            self.is_keyframe = true;
+        }
+
+        let mut elements = Vec::new();
+        while payload.has_remaining() {
+            // If W is set, the last element does not have the length field.
+            let element_len = match elements_count {
+                0 => read_leb128(&mut payload)? as usize,
+                count if elements.len() + 1 < count => read_leb128(&mut payload)? as usize,
+                _ => payload.len(),
+            };
+            if element_len > payload.len() {
+                return Err(Av1DepayloadingError::PacketTooShort);
+            }
+            elements.push(payload.split_to(element_len));
+        }
+
+        if !starts_with_fragment && self.fragment.take().is_some() {
+            debug!("Dropping incomplete OBU. Packet with its end was lost.");
+        }
+
+        let last_index = elements.len().saturating_sub(1);
+        for (index, element) in elements.into_iter().enumerate() {
+            let obu = match (index, starts_with_fragment) {
+                (0, true) => match self.fragment.take() {
+                    Some(mut fragment) => {
+                        fragment.extend_from_slice(&element);
+                        fragment
+                    }
+                    None => {
+                        debug!("Dropping incomplete OBU. Packet with its beginning was lost.");
+                        continue;
+                    }
+                },
+                _ => BytesMut::from(&element[..]),
+            };
+
+            if index == last_index && ends_with_fragment {
+                self.fragment = Some(obu);
+            } else {
+                self.push_obu(obu.freeze())?;
+            }
+        }
+
+        if !packet.header.marker || self.temporal_unit.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut data = BytesMut::from(&TEMPORAL_DELIMITER[..]);
+        data.extend_from_slice(&self.temporal_unit.split());
+        let timestamp = self.rollover_state.timestamp(packet.header.timestamp);
+        let is_keyframe = match std::mem::take(&mut self.is_keyframe) {
+            true => IsKeyframe::Yes,
+            false => IsKeyframe::Unknown,
+        };
+
+        Ok(vec![EncodedChunk {
+            data: data.freeze(),
+            pts: Duration::from_secs_f64(timestamp as f64 / 90000.0),
+            dts: None,
+            is_keyframe,
+            kind: EncodedChunkKind::Video(VideoCodec::Av1),
+        }])
+    }
+
+    /// Appends the OBU to the temporal unit with the size field added.
+    fn push_obu(&mut self, mut obu: Bytes) -> Result<(), Av1DepayloadingError> {
+        let obu = Obu::read(&mut obu)?;
+        if obu.obu_type() != OBU_TYPE_TEMPORAL_DELIMITER {
+            obu.write_with_size(&mut self.temporal_unit);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn packet(payload: &[u8], marker: bool) -> rtp::packet::Packet {
+        rtp::packet::Packet {
+            header: rtp::header::Header {
+                marker,
+                timestamp: 90000,
+                ..Default::default()
+            },
+            payload: Bytes::copy_from_slice(payload),
+        }
+    }
+
+    #[test]
+    fn depayload_fragmented_obu() {
+        let mut depayloader = Av1Depayloader::default();
+
+        // N=1, W=2, sequence header with a length field and the beginning of the frame
+        let first_packet = [0x68, 3, 0x08, 0xa1, 0xa2, 0x30, 0xb1, 0xb2, 0xb3];
+        let chunks = depayloader.depayload(packet(&first_packet, false)).unwrap();
+        assert!(chunks.is_empty());
+
+        // Z=1, W=1, rest of the frame
+        let second_packet = [0x90, 0xb4, 0xb5, 0xb6];
+        let chunks = depayloader.depayload(packet(&second_packet, true)).unwrap();
+
+        let temporal_delimiter = [0x12, 0];
+        let sequence_header = [0x0a, 2, 0xa1, 0xa2];
+        let frame = [0x32, 6, 0xb1, 0xb2, 0xb3, 0xb4, 0xb5, 0xb6];
+        let temporal_unit = [&temporal_delimiter[..], &sequence_header, &frame].concat();
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].data, temporal_unit);
+        assert_eq!(chunks[0].pts, Duration::from_secs(1));
+        assert_eq!(chunks[0].is_keyframe, IsKeyframe::Yes);
+    }
+}
@@ -0,0 +1,205 @@
+use std::time::Duration;
+
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use tracing::debug;
+
+use crate::pipeline::{
+    structs::{EncodedChunk, EncodedChunkKind, IsKeyframe},
+    VideoCodec,
+};
+
+use super::RolloverState;
+
+const NAL_UNIT_HEADER_SIZE: usize = 2;
+const FU_HEADER_SIZE: usize = 1;
+
+const AGGREGATION_PACKET: u8 = 48;
+const FRAGMENTATION_UNIT: u8 = 49;
+const PACI_PACKET: u8 = 50;
+/// NAL unit types of IRAP (intra random access point) pictures.
+const IRAP_NAL_UNIT_TYPES: std::ops::RangeInclusive<u8> = 16..=21;
+
+const ANNEX_B_START_CODE: [u8; 4] = [0, 0, 0, 1];
+
+#[derive(Debug, thiserror::Error)]
+pub enum H265DepayloadingError {
+    #[error("Packet too short")]
+    PacketTooShort,
+
+    #[error("PACI packets are not supported")]
+    PaciNotSupported,
+}
+
+/// Depayloader for the [H265 RTP payload format](https://datatracker.ietf.org/doc/html/rfc7798).
+/// Produces access units in the Annex B format. DONL fields are not supported, so streams
+/// have to be sent with `sprop-max-don-diff` equal to 0 (the default).
+#[derive(Default)]
+pub struct H265Depayloader {
+    /// NAL units of the access unit that is currently received, in the Annex B format.
+    access_unit: BytesMut,
+    /// NAL unit that is fragmented over multiple packets.
+    fragment: Option<BytesMut>,
+    is_keyframe: bool,
+    rollover_state: RolloverState,
+}
+
+impl H265Depayloader {
+    pub fn depayload(
+        &mut self,
+        packet: rtp::packet::Packet,
+    ) -> Result<Vec<EncodedChunk>, H265DepayloadingError> {
+        let payload = packet.payload;
+        if payload.len() < NAL_UNIT_HEADER_SIZE {
+            return Err(H265DepayloadingError::PacketTooShort);
+        }
+
+        let nal_unit_type = nal_unit_type(&payload);
+        if nal_unit_type != FRAGMENTATION_UNIT && self.fragment.take().is_some() {
+            debug!("Dropping incomplete NAL unit. Packet with its end was lost.");
+        }
+        match nal_unit_type {
+            AGGREGATION_PACKET => self.depayload_aggregation_packet(payload)?,
+            FRAGMENTATION_UNIT => self.depayload_fragmentation_unit(payload)?,
+            PACI_PACKET => return Err(H265DepayloadingError::PaciNotSupported),
+            _ => self.push_nal_unit(&payload),
+        }
+
+        if !packet.header.marker || self.access_unit.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let timestamp = self.rollover_state.timestamp(packet.header.timestamp);
+        let is_keyframe = match std::mem::take(&mut self.is_keyframe) {
+            true => IsKeyframe::Yes,
+            false => IsKeyframe::Unknown,
+        };
+
+        Ok(vec![EncodedChunk {
+            data: self.access_unit.split().freeze(),
+            pts: Duration::from_secs_f64(timestamp as f64 / 90000.0),
+            dts: None,
+            is_keyframe,
+            kind: EncodedChunkKind::Video(VideoCodec::H265),
+        }])
+    }
+
+    /// RFC 7798, 4.4.2
+    fn depayload_aggregation_packet(
+        &mut self,
+        mut payload: Bytes,
+    ) -> Result<(), H265DepayloadingError> {
+        payload.advance(NAL_UNIT_HEADER_SIZE);
+        while payload.has_remaining() {
+            if payload.remaining() < 2 {
+                return Err(H265DepayloadingError::PacketTooShort);
+            }
+            let nal_unit_len = payload.get_u16() as usize;
+            if nal_unit_len < NAL_UNIT_HEADER_SIZE || payload.remaining() < nal_unit_len {
+                return Err(H265DepayloadingError::PacketTooShort);
+            }
+            let nal_unit = payload.split_to(nal_unit_len);
+            self.push_nal_unit(&nal_unit);
+        }
+        Ok(())
+    }
+
+    /// RFC 7798, 4.4.3
+    fn depayload_fragmentation_unit(
+        &mut self,
+        payload: Bytes,
+    ) -> Result<(), H265DepayloadingError> {
+        if payload.len() < NAL_UNIT_HEADER_SIZE + FU_HEADER_SIZE {
+            return Err(H265DepayloadingError::PacketTooShort);
+        }
+        let fu_header = payload[NAL_UNIT_HEADER_SIZE];
+        let is_start = fu_header & 0b1000_0000 != 0;
+        let is_end = fu_header & 0b0100_0000 != 0;
+        let fragment_data = &payload[NAL_UNIT_HEADER_SIZE + FU_HEADER_SIZE..];
+
+        if is_start {
+            if self.fragment.is_some() {
+                debug!("Dropping incomplete NAL unit. Packet with its end was lost.");
+            }
+            // NAL unit header is the payload header with the type taken from the FU header.
+            let mut nal_unit = BytesMut::new();
+            nal_unit.put_u8((payload[0] & 0b1000_0001) | ((fu_header & 0b0011_1111) << 1));
+            nal_unit.put_u8(payload[1]);
+            nal_unit.extend_from_slice(fragment_data);
+            self.fragment = Some(nal_unit);
+        } else {
+            match self.fragment.as_mut() {
+                Some(fragment) => fragment.extend_from_slice(fragment_data),
+                None => {
+                    debug!("Dropping incomplete NAL unit. Packet with its beginning was lost.");
+                    return Ok(());
+                }
+            }
+        }
+
+        if is_end {
+            if let Some(nal_unit) = self.fragment.take() {
+                self.push_nal_unit(&nal_unit);
+            }
+        }
+        Ok(())
+    }
+
+    fn push_nal_unit(&mut self, nal_unit: &[u8]) {
+        if IRAP_NAL_UNIT_TYPES.contains(&nal_unit_type(nal_unit)) {
+            self.is_keyframe = true;
+        }
+        self.access_unit.extend_from_slice(&ANNEX_B_START_CODE);
+        self.access_unit.extend_from_slice(nal_unit);
+    }
+}
+
+fn nal_unit_type(nal_unit: &[u8]) -> u8 {
+    (nal_unit[0] >> 1) & 0b0011_1111
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn packet(payload: &[u8], marker: bool) -> rtp::packet::Packet {
+        rtp::packet::Packet {
+            header: rtp::header::Header {
+                marker,
+                timestamp: 90000,
+                ..Default::default()
+            },
+            payload: Bytes::copy_from_slice(payload),
+        }
+    }
+
+    #[test]
+    fn depayload_aggregation_and_fragmentation_units() {
+        let mut depayloader = H265Depayloader::default();
+
+        // VPS (type 32) and SPS (type 33) in an aggregation packet
+        let aggregation_packet = [0x60, 0x01, 0, 3, 0x40, 0x01, 0xa1, 0, 3, 0x42, 0x01, 0xa2];
+        let chunks = depayloader
+            .depayload(packet(&aggregation_packet, false))
+            .unwrap();
+        assert!(chunks.is_empty());
+
+        // IDR_W_RADL (type 19) slice fragmented into 2 fragmentation units
+        let first_fragment = [0x62, 0x01, 0b1000_0000 | 19, 0xb1, 0xb2];
+        let second_fragment = [0x62, 0x01, 0b0100_0000 | 19, 0xb3];
+        let chunks = depayloader
+            .depayload(packet(&first_fragment, false))
+            .unwrap();
+        assert!(chunks.is_empty());
+        let chunks = depayloader
+            .depayload(packet(&second_fragment, true))
+            .unwrap();
+
+        let vps = [0, 0, 0, 1, 0x40, 0x01, 0xa1];
+        let sps = [0, 0, 0, 1, 0x42, 0x01, 0xa2];
+        let slice = [0, 0, 0, 1, 0x26, 0x01, 0xb1, 0xb2, 0xb3];
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].data, [&vps[..], &sps, &slice].concat());
+        assert_eq!(chunks[0].pts, Duration::from_secs(1));
+        assert_eq!(chunks[0].is_keyframe, IsKeyframe::Yes);
+    }
+}
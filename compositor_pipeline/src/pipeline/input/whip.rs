@@ -0,0 +1,276 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+};
+
+use bytes::Bytes;
+use compositor_render::InputId;
+use crossbeam_channel::{bounded, Sender, TrySendError};
+use tokio::runtime::Handle;
+use tracing::{debug, info, span, warn, Instrument, Level};
+use webrtc::{
+    peer_connection::{peer_connection_state::RTCPeerConnectionState, RTCPeerConnection},
+    rtp_transceiver::rtp_codec::RTPCodecType,
+    track::track_remote::TrackRemote,
+};
+use webrtc_util::Marshal;
+
+use crate::pipeline::{
+    decoder::{AudioDecoderOptions, DecoderOptions},
+    rtp::{AUDIO_PAYLOAD_TYPE, VIDEO_PAYLOAD_TYPE},
+    webrtc_utils::{self, NegotiationError},
+    VideoCodec,
+};
+
+use super::{
+    rtp::{
+        depayloader::{Depayloader, DepayloaderNewError},
+        InputAudioStream, InputVideoStream, RtpReceiver, RtpStream,
+    },
+    ChunksReceiver,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum WhipReceiverError {
+    #[error("WHIP input supports only H264 video and Opus audio.")]
+    UnsupportedCodec,
+
+    #[error(transparent)]
+    DepayloaderError(#[from] DepayloaderNewError),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum WhipSessionError {
+    #[error("WHIP session was already started for this input.")]
+    SessionAlreadyStarted,
+
+    #[error("WHIP session for this input has already ended.")]
+    SessionEnded,
+
+    #[error("Failed to apply the SDP offer.")]
+    InvalidOffer(#[source] webrtc::Error),
+
+    #[error("Failed to create the WebRTC peer connection.")]
+    PeerConnection(#[source] webrtc::Error),
+
+    #[error("WebRTC peer connection did not produce the SDP answer.")]
+    MissingAnswer,
+}
+
+#[derive(Debug, Clone)]
+pub struct WhipReceiverOptions {
+    pub video: Option<InputVideoStream>,
+    pub audio: Option<InputAudioStream>,
+}
+
+/// Input that receives media from a WebRTC peer (e.g. a browser) negotiated with
+/// [WHIP](https://datatracker.ietf.org/doc/html/draft-ietf-wish-whip). Received RTP packets
+/// are rewritten to the payload types expected by the RTP depayloader.
+pub struct WhipReceiver {
+    session: WhipSession,
+}
+
+/// Handle used to negotiate and terminate a WHIP session of an input.
+#[derive(Clone)]
+pub struct WhipSession {
+    options: WhipReceiverOptions,
+    /// Dropping the sender closes the depayloader, which then sends EOS for both tracks.
+    packets_sender: Arc<Mutex<Option<Sender<Bytes>>>>,
+    is_started: Arc<AtomicBool>,
+    peer_connection: Arc<Mutex<Option<(Arc<RTCPeerConnection>, Handle)>>>,
+}
+
+impl WhipReceiver {
+    pub fn new(
+        input_id: &InputId,
+        opts: WhipReceiverOptions,
+    ) -> Result<(Self, ChunksReceiver, DecoderOptions), WhipReceiverError> {
+        let is_video_supported = opts
+            .video
+            .as_ref()
+            .map_or(true, |video| video.options.codec == VideoCodec::H264);
+        let is_audio_supported = opts.audio.as_ref().map_or(true, |audio| {
+            matches!(audio.options, AudioDecoderOptions::Opus(_))
+        });
+        if !is_video_supported || !is_audio_supported {
+            return Err(WhipReceiverError::UnsupportedCodec);
+        }
+
+        let depayloader = Depayloader::new(&RtpStream {
+            video: opts.video.clone(),
+            audio: opts.audio.clone(),
+        })?;
+        let (packets_sender, packets_receiver) = bounded(1000);
+        let chunks_receiver =
+            RtpReceiver::start_depayloader_thread(input_id, packets_receiver, depayloader);
+
+        let decoder_options = DecoderOptions {
+            video: opts.video.as_ref().map(|v| v.options.clone()),
+            audio: opts.audio.as_ref().map(|a| a.options.clone()),
+        };
+
+        let session = WhipSession {
+            options: opts,
+            packets_sender: Arc::new(Mutex::new(Some(packets_sender))),
+            is_started: Arc::new(AtomicBool::new(false)),
+            peer_connection: Arc::new(Mutex::new(None)),
+        };
+
+        Ok((Self { session }, chunks_receiver, decoder_options))
+    }
+
+    pub fn session(&self) -> WhipSession {
+        self.session.clone()
+    }
+}
+
+impl Drop for WhipReceiver {
+    fn drop(&mut self) {
+        self.session.end_stream();
+        if let Some((peer_connection, runtime)) =
+            self.session.peer_connection.lock().unwrap().take()
+        {
+            runtime.spawn(async move {
+                if let Err(err) = peer_connection.close().await {
+                    debug!(%err, "Failed to close WHIP peer connection.");
+                }
+            });
+        }
+    }
+}
+
+impl WhipSession {
+    /// Applies the SDP offer and returns the SDP answer with all ICE candidates included,
+    /// so trickle ICE is not needed. Only one session can be started for an input.
+    pub async fn negotiate(
+        &self,
+        input_id: &InputId,
+        offer: String,
+    ) -> Result<String, WhipSessionError> {
+        if self.packets_sender.lock().unwrap().is_none() {
+            return Err(WhipSessionError::SessionEnded);
+        }
+        if self.is_started.swap(true, Ordering::Relaxed) {
+            return Err(WhipSessionError::SessionAlreadyStarted);
+        }
+
+        let result = self.start_peer_connection(input_id, offer).await;
+        if result.is_err() {
+            // Allow the client to retry with a different offer.
+            if let Some((peer_connection, _)) = self.peer_connection.lock().unwrap().take() {
+                tokio::spawn(async move { peer_connection.close().await });
+            }
+            self.is_started.store(false, Ordering::Relaxed);
+        }
+        result
+    }
+
+    async fn start_peer_connection(
+        &self,
+        input_id: &InputId,
+        offer: String,
+    ) -> Result<String, WhipSessionError> {
+        let peer_connection = Arc::new(
+            webrtc_utils::new_peer_connection(
+                self.options.video.is_some(),
+                self.options.audio.is_some(),
+            )
+            .await
+            .map_err(WhipSessionError::PeerConnection)?,
+        );
+        *self.peer_connection.lock().unwrap() = Some((peer_connection.clone(), Handle::current()));
+
+        let span = span!(Level::INFO, "WHIP input", input_id = input_id.to_string());
+        let packets_sender = self.packets_sender.clone();
+        peer_connection.on_track(Box::new(move |track, _, _| {
+            let packets_sender = packets_sender.clone();
+            let span = span.clone();
+            Box::pin(async move {
+                tokio::spawn(forward_track(track, packets_sender).instrument(span));
+            })
+        }));
+
+        let session = self.clone();
+        let input_id = input_id.clone();
+        peer_connection.on_peer_connection_state_change(Box::new(move |state| {
+            info!(?input_id, ?state, "WHIP peer connection state changed.");
+            if matches!(
+                state,
+                RTCPeerConnectionState::Failed | RTCPeerConnectionState::Closed
+            ) {
+                session.end_stream();
+            }
+            Box::pin(async {})
+        }));
+
+        webrtc_utils::negotiate_answer(&peer_connection, offer)
+            .await
+            .map_err(Into::into)
+    }
+
+    /// Closes the peer connection and ends the input stream.
+    pub async fn terminate(&self) -> Result<(), WhipSessionError> {
+        let peer_connection = self.peer_connection.lock().unwrap().take();
+        self.end_stream();
+        match peer_connection {
+            Some((peer_connection, _)) => peer_connection
+                .close()
+                .await
+                .map_err(WhipSessionError::PeerConnection),
+            None => Err(WhipSessionError::SessionEnded),
+        }
+    }
+
+    fn end_stream(&self) {
+        self.packets_sender.lock().unwrap().take();
+    }
+}
+
+impl From<NegotiationError> for WhipSessionError {
+    fn from(err: NegotiationError) -> Self {
+        match err {
+            NegotiationError::InvalidOffer(err) => WhipSessionError::InvalidOffer(err),
+            NegotiationError::PeerConnection(err) => WhipSessionError::PeerConnection(err),
+            NegotiationError::MissingAnswer => WhipSessionError::MissingAnswer,
+        }
+    }
+}
+
+async fn forward_track(track: Arc<TrackRemote>, packets_sender: Arc<Mutex<Option<Sender<Bytes>>>>) {
+    let payload_type = match track.kind() {
+        RTPCodecType::Video => VIDEO_PAYLOAD_TYPE,
+        RTPCodecType::Audio => AUDIO_PAYLOAD_TYPE,
+        RTPCodecType::Unspecified => {
+            warn!("Received WHIP track of unspecified kind.");
+            return;
+        }
+    };
+    debug!(kind=?track.kind(), codec=?track.codec().capability.mime_type, "Received WHIP track.");
+
+    while let Ok((mut packet, _)) = track.read_rtp().await {
+        packet.header.payload_type = payload_type;
+        let packet = match packet.marshal() {
+            Ok(packet) => packet,
+            Err(err) => {
+                warn!(%err, "Failed to serialize RTP packet.");
+                continue;
+            }
+        };
+        let send_result = match packets_sender.lock().unwrap().as_ref() {
+            Some(sender) => sender.try_send(packet),
+            None => {
+                debug!("WHIP session ended. Stopping track.");
+                return;
+            }
+        };
+        match send_result {
+            Ok(()) => (),
+            Err(TrySendError::Full(_)) => warn!("Dropping RTP packet. Depayloader queue is full."),
+            Err(TrySendError::Disconnected(_)) => {
+                debug!("Failed to forward WHIP RTP packet. Channel closed.");
+                return;
+            }
+        }
+    }
+    debug!(kind=?track.kind(), "WHIP track ended.");
+}
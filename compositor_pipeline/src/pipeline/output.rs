@@ -3,33 +3,69 @@ use crossbeam_channel::Receiver;
 
 use crate::error::OutputInitError;
 
-use self::rtp::{RtpSender, RtpSenderOptions};
+use self::{
+    hls::{HlsOutputOptions, HlsWriter},
+    mp4::{Mp4FileWriter, Mp4OutputOptions},
+    rtmp::{RtmpSender, RtmpSenderOptions},
+    rtp::{RtpSender, RtpSenderOptions},
+    whep::{WhepSender, WhepSenderOptions},
+};
 
-use super::{structs::EncoderOutputEvent, Port};
+use super::{encoder::EncoderContext, structs::EncoderOutputEvent, Port};
 
+mod ffmpeg_utils;
+pub mod hls;
+pub mod mp4;
+pub mod rtmp;
 pub mod rtp;
+pub mod whep;
 
 #[derive(Debug)]
 pub enum Output {
     Rtp(RtpSender),
+    Mp4(Mp4FileWriter),
+    Rtmp(RtmpSender),
+    Hls(HlsWriter),
+    Whep(WhepSender),
 }
 
 #[derive(Debug, Clone)]
 pub enum OutputOptions {
     Rtp(RtpSenderOptions),
+    Mp4(Mp4OutputOptions),
+    Rtmp(RtmpSenderOptions),
+    Hls(HlsOutputOptions),
+    Whep(WhepSenderOptions),
 }
 
 impl Output {
     pub fn new(
         output_id: &OutputId,
         options: OutputOptions,
+        encoder_ctx: EncoderContext,
         packets: Receiver<EncoderOutputEvent>,
     ) -> Result<(Self, Option<Port>), OutputInitError> {
         match options {
             OutputOptions::Rtp(options) => {
-                let (sender, port) = rtp::RtpSender::new(output_id, options, packets)?;
+                let (sender, port) = rtp::RtpSender::new(output_id, options, encoder_ctx, packets)?;
                 Ok((Self::Rtp(sender), port))
             }
+            OutputOptions::Mp4(options) => {
+                let writer = Mp4FileWriter::new(output_id, options, encoder_ctx, packets)?;
+                Ok((Self::Mp4(writer), None))
+            }
+            OutputOptions::Rtmp(options) => {
+                let sender = RtmpSender::new(output_id, options, encoder_ctx, packets)?;
+                Ok((Self::Rtmp(sender), None))
+            }
+            OutputOptions::Hls(options) => {
+                let writer = HlsWriter::new(output_id, options, encoder_ctx, packets)?;
+                Ok((Self::Hls(writer), None))
+            }
+            OutputOptions::Whep(options) => {
+                let sender = WhepSender::new(output_id, options, encoder_ctx, packets)?;
+                Ok((Self::Whep(sender), None))
+            }
         }
     }
 }
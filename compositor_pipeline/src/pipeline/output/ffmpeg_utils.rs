@@ -0,0 +1,270 @@
+use std::{ptr, time::Duration};
+
+use crossbeam_channel::Receiver;
+use ffmpeg_next::{self as ffmpeg, Rational};
+use tracing::{error, trace, warn};
+
+use crate::{
+    audio_mixer::AudioChannels,
+    pipeline::{
+        encoder::{opus::multistream, AudioEncoderContext},
+        structs::{EncodedChunk, EncodedChunkKind, EncoderOutputEvent, IsKeyframe},
+        VideoCodec,
+    },
+};
+
+/// Timestamps of encoded chunks are passed to the muxer in microseconds.
+/// Muxer can change stream time base when the header is written, so
+/// packets are rescaled to the final value before they are written.
+const TIME_BASE: i32 = 1_000_000;
+
+/// Sample rate of the Opus stream stored in the `OpusHead` header.
+/// Opus decoders always operate at 48kHz, the value is only informational.
+const OPUS_SAMPLE_RATE: u32 = 48_000;
+
+pub(super) struct StreamState {
+    pub index: usize,
+    pub time_base: Rational,
+}
+
+impl StreamState {
+    /// Muxer is allowed to override time base of a stream when writing the header,
+    /// so this should be called after `write_header`.
+    pub fn new(output_ctx: &ffmpeg::format::context::Output, index: usize) -> Self {
+        Self {
+            index,
+            time_base: output_ctx
+                .stream(index)
+                .map(|stream| stream.time_base())
+                .unwrap_or(Rational::new(1, TIME_BASE)),
+        }
+    }
+}
+
+pub(super) fn add_video_stream(
+    output_ctx: &mut ffmpeg::format::context::Output,
+    codec: VideoCodec,
+    width: u32,
+    height: u32,
+    extradata: Option<&[u8]>,
+) -> Result<usize, ffmpeg::Error> {
+    let codec_id = match codec {
+        VideoCodec::H264 => ffmpeg::codec::Id::H264,
+        VideoCodec::H265 => ffmpeg::codec::Id::HEVC,
+        VideoCodec::Vp8 => ffmpeg::codec::Id::VP8,
+        VideoCodec::Vp9 => ffmpeg::codec::Id::VP9,
+        VideoCodec::Av1 => ffmpeg::codec::Id::AV1,
+    };
+
+    let mut stream = output_ctx.add_stream(ffmpeg::encoder::find(codec_id))?;
+    stream.set_time_base(Rational::new(1, TIME_BASE));
+
+    unsafe {
+        let codecpar = &mut *(*stream.as_mut_ptr()).codecpar;
+        codecpar.codec_type = ffmpeg::media::Type::Video.into();
+        codecpar.codec_id = codec_id.into();
+        codecpar.width = width as i32;
+        codecpar.height = height as i32;
+        if let Some(extradata) = extradata {
+            set_extradata(codecpar, extradata)?;
+        }
+    }
+
+    Ok(stream.index())
+}
+
+pub(super) fn add_audio_stream(
+    output_ctx: &mut ffmpeg::format::context::Output,
+    channels: AudioChannels,
+    encoder_ctx: &AudioEncoderContext,
+) -> Result<usize, ffmpeg::Error> {
+    let (codec_id, sample_rate, extradata) = match encoder_ctx {
+        AudioEncoderContext::Opus => (
+            ffmpeg::codec::Id::OPUS,
+            OPUS_SAMPLE_RATE,
+            opus_head(channels),
+        ),
+        AudioEncoderContext::Aac { asc, sample_rate } => {
+            (ffmpeg::codec::Id::AAC, *sample_rate, asc.to_vec())
+        }
+    };
+
+    let channel_count = channels.count() as i32;
+
+    let mut stream = output_ctx.add_stream(ffmpeg::encoder::find(codec_id))?;
+    stream.set_time_base(Rational::new(1, TIME_BASE));
+
+    unsafe {
+        let codecpar = &mut *(*stream.as_mut_ptr()).codecpar;
+        codecpar.codec_type = ffmpeg::media::Type::Audio.into();
+        codecpar.codec_id = codec_id.into();
+        codecpar.sample_rate = sample_rate as i32;
+        ffmpeg::ffi::av_channel_layout_default(&mut codecpar.ch_layout, channel_count);
+        set_extradata(codecpar, &extradata)?;
+    }
+
+    Ok(stream.index())
+}
+
+/// Extradata has to be allocated with ffmpeg allocator, because it
+/// is freed by ffmpeg together with the stream.
+unsafe fn set_extradata(
+    codecpar: &mut ffmpeg::ffi::AVCodecParameters,
+    extradata: &[u8],
+) -> Result<(), ffmpeg::Error> {
+    let padding = ffmpeg::ffi::AV_INPUT_BUFFER_PADDING_SIZE as usize;
+    let data = ffmpeg::ffi::av_mallocz(extradata.len() + padding) as *mut u8;
+    if data.is_null() {
+        return Err(ffmpeg::Error::Other {
+            errno: ffmpeg::error::ENOMEM,
+        });
+    }
+    ptr::copy_nonoverlapping(extradata.as_ptr(), data, extradata.len());
+    codecpar.extradata = data;
+    codecpar.extradata_size = extradata.len() as i32;
+    Ok(())
+}
+
+/// Identification header as described in [RFC 7845, section 5.1](https://datatracker.ietf.org/doc/html/rfc7845#section-5.1).
+/// Muxers require it e.g. to write the `dOps` box in MP4.
+fn opus_head(channels: AudioChannels) -> Vec<u8> {
+    let mut header = Vec::with_capacity(27);
+    header.extend_from_slice(b"OpusHead");
+    header.push(1); // version
+    header.push(channels.count() as u8);
+    header.extend_from_slice(&0u16.to_le_bytes()); // pre-skip
+    header.extend_from_slice(&OPUS_SAMPLE_RATE.to_le_bytes()); // input sample rate
+    header.extend_from_slice(&0i16.to_le_bytes()); // output gain
+    match channels {
+        AudioChannels::Mono | AudioChannels::Stereo => header.push(0), // channel mapping family
+        AudioChannels::Surround51 => {
+            header.push(1); // channel mapping family
+            header.push(multistream::SURROUND51_STREAMS);
+            header.push(multistream::SURROUND51_COUPLED_STREAMS);
+            header.extend_from_slice(&multistream::SURROUND51_MAPPING);
+        }
+    }
+    header
+}
+
+/// Writes buffered chunks with timestamps shifted by the earliest of their timestamps,
+/// which is returned. Returns `None` if there are no chunks.
+fn write_pending_chunks(
+    pending_chunks: &mut Vec<EncodedChunk>,
+    write: &mut impl FnMut(&EncodedChunk, Duration),
+) -> Option<Duration> {
+    let offset = pending_chunks
+        .iter()
+        .map(|chunk| chunk.dts.unwrap_or(chunk.pts))
+        .min()?;
+    for chunk in pending_chunks.drain(..) {
+        write(&chunk, offset);
+    }
+    Some(offset)
+}
+
+/// Writes chunk with timestamps shifted by `timestamp_offset`.
+pub(super) fn write_chunk(
+    chunk: &EncodedChunk,
+    timestamp_offset: Duration,
+    stream: &StreamState,
+    output_ctx: &mut ffmpeg::format::context::Output,
+) -> Result<(), ffmpeg::Error> {
+    let to_ticks = |ts: Duration| ts.as_micros() as i64 - timestamp_offset.as_micros() as i64;
+
+    let mut packet = ffmpeg::Packet::copy(&chunk.data);
+    packet.set_pts(Some(to_ticks(chunk.pts)));
+    packet.set_dts(chunk.dts.map(to_ticks).or(Some(to_ticks(chunk.pts))));
+    packet.set_stream(stream.index);
+    match chunk.is_keyframe {
+        IsKeyframe::Yes | IsKeyframe::NoKeyframes => packet.set_flags(ffmpeg::packet::Flags::KEY),
+        IsKeyframe::No | IsKeyframe::Unknown => (),
+    }
+    packet.rescale_ts(Rational::new(1, TIME_BASE), stream.time_base);
+
+    packet.write_interleaved(output_ctx)
+}
+
+/// Writes all received chunks to the muxer until EOS is received on all tracks
+/// or the channel is closed.
+pub(super) fn run_muxer_thread(
+    mut output_ctx: ffmpeg::format::context::Output,
+    video_stream: Option<StreamState>,
+    audio_stream: Option<StreamState>,
+    packets_receiver: Receiver<EncoderOutputEvent>,
+) {
+    let mut received_video_eos = video_stream.as_ref().map(|_| false);
+    let mut received_audio_eos = audio_stream.as_ref().map(|_| false);
+
+    // Output timestamps start from 0. The same offset is used for both tracks to
+    // preserve audio-video synchronization, so it is the earliest timestamp of the
+    // first chunks of all tracks. Until each track produces a chunk (or ends), chunks
+    // are buffered, otherwise the track that starts later would get negative timestamps.
+    let mut timestamp_offset = None;
+    let mut waiting_for_video = video_stream.is_some();
+    let mut waiting_for_audio = audio_stream.is_some();
+    let mut pending_chunks = Vec::new();
+
+    let mut write = |chunk: &EncodedChunk, offset: Duration| {
+        let stream = match chunk.kind {
+            EncodedChunkKind::Video(_) => video_stream.as_ref(),
+            EncodedChunkKind::Audio(_) => audio_stream.as_ref(),
+        };
+        let Some(stream) = stream else {
+            error!(kind=?chunk.kind, "Received chunk for a track that is not present in the output.");
+            return;
+        };
+        trace!(pts=?chunk.pts, kind=?chunk.kind, "Write chunk to the muxer.");
+        if let Err(err) = write_chunk(chunk, offset, stream, &mut output_ctx) {
+            error!(%err, "Failed to write chunk to the muxer.");
+        }
+    };
+
+    for packet in packets_receiver {
+        match packet {
+            EncoderOutputEvent::Data(chunk) => {
+                if let Some(offset) = timestamp_offset {
+                    write(&chunk, offset);
+                    continue;
+                }
+                match chunk.kind {
+                    EncodedChunkKind::Video(_) => waiting_for_video = false,
+                    EncodedChunkKind::Audio(_) => waiting_for_audio = false,
+                }
+                pending_chunks.push(chunk);
+            }
+            EncoderOutputEvent::VideoEOS => {
+                waiting_for_video = false;
+                match received_video_eos {
+                    Some(false) => received_video_eos = Some(true),
+                    Some(true) => warn!("Received multiple video EOS events."),
+                    None => error!("Received video EOS event on non video output."),
+                }
+            }
+            EncoderOutputEvent::AudioEOS => {
+                waiting_for_audio = false;
+                match received_audio_eos {
+                    Some(false) => received_audio_eos = Some(true),
+                    Some(true) => warn!("Received multiple audio EOS events."),
+                    None => error!("Received audio EOS event on non audio output."),
+                }
+            }
+        };
+
+        if timestamp_offset.is_none() && !waiting_for_video && !waiting_for_audio {
+            timestamp_offset = write_pending_chunks(&mut pending_chunks, &mut write);
+        }
+        if received_video_eos.unwrap_or(true) && received_audio_eos.unwrap_or(true) {
+            break;
+        }
+    }
+    if timestamp_offset.is_none() {
+        write_pending_chunks(&mut pending_chunks, &mut write);
+    }
+
+    // Trailer is written both after EOS and after the channel is closed
+    // (output was unregistered), so the output is always finalized.
+    if let Err(err) = output_ctx.write_trailer() {
+        error!(%err, "Failed to write trailer.");
+    };
+}
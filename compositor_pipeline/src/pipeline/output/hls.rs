@@ -0,0 +1,197 @@
+use std::{fs, path::PathBuf, time::Duration};
+
+use compositor_render::OutputId;
+use crossbeam_channel::Receiver;
+use ffmpeg_next::{self as ffmpeg, Dictionary};
+use tracing::{debug, span, Level};
+
+use crate::{
+    audio_mixer::AudioChannels,
+    error::OutputInitError,
+    pipeline::{encoder::EncoderContext, structs::EncoderOutputEvent, AudioCodec, VideoCodec},
+};
+
+use super::ffmpeg_utils::{self, StreamState};
+
+const PLAYLIST_NAME: &str = "index.m3u8";
+const FMP4_INIT_SEGMENT_NAME: &str = "init.mp4";
+
+#[derive(Debug, Clone)]
+pub struct HlsOutputOptions {
+    /// Directory where the playlist and segments are written.
+    pub directory: PathBuf,
+    /// Target duration of a segment (FFmpeg `hls_time`). A segment is cut on the first
+    /// keyframe after this duration elapsed, not on every keyframe, so video encoder
+    /// should produce a keyframe at least that often.
+    pub segment_duration: Duration,
+    /// Number of segments listed in the playlist. If 0, all segments are listed.
+    pub playlist_size: usize,
+    /// Remove segment files after they are no longer listed in the playlist.
+    pub delete_old_segments: bool,
+    /// Container format of the media segments.
+    pub segment_format: HlsSegmentFormat,
+    pub video: Option<HlsVideoTrack>,
+    pub audio: Option<HlsAudioTrack>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HlsSegmentFormat {
+    MpegTs,
+    Fmp4,
+}
+
+#[derive(Debug, Clone)]
+pub struct HlsVideoTrack {
+    pub codec: VideoCodec,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct HlsAudioTrack {
+    pub codec: AudioCodec,
+    pub channels: AudioChannels,
+}
+
+#[derive(Debug)]
+pub struct HlsWriter {
+    pub playlist_path: PathBuf,
+}
+
+impl HlsWriter {
+    pub fn new(
+        output_id: &OutputId,
+        options: HlsOutputOptions,
+        encoder_ctx: EncoderContext,
+        packets_receiver: Receiver<EncoderOutputEvent>,
+    ) -> Result<Self, OutputInitError> {
+        let playlist_path = options.directory.join(PLAYLIST_NAME);
+        if playlist_path.exists() {
+            return Err(OutputInitError::HlsPlaylistAlreadyExists(playlist_path));
+        }
+        if let Some(video) = options
+            .video
+            .as_ref()
+            .filter(|v| v.codec != VideoCodec::H264)
+        {
+            return Err(OutputInitError::UnsupportedVideoCodec(video.codec));
+        }
+        // MPEG-TS muxer in FFmpeg does not support Opus.
+        if let Some(audio) = options.audio.as_ref().filter(|a| {
+            a.codec == AudioCodec::Opus && options.segment_format == HlsSegmentFormat::MpegTs
+        }) {
+            return Err(OutputInitError::UnsupportedAudioCodec(audio.codec));
+        }
+        fs::create_dir_all(&options.directory)
+            .map_err(|err| OutputInitError::HlsDirectoryError(options.directory.clone(), err))?;
+
+        let (output_ctx, video_stream, audio_stream) = init_ffmpeg_output(&options, &encoder_ctx)?;
+
+        let output_id = output_id.clone();
+        std::thread::Builder::new()
+            .name(format!("HLS writer thread for output {}", output_id))
+            .spawn(move || {
+                let _span =
+                    span!(Level::INFO, "HLS writer", output_id = output_id.to_string()).entered();
+
+                ffmpeg_utils::run_muxer_thread(
+                    output_ctx,
+                    video_stream,
+                    audio_stream,
+                    packets_receiver,
+                );
+                debug!("Closing HLS writer thread.");
+            })
+            .unwrap();
+
+        Ok(Self { playlist_path })
+    }
+}
+
+fn init_ffmpeg_output(
+    options: &HlsOutputOptions,
+    encoder_ctx: &EncoderContext,
+) -> Result<
+    (
+        ffmpeg::format::context::Output,
+        Option<StreamState>,
+        Option<StreamState>,
+    ),
+    OutputInitError,
+> {
+    let playlist_path = options.directory.join(PLAYLIST_NAME);
+    let segment_extension = match options.segment_format {
+        HlsSegmentFormat::MpegTs => "ts",
+        HlsSegmentFormat::Fmp4 => "m4s",
+    };
+    let segment_path = options
+        .directory
+        .join(format!("segment_%05d.{segment_extension}"));
+
+    let mut muxer_options = Dictionary::new();
+    muxer_options.set(
+        "hls_time",
+        &options.segment_duration.as_secs_f64().to_string(),
+    );
+    muxer_options.set("hls_list_size", &options.playlist_size.to_string());
+    muxer_options.set("hls_segment_filename", &segment_path.to_string_lossy());
+    muxer_options.set("hls_flags", &hls_flags(options).join("+"));
+    match options.segment_format {
+        HlsSegmentFormat::MpegTs => muxer_options.set("hls_segment_type", "mpegts"),
+        HlsSegmentFormat::Fmp4 => {
+            muxer_options.set("hls_segment_type", "fmp4");
+            muxer_options.set("hls_fmp4_init_filename", FMP4_INIT_SEGMENT_NAME);
+        }
+    }
+
+    let mut output_ctx = ffmpeg::format::output_as_with(&playlist_path, "hls", muxer_options)
+        .map_err(OutputInitError::FfmpegHlsError)?;
+
+    let video_stream = options
+        .video
+        .as_ref()
+        .map(|video| {
+            ffmpeg_utils::add_video_stream(
+                &mut output_ctx,
+                video.codec,
+                video.width,
+                video.height,
+                None,
+            )
+        })
+        .transpose()
+        .map_err(OutputInitError::FfmpegHlsError)?;
+
+    let audio_stream = options
+        .audio
+        .as_ref()
+        .zip(encoder_ctx.audio.as_ref())
+        .map(|(audio, audio_ctx)| {
+            ffmpeg_utils::add_audio_stream(&mut output_ctx, audio.channels, audio_ctx)
+        })
+        .transpose()
+        .map_err(OutputInitError::FfmpegHlsError)?;
+
+    output_ctx
+        .write_header()
+        .map_err(OutputInitError::FfmpegHlsError)?;
+
+    let video_stream = video_stream.map(|index| StreamState::new(&output_ctx, index));
+    let audio_stream = audio_stream.map(|index| StreamState::new(&output_ctx, index));
+
+    Ok((output_ctx, video_stream, audio_stream))
+}
+
+fn hls_flags(options: &HlsOutputOptions) -> Vec<&'static str> {
+    // Playlist is written to a temporary file and renamed, so a file server
+    // never serves a partially written playlist.
+    let mut flags = vec!["temp_file"];
+    if options.video.is_some() {
+        // Segments are split on keyframes, so each of them can be decoded on its own.
+        flags.push("independent_segments");
+    }
+    if options.delete_old_segments {
+        flags.push("delete_segments");
+    }
+    flags
+}
@@ -0,0 +1,131 @@
+use std::path::PathBuf;
+
+use compositor_render::OutputId;
+use crossbeam_channel::Receiver;
+use ffmpeg_next as ffmpeg;
+use tracing::{debug, span, Level};
+
+use crate::{
+    audio_mixer::AudioChannels,
+    error::OutputInitError,
+    pipeline::{encoder::EncoderContext, structs::EncoderOutputEvent, AudioCodec, VideoCodec},
+};
+
+use super::ffmpeg_utils::{self, StreamState};
+
+#[derive(Debug, Clone)]
+pub struct Mp4OutputOptions {
+    pub output_path: PathBuf,
+    pub video: Option<Mp4VideoTrack>,
+    pub audio: Option<Mp4AudioTrack>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Mp4VideoTrack {
+    pub codec: VideoCodec,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct Mp4AudioTrack {
+    pub codec: AudioCodec,
+    pub channels: AudioChannels,
+}
+
+#[derive(Debug)]
+pub struct Mp4FileWriter {
+    pub output_path: PathBuf,
+}
+
+impl Mp4FileWriter {
+    pub fn new(
+        output_id: &OutputId,
+        options: Mp4OutputOptions,
+        encoder_ctx: EncoderContext,
+        packets_receiver: Receiver<EncoderOutputEvent>,
+    ) -> Result<Self, OutputInitError> {
+        if options.output_path.exists() {
+            return Err(OutputInitError::Mp4FileAlreadyExists(options.output_path));
+        }
+        if let Some(video) = options
+            .video
+            .as_ref()
+            .filter(|v| v.codec != VideoCodec::H264)
+        {
+            return Err(OutputInitError::UnsupportedVideoCodec(video.codec));
+        }
+
+        let (output_ctx, video_stream, audio_stream) = init_ffmpeg_output(&options, &encoder_ctx)?;
+
+        let output_id = output_id.clone();
+        std::thread::Builder::new()
+            .name(format!("MP4 writer thread for output {}", output_id))
+            .spawn(move || {
+                let _span =
+                    span!(Level::INFO, "MP4 writer", output_id = output_id.to_string()).entered();
+
+                ffmpeg_utils::run_muxer_thread(
+                    output_ctx,
+                    video_stream,
+                    audio_stream,
+                    packets_receiver,
+                );
+                debug!("Closing MP4 writer thread.");
+            })
+            .unwrap();
+
+        Ok(Mp4FileWriter {
+            output_path: options.output_path,
+        })
+    }
+}
+
+fn init_ffmpeg_output(
+    options: &Mp4OutputOptions,
+    encoder_ctx: &EncoderContext,
+) -> Result<
+    (
+        ffmpeg::format::context::Output,
+        Option<StreamState>,
+        Option<StreamState>,
+    ),
+    OutputInitError,
+> {
+    let mut output_ctx = ffmpeg::format::output_as(&options.output_path, "mp4")
+        .map_err(OutputInitError::FfmpegMp4Error)?;
+
+    let video_stream = options
+        .video
+        .as_ref()
+        .map(|video| {
+            ffmpeg_utils::add_video_stream(
+                &mut output_ctx,
+                video.codec,
+                video.width,
+                video.height,
+                None,
+            )
+        })
+        .transpose()
+        .map_err(OutputInitError::FfmpegMp4Error)?;
+
+    let audio_stream = options
+        .audio
+        .as_ref()
+        .zip(encoder_ctx.audio.as_ref())
+        .map(|(audio, audio_ctx)| {
+            ffmpeg_utils::add_audio_stream(&mut output_ctx, audio.channels, audio_ctx)
+        })
+        .transpose()
+        .map_err(OutputInitError::FfmpegMp4Error)?;
+
+    output_ctx
+        .write_header()
+        .map_err(OutputInitError::FfmpegMp4Error)?;
+
+    let video_stream = video_stream.map(|index| StreamState::new(&output_ctx, index));
+    let audio_stream = audio_stream.map(|index| StreamState::new(&output_ctx, index));
+
+    Ok((output_ctx, video_stream, audio_stream))
+}
@@ -0,0 +1,445 @@
+use std::{
+    cmp::min,
+    time::{Duration, Instant},
+};
+
+use bytes::{Bytes, BytesMut};
+use compositor_render::OutputId;
+use crossbeam_channel::{Receiver, TryRecvError};
+use ffmpeg_next::{self as ffmpeg, Dictionary};
+use tracing::{debug, error, info, span, trace, warn, Level, Span};
+
+use crate::{
+    audio_mixer::AudioChannels,
+    error::OutputInitError,
+    pipeline::{
+        encoder::EncoderContext,
+        structs::{EncodedChunk, EncodedChunkKind, EncoderOutputEvent, IsKeyframe},
+        AudioCodec, VideoCodec,
+    },
+};
+
+use super::ffmpeg_utils::{self, StreamState};
+
+const INITIAL_RECONNECT_DELAY: Duration = Duration::from_millis(500);
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(10);
+
+/// Timeout (in microseconds) for a single read or write on the RTMP socket. It ensures
+/// that a connection that stopped responding is detected and re-established.
+const RW_TIMEOUT_MICROS: &str = "5000000";
+
+#[derive(Debug, Clone)]
+pub struct RtmpSenderOptions {
+    pub url: String,
+    pub video: Option<RtmpVideoTrack>,
+    pub audio: Option<RtmpAudioTrack>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RtmpVideoTrack {
+    pub codec: VideoCodec,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct RtmpAudioTrack {
+    pub codec: AudioCodec,
+    pub channels: AudioChannels,
+}
+
+#[derive(Debug)]
+pub struct RtmpSender {
+    pub url: String,
+}
+
+impl RtmpSender {
+    pub fn new(
+        output_id: &OutputId,
+        options: RtmpSenderOptions,
+        encoder_ctx: EncoderContext,
+        packets_receiver: Receiver<EncoderOutputEvent>,
+    ) -> Result<Self, OutputInitError> {
+        if !options.url.starts_with("rtmp://") && !options.url.starts_with("rtmps://") {
+            return Err(OutputInitError::InvalidRtmpUrl(options.url));
+        }
+        if let Some(video) = options
+            .video
+            .as_ref()
+            .filter(|v| v.codec != VideoCodec::H264)
+        {
+            return Err(OutputInitError::UnsupportedVideoCodec(video.codec));
+        }
+
+        let url = options.url.clone();
+        let output_id = output_id.clone();
+        std::thread::Builder::new()
+            .name(format!("RTMP sender thread for output {}", output_id))
+            .spawn(move || {
+                let _span = span!(
+                    Level::INFO,
+                    "RTMP sender",
+                    output_id = output_id.to_string()
+                )
+                .entered();
+
+                run_rtmp_sender_thread(options, encoder_ctx, packets_receiver);
+                debug!("Closing RTMP sender thread.");
+            })
+            .unwrap();
+
+        Ok(Self { url })
+    }
+}
+
+struct RtmpConnection {
+    output_ctx: ffmpeg::format::context::Output,
+    video_stream: Option<StreamState>,
+    audio_stream: Option<StreamState>,
+    /// Timestamps in each RTMP session start from 0. The same offset is used for
+    /// both tracks to preserve audio-video synchronization.
+    timestamp_offset: Duration,
+    /// Video chunks are dropped until the first keyframe, because chunks encoded
+    /// while connecting are not sent.
+    waiting_for_keyframe: bool,
+}
+
+impl RtmpConnection {
+    fn new(
+        options: &RtmpSenderOptions,
+        encoder_ctx: &EncoderContext,
+        video_config: Option<&[u8]>,
+        timestamp_offset: Duration,
+    ) -> Result<Self, ffmpeg::Error> {
+        let mut connection_options = Dictionary::new();
+        connection_options.set("rw_timeout", RW_TIMEOUT_MICROS);
+        connection_options.set("rtmp_live", "live");
+        let mut output_ctx =
+            ffmpeg::format::output_as_with(&options.url, "flv", connection_options)?;
+
+        let video_stream = options
+            .video
+            .as_ref()
+            .map(|video| {
+                ffmpeg_utils::add_video_stream(
+                    &mut output_ctx,
+                    video.codec,
+                    video.width,
+                    video.height,
+                    video_config,
+                )
+            })
+            .transpose()?;
+
+        let audio_stream = options
+            .audio
+            .as_ref()
+            .zip(encoder_ctx.audio.as_ref())
+            .map(|(audio, audio_ctx)| {
+                ffmpeg_utils::add_audio_stream(&mut output_ctx, audio.channels, audio_ctx)
+            })
+            .transpose()?;
+
+        output_ctx.write_header()?;
+
+        let video_stream = video_stream.map(|index| StreamState::new(&output_ctx, index));
+        let audio_stream = audio_stream.map(|index| StreamState::new(&output_ctx, index));
+
+        Ok(Self {
+            output_ctx,
+            video_stream,
+            audio_stream,
+            timestamp_offset,
+            waiting_for_keyframe: true,
+        })
+    }
+
+    fn write_chunk(&mut self, chunk: &EncodedChunk) -> Result<(), ffmpeg::Error> {
+        let stream = match chunk.kind {
+            EncodedChunkKind::Video(_) => self.video_stream.as_ref(),
+            EncodedChunkKind::Audio(_) => self.audio_stream.as_ref(),
+        };
+        let Some(stream) = stream else {
+            error!(kind=?chunk.kind, "Received chunk for a track that is not present in the RTMP stream.");
+            return Ok(());
+        };
+        if let EncodedChunkKind::Video(_) = chunk.kind {
+            if self.waiting_for_keyframe && chunk.is_keyframe != IsKeyframe::Yes {
+                trace!(pts=?chunk.pts, "Dropping video chunk before the first keyframe.");
+                return Ok(());
+            }
+            self.waiting_for_keyframe = false;
+        }
+        if chunk.dts.unwrap_or(chunk.pts) < self.timestamp_offset {
+            // FLV timestamps can not be negative.
+            trace!(pts=?chunk.pts, "Dropping chunk older than the start of the RTMP session.");
+            return Ok(());
+        }
+
+        trace!(pts=?chunk.pts, kind=?chunk.kind, "Send chunk over RTMP.");
+        ffmpeg_utils::write_chunk(chunk, self.timestamp_offset, stream, &mut self.output_ctx)
+    }
+}
+
+enum ConnectionState {
+    Disconnected {
+        next_attempt: Instant,
+        keyframe_requested: bool,
+    },
+    /// Connecting blocks until the server responds or the socket times out, so it is
+    /// done on a separate thread and chunks produced in the meantime are dropped.
+    Connecting(Receiver<Result<RtmpConnection, ffmpeg::Error>>),
+    Connected(RtmpConnection),
+}
+
+impl ConnectionState {
+    fn disconnected(next_attempt: Instant) -> Self {
+        Self::Disconnected {
+            next_attempt,
+            keyframe_requested: false,
+        }
+    }
+}
+
+/// Connection is established lazily when the first chunk that can start a stream
+/// is produced. For outputs with video this is a keyframe, because its parameter sets are
+/// needed to write the FLV sequence header. If the connection drops, all chunks are dropped
+/// until the connection is re-established after the reconnect delay. Keyframes are
+/// requested from the encoder, so the stream can be restarted without waiting for the
+/// next regular keyframe.
+fn run_rtmp_sender_thread(
+    options: RtmpSenderOptions,
+    encoder_ctx: EncoderContext,
+    packets_receiver: Receiver<EncoderOutputEvent>,
+) {
+    let mut state = ConnectionState::disconnected(Instant::now());
+    let mut reconnect_delay = INITIAL_RECONNECT_DELAY;
+
+    let mut received_video_eos = options.video.as_ref().map(|_| false);
+    let mut received_audio_eos = options.audio.as_ref().map(|_| false);
+
+    let request_keyframe = || {
+        if let Some(sender) = &encoder_ctx.keyframe_request_sender {
+            if sender.send(()).is_err() {
+                debug!("Failed to request a keyframe. Encoder already finished.");
+            }
+        }
+    };
+
+    for packet in packets_receiver {
+        let chunk = match packet {
+            EncoderOutputEvent::Data(chunk) => chunk,
+            EncoderOutputEvent::VideoEOS => {
+                match received_video_eos {
+                    Some(false) => received_video_eos = Some(true),
+                    Some(true) => warn!("Received multiple video EOS events."),
+                    None => error!("Received video EOS event on non video output."),
+                };
+                if received_video_eos.unwrap_or(true) && received_audio_eos.unwrap_or(true) {
+                    break;
+                }
+                continue;
+            }
+            EncoderOutputEvent::AudioEOS => {
+                match received_audio_eos {
+                    Some(false) => received_audio_eos = Some(true),
+                    Some(true) => warn!("Received multiple audio EOS events."),
+                    None => error!("Received audio EOS event on non audio output."),
+                };
+                if received_video_eos.unwrap_or(true) && received_audio_eos.unwrap_or(true) {
+                    break;
+                }
+                continue;
+            }
+        };
+
+        if let ConnectionState::Connecting(result_receiver) = &state {
+            match result_receiver.try_recv() {
+                Ok(Ok(connection)) => {
+                    info!(url = %options.url, "Connected to the RTMP server.");
+                    reconnect_delay = INITIAL_RECONNECT_DELAY;
+                    state = ConnectionState::Connected(connection);
+                    // Chunks encoded since the stream start keyframe were dropped.
+                    request_keyframe();
+                }
+                Ok(Err(err)) => {
+                    warn!(%err, url = %options.url, "Failed to connect to the RTMP server. Retrying in {reconnect_delay:?}.");
+                    state = ConnectionState::disconnected(Instant::now() + reconnect_delay);
+                    reconnect_delay = min(reconnect_delay * 2, MAX_RECONNECT_DELAY);
+                }
+                Err(TryRecvError::Empty) => (),
+                Err(TryRecvError::Disconnected) => {
+                    error!("RTMP connection thread finished without a result.");
+                    state = ConnectionState::disconnected(Instant::now() + reconnect_delay);
+                }
+            }
+        }
+
+        if let ConnectionState::Disconnected {
+            next_attempt,
+            keyframe_requested,
+        } = &mut state
+        {
+            if Instant::now() < *next_attempt {
+                continue;
+            }
+            let video_config = match options.video {
+                Some(_) => match stream_start_video_config(&chunk) {
+                    Some(config) => Some(config),
+                    None => {
+                        if !*keyframe_requested {
+                            request_keyframe();
+                            *keyframe_requested = true;
+                        }
+                        continue;
+                    }
+                },
+                None => None,
+            };
+            let timestamp_offset = chunk.dts.unwrap_or(chunk.pts);
+            state = ConnectionState::Connecting(connect(
+                options.clone(),
+                encoder_ctx.clone(),
+                video_config,
+                timestamp_offset,
+            ));
+            continue;
+        }
+
+        let ConnectionState::Connected(connection) = &mut state else {
+            continue;
+        };
+        if let Err(err) = connection.write_chunk(&chunk) {
+            warn!(%err, "RTMP connection dropped. Reconnecting.");
+            state = ConnectionState::disconnected(Instant::now() + reconnect_delay);
+        }
+    }
+
+    if let ConnectionState::Connected(mut connection) = state {
+        if let Err(err) = connection.output_ctx.write_trailer() {
+            warn!(%err, "Failed to write FLV trailer.");
+        }
+    }
+}
+
+fn connect(
+    options: RtmpSenderOptions,
+    encoder_ctx: EncoderContext,
+    video_config: Option<Bytes>,
+    timestamp_offset: Duration,
+) -> Receiver<Result<RtmpConnection, ffmpeg::Error>> {
+    let (result_sender, result_receiver) = crossbeam_channel::bounded(1);
+    let span = Span::current();
+    std::thread::Builder::new()
+        .name("RTMP connection thread".to_string())
+        .spawn(move || {
+            let _span = span.entered();
+            let result = RtmpConnection::new(
+                &options,
+                &encoder_ctx,
+                video_config.as_deref(),
+                timestamp_offset,
+            );
+            // Receiver is dropped if the output finished in the meantime.
+            let _ = result_sender.send(result);
+        })
+        .unwrap();
+    result_receiver
+}
+
+/// Returns H264 parameter sets (SPS and PPS in Annex B format) if the chunk
+/// is a keyframe that can be used to start a new RTMP stream.
+fn stream_start_video_config(chunk: &EncodedChunk) -> Option<Bytes> {
+    match chunk.kind {
+        EncodedChunkKind::Video(VideoCodec::H264) => (),
+        EncodedChunkKind::Video(_) | EncodedChunkKind::Audio(_) => return None,
+    }
+    if chunk.is_keyframe != IsKeyframe::Yes {
+        return None;
+    }
+
+    const SPS_NAL_TYPE: u8 = 7;
+    const PPS_NAL_TYPE: u8 = 8;
+
+    let mut config = BytesMut::new();
+    for nal in annex_b_nal_units(&chunk.data) {
+        let nal_type = nal[0] & 0x1F;
+        if nal_type == SPS_NAL_TYPE || nal_type == PPS_NAL_TYPE {
+            config.extend_from_slice(&[0, 0, 0, 1]);
+            config.extend_from_slice(nal);
+        }
+    }
+
+    match config.is_empty() {
+        true => None,
+        false => Some(config.freeze()),
+    }
+}
+
+/// Splits H264 Annex B byte stream into NAL units (without start codes).
+fn annex_b_nal_units(data: &[u8]) -> impl Iterator<Item = &[u8]> {
+    let mut nal_starts = Vec::new();
+    let mut i = 0;
+    while i + 3 <= data.len() {
+        if data[i..i + 3] == [0, 0, 1] {
+            nal_starts.push(i + 3);
+            i += 3;
+        } else {
+            i += 1;
+        }
+    }
+
+    let nal_ends = nal_starts
+        .iter()
+        .skip(1)
+        .map(|start| {
+            // Start code can be either 3 or 4 bytes long.
+            match start >= 4 && data[start - 4] == 0 {
+                true => start - 4,
+                false => start - 3,
+            }
+        })
+        .chain([data.len()])
+        .collect::<Vec<_>>();
+
+    nal_starts
+        .into_iter()
+        .zip(nal_ends)
+        .filter(|(start, end)| start < end)
+        .map(move |(start, end)| &data[start..end])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_annex_b() {
+        let data = [
+            0, 0, 0, 1, 0x67, 1, 2, 0, 0, 1, 0x68, 3, 0, 0, 0, 1, 0x65, 4, 5, 6,
+        ];
+        let nals: Vec<&[u8]> = annex_b_nal_units(&data).collect();
+
+        assert_eq!(nals, vec![&[0x67, 1, 2][..], &[0x68, 3], &[0x65, 4, 5, 6]]);
+    }
+
+    #[test]
+    fn keyframe_parameter_sets() {
+        let chunk = EncodedChunk {
+            data: Bytes::from_static(&[
+                0, 0, 0, 1, 0x67, 1, 2, 0, 0, 0, 1, 0x68, 3, 0, 0, 0, 1, 0x65, 4, 5,
+            ]),
+            pts: Duration::ZERO,
+            dts: None,
+            is_keyframe: IsKeyframe::Yes,
+            kind: EncodedChunkKind::Video(VideoCodec::H264),
+        };
+
+        assert_eq!(
+            stream_start_video_config(&chunk),
+            Some(Bytes::from_static(&[
+                0, 0, 0, 1, 0x67, 1, 2, 0, 0, 0, 1, 0x68, 3
+            ]))
+        );
+    }
+}
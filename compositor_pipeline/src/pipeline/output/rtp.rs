@@ -5,13 +5,16 @@ use tracing::{debug, span, Level};
 
 use crate::{
     error::OutputInitError,
-    pipeline::{rtp::RequestedPort, structs::EncoderOutputEvent, AudioCodec, Port, VideoCodec},
+    pipeline::{
+        encoder::EncoderContext, rtp::RequestedPort, structs::EncoderOutputEvent, AudioCodec, Port,
+        VideoCodec,
+    },
 };
 
 use self::{packet_stream::PacketStream, payloader::Payloader};
 
 mod packet_stream;
-mod payloader;
+pub(super) mod payloader;
 mod tcp_server;
 mod udp;
 
@@ -46,9 +49,10 @@ impl RtpSender {
     pub fn new(
         output_id: &OutputId,
         options: RtpSenderOptions,
+        encoder_ctx: EncoderContext,
         packets_receiver: Receiver<EncoderOutputEvent>,
     ) -> Result<(Self, Option<Port>), OutputInitError> {
-        let payloader = Payloader::new(options.video, options.audio);
+        let payloader = Payloader::new(options.video, encoder_ctx.audio);
         let mtu = match options.connection_options {
             RtpConnectionOptions::Udp { .. } => 1400,
             RtpConnectionOptions::TcpServer { .. } => 64000,
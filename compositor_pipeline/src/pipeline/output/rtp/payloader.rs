@@ -4,15 +4,27 @@ use tracing::error;
 use webrtc_util::Marshal;
 
 use rand::Rng;
-use rtp::codecs::{h264::H264Payloader, opus::OpusPayloader};
+use rtp::codecs::{h264::H264Payloader, opus::OpusPayloader, vp8::Vp8Payloader, vp9::Vp9Payloader};
 
 use crate::pipeline::{
+    encoder::AudioEncoderContext,
     rtp::{AUDIO_PAYLOAD_TYPE, VIDEO_PAYLOAD_TYPE},
     structs::{EncodedChunk, EncodedChunkKind},
     AudioCodec, VideoCodec,
 };
 
+use self::{
+    aac::{AacPayloader, AacPayloadingError},
+    av1::{Av1Payloader, Av1PayloadingError},
+};
+
+mod aac;
+mod av1;
+
 const H264_CLOCK_RATE: u32 = 90000;
+const VP8_CLOCK_RATE: u32 = 90000;
+const VP9_CLOCK_RATE: u32 = 90000;
+const AV1_CLOCK_RATE: u32 = 90000;
 const OPUS_CLOCK_RATE: u32 = 48000;
 
 struct RtpStreamContext {
@@ -69,6 +81,12 @@ pub enum PayloadingError {
     #[error(transparent)]
     MarshalError(#[from] webrtc_util::Error),
 
+    #[error(transparent)]
+    Av1(#[from] Av1PayloadingError),
+
+    #[error(transparent)]
+    Aac(#[from] AacPayloadingError),
+
     #[error("Audio EOS already sent.")]
     AudioEOSAlreadySent,
 
@@ -86,6 +104,18 @@ enum VideoPayloader {
         payloader: H264Payloader,
         context: RtpStreamContext,
     },
+    Vp8 {
+        payloader: Vp8Payloader,
+        context: RtpStreamContext,
+    },
+    Vp9 {
+        payloader: Vp9Payloader,
+        context: RtpStreamContext,
+    },
+    Av1 {
+        payloader: Av1Payloader,
+        context: RtpStreamContext,
+    },
 }
 
 enum AudioPayloader {
@@ -93,17 +123,23 @@ enum AudioPayloader {
         payloader: OpusPayloader,
         context: RtpStreamContext,
     },
+    Aac {
+        payloader: AacPayloader,
+        context: RtpStreamContext,
+        /// RTP clock rate of AAC is equal to the sample rate.
+        clock_rate: u32,
+    },
 }
 
 impl Payloader {
-    pub fn new(video: Option<VideoCodec>, audio: Option<AudioCodec>) -> Self {
+    pub fn new(video: Option<VideoCodec>, audio: Option<AudioEncoderContext>) -> Self {
         Self {
             video: video.map(VideoPayloader::new),
             audio: audio.map(AudioPayloader::new),
         }
     }
 
-    pub(super) fn payload(
+    pub(crate) fn payload(
         &mut self,
         mtu: usize,
         data: EncodedChunk,
@@ -186,12 +222,28 @@ impl VideoPayloader {
                 payloader: H264Payloader::default(),
                 context: RtpStreamContext::new(),
             },
+            VideoCodec::H265 => panic!("H265 video output is not supported yet"),
+            VideoCodec::Vp8 => Self::Vp8 {
+                payloader: Vp8Payloader::default(),
+                context: RtpStreamContext::new(),
+            },
+            VideoCodec::Vp9 => Self::Vp9 {
+                payloader: Vp9Payloader::default(),
+                context: RtpStreamContext::new(),
+            },
+            VideoCodec::Av1 => Self::Av1 {
+                payloader: Av1Payloader,
+                context: RtpStreamContext::new(),
+            },
         }
     }
 
     fn codec(&self) -> VideoCodec {
         match self {
             VideoPayloader::H264 { .. } => VideoCodec::H264,
+            VideoPayloader::Vp8 { .. } => VideoCodec::Vp8,
+            VideoPayloader::Vp9 { .. } => VideoCodec::Vp9,
+            VideoPayloader::Av1 { .. } => VideoCodec::Av1,
         }
     }
 
@@ -212,30 +264,73 @@ impl VideoPayloader {
                 VIDEO_PAYLOAD_TYPE,
                 H264_CLOCK_RATE,
             ),
+            VideoPayloader::Vp8 {
+                ref mut payloader,
+                ref mut context,
+            } => payload(
+                payloader,
+                context,
+                chunk,
+                mtu,
+                VIDEO_PAYLOAD_TYPE,
+                VP8_CLOCK_RATE,
+            ),
+            VideoPayloader::Vp9 {
+                ref mut payloader,
+                ref mut context,
+            } => payload(
+                payloader,
+                context,
+                chunk,
+                mtu,
+                VIDEO_PAYLOAD_TYPE,
+                VP9_CLOCK_RATE,
+            ),
+            VideoPayloader::Av1 {
+                ref mut payloader,
+                ref mut context,
+            } => {
+                let payloads = payloader.payload(mtu, &chunk.data)?;
+                into_rtp_packets(
+                    payloads,
+                    context,
+                    &chunk,
+                    VIDEO_PAYLOAD_TYPE,
+                    AV1_CLOCK_RATE,
+                )
+            }
         }
     }
 
     fn context_mut(&mut self) -> &mut RtpStreamContext {
         match self {
             VideoPayloader::H264 { context, .. } => context,
+            VideoPayloader::Vp8 { context, .. } => context,
+            VideoPayloader::Vp9 { context, .. } => context,
+            VideoPayloader::Av1 { context, .. } => context,
         }
     }
 }
 
 impl AudioPayloader {
-    fn new(codec: AudioCodec) -> Self {
-        match codec {
-            AudioCodec::Opus => Self::Opus {
+    fn new(encoder_context: AudioEncoderContext) -> Self {
+        match encoder_context {
+            AudioEncoderContext::Opus => Self::Opus {
                 payloader: OpusPayloader,
                 context: RtpStreamContext::new(),
             },
-            AudioCodec::Aac => panic!("Aac audio output is not supported yet"),
+            AudioEncoderContext::Aac { sample_rate, .. } => Self::Aac {
+                payloader: AacPayloader,
+                context: RtpStreamContext::new(),
+                clock_rate: sample_rate,
+            },
         }
     }
 
     fn codec(&self) -> AudioCodec {
         match self {
             AudioPayloader::Opus { .. } => AudioCodec::Opus,
+            AudioPayloader::Aac { .. } => AudioCodec::Aac,
         }
     }
 
@@ -256,12 +351,21 @@ impl AudioPayloader {
                 AUDIO_PAYLOAD_TYPE,
                 OPUS_CLOCK_RATE,
             ),
+            AudioPayloader::Aac {
+                ref mut payloader,
+                ref mut context,
+                clock_rate,
+            } => {
+                let payloads = payloader.payload(mtu, &chunk.data)?;
+                into_rtp_packets(payloads, context, &chunk, AUDIO_PAYLOAD_TYPE, *clock_rate)
+            }
         }
     }
 
     fn context_mut(&mut self) -> &mut RtpStreamContext {
         match self {
             AudioPayloader::Opus { context, .. } => context,
+            AudioPayloader::Aac { context, .. } => context,
         }
     }
 }
@@ -275,6 +379,16 @@ fn payload<T: rtp::packetizer::Payloader>(
     clock_rate: u32,
 ) -> Result<VecDeque<Bytes>, PayloadingError> {
     let payloads = payloader.payload(mtu, &chunk.data)?;
+    into_rtp_packets(payloads, context, &chunk, payload_type, clock_rate)
+}
+
+fn into_rtp_packets(
+    payloads: Vec<Bytes>,
+    context: &mut RtpStreamContext,
+    chunk: &EncodedChunk,
+    payload_type: u8,
+    clock_rate: u32,
+) -> Result<VecDeque<Bytes>, PayloadingError> {
     let packets_amount = payloads.len();
 
     payloads
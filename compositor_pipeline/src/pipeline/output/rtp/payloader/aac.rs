@@ -0,0 +1,64 @@
+use bytes::{BufMut, Bytes, BytesMut};
+
+/// AU-headers-length field and a single AU-header with 13 bit size and 3 bit index.
+const AU_HEADER_SECTION_SIZE: usize = 4;
+const MAX_AU_SIZE: usize = (1 << 13) - 1;
+
+#[derive(Debug, thiserror::Error)]
+pub enum AacPayloadingError {
+    #[error("MTU is too small to fit an AAC RTP packet.")]
+    MtuTooSmall,
+
+    #[error("AAC frame of size {0} is too large to be described by an AU-header.")]
+    FrameTooLarge(usize),
+}
+
+/// Payloads AAC frames as described in [RFC 3640](https://datatracker.ietf.org/doc/html/rfc3640)
+/// in the AAC-hbr mode (`sizeLength=13; indexLength=3; indexDeltaLength=3`). Each packet
+/// carries one frame, frames that do not fit in a packet are fragmented.
+#[derive(Debug, Default)]
+pub struct AacPayloader;
+
+impl AacPayloader {
+    pub fn payload(&mut self, mtu: usize, data: &Bytes) -> Result<Vec<Bytes>, AacPayloadingError> {
+        if mtu <= AU_HEADER_SECTION_SIZE {
+            return Err(AacPayloadingError::MtuTooSmall);
+        }
+        if data.len() > MAX_AU_SIZE {
+            return Err(AacPayloadingError::FrameTooLarge(data.len()));
+        }
+
+        // RFC 3640, section 3.2.3.1: Fragments of an access unit contain the AU-header
+        // with the size of the whole access unit.
+        let au_header = (data.len() as u16) << 3;
+        let packets = data
+            .chunks(mtu - AU_HEADER_SECTION_SIZE)
+            .map(|fragment| {
+                let mut packet = BytesMut::with_capacity(AU_HEADER_SECTION_SIZE + fragment.len());
+                packet.put_u16(16); // AU-headers-length in bits
+                packet.put_u16(au_header);
+                packet.put_slice(fragment);
+                packet.freeze()
+            })
+            .collect();
+
+        Ok(packets)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fragment_frame() {
+        let frame = Bytes::from_static(&[0xa1, 0xa2, 0xa3, 0xa4, 0xa5]);
+
+        let packets = AacPayloader.payload(7, &frame).unwrap();
+
+        // Both fragments start with the AU-header of the whole frame (size 5, index 0).
+        let first_packet = [0x00, 0x10, 0x00, 0x28, 0xa1, 0xa2, 0xa3];
+        let second_packet = [0x00, 0x10, 0x00, 0x28, 0xa4, 0xa5];
+        assert_eq!(packets, vec![&first_packet[..], &second_packet[..]]);
+    }
+}
@@ -0,0 +1,150 @@
+use bytes::{BufMut, Bytes, BytesMut};
+
+use crate::pipeline::rtp::av1::{
+    leb128_size, write_leb128, Obu, ObuParseError, AGGREGATION_HEADER_N,
+    AGGREGATION_HEADER_W_SHIFT, AGGREGATION_HEADER_Y, AGGREGATION_HEADER_Z, OBU_TYPE_PADDING,
+    OBU_TYPE_SEQUENCE_HEADER, OBU_TYPE_TEMPORAL_DELIMITER, OBU_TYPE_TILE_LIST,
+};
+
+const AGGREGATION_HEADER_SIZE: usize = 1;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Av1PayloadingError {
+    #[error("MTU is too small to fit an AV1 RTP packet.")]
+    MtuTooSmall,
+
+    #[error("Failed to parse AV1 temporal unit.")]
+    InvalidObu(#[from] ObuParseError),
+}
+
+/// Splits AV1 temporal units into payloads of the
+/// [AV1 RTP payload format](https://aomediacodec.github.io/av1-rtp-spec/).
+/// OBUs are aggregated into as few packets as possible and fragmented if they do not fit.
+#[derive(Debug, Default)]
+pub struct Av1Payloader;
+
+impl Av1Payloader {
+    pub fn payload(&mut self, mtu: usize, data: &Bytes) -> Result<Vec<Bytes>, Av1PayloadingError> {
+        // Aggregation header, length field and at least one byte of an OBU element.
+        if mtu < AGGREGATION_HEADER_SIZE + 2 {
+            return Err(Av1PayloadingError::MtuTooSmall);
+        }
+        let obus = Obu::read_all(data.clone())?;
+
+        // Temporal delimiters and tile lists should be removed, padding is not needed.
+        let elements = obus.iter().filter(|obu| {
+            !matches!(
+                obu.obu_type(),
+                OBU_TYPE_TEMPORAL_DELIMITER | OBU_TYPE_TILE_LIST | OBU_TYPE_PADDING
+            )
+        });
+
+        let mut packets = Vec::new();
+        let mut packet = PacketBuilder::default();
+        for obu in elements {
+            let mut element = BytesMut::new();
+            obu.write_without_size(&mut element);
+            let mut element = element.freeze();
+
+            loop {
+                let available = mtu - AGGREGATION_HEADER_SIZE - packet.size;
+                // Space for the length field is always reserved, even though it is omitted
+                // for the last element in the packet.
+                let max_element_len = available.saturating_sub(leb128_size(available as u64));
+                if max_element_len == 0 {
+                    packets.push(packet.finish(false));
+                    packet = PacketBuilder::default();
+                    continue;
+                }
+
+                if element.len() <= max_element_len {
+                    packet.push(element);
+                    break;
+                }
+
+                packet.push(element.split_to(max_element_len));
+                packets.push(packet.finish(true));
+                packet = PacketBuilder {
+                    starts_with_fragment: true,
+                    ..Default::default()
+                };
+            }
+        }
+        if !packet.elements.is_empty() {
+            packets.push(packet.finish(false));
+        }
+
+        let is_new_coded_video_sequence = obus
+            .iter()
+            .any(|obu| obu.obu_type() == OBU_TYPE_SEQUENCE_HEADER);
+        if let Some(first_packet) = packets.first_mut().filter(|_| is_new_coded_video_sequence) {
+            first_packet[0] |= AGGREGATION_HEADER_N;
+        }
+
+        Ok(packets.into_iter().map(BytesMut::freeze).collect())
+    }
+}
+
+#[derive(Default)]
+struct PacketBuilder {
+    elements: Vec<Bytes>,
+    /// Size of the elements including their length fields.
+    size: usize,
+    starts_with_fragment: bool,
+}
+
+impl PacketBuilder {
+    fn push(&mut self, element: Bytes) {
+        self.size += leb128_size(element.len() as u64) + element.len();
+        self.elements.push(element);
+    }
+
+    fn finish(self, ends_with_fragment: bool) -> BytesMut {
+        // W field can only describe up to 3 elements.
+        let elements_count = match self.elements.len() {
+            count @ 1..=3 => count as u8,
+            _ => 0,
+        };
+
+        let mut aggregation_header = elements_count << AGGREGATION_HEADER_W_SHIFT;
+        if self.starts_with_fragment {
+            aggregation_header |= AGGREGATION_HEADER_Z;
+        }
+        if ends_with_fragment {
+            aggregation_header |= AGGREGATION_HEADER_Y;
+        }
+
+        let mut packet = BytesMut::with_capacity(AGGREGATION_HEADER_SIZE + self.size);
+        packet.put_u8(aggregation_header);
+        let last_index = self.elements.len() - 1;
+        for (index, element) in self.elements.into_iter().enumerate() {
+            if elements_count == 0 || index != last_index {
+                write_leb128(&mut packet, element.len() as u64);
+            }
+            packet.extend_from_slice(&element);
+        }
+        packet
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aggregate_and_fragment_obus() {
+        let temporal_delimiter = [0b0001_0010, 0];
+        let sequence_header = [0b0000_1010, 2, 0xa1, 0xa2];
+        let frame = [0b0011_0010, 6, 0xb1, 0xb2, 0xb3, 0xb4, 0xb5, 0xb6];
+        let temporal_unit =
+            Bytes::from([&temporal_delimiter[..], &sequence_header, &frame].concat());
+
+        let packets = Av1Payloader.payload(10, &temporal_unit).unwrap();
+
+        // N=1, W=2, sequence header with a length field and the beginning of the frame
+        let first_packet = [0x68, 3, 0x08, 0xa1, 0xa2, 0x30, 0xb1, 0xb2, 0xb3];
+        // Z=1, W=1, rest of the frame
+        let second_packet = [0x90, 0xb4, 0xb5, 0xb6];
+        assert_eq!(packets, vec![&first_packet[..], &second_packet[..]]);
+    }
+}
@@ -0,0 +1,358 @@
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    sync::{Arc, Mutex},
+};
+
+use compositor_render::OutputId;
+use crossbeam_channel::{Receiver, Sender};
+use rtcp::payload_feedbacks::{
+    full_intra_request::FullIntraRequest, picture_loss_indication::PictureLossIndication,
+};
+use tokio::runtime::Handle;
+use tracing::{debug, error, info, span, warn, Instrument, Level};
+use webrtc::{
+    peer_connection::{peer_connection_state::RTCPeerConnectionState, RTCPeerConnection},
+    rtp_transceiver::rtp_sender::RTCRtpSender,
+    track::track_local::{
+        track_local_static_rtp::TrackLocalStaticRTP, TrackLocal, TrackLocalWriter,
+    },
+};
+
+use crate::{
+    error::OutputInitError,
+    pipeline::{
+        encoder::EncoderContext,
+        structs::{EncodedChunkKind, EncoderOutputEvent},
+        webrtc_utils::{self, NegotiationError},
+        AudioCodec, VideoCodec,
+    },
+};
+
+use super::rtp::payloader::Payloader;
+
+/// Packets are sent over UDP with DTLS-SRTP overhead, so they have to be smaller
+/// than a typical MTU.
+const MTU: usize = 1200;
+
+#[derive(Debug, thiserror::Error)]
+pub enum WhepSessionError {
+    #[error("WHEP output was already unregistered.")]
+    OutputEnded,
+
+    #[error("WHEP session \"{0}\" does not exist.")]
+    SessionNotFound(Arc<str>),
+
+    #[error("Failed to apply the SDP offer.")]
+    InvalidOffer(#[source] webrtc::Error),
+
+    #[error("Failed to create the WebRTC peer connection.")]
+    PeerConnection(#[source] webrtc::Error),
+
+    #[error("WebRTC peer connection did not produce the SDP answer.")]
+    MissingAnswer,
+}
+
+#[derive(Debug, Clone)]
+pub struct WhepSenderOptions {
+    pub video: Option<VideoCodec>,
+    pub audio: Option<AudioCodec>,
+}
+
+/// Output that serves media to WebRTC peers (e.g. browsers) negotiated with
+/// [WHEP](https://datatracker.ietf.org/doc/html/draft-murillo-whep). Encoded chunks are
+/// payloaded once and written to tracks shared by all viewers.
+#[derive(Debug)]
+pub struct WhepSender {
+    viewers: WhepViewers,
+}
+
+/// Handle used to add and remove viewers of a WHEP output.
+#[derive(Clone)]
+pub struct WhepViewers {
+    video_track: Option<Arc<TrackLocalStaticRTP>>,
+    audio_track: Option<Arc<TrackLocalStaticRTP>>,
+    keyframe_request_sender: Option<Sender<()>>,
+    /// Peer connections of viewers by session id. `None` after the output was unregistered.
+    sessions: Arc<Mutex<Option<HashMap<Arc<str>, (Arc<RTCPeerConnection>, Handle)>>>>,
+}
+
+impl WhepSender {
+    pub fn new(
+        output_id: &OutputId,
+        options: WhepSenderOptions,
+        encoder_ctx: EncoderContext,
+        packets_receiver: Receiver<EncoderOutputEvent>,
+    ) -> Result<Self, OutputInitError> {
+        if let Some(AudioCodec::Aac) = options.audio {
+            return Err(OutputInitError::UnsupportedAudioCodec(AudioCodec::Aac));
+        }
+        if let Some(codec) = options.video.filter(|codec| *codec != VideoCodec::H264) {
+            return Err(OutputInitError::UnsupportedVideoCodec(codec));
+        }
+
+        let stream_id = format!("live_compositor_{output_id}");
+        let video_track = options.video.map(|_| {
+            Arc::new(TrackLocalStaticRTP::new(
+                webrtc_utils::h264_codec_capability(),
+                "video".to_owned(),
+                stream_id.clone(),
+            ))
+        });
+        let audio_track = options.audio.map(|_| {
+            Arc::new(TrackLocalStaticRTP::new(
+                webrtc_utils::opus_codec_capability(),
+                "audio".to_owned(),
+                stream_id.clone(),
+            ))
+        });
+
+        let keyframe_request_sender = encoder_ctx.keyframe_request_sender;
+        let payloader = Payloader::new(options.video, encoder_ctx.audio);
+        let output_id = output_id.clone();
+        let (video, audio) = (video_track.clone(), audio_track.clone());
+        std::thread::Builder::new()
+            .name(format!("WHEP sender for output {}", output_id))
+            .spawn(move || {
+                let _span = span!(
+                    Level::INFO,
+                    "WHEP sender",
+                    output_id = output_id.to_string()
+                )
+                .entered();
+                run_sender_thread(packets_receiver, payloader, video, audio);
+                debug!("Closing WHEP sender thread.");
+            })
+            .unwrap();
+
+        Ok(Self {
+            viewers: WhepViewers {
+                video_track,
+                audio_track,
+                keyframe_request_sender,
+                sessions: Arc::new(Mutex::new(Some(HashMap::new()))),
+            },
+        })
+    }
+
+    pub fn viewers(&self) -> WhepViewers {
+        self.viewers.clone()
+    }
+}
+
+impl Drop for WhepSender {
+    fn drop(&mut self) {
+        let sessions = self.viewers.sessions.lock().unwrap().take();
+        for (session_id, (peer_connection, runtime)) in sessions.into_iter().flatten() {
+            runtime.spawn(async move {
+                if let Err(err) = peer_connection.close().await {
+                    debug!(%err, %session_id, "Failed to close WHEP peer connection.");
+                }
+            });
+        }
+    }
+}
+
+impl WhepViewers {
+    /// Applies the SDP offer of a new viewer and returns the session id and the SDP answer
+    /// with all ICE candidates included, so trickle ICE is not needed.
+    pub async fn add_viewer(
+        &self,
+        output_id: &OutputId,
+        offer: String,
+    ) -> Result<(Arc<str>, String), WhepSessionError> {
+        if self.sessions.lock().unwrap().is_none() {
+            return Err(WhepSessionError::OutputEnded);
+        }
+
+        let session_id: Arc<str> = format!("{:016x}", rand::random::<u64>()).into();
+        let peer_connection = Arc::new(
+            webrtc_utils::new_peer_connection(
+                self.video_track.is_some(),
+                self.audio_track.is_some(),
+            )
+            .await
+            .map_err(WhepSessionError::PeerConnection)?,
+        );
+
+        let result = self
+            .start_peer_connection(output_id, &session_id, &peer_connection, offer)
+            .await;
+        let answer = match result {
+            Ok(answer) => answer,
+            Err(err) => {
+                tokio::spawn(async move { peer_connection.close().await });
+                return Err(err);
+            }
+        };
+
+        let mut guard = self.sessions.lock().unwrap();
+        let Some(sessions) = guard.as_mut() else {
+            tokio::spawn(async move { peer_connection.close().await });
+            return Err(WhepSessionError::OutputEnded);
+        };
+        sessions.insert(session_id.clone(), (peer_connection, Handle::current()));
+
+        Ok((session_id, answer))
+    }
+
+    async fn start_peer_connection(
+        &self,
+        output_id: &OutputId,
+        session_id: &Arc<str>,
+        peer_connection: &Arc<RTCPeerConnection>,
+        offer: String,
+    ) -> Result<String, WhepSessionError> {
+        let span = span!(
+            Level::INFO,
+            "WHEP viewer",
+            output_id = output_id.to_string(),
+            session_id = session_id.to_string()
+        );
+        let tracks = [
+            (&self.video_track, self.keyframe_request_sender.clone()),
+            (&self.audio_track, None),
+        ];
+        for (track, keyframe_request_sender) in tracks {
+            let Some(track) = track else {
+                continue;
+            };
+            let rtp_sender = peer_connection
+                .add_track(track.clone() as Arc<dyn TrackLocal + Send + Sync>)
+                .await
+                .map_err(WhepSessionError::PeerConnection)?;
+            tokio::spawn(read_rtcp(rtp_sender, keyframe_request_sender).instrument(span.clone()));
+        }
+
+        let viewers = self.clone();
+        let output_id = output_id.clone();
+        let session_id = session_id.clone();
+        peer_connection.on_peer_connection_state_change(Box::new(move |state| {
+            info!(?output_id, %session_id, ?state, "WHEP peer connection state changed.");
+            let viewers = viewers.clone();
+            let session_id = session_id.clone();
+            Box::pin(async move {
+                if matches!(
+                    state,
+                    RTCPeerConnectionState::Failed | RTCPeerConnectionState::Closed
+                ) {
+                    // Session is already removed if it was terminated by the viewer.
+                    let _ = viewers.remove_viewer(&session_id).await;
+                }
+            })
+        }));
+
+        webrtc_utils::negotiate_answer(peer_connection, offer)
+            .await
+            .map_err(Into::into)
+    }
+
+    /// Closes the peer connection of a viewer.
+    pub async fn remove_viewer(&self, session_id: &str) -> Result<(), WhepSessionError> {
+        let peer_connection = match self.sessions.lock().unwrap().as_mut() {
+            Some(sessions) => sessions.remove(session_id),
+            None => return Err(WhepSessionError::OutputEnded),
+        };
+        match peer_connection {
+            Some((peer_connection, _)) => peer_connection
+                .close()
+                .await
+                .map_err(WhepSessionError::PeerConnection),
+            None => Err(WhepSessionError::SessionNotFound(session_id.into())),
+        }
+    }
+}
+
+impl Debug for WhepViewers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let viewers_count = self
+            .sessions
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map_or(0, HashMap::len);
+        f.debug_struct("WhepViewers")
+            .field("viewers_count", &viewers_count)
+            .finish()
+    }
+}
+
+impl From<NegotiationError> for WhepSessionError {
+    fn from(err: NegotiationError) -> Self {
+        match err {
+            NegotiationError::InvalidOffer(err) => WhepSessionError::InvalidOffer(err),
+            NegotiationError::PeerConnection(err) => WhepSessionError::PeerConnection(err),
+            NegotiationError::MissingAnswer => WhepSessionError::MissingAnswer,
+        }
+    }
+}
+
+fn run_sender_thread(
+    packets_receiver: Receiver<EncoderOutputEvent>,
+    mut payloader: Payloader,
+    video_track: Option<Arc<TrackLocalStaticRTP>>,
+    audio_track: Option<Arc<TrackLocalStaticRTP>>,
+) {
+    // Tracks are written from this thread, while peer connections are driven
+    // by the runtime of the API server.
+    let runtime = match tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+    {
+        Ok(runtime) => runtime,
+        Err(err) => {
+            error!(%err, "Failed to start WHEP sender runtime.");
+            return;
+        }
+    };
+
+    for event in packets_receiver {
+        // WebRTC does not signal the end of a stream. Viewers are disconnected
+        // when the output is unregistered.
+        let EncoderOutputEvent::Data(chunk) = event else {
+            continue;
+        };
+        let track = match chunk.kind {
+            EncodedChunkKind::Video(_) => &video_track,
+            EncodedChunkKind::Audio(_) => &audio_track,
+        };
+        let Some(track) = track else {
+            continue;
+        };
+
+        let packets = match payloader.payload(MTU, chunk) {
+            Ok(packets) => packets,
+            Err(err) => {
+                warn!(%err, "Failed to payload chunk.");
+                continue;
+            }
+        };
+        for packet in packets {
+            // Payload type and SSRC are rewritten for each viewer.
+            if let Err(err) = runtime.block_on(track.write(&packet)) {
+                debug!(%err, "Failed to send RTP packet to some of the WHEP viewers.");
+            }
+        }
+    }
+}
+
+/// RTCP packets have to be read, so interceptors (e.g. NACK responder) can process them.
+/// Picture loss reported by a viewer (e.g. a new viewer that joined between keyframes)
+/// is handled by requesting a keyframe from the encoder.
+async fn read_rtcp(rtp_sender: Arc<RTCRtpSender>, keyframe_request_sender: Option<Sender<()>>) {
+    while let Ok((packets, _)) = rtp_sender.read_rtcp().await {
+        let Some(keyframe_request_sender) = &keyframe_request_sender else {
+            continue;
+        };
+        let is_keyframe_requested = packets.iter().any(|packet| {
+            let packet = packet.as_any();
+            packet.is::<PictureLossIndication>() || packet.is::<FullIntraRequest>()
+        });
+        if is_keyframe_requested {
+            debug!("WHEP viewer requested a keyframe.");
+            if keyframe_request_sender.send(()).is_err() {
+                debug!("Failed to request a keyframe. Encoder already finished.");
+            }
+        }
+    }
+}
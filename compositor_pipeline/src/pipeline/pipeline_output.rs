@@ -10,7 +10,7 @@ use tracing::{info, warn};
 use crate::{audio_mixer::OutputSamples, error::RegisterOutputError, queue::PipelineEvent};
 
 use super::{
-    encoder::{self, opus, AudioEncoderOptions, Encoder, EncoderOptions},
+    encoder::{self, Encoder, EncoderOptions},
     output::{self, Output},
     Pipeline, PipelineInput, Port, RegisterOutputOptions,
 };
@@ -60,18 +60,15 @@ impl Pipeline {
             video: video
                 .as_ref()
                 .map(|video_opts| video_opts.encoder_opts.clone()),
-            audio: audio.as_ref().map(|audio_opts| {
-                AudioEncoderOptions::Opus(opus::Options {
-                    channels: audio_opts.channels,
-                    preset: audio_opts.encoder_preset,
-                })
-            }),
+            audio: audio
+                .as_ref()
+                .map(|audio_opts| audio_opts.encoder_opts.clone()),
         };
 
         let (encoder, packets) = Encoder::new(&output_id, encoder_opts, self.output_sample_rate)
             .map_err(|e| RegisterOutputError::EncoderError(output_id.clone(), e))?;
 
-        let (output, port) = Output::new(&output_id, output_options, packets)
+        let (output, port) = Output::new(&output_id, output_options, encoder.context(), packets)
             .map_err(|e| RegisterOutputError::OutputError(output_id.clone(), e))?;
 
         let output = PipelineOutput {
@@ -109,7 +106,7 @@ impl Pipeline {
                 output_id.clone(),
                 audio_opts.initial,
                 audio_opts.mixing_strategy,
-                audio_opts.channels,
+                audio_opts.encoder_opts.channels(),
             );
         }
 
@@ -2,6 +2,8 @@ use std::net;
 
 use super::Port;
 
+pub(crate) mod av1;
+
 pub(crate) const VIDEO_PAYLOAD_TYPE: u8 = 96;
 pub(crate) const AUDIO_PAYLOAD_TYPE: u8 = 97;
 
@@ -0,0 +1,168 @@
+//! Parts of the [AV1 RTP payload format](https://aomediacodec.github.io/av1-rtp-spec/)
+//! shared by the payloader and the depayloader.
+
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+
+pub(crate) const OBU_TYPE_SEQUENCE_HEADER: u8 = 1;
+pub(crate) const OBU_TYPE_TEMPORAL_DELIMITER: u8 = 2;
+pub(crate) const OBU_TYPE_TILE_LIST: u8 = 8;
+pub(crate) const OBU_TYPE_PADDING: u8 = 15;
+
+const OBU_EXTENSION_FLAG: u8 = 0b0000_0100;
+const OBU_HAS_SIZE_FIELD: u8 = 0b0000_0010;
+
+/// First OBU element of the packet is a continuation of the OBU from the previous packet.
+pub(crate) const AGGREGATION_HEADER_Z: u8 = 0b1000_0000;
+/// Last OBU element of the packet continues in the next packet.
+pub(crate) const AGGREGATION_HEADER_Y: u8 = 0b0100_0000;
+/// Packet is the first packet of a coded video sequence.
+pub(crate) const AGGREGATION_HEADER_N: u8 = 0b0000_1000;
+/// Position of the 2 bit W field, the number of OBU elements in the packet. If W is 0,
+/// every element is preceded by its length, otherwise the last element has no length field.
+pub(crate) const AGGREGATION_HEADER_W_SHIFT: u8 = 4;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ObuParseError {
+    #[error("OBU is truncated.")]
+    Truncated,
+
+    #[error("Invalid LEB128 encoded value.")]
+    InvalidLeb128,
+}
+
+/// Open Bitstream Unit, the basic unit of an AV1 bitstream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Obu {
+    header: u8,
+    extension_header: Option<u8>,
+    payload: Bytes,
+}
+
+impl Obu {
+    /// Reads a single OBU. If the OBU does not have the size field, it spans to the end of `data`.
+    pub fn read(data: &mut Bytes) -> Result<Self, ObuParseError> {
+        if !data.has_remaining() {
+            return Err(ObuParseError::Truncated);
+        }
+        let header = data.get_u8();
+        let extension_header = match header & OBU_EXTENSION_FLAG {
+            0 => None,
+            _ if data.has_remaining() => Some(data.get_u8()),
+            _ => return Err(ObuParseError::Truncated),
+        };
+
+        let payload_len = match header & OBU_HAS_SIZE_FIELD {
+            0 => data.len(),
+            _ => read_leb128(data)? as usize,
+        };
+        if payload_len > data.len() {
+            return Err(ObuParseError::Truncated);
+        }
+
+        Ok(Self {
+            header: header & !OBU_HAS_SIZE_FIELD,
+            extension_header,
+            payload: data.split_to(payload_len),
+        })
+    }
+
+    /// Reads all OBUs from `data`, e.g. a temporal unit in the low overhead bitstream format.
+    pub fn read_all(mut data: Bytes) -> Result<Vec<Self>, ObuParseError> {
+        let mut obus = Vec::new();
+        while data.has_remaining() {
+            obus.push(Self::read(&mut data)?);
+        }
+        Ok(obus)
+    }
+
+    pub fn obu_type(&self) -> u8 {
+        (self.header >> 3) & 0b1111
+    }
+
+    /// Writes the OBU without the size field, as it is done in RTP packets.
+    pub fn write_without_size(&self, buf: &mut BytesMut) {
+        buf.put_u8(self.header);
+        if let Some(extension_header) = self.extension_header {
+            buf.put_u8(extension_header);
+        }
+        buf.extend_from_slice(&self.payload);
+    }
+
+    /// Writes the OBU with the size field, as it is done in the low overhead bitstream format.
+    pub fn write_with_size(&self, buf: &mut BytesMut) {
+        buf.put_u8(self.header | OBU_HAS_SIZE_FIELD);
+        if let Some(extension_header) = self.extension_header {
+            buf.put_u8(extension_header);
+        }
+        write_leb128(buf, self.payload.len() as u64);
+        buf.extend_from_slice(&self.payload);
+    }
+}
+
+pub(crate) fn read_leb128(data: &mut Bytes) -> Result<u64, ObuParseError> {
+    let mut value = 0;
+    // AV1 spec limits LEB128 values to 8 bytes.
+    for i in 0..8 {
+        if !data.has_remaining() {
+            return Err(ObuParseError::Truncated);
+        }
+        let byte = data.get_u8();
+        value |= ((byte & 0x7f) as u64) << (i * 7);
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(ObuParseError::InvalidLeb128)
+}
+
+pub(crate) fn write_leb128(buf: &mut BytesMut, mut value: u64) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            buf.put_u8(byte);
+            return;
+        }
+        buf.put_u8(byte | 0x80);
+    }
+}
+
+pub(crate) fn leb128_size(value: u64) -> usize {
+    let bits = u64::BITS - value.leading_zeros();
+    usize::max(1, bits.div_ceil(7) as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn leb128_round_trip() {
+        for value in [0, 1, 127, 128, 300, 16383, 16384, u32::MAX as u64] {
+            let mut buf = BytesMut::new();
+            write_leb128(&mut buf, value);
+            assert_eq!(buf.len(), leb128_size(value));
+            assert_eq!(read_leb128(&mut buf.freeze()).unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn obu_size_field() {
+        // Sequence header OBU with extension header and without size field.
+        let data = Bytes::from_static(&[0b0000_1100, 0xaa, 1, 2, 3]);
+        let obu = Obu::read(&mut data.clone()).unwrap();
+        assert_eq!(obu.obu_type(), OBU_TYPE_SEQUENCE_HEADER);
+
+        let mut with_size = BytesMut::new();
+        obu.write_with_size(&mut with_size);
+        assert_eq!(&with_size[..], &[0b0000_1110, 0xaa, 3, 1, 2, 3]);
+
+        let mut with_size = with_size.freeze();
+        assert_eq!(Obu::read(&mut with_size).unwrap(), obu);
+        assert!(with_size.is_empty());
+
+        let mut without_size = BytesMut::new();
+        obu.write_without_size(&mut without_size);
+        assert_eq!(without_size.freeze(), data);
+    }
+}
@@ -10,9 +10,22 @@ pub struct EncodedChunk {
     pub data: Bytes,
     pub pts: Duration,
     pub dts: Option<Duration>,
+    pub is_keyframe: IsKeyframe,
     pub kind: EncodedChunkKind,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IsKeyframe {
+    /// this is a keyframe
+    Yes,
+    /// this is not a keyframe
+    No,
+    /// it's unknown whether this frame is a keyframe or not
+    Unknown,
+    /// the codec this chunk is encoded in does not have keyframes at all
+    NoKeyframes,
+}
+
 pub enum EncoderOutputEvent {
     Data(EncodedChunk),
     AudioEOS,
@@ -53,6 +66,10 @@ impl EncodedChunk {
                 .map(rescale)
                 .ok_or(ChunkFromFfmpegError::NoPts)?,
             dts: value.dts().map(rescale),
+            is_keyframe: match value.is_key() {
+                true => IsKeyframe::Yes,
+                false => IsKeyframe::No,
+            },
             kind,
         })
     }
@@ -61,6 +78,10 @@ impl EncodedChunk {
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum VideoCodec {
     H264,
+    H265,
+    Vp8,
+    Vp9,
+    Av1,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -81,6 +102,10 @@ impl TryFrom<ffmpeg_next::Codec> for VideoCodec {
     fn try_from(value: ffmpeg_next::Codec) -> Result<Self, Self::Error> {
         match value.id() {
             ffmpeg_next::codec::Id::H264 => Ok(Self::H264),
+            ffmpeg_next::codec::Id::HEVC => Ok(Self::H265),
+            ffmpeg_next::codec::Id::VP8 => Ok(Self::Vp8),
+            ffmpeg_next::codec::Id::VP9 => Ok(Self::Vp9),
+            ffmpeg_next::codec::Id::AV1 => Ok(Self::Av1),
             v => Err(CodecFromFfmpegError::UnsupportedCodec(v)),
         }
     }
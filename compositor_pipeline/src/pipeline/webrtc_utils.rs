@@ -0,0 +1,141 @@
+use webrtc::{
+    api::{
+        interceptor_registry::register_default_interceptors,
+        media_engine::{MediaEngine, MIME_TYPE_H264, MIME_TYPE_OPUS},
+        setting_engine::SettingEngine,
+        APIBuilder,
+    },
+    interceptor::registry::Registry,
+    peer_connection::{
+        configuration::RTCConfiguration, sdp::session_description::RTCSessionDescription,
+        RTCPeerConnection,
+    },
+    rtp_transceiver::{
+        rtp_codec::{RTCRtpCodecCapability, RTCRtpCodecParameters, RTPCodecType},
+        RTCPFeedback,
+    },
+};
+
+const H264_CLOCK_RATE: u32 = 90000;
+const OPUS_CLOCK_RATE: u32 = 48000;
+
+/// Creates a peer connection used by WHIP and WHEP endpoints. The compositor runs
+/// as an ICE-lite agent, so it has to be reachable on its host addresses.
+pub(super) async fn new_peer_connection(
+    video: bool,
+    audio: bool,
+) -> Result<RTCPeerConnection, webrtc::Error> {
+    let mut media_engine = MediaEngine::default();
+    if video {
+        register_h264_codecs(&mut media_engine)?;
+    }
+    if audio {
+        media_engine.register_codec(
+            RTCRtpCodecParameters {
+                capability: opus_codec_capability(),
+                payload_type: 111,
+                ..Default::default()
+            },
+            RTPCodecType::Audio,
+        )?;
+    }
+
+    let registry = register_default_interceptors(Registry::new(), &mut media_engine)?;
+
+    let mut setting_engine = SettingEngine::default();
+    setting_engine.set_lite(true);
+
+    let api = APIBuilder::new()
+        .with_media_engine(media_engine)
+        .with_interceptor_registry(registry)
+        .with_setting_engine(setting_engine)
+        .build();
+
+    api.new_peer_connection(RTCConfiguration::default()).await
+}
+
+pub(super) enum NegotiationError {
+    InvalidOffer(webrtc::Error),
+    PeerConnection(webrtc::Error),
+    MissingAnswer,
+}
+
+/// Applies the SDP offer and returns the SDP answer with all ICE candidates included,
+/// so trickle ICE is not needed.
+pub(super) async fn negotiate_answer(
+    peer_connection: &RTCPeerConnection,
+    offer: String,
+) -> Result<String, NegotiationError> {
+    let offer = RTCSessionDescription::offer(offer).map_err(NegotiationError::InvalidOffer)?;
+    peer_connection
+        .set_remote_description(offer)
+        .await
+        .map_err(NegotiationError::InvalidOffer)?;
+
+    let answer = peer_connection
+        .create_answer(None)
+        .await
+        .map_err(NegotiationError::InvalidOffer)?;
+    let mut gathering_complete = peer_connection.gathering_complete_promise().await;
+    peer_connection
+        .set_local_description(answer)
+        .await
+        .map_err(NegotiationError::PeerConnection)?;
+    let _ = gathering_complete.recv().await;
+
+    peer_connection
+        .local_description()
+        .await
+        .map(|answer| answer.sdp)
+        .ok_or(NegotiationError::MissingAnswer)
+}
+
+pub(super) fn h264_codec_capability() -> RTCRtpCodecCapability {
+    RTCRtpCodecCapability {
+        mime_type: MIME_TYPE_H264.to_owned(),
+        clock_rate: H264_CLOCK_RATE,
+        ..Default::default()
+    }
+}
+
+pub(super) fn opus_codec_capability() -> RTCRtpCodecCapability {
+    RTCRtpCodecCapability {
+        mime_type: MIME_TYPE_OPUS.to_owned(),
+        clock_rate: OPUS_CLOCK_RATE,
+        channels: 2,
+        sdp_fmtp_line: "minptime=10;useinbandfec=1".to_owned(),
+        rtcp_feedback: vec![],
+    }
+}
+
+/// Registers H264 with profiles commonly offered by browsers. Payload types only
+/// matter for the local description, the answer uses payload types from the offer.
+fn register_h264_codecs(media_engine: &mut MediaEngine) -> Result<(), webrtc::Error> {
+    const H264_FMTP: &str = "level-asymmetry-allowed=1;packetization-mode=1";
+    const PROFILES: [(u8, &str); 3] = [(102, "42001f"), (108, "42e01f"), (112, "64001f")];
+
+    for (payload_type, profile_level_id) in PROFILES {
+        media_engine.register_codec(
+            RTCRtpCodecParameters {
+                capability: RTCRtpCodecCapability {
+                    sdp_fmtp_line: format!("{H264_FMTP};profile-level-id={profile_level_id}"),
+                    rtcp_feedback: vec![
+                        RTCPFeedback {
+                            typ: "nack".to_owned(),
+                            parameter: "pli".to_owned(),
+                        },
+                        RTCPFeedback {
+                            typ: "ccm".to_owned(),
+                            parameter: "fir".to_owned(),
+                        },
+                    ],
+                    ..h264_codec_capability()
+                },
+                payload_type,
+                ..Default::default()
+            },
+            RTPCodecType::Video,
+        )?;
+    }
+    Ok(())
+}
@@ -10,7 +10,25 @@ use log::trace;
 #[derive(Debug, Clone)]
 pub struct Event {
     pub kind: String,
-    pub properties: Vec<(String, String)>,
+    pub properties: Vec<(String, EventValue)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EventValue {
+    String(String),
+    NumberList(Vec<f64>),
+}
+
+impl From<String> for EventValue {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+impl From<Vec<f64>> for EventValue {
+    fn from(value: Vec<f64>) -> Self {
+        Self::NumberList(value)
+    }
 }
 
 pub fn emit_event<T: Into<Event>>(event: T) {
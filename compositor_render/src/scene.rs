@@ -19,11 +19,14 @@ use self::web_view_component::StatefulWebViewComponent;
 pub(crate) use layout::LayoutNode;
 pub(crate) use scene_state::{OutputNode, SceneState};
 pub(crate) use shader_component::ShaderComponentParams;
+pub(crate) use transition::TransitionValue;
 
 pub use components::*;
 pub use types::*;
 
+mod animation;
 mod components;
+mod enter_exit_transition;
 mod image_component;
 mod input_stream_component;
 mod layout;
@@ -141,6 +144,17 @@ impl StatefulComponent {
         }
     }
 
+    fn children(&self) -> Vec<&StatefulComponent> {
+        match self {
+            StatefulComponent::InputStream(_) => vec![],
+            StatefulComponent::Shader(shader) => shader.children.iter().collect(),
+            StatefulComponent::WebView(web) => web.children.iter().collect(),
+            StatefulComponent::Image(_) => vec![],
+            StatefulComponent::Text(_) => vec![],
+            StatefulComponent::Layout(layout) => layout.children(),
+        }
+    }
+
     fn children_mut(&mut self) -> Vec<&mut StatefulComponent> {
         match self {
             StatefulComponent::InputStream(_) => vec![],
@@ -214,4 +228,10 @@ pub enum SceneError {
 
     #[error("More than one component has an id \"{0}\". Component IDs in scene definition need to be unique.")]
     DuplicateComponentId(ComponentId),
+
+    #[error("Component with an exit transition needs to have an \"id\" property.")]
+    ExitTransitionWithoutId,
+
+    #[error("Component \"{0}\" defines an exit transition, but its parent is not a View component with an \"id\". Exit transitions are only supported for children of such components.")]
+    UnsupportedExitTransitionParent(ComponentId),
 }
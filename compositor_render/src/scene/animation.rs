@@ -0,0 +1,186 @@
+use std::time::Duration;
+
+use super::{
+    types::interpolation::{ContinuousValue, InterpolationState},
+    AbsolutePosition, Animation, AnimationRepeat, HorizontalPosition, KeyframeValues, Position,
+    VerticalPosition,
+};
+
+/// State of a keyframe animation of a layout component.
+#[derive(Debug, Clone)]
+pub(super) struct AnimationState {
+    animation: Animation,
+    start_pts: Duration,
+}
+
+impl AnimationState {
+    /// Animation from `previous` state is continued if its definition did not change,
+    /// so scene updates do not restart running animations.
+    pub fn new(
+        animation: Option<Animation>,
+        previous: Option<&AnimationState>,
+        last_pts: Duration,
+    ) -> Option<Self> {
+        let animation = animation?;
+        let start_pts = previous
+            .filter(|previous| previous.animation == animation)
+            .map(|previous| previous.start_pts)
+            .unwrap_or(last_pts);
+        Some(Self {
+            animation,
+            start_pts,
+        })
+    }
+
+    pub fn apply_position(&self, position: Position, pts: Duration) -> Position {
+        let values = self.values(pts);
+        match position {
+            Position::Static { width, height } => Position::Static {
+                width: values.width.or(width),
+                height: values.height.or(height),
+            },
+            Position::Absolute(position) => Position::Absolute(AbsolutePosition {
+                width: values.width.or(position.width),
+                height: values.height.or(position.height),
+                position_horizontal: match (values.left, values.right) {
+                    (Some(left), _) => HorizontalPosition::LeftOffset(left),
+                    (None, Some(right)) => HorizontalPosition::RightOffset(right),
+                    (None, None) => position.position_horizontal,
+                },
+                position_vertical: match (values.top, values.bottom) {
+                    (Some(top), _) => VerticalPosition::TopOffset(top),
+                    (None, Some(bottom)) => VerticalPosition::BottomOffset(bottom),
+                    (None, None) => position.position_vertical,
+                },
+                rotation_degrees: values.rotation_degrees.unwrap_or(position.rotation_degrees),
+            }),
+        }
+    }
+
+    pub fn apply_opacity(&self, opacity: f32, pts: Duration) -> f32 {
+        // Easing functions like cubic Bézier can overshoot the keyframe values.
+        self.values(pts)
+            .opacity
+            .map_or(opacity, |opacity| opacity.clamp(0.0, 1.0))
+    }
+
+    fn values(&self, pts: Duration) -> KeyframeValues {
+        let progress = self.progress(pts);
+        KeyframeValues {
+            width: self.value(progress, |values| values.width),
+            height: self.value(progress, |values| values.height),
+            top: self.value(progress, |values| values.top),
+            left: self.value(progress, |values| values.left),
+            bottom: self.value(progress, |values| values.bottom),
+            right: self.value(progress, |values| values.right),
+            rotation_degrees: self.value(progress, |values| values.rotation_degrees),
+            opacity: self.value(progress, |values| values.opacity),
+        }
+    }
+
+    /// Position within the current iteration in the `[0, 1]` range.
+    fn progress(&self, pts: Duration) -> f64 {
+        let duration = self.animation.duration.as_secs_f64();
+        if duration <= 0.0 {
+            return 1.0;
+        }
+        let iteration = pts.saturating_sub(self.start_pts).as_secs_f64() / duration;
+        match self.animation.repeat {
+            AnimationRepeat::None => f64::min(iteration, 1.0),
+            AnimationRepeat::Loop => iteration.fract(),
+            AnimationRepeat::PingPong if (iteration as u64).is_multiple_of(2) => iteration.fract(),
+            AnimationRepeat::PingPong => 1.0 - iteration.fract(),
+        }
+    }
+
+    /// Interpolates between the keyframes that define a value selected by `get`.
+    /// Before the first and after the last of those keyframes, the value is constant.
+    fn value(&self, progress: f64, get: impl Fn(&KeyframeValues) -> Option<f32>) -> Option<f32> {
+        let keyframes = || {
+            self.animation
+                .keyframes
+                .iter()
+                .filter_map(|keyframe| get(&keyframe.values).map(|value| (keyframe, value)))
+        };
+        let previous = keyframes().rfind(|(keyframe, _)| keyframe.offset <= progress);
+        let next = keyframes().find(|(keyframe, _)| keyframe.offset > progress);
+        match (previous, next) {
+            (Some((start, start_value)), Some((end, end_value))) => {
+                let t = (progress - start.offset) / (end.offset - start.offset);
+                let state = InterpolationState(start.interpolation_kind.ease(t));
+                Some(ContinuousValue::interpolate(
+                    &start_value,
+                    &end_value,
+                    state,
+                ))
+            }
+            (Some((_, value)), None) | (None, Some((_, value))) => Some(value),
+            (None, None) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scene::{InterpolationKind, Keyframe};
+
+    fn keyframe(offset: f64, left: Option<f32>) -> Keyframe {
+        Keyframe {
+            offset,
+            interpolation_kind: InterpolationKind::Linear,
+            values: KeyframeValues {
+                left,
+                ..Default::default()
+            },
+        }
+    }
+
+    fn left_at(state: &AnimationState, pts_ms: u64) -> Option<f32> {
+        state.values(Duration::from_millis(pts_ms)).left
+    }
+
+    #[test]
+    fn keyframes_without_value_are_skipped() {
+        let state = AnimationState::new(
+            Some(Animation {
+                duration: Duration::from_secs(1),
+                keyframes: vec![
+                    keyframe(0.0, Some(0.0)),
+                    keyframe(0.5, None),
+                    keyframe(1.0, Some(100.0)),
+                ],
+                repeat: AnimationRepeat::None,
+            }),
+            None,
+            Duration::ZERO,
+        )
+        .unwrap();
+
+        assert_eq!(left_at(&state, 500), Some(50.0));
+        assert_eq!(left_at(&state, 2000), Some(100.0));
+        assert_eq!(state.values(Duration::from_millis(500)).top, None);
+    }
+
+    #[test]
+    fn repeat_modes() {
+        let animation = |repeat| {
+            AnimationState::new(
+                Some(Animation {
+                    duration: Duration::from_secs(1),
+                    keyframes: vec![keyframe(0.0, Some(0.0)), keyframe(1.0, Some(100.0))],
+                    repeat,
+                }),
+                None,
+                Duration::from_secs(10),
+            )
+            .unwrap()
+        };
+
+        let looped = animation(AnimationRepeat::Loop);
+        assert_eq!(left_at(&looped, 11_250), Some(25.0));
+        let ping_pong = animation(AnimationRepeat::PingPong);
+        assert_eq!(left_at(&ping_pong, 10_250), Some(25.0));
+        assert_eq!(left_at(&ping_pong, 11_250), Some(75.0));
+    }
+}
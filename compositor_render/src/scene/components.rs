@@ -32,6 +32,10 @@ pub struct ShaderComponent {
     pub shader_param: Option<ShaderParam>,
 
     pub size: Size,
+
+    /// Numeric values of `shader_param` are interpolated if the previous scene
+    /// contained a shader component with the same id.
+    pub transition: Option<Transition>,
 }
 
 #[derive(Debug, Clone)]
@@ -81,6 +85,8 @@ pub struct TextComponent {
     pub wrap: TextWrap,
     pub background_color: RGBAColor,
     pub dimensions: TextDimensions,
+
+    pub transition: Option<Transition>,
 }
 
 #[derive(Debug, Clone)]
@@ -137,9 +143,21 @@ pub struct ViewComponent {
     pub direction: ViewChildrenDirection,
     pub position: Position,
     pub transition: Option<Transition>,
+    /// Animation played when the component is added to the scene.
+    pub enter: Option<EnterExitTransition>,
+    /// Animation played when the component is removed from the scene.
+    pub exit: Option<EnterExitTransition>,
+    pub animation: Option<Animation>,
     pub overflow: Overflow,
 
     pub background_color: RGBAColor,
+    /// Opacity in the `[0, 1]` range, applied to the component and its children.
+    pub opacity: f32,
+
+    pub border_radius: f32,
+    pub border_width: f32,
+    pub border_color: RGBAColor,
+    pub box_shadow: Vec<BoxShadow>,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -149,12 +167,92 @@ pub enum Overflow {
     Fit,
 }
 
+#[derive(Debug, Clone, Copy)]
+pub struct BoxShadow {
+    pub offset_x: f32,
+    pub offset_y: f32,
+    pub blur_radius: f32,
+    pub color: RGBAColor,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct Transition {
     pub duration: Duration,
     pub interpolation_kind: InterpolationKind,
 }
 
+/// Animation of a component that appears in or disappears from the scene. It
+/// requires the component to have an `id`.
+#[derive(Debug, Clone, Copy)]
+pub struct EnterExitTransition {
+    pub duration: Duration,
+    pub interpolation_kind: InterpolationKind,
+    pub effect: EnterExitEffect,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum EnterExitEffect {
+    Fade,
+    /// Component moves from/to the outside of the parent area through the specified edge.
+    Slide(Edge),
+    /// Component is scaled from/to 0 relative to its center.
+    Scale,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Edge {
+    Top,
+    Right,
+    Bottom,
+    Left,
+}
+
+/// Keyframe animation evaluated on every frame, so a looping animation needs only
+/// one scene update. Animation starts when the component is added to the scene
+/// or when the animation definition changes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Animation {
+    /// Duration of a single iteration.
+    pub duration: Duration,
+    /// Keyframes sorted by `offset`.
+    pub keyframes: Vec<Keyframe>,
+    pub repeat: AnimationRepeat,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keyframe {
+    /// Position of the keyframe within a single iteration in the `[0, 1]` range.
+    pub offset: f64,
+    /// Easing used between this keyframe and the next one that defines the same value.
+    pub interpolation_kind: InterpolationKind,
+    pub values: KeyframeValues,
+}
+
+/// Values that are not defined are interpolated between the neighbouring keyframes
+/// that define them. If none of the keyframes defines a value, the value from
+/// the component is used.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct KeyframeValues {
+    pub width: Option<f32>,
+    pub height: Option<f32>,
+    pub top: Option<f32>,
+    pub left: Option<f32>,
+    pub bottom: Option<f32>,
+    pub right: Option<f32>,
+    pub rotation_degrees: Option<f32>,
+    pub opacity: Option<f32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnimationRepeat {
+    /// Play once and keep the values from the last keyframe.
+    None,
+    /// Restart from the first keyframe.
+    Loop,
+    /// Play alternately forwards and backwards.
+    PingPong,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum Position {
     Static {
@@ -177,10 +275,22 @@ pub struct RescalerComponent {
 
     pub position: Position,
     pub transition: Option<Transition>,
+    /// Animation played when the component is added to the scene.
+    pub enter: Option<EnterExitTransition>,
+    /// Animation played when the component is removed from the scene.
+    pub exit: Option<EnterExitTransition>,
+    pub animation: Option<Animation>,
 
     pub mode: RescaleMode,
     pub horizontal_align: HorizontalAlign,
     pub vertical_align: VerticalAlign,
+    /// Opacity in the `[0, 1]` range, applied to the component and its child.
+    pub opacity: f32,
+
+    pub border_radius: f32,
+    pub border_width: f32,
+    pub border_color: RGBAColor,
+    pub box_shadow: Vec<BoxShadow>,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -203,6 +313,13 @@ pub struct TilesComponent {
     pub padding: f32,
     pub horizontal_align: HorizontalAlign,
     pub vertical_align: VerticalAlign,
+    /// Opacity in the `[0, 1]` range, applied to the component and its children.
+    pub opacity: f32,
 
     pub transition: Option<Transition>,
+    /// Animation played when the component is added to the scene.
+    pub enter: Option<EnterExitTransition>,
+    /// Animation played when the component is removed from the scene.
+    pub exit: Option<EnterExitTransition>,
+    pub animation: Option<Animation>,
 }
@@ -0,0 +1,151 @@
+use std::time::Duration;
+
+use crate::transformations::layout::{LayoutContent, NestedLayout};
+
+use super::{
+    transition::{TransitionOptions, TransitionState},
+    Edge, EnterExitEffect, EnterExitTransition, RGBAColor, Size,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Phase {
+    Enter,
+    Exit,
+}
+
+/// State of the enter/exit animation of a layout component. Animations are applied
+/// by the parent layout component on the layout of the child.
+#[derive(Debug, Clone)]
+pub(super) struct EnterExitState {
+    exit: Option<EnterExitTransition>,
+    animation: Option<(Phase, EnterExitEffect, TransitionState)>,
+}
+
+impl EnterExitState {
+    /// * `is_new` - component was not present in the previous scene (or it was being removed).
+    pub fn new(
+        enter: Option<EnterExitTransition>,
+        exit: Option<EnterExitTransition>,
+        is_new: bool,
+        last_pts: Duration,
+    ) -> Self {
+        let animation = enter.filter(|_| is_new).map(|enter| {
+            (
+                Phase::Enter,
+                enter.effect,
+                Self::transition(enter, last_pts),
+            )
+        });
+        Self { exit, animation }
+    }
+
+    /// Starts an exit animation. Returns `false` if component does not define one.
+    pub fn start_exit(&mut self, last_pts: Duration) -> bool {
+        if self.is_exiting() {
+            return true;
+        }
+        let Some(exit) = self.exit else {
+            return false;
+        };
+        self.animation = Some((Phase::Exit, exit.effect, Self::transition(exit, last_pts)));
+        true
+    }
+
+    pub fn is_exiting(&self) -> bool {
+        matches!(self.animation, Some((Phase::Exit, _, _)))
+    }
+
+    pub fn is_exit_finished(&self, pts: Duration) -> bool {
+        match &self.animation {
+            Some((Phase::Exit, _, transition)) => transition.is_finished(pts),
+            _ => false,
+        }
+    }
+
+    /// Applies the animation on the layout of the component. `parent_size` is a size
+    /// of the area in which the layout is positioned.
+    pub fn apply(&self, layout: NestedLayout, parent_size: Size, pts: Duration) -> NestedLayout {
+        let Some((phase, effect, transition)) = &self.animation else {
+            return layout;
+        };
+        // 1 means that the component is fully visible.
+        let visibility = match phase {
+            Phase::Enter => transition.state(pts).0,
+            Phase::Exit => 1.0 - transition.state(pts).0,
+        } as f32;
+        if visibility >= 1.0 {
+            return layout;
+        }
+
+        match effect {
+            EnterExitEffect::Fade => NestedLayout {
+                opacity: layout.opacity * f32::max(visibility, 0.0),
+                ..layout
+            },
+            EnterExitEffect::Slide(edge) => {
+                let hidden = 1.0 - visibility;
+                let (top, left) = match edge {
+                    Edge::Top => (
+                        layout.top - (layout.top + layout.height) * hidden,
+                        layout.left,
+                    ),
+                    Edge::Bottom => (
+                        layout.top + (parent_size.height - layout.top) * hidden,
+                        layout.left,
+                    ),
+                    Edge::Left => (
+                        layout.top,
+                        layout.left - (layout.left + layout.width) * hidden,
+                    ),
+                    Edge::Right => (
+                        layout.top,
+                        layout.left + (parent_size.width - layout.left) * hidden,
+                    ),
+                };
+                NestedLayout {
+                    top,
+                    left,
+                    ..layout
+                }
+            }
+            EnterExitEffect::Scale => {
+                let scale = f32::max(visibility, 0.0);
+                NestedLayout {
+                    top: layout.top + layout.height * (1.0 - scale) / 2.0,
+                    left: layout.left + layout.width * (1.0 - scale) / 2.0,
+                    width: layout.width * scale,
+                    height: layout.height * scale,
+                    rotation_degrees: 0.0,
+                    opacity: 1.0,
+                    border_radius: 0.0,
+                    border_width: 0.0,
+                    border_color: RGBAColor(0, 0, 0, 0),
+                    box_shadow: vec![],
+                    scale_x: scale,
+                    scale_y: scale,
+                    crop: None,
+                    content: LayoutContent::None,
+                    child_nodes_count: layout.child_nodes_count,
+                    children: vec![NestedLayout {
+                        top: 0.0,
+                        left: 0.0,
+                        ..layout
+                    }],
+                }
+            }
+        }
+    }
+
+    fn transition(transition: EnterExitTransition, last_pts: Duration) -> TransitionState {
+        // Transition state can't be empty if the current transition is defined.
+        TransitionState::new(
+            Some(TransitionOptions {
+                duration: transition.duration,
+                interpolation_kind: transition.interpolation_kind,
+            }),
+            None,
+            last_pts,
+        )
+        .unwrap()
+    }
+}
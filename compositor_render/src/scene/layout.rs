@@ -6,9 +6,10 @@ use crate::{
 };
 
 use super::{
-    rescaler_component::StatefulRescalerComponent, tiles_component::StatefulTilesComponent,
-    view_component::StatefulViewComponent, AbsolutePosition, ComponentId, HorizontalPosition,
-    Position, Size, StatefulComponent, VerticalPosition,
+    enter_exit_transition::EnterExitState, rescaler_component::StatefulRescalerComponent,
+    tiles_component::StatefulTilesComponent, view_component::StatefulViewComponent,
+    AbsolutePosition, ComponentId, HorizontalPosition, Position, RGBAColor, Size,
+    StatefulComponent, VerticalPosition,
 };
 
 #[derive(Debug, Clone)]
@@ -65,6 +66,40 @@ impl StatefulLayoutComponent {
         }
     }
 
+    pub(super) fn enter_exit(&self) -> &EnterExitState {
+        match self {
+            StatefulLayoutComponent::View(view) => view.enter_exit(),
+            StatefulLayoutComponent::Tiles(tiles) => tiles.enter_exit(),
+            StatefulLayoutComponent::Rescaler(rescaler) => rescaler.enter_exit(),
+        }
+    }
+
+    pub(super) fn enter_exit_mut(&mut self) -> &mut EnterExitState {
+        match self {
+            StatefulLayoutComponent::View(view) => view.enter_exit_mut(),
+            StatefulLayoutComponent::Tiles(tiles) => tiles.enter_exit_mut(),
+            StatefulLayoutComponent::Rescaler(rescaler) => rescaler.enter_exit_mut(),
+        }
+    }
+
+    /// Applies enter/exit animation of a child component on its layout. Only layout
+    /// components support those animations.
+    pub(super) fn apply_enter_exit_transition(
+        child: &StatefulComponent,
+        layout: NestedLayout,
+        parent_size: Size,
+        pts: Duration,
+    ) -> NestedLayout {
+        match child {
+            StatefulComponent::Layout(layout_component) => {
+                layout_component
+                    .enter_exit()
+                    .apply(layout, parent_size, pts)
+            }
+            _ => layout,
+        }
+    }
+
     pub(crate) fn component_type(&self) -> &'static str {
         match self {
             StatefulLayoutComponent::View(_) => "View",
@@ -191,6 +226,11 @@ impl StatefulLayoutComponent {
                     width,
                     height,
                     rotation_degrees,
+                    opacity: 1.0,
+                    border_radius: 0.0,
+                    border_width: 0.0,
+                    border_color: RGBAColor(0, 0, 0, 0),
+                    box_shadow: vec![],
                     scale_x: 1.0,
                     scale_y: 1.0,
                     crop,
@@ -212,6 +252,11 @@ impl StatefulLayoutComponent {
                     width,
                     height,
                     rotation_degrees,
+                    opacity: 1.0,
+                    border_radius: 0.0,
+                    border_width: 0.0,
+                    border_color: RGBAColor(0, 0, 0, 0),
+                    box_shadow: vec![],
                     scale_x: 1.0,
                     scale_y: 1.0,
                     crop,
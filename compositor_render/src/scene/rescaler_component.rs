@@ -3,13 +3,15 @@ use std::time::Duration;
 use crate::transformations::layout::NestedLayout;
 
 use super::{
+    animation::AnimationState,
     components::RescalerComponent,
+    enter_exit_transition::EnterExitState,
     layout::StatefulLayoutComponent,
     scene_state::BuildStateTreeCtx,
     transition::{TransitionOptions, TransitionState},
     types::interpolation::ContinuousValue,
-    Component, ComponentId, HorizontalAlign, IntermediateNode, Position, RescaleMode, SceneError,
-    Size, StatefulComponent, VerticalAlign,
+    BoxShadow, Component, ComponentId, HorizontalAlign, IntermediateNode, Position, RGBAColor,
+    RescaleMode, SceneError, Size, StatefulComponent, VerticalAlign,
 };
 
 mod interpolation;
@@ -17,9 +19,11 @@ mod layout;
 
 #[derive(Debug, Clone)]
 pub(super) struct StatefulRescalerComponent {
-    start: Option<RescalerComponentParam>,
+    start: Option<Box<RescalerComponentParam>>,
     end: RescalerComponentParam,
     transition: Option<TransitionState>,
+    enter_exit: Box<EnterExitState>,
+    animation: Option<Box<AnimationState>>,
     child: Box<StatefulComponent>,
 }
 
@@ -31,16 +35,29 @@ struct RescalerComponentParam {
     mode: RescaleMode,
     horizontal_align: HorizontalAlign,
     vertical_align: VerticalAlign,
+    opacity: f32,
+
+    border_radius: f32,
+    border_width: f32,
+    border_color: RGBAColor,
+    box_shadow: Vec<BoxShadow>,
 }
 
 impl StatefulRescalerComponent {
     /// Generate state of the component for particular pts value.
     fn transition_snapshot(&self, pts: Duration) -> RescalerComponentParam {
-        let (Some(transition), Some(start)) = (&self.transition, &self.start) else {
-            return self.end.clone();
+        let mut rescaler = match (&self.transition, self.start.as_deref()) {
+            (Some(transition), Some(start)) => {
+                let interpolation_progress = transition.state(pts);
+                ContinuousValue::interpolate(start, &self.end, interpolation_progress)
+            }
+            _ => self.end.clone(),
         };
-        let interpolation_progress = transition.state(pts);
-        ContinuousValue::interpolate(start, &self.end, interpolation_progress)
+        if let Some(animation) = &self.animation {
+            rescaler.position = animation.apply_position(rescaler.position, pts);
+            rescaler.opacity = animation.apply_opacity(rescaler.opacity, pts);
+        }
+        rescaler
     }
 
     pub(super) fn children(&self) -> Vec<&StatefulComponent> {
@@ -51,6 +68,14 @@ impl StatefulRescalerComponent {
         vec![&mut self.child]
     }
 
+    pub(super) fn enter_exit(&self) -> &EnterExitState {
+        &self.enter_exit
+    }
+
+    pub(super) fn enter_exit_mut(&mut self) -> &mut EnterExitState {
+        &mut self.enter_exit
+    }
+
     pub(super) fn position(&self, pts: Duration) -> Position {
         self.transition_snapshot(pts).position
     }
@@ -98,7 +123,8 @@ impl RescalerComponent {
 
         // TODO: to handle cases like transition from top to bottom this view needs
         // to be further processed to use the same type of coordinates as end
-        let start = previous_state.map(|state| state.transition_snapshot(ctx.last_render_pts));
+        let start =
+            previous_state.map(|state| Box::new(state.transition_snapshot(ctx.last_render_pts)));
         let transition = TransitionState::new(
             self.transition.map(|transition| TransitionOptions {
                 duration: transition.duration,
@@ -107,6 +133,18 @@ impl RescalerComponent {
             previous_state.and_then(|s| s.transition.clone()),
             ctx.last_render_pts,
         );
+        let enter_exit = EnterExitState::new(
+            self.enter,
+            self.exit,
+            self.id.is_some() && previous_state.is_none_or(|s| s.enter_exit.is_exiting()),
+            ctx.last_render_pts,
+        );
+        let animation = AnimationState::new(
+            self.animation,
+            previous_state.and_then(|s| s.animation.as_deref()),
+            ctx.last_render_pts,
+        )
+        .map(Box::new);
         let view = StatefulRescalerComponent {
             start,
             end: RescalerComponentParam {
@@ -115,8 +153,15 @@ impl RescalerComponent {
                 mode: self.mode,
                 horizontal_align: self.horizontal_align,
                 vertical_align: self.vertical_align,
+                opacity: self.opacity,
+                border_radius: self.border_radius,
+                border_width: self.border_width,
+                border_color: self.border_color,
+                box_shadow: self.box_shadow,
             },
             transition,
+            enter_exit: Box::new(enter_exit),
+            animation,
             child: Box::new(Component::stateful_component(*self.child, ctx)?),
         };
         Ok(StatefulComponent::Layout(
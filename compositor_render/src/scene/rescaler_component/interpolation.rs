@@ -10,6 +10,23 @@ impl ContinuousValue for RescalerComponentParam {
             mode: end.mode,
             horizontal_align: end.horizontal_align,
             vertical_align: end.vertical_align,
+            opacity: ContinuousValue::interpolate(&start.opacity, &end.opacity, state),
+            border_radius: ContinuousValue::interpolate(
+                &start.border_radius,
+                &end.border_radius,
+                state,
+            ),
+            border_width: ContinuousValue::interpolate(
+                &start.border_width,
+                &end.border_width,
+                state,
+            ),
+            border_color: ContinuousValue::interpolate(
+                &start.border_color,
+                &end.border_color,
+                state,
+            ),
+            box_shadow: ContinuousValue::interpolate(&start.box_shadow, &end.box_shadow, state),
         }
     }
 }
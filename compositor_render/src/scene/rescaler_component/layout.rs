@@ -2,8 +2,8 @@ use std::time::Duration;
 
 use crate::{
     scene::{
-        layout::StatefulLayoutComponent, HorizontalAlign, RescaleMode, Size, StatefulComponent,
-        VerticalAlign,
+        layout::StatefulLayoutComponent, HorizontalAlign, RGBAColor, RescaleMode, Size,
+        StatefulComponent, VerticalAlign,
     },
     transformations::layout::{Crop, LayoutContent, NestedLayout},
 };
@@ -16,6 +16,38 @@ impl RescalerComponentParam {
         size: Size,
         child: &mut StatefulComponent,
         pts: Duration,
+    ) -> NestedLayout {
+        let content_size = Size {
+            width: f32::max(size.width - 2.0 * self.border_width, 0.0),
+            height: f32::max(size.height - 2.0 * self.border_width, 0.0),
+        };
+        let content_layout = self.content_layout(content_size, child, pts);
+        NestedLayout {
+            top: 0.0,
+            left: 0.0,
+            width: size.width,
+            height: size.height,
+            rotation_degrees: 0.0,
+            opacity: self.opacity,
+            border_radius: self.border_radius,
+            border_width: self.border_width,
+            border_color: self.border_color,
+            box_shadow: self.box_shadow.clone(),
+            scale_x: 1.0,
+            scale_y: 1.0,
+            crop: None,
+            content: LayoutContent::None,
+            child_nodes_count: content_layout.child_nodes_count,
+            children: vec![content_layout],
+        }
+    }
+
+    /// Layout of the area inside of the border, the child is rescaled to fit it.
+    fn content_layout(
+        &self,
+        size: Size,
+        child: &mut StatefulComponent,
+        pts: Duration,
     ) -> NestedLayout {
         let child_width = child.width(pts);
         let child_height = child.height(pts);
@@ -100,11 +132,16 @@ impl RescalerComponentParam {
             .unwrap_or(size.height);
 
         NestedLayout {
-            top: 0.0,
-            left: 0.0,
+            top: self.border_width,
+            left: self.border_width,
             width: size.width,
             height: size.height,
             rotation_degrees: 0.0,
+            opacity: 1.0,
+            border_radius: f32::max(self.border_radius - self.border_width, 0.0),
+            border_width: 0.0,
+            border_color: RGBAColor(0, 0, 0, 0),
+            box_shadow: vec![],
             scale_x: 1.0,
             scale_y: 1.0,
             crop: Some(Crop {
@@ -114,19 +151,29 @@ impl RescalerComponentParam {
                 height: size.height,
             }),
             content: LayoutContent::None,
-            children: vec![NestedLayout {
-                top,
-                left,
-                width,
-                height,
-                rotation_degrees: 0.0,
-                scale_x: scale,
-                scale_y: scale,
-                crop: None,
-                content,
-                child_nodes_count,
-                children,
-            }],
+            children: vec![StatefulLayoutComponent::apply_enter_exit_transition(
+                child,
+                NestedLayout {
+                    top,
+                    left,
+                    width,
+                    height,
+                    rotation_degrees: 0.0,
+                    opacity: 1.0,
+                    border_radius: 0.0,
+                    border_width: 0.0,
+                    border_color: RGBAColor(0, 0, 0, 0),
+                    box_shadow: vec![],
+                    scale_x: scale,
+                    scale_y: scale,
+                    crop: None,
+                    content,
+                    child_nodes_count,
+                    children,
+                },
+                size,
+                pts,
+            )],
             child_nodes_count,
         }
     }
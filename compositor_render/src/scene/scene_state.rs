@@ -1,4 +1,7 @@
-use std::{collections::HashMap, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
 
 use log::error;
 
@@ -105,8 +108,12 @@ impl SceneState {
             renderers,
         };
 
+        let mut root = output_scene.scene_root.clone().stateful_component(&ctx)?;
+        if let Some(prev_output_state) = self.output_states.get(&output_id) {
+            retain_exiting_components(&prev_output_state.root, &mut root, self.last_pts);
+        }
         let output_state_tree = OutputSceneState {
-            root: output_scene.scene_root.clone().stateful_component(&ctx)?,
+            root,
             resolution: output_scene.resolution,
         };
 
@@ -124,6 +131,35 @@ impl SceneState {
 
         Ok(output_node_tree)
     }
+
+    /// Removes components with finished exit transitions. Returns new node trees for
+    /// outputs that were affected.
+    pub(crate) fn remove_finished_exit_transitions(&mut self) -> Vec<OutputNode> {
+        let last_pts = self.last_pts;
+        self.output_states
+            .iter_mut()
+            .filter_map(|(output_id, output)| {
+                if !remove_finished_exit_transitions(&mut output.root, last_pts) {
+                    return None;
+                }
+                let node = output
+                    .root
+                    .intermediate_node()
+                    .build_tree(Some(output.resolution), last_pts);
+                match node {
+                    Ok(node) => Some(OutputNode {
+                        output_id: output_id.clone(),
+                        node,
+                        resolution: output.resolution,
+                    }),
+                    Err(err) => {
+                        error!("Failed to rebuild scene after exit transition: {err}");
+                        None
+                    }
+                }
+            })
+            .collect()
+    }
 }
 
 /// Intermediate representation of a node tree while it's being constructed.
@@ -307,3 +343,250 @@ fn gather_components_with_id<'a>(
         }
     }
 }
+
+/// Components that are removed in the new scene, but define an exit transition, are
+/// moved to the new tree, so they can be rendered until the animation finishes. It is
+/// only supported if their parent is a `View` component that is still present in the
+/// new scene (matched by `id`).
+fn retain_exiting_components(
+    prev_root: &StatefulComponent,
+    new_root: &mut StatefulComponent,
+    last_pts: Duration,
+) {
+    let mut new_components = HashMap::new();
+    gather_components_with_id(new_root, &mut new_components);
+    let new_ids: HashSet<ComponentId> = new_components.into_keys().collect();
+
+    let mut exiting_components = vec![];
+    gather_exiting_components(prev_root, &new_ids, last_pts, &mut exiting_components);
+
+    // Components are ordered by index for each parent, so inserting them one by one
+    // restores their previous order.
+    for (parent_id, index, component) in exiting_components {
+        if let Some(StatefulComponent::Layout(StatefulLayoutComponent::View(view))) =
+            find_component_mut(new_root, &parent_id)
+        {
+            view.insert_child(index, component);
+        }
+    }
+}
+
+fn gather_exiting_components(
+    component: &StatefulComponent,
+    new_ids: &HashSet<ComponentId>,
+    last_pts: Duration,
+    exiting_components: &mut Vec<(ComponentId, usize, StatefulComponent)>,
+) {
+    if let StatefulComponent::Layout(StatefulLayoutComponent::View(view)) = component {
+        if let Some(parent_id) = view.component_id().filter(|id| new_ids.contains(id)) {
+            for (index, child) in view.children().into_iter().enumerate() {
+                let StatefulComponent::Layout(layout) = child else {
+                    continue;
+                };
+                let is_removed = layout
+                    .component_id()
+                    .is_some_and(|id| !new_ids.contains(id));
+                if !is_removed || layout.enter_exit().is_exit_finished(last_pts) {
+                    continue;
+                }
+                // Keeping a subtree with ids that are still used would result in duplicated ids.
+                let mut subtree_components = HashMap::new();
+                gather_components_with_id(child, &mut subtree_components);
+                if subtree_components.keys().any(|id| new_ids.contains(id)) {
+                    continue;
+                }
+
+                let mut layout = layout.clone();
+                if layout.enter_exit_mut().start_exit(last_pts) {
+                    exiting_components.push((
+                        parent_id.clone(),
+                        index,
+                        StatefulComponent::Layout(layout),
+                    ));
+                }
+            }
+        }
+    }
+    for child in component.children() {
+        gather_exiting_components(child, new_ids, last_pts, exiting_components);
+    }
+}
+
+fn find_component_mut<'a>(
+    component: &'a mut StatefulComponent,
+    id: &ComponentId,
+) -> Option<&'a mut StatefulComponent> {
+    if component.component_id() == Some(id) {
+        return Some(component);
+    }
+    component
+        .children_mut()
+        .into_iter()
+        .find_map(|child| find_component_mut(child, id))
+}
+
+/// Returns `true` if any component was removed.
+fn remove_finished_exit_transitions(component: &mut StatefulComponent, pts: Duration) -> bool {
+    let mut removed = false;
+    if let StatefulComponent::Layout(StatefulLayoutComponent::View(view)) = component {
+        removed = view.remove_children(|child| match child {
+            StatefulComponent::Layout(layout) => layout.enter_exit().is_exit_finished(pts),
+            _ => false,
+        });
+    }
+    for child in component.children_mut() {
+        removed |= remove_finished_exit_transitions(child, pts);
+    }
+    removed
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::scene::{
+        EnterExitEffect, EnterExitTransition, InterpolationKind, Overflow, RGBAColor,
+        ViewChildrenDirection, ViewComponent,
+    };
+
+    use super::*;
+
+    fn view(
+        id: &str,
+        children: Vec<StatefulComponent>,
+        exit: Option<EnterExitTransition>,
+    ) -> StatefulComponent {
+        let view = ViewComponent {
+            id: Some(ComponentId(Arc::from(id))),
+            children: vec![],
+            direction: ViewChildrenDirection::Row,
+            position: Position::Static {
+                width: None,
+                height: None,
+            },
+            transition: None,
+            enter: None,
+            exit,
+            animation: None,
+            overflow: Overflow::Hidden,
+            background_color: RGBAColor(0, 0, 0, 0),
+            opacity: 1.0,
+            border_radius: 0.0,
+            border_width: 0.0,
+            border_color: RGBAColor(0, 0, 0, 0),
+            box_shadow: vec![],
+        };
+        StatefulComponent::Layout(StatefulLayoutComponent::View(view.stateful_view(
+            None,
+            Duration::ZERO,
+            children,
+        )))
+    }
+
+    fn fade_out() -> Option<EnterExitTransition> {
+        Some(EnterExitTransition {
+            duration: Duration::from_secs(1),
+            interpolation_kind: InterpolationKind::Linear,
+            effect: EnterExitEffect::Fade,
+        })
+    }
+
+    fn child_ids(root: &StatefulComponent) -> Vec<String> {
+        root.children()
+            .into_iter()
+            .filter_map(|child| child.component_id().map(|id| id.0.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn removed_component_is_kept_until_exit_transition_finishes() {
+        let prev_root = view(
+            "root",
+            vec![
+                view("a", vec![], fade_out()),
+                view("b", vec![], None),
+                view("c", vec![], fade_out()),
+            ],
+            None,
+        );
+        let mut root = view("root", vec![view("b", vec![], None)], None);
+
+        retain_exiting_components(&prev_root, &mut root, Duration::from_secs(1));
+        assert_eq!(child_ids(&root), vec!["a", "b", "c"]);
+
+        assert!(!remove_finished_exit_transitions(
+            &mut root,
+            Duration::from_millis(1500)
+        ));
+        assert_eq!(child_ids(&root), vec!["a", "b", "c"]);
+
+        assert!(remove_finished_exit_transitions(
+            &mut root,
+            Duration::from_secs(2)
+        ));
+        assert_eq!(child_ids(&root), vec!["b"]);
+    }
+
+    #[test]
+    fn removed_component_without_exit_transition_is_removed_immediately() {
+        let prev_root = view(
+            "root",
+            vec![view("a", vec![], None), view("b", vec![], None)],
+            None,
+        );
+        let mut root = view("root", vec![view("b", vec![], None)], None);
+
+        retain_exiting_components(&prev_root, &mut root, Duration::from_secs(1));
+        assert_eq!(child_ids(&root), vec!["b"]);
+    }
+
+    #[test]
+    fn exiting_component_is_replaced_when_added_back() {
+        let prev_root = view("root", vec![view("a", vec![], fade_out())], None);
+        let mut exiting_root = view("root", vec![], None);
+        retain_exiting_components(&prev_root, &mut exiting_root, Duration::from_secs(1));
+        assert_eq!(child_ids(&exiting_root), vec!["a"]);
+
+        let mut root = view("root", vec![view("a", vec![], fade_out())], None);
+        retain_exiting_components(&exiting_root, &mut root, Duration::from_millis(1500));
+        assert_eq!(child_ids(&root), vec!["a"]);
+
+        assert!(!remove_finished_exit_transitions(
+            &mut root,
+            Duration::from_secs(3)
+        ));
+        assert_eq!(child_ids(&root), vec!["a"]);
+    }
+
+    #[test]
+    fn exiting_subtree_with_ids_used_in_new_scene_is_not_kept() {
+        let prev_root = view(
+            "root",
+            vec![view("a", vec![view("b", vec![], None)], fade_out())],
+            None,
+        );
+        let new_ids = HashSet::from([ComponentId(Arc::from("root")), ComponentId(Arc::from("b"))]);
+
+        let mut exiting_components = vec![];
+        gather_exiting_components(
+            &prev_root,
+            &new_ids,
+            Duration::from_secs(1),
+            &mut exiting_components,
+        );
+        assert!(exiting_components.is_empty());
+    }
+
+    #[test]
+    fn exiting_component_is_not_kept_if_parent_is_removed() {
+        let prev_root = view(
+            "root",
+            vec![view("parent", vec![view("a", vec![], fade_out())], None)],
+            None,
+        );
+        let mut root = view("root", vec![], None);
+
+        retain_exiting_components(&prev_root, &mut root, Duration::from_secs(1));
+        assert!(child_ids(&root).is_empty());
+    }
+}
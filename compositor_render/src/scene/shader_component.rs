@@ -3,8 +3,10 @@ use std::sync::Arc;
 use crate::transformations::shader::Shader;
 
 use super::{
-    scene_state::BuildStateTreeCtx, Component, ComponentId, IntermediateNode, SceneError,
-    ShaderComponent, ShaderParam, Size, StatefulComponent,
+    scene_state::BuildStateTreeCtx,
+    transition::{TransitionOptions, TransitionValue},
+    Component, ComponentId, IntermediateNode, SceneError, ShaderComponent, ShaderParam, Size,
+    StatefulComponent,
 };
 
 #[derive(Debug, Clone)]
@@ -17,7 +19,7 @@ pub(super) struct StatefulShaderComponent {
 #[derive(Debug, Clone)]
 pub(crate) struct ShaderComponentParams {
     pub(crate) id: Option<ComponentId>,
-    pub(crate) shader_param: Option<ShaderParam>,
+    pub(crate) shader_param: TransitionValue<Option<ShaderParam>>,
     pub(crate) size: Size,
 }
 
@@ -56,6 +58,26 @@ impl ShaderComponent {
             })?
         }
 
+        let previous_state = self
+            .id
+            .as_ref()
+            .and_then(|id| ctx.prev_state.get(id))
+            .and_then(|component| match component {
+                StatefulComponent::Shader(shader_state) => Some(shader_state),
+                _ => None,
+            })
+            // Params of a different shader can't be interpolated.
+            .filter(|state| Arc::ptr_eq(&state.shader, &shader));
+        let shader_param = TransitionValue::new(
+            self.shader_param,
+            self.transition.map(|transition| TransitionOptions {
+                duration: transition.duration,
+                interpolation_kind: transition.interpolation_kind,
+            }),
+            previous_state.map(|state| &state.component.shader_param),
+            ctx.last_render_pts,
+        );
+
         let children = self
             .children
             .into_iter()
@@ -64,7 +86,7 @@ impl ShaderComponent {
         Ok(StatefulComponent::Shader(StatefulShaderComponent {
             component: ShaderComponentParams {
                 id: self.id,
-                shader_param: self.shader_param,
+                shader_param,
                 size: self.size,
             },
             shader,
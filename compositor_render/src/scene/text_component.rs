@@ -1,8 +1,9 @@
 use crate::transformations::text_renderer::TextRenderParams;
 
 use super::{
-    scene_state::BuildStateTreeCtx, ComponentId, IntermediateNode, SceneError, Size,
-    StatefulComponent, TextComponent,
+    scene_state::BuildStateTreeCtx,
+    transition::{TransitionOptions, TransitionValue},
+    ComponentId, IntermediateNode, SceneError, Size, StatefulComponent, TextComponent,
 };
 
 #[derive(Debug, Clone)]
@@ -38,6 +39,24 @@ impl TextComponent {
         self,
         ctx: &BuildStateTreeCtx,
     ) -> Result<StatefulComponent, SceneError> {
+        let previous_state = self
+            .id
+            .as_ref()
+            .and_then(|id| ctx.prev_state.get(id))
+            .and_then(|component| match component {
+                StatefulComponent::Text(text_state) => Some(text_state),
+                _ => None,
+            });
+        let color = TransitionValue::new(
+            self.color,
+            self.transition.map(|transition| TransitionOptions {
+                duration: transition.duration,
+                interpolation_kind: transition.interpolation_kind,
+            }),
+            previous_state.map(|state| &state.params.color),
+            ctx.last_render_pts,
+        );
+
         let (buffer, resolution) = ctx
             .text_renderer_ctx
             .layout_text((&self).into(), self.dimensions);
@@ -47,6 +66,7 @@ impl TextComponent {
                 buffer,
                 resolution,
                 background_color: self.background_color,
+                color,
             },
         }))
     }
@@ -8,6 +8,8 @@ use self::{
 };
 
 use super::{
+    animation::AnimationState,
+    enter_exit_transition::EnterExitState,
     layout::StatefulLayoutComponent,
     scene_state::BuildStateTreeCtx,
     transition::{TransitionOptions, TransitionState},
@@ -30,7 +32,15 @@ pub(super) struct StatefulTilesComponent {
     /// `start` value after scene update.
     last_layout: Option<(Vec<Option<Tile>>, Size)>,
 
+    /// Initial opacity for transition.
+    start_opacity: Option<f32>,
+
+    /// Initial background color for transition.
+    start_background_color: Option<RGBAColor>,
+
     transition: Option<TransitionState>,
+    enter_exit: Box<EnterExitState>,
+    animation: Option<Box<AnimationState>>,
 
     component: TilesComponentParams,
     children: Vec<StatefulComponent>,
@@ -49,6 +59,7 @@ struct TilesComponentParams {
     padding: f32,
     horizontal_align: HorizontalAlign,
     vertical_align: VerticalAlign,
+    opacity: f32,
 }
 
 impl StatefulTilesComponent {
@@ -66,10 +77,41 @@ impl StatefulTilesComponent {
         ContinuousValue::interpolate(&start, &end, interpolation_progress)
     }
 
-    pub(super) fn position(&self, _pts: Duration) -> Position {
-        Position::Static {
+    fn opacity(&self, pts: Duration) -> f32 {
+        let opacity = match (&self.start_opacity, &self.transition) {
+            (Some(start), Some(transition)) => {
+                let interpolation_progress = transition.state(pts);
+                ContinuousValue::interpolate(start, &self.component.opacity, interpolation_progress)
+            }
+            _ => self.component.opacity,
+        };
+        match &self.animation {
+            Some(animation) => animation.apply_opacity(opacity, pts),
+            None => opacity,
+        }
+    }
+
+    fn background_color(&self, pts: Duration) -> RGBAColor {
+        let (Some(start), Some(transition)) = (&self.start_background_color, &self.transition)
+        else {
+            return self.component.background_color;
+        };
+        let interpolation_progress = transition.state(pts);
+        ContinuousValue::interpolate(
+            start,
+            &self.component.background_color,
+            interpolation_progress,
+        )
+    }
+
+    pub(super) fn position(&self, pts: Duration) -> Position {
+        let position = Position::Static {
             width: self.component.width,
             height: self.component.height,
+        };
+        match &self.animation {
+            Some(animation) => animation.apply_position(position, pts),
+            None => position,
         }
     }
 
@@ -77,6 +119,14 @@ impl StatefulTilesComponent {
         self.children.iter().collect()
     }
 
+    pub(super) fn enter_exit(&self) -> &EnterExitState {
+        &self.enter_exit
+    }
+
+    pub(super) fn enter_exit_mut(&mut self) -> &mut EnterExitState {
+        &mut self.enter_exit
+    }
+
     pub(super) fn children_mut(&mut self) -> Vec<&mut StatefulComponent> {
         self.children.iter_mut().collect()
     }
@@ -102,12 +152,15 @@ impl StatefulTilesComponent {
 
     pub(super) fn layout(&mut self, size: Size, pts: Duration) -> NestedLayout {
         let tiles = self.tiles(size, pts);
+        let opacity = self.opacity(pts);
+        let background_color = self.background_color(pts);
         let layout = layout_tiles(
             &tiles,
             size,
             &mut self.children,
             pts,
-            self.component.background_color,
+            background_color,
+            opacity,
         );
         self.last_layout = Some((tiles, size));
         layout
@@ -139,10 +192,25 @@ impl TilesComponent {
             previous_state.and_then(|s| s.transition.clone()),
             ctx.last_render_pts,
         );
+        let enter_exit = EnterExitState::new(
+            self.enter,
+            self.exit,
+            self.id.is_some() && previous_state.is_none_or(|s| s.enter_exit.is_exiting()),
+            ctx.last_render_pts,
+        );
+        let animation = AnimationState::new(
+            self.animation,
+            previous_state.and_then(|s| s.animation.as_deref()),
+            ctx.last_render_pts,
+        )
+        .map(Box::new);
 
         let tiles = StatefulTilesComponent {
             start,
             last_layout: previous_state.and_then(|state| state.last_layout.clone()),
+            start_opacity: previous_state.map(|state| state.opacity(ctx.last_render_pts)),
+            start_background_color: previous_state
+                .map(|state| state.background_color(ctx.last_render_pts)),
             component: TilesComponentParams {
                 id: self.id,
                 width: self.width,
@@ -153,8 +221,11 @@ impl TilesComponent {
                 padding: self.padding,
                 horizontal_align: self.horizontal_align,
                 vertical_align: self.vertical_align,
+                opacity: self.opacity,
             },
             transition,
+            enter_exit: Box::new(enter_exit),
+            animation,
             children: self
                 .children
                 .into_iter()
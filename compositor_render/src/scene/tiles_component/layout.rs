@@ -13,11 +13,15 @@ pub(super) fn layout_tiles(
     children: &mut [StatefulComponent],
     pts: Duration,
     background_color: RGBAColor,
+    opacity: f32,
 ) -> NestedLayout {
     let children = children
         .iter_mut()
         .zip(tiles)
-        .map(|(component, tile)| layout_child(component, tile.clone(), pts))
+        .map(|(component, tile)| {
+            let layout = layout_child(component, tile.clone(), pts);
+            StatefulLayoutComponent::apply_enter_exit_transition(component, layout, size, pts)
+        })
         .collect::<Vec<_>>();
 
     NestedLayout {
@@ -26,6 +30,11 @@ pub(super) fn layout_tiles(
         width: size.width,
         height: size.height,
         rotation_degrees: 0.0,
+        opacity,
+        border_radius: 0.0,
+        border_width: 0.0,
+        border_color: RGBAColor(0, 0, 0, 0),
+        box_shadow: vec![],
         scale_x: 1.0,
         scale_y: 1.0,
         crop: None,
@@ -61,6 +70,11 @@ fn layout_child(child: &mut StatefulComponent, tile: Option<Tile>, pts: Duration
                 width: tile.width,
                 height: tile.height,
                 rotation_degrees: 0.0,
+                opacity: 1.0,
+                border_radius: 0.0,
+                border_width: 0.0,
+                border_color: RGBAColor(0, 0, 0, 0),
+                box_shadow: vec![],
                 scale_x: 1.0,
                 scale_y: 1.0,
                 crop: None,
@@ -78,6 +92,11 @@ fn layout_child(child: &mut StatefulComponent, tile: Option<Tile>, pts: Duration
                 width: fitted.width,
                 height: fitted.height,
                 rotation_degrees: 0.0,
+                opacity: 1.0,
+                border_radius: 0.0,
+                border_width: 0.0,
+                border_color: RGBAColor(0, 0, 0, 0),
+                box_shadow: vec![],
                 scale_x: 1.0,
                 scale_y: 1.0,
                 crop: None,
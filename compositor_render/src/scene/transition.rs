@@ -2,7 +2,10 @@ use std::time::Duration;
 
 use self::{bounce::bounce_easing, cubic_bezier::cubic_bezier_easing};
 
-use super::{types::interpolation::InterpolationState, InterpolationKind};
+use super::{
+    types::interpolation::{ContinuousValue, InterpolationState},
+    InterpolationKind,
+};
 
 mod bounce;
 mod cubic_bezier;
@@ -35,6 +38,48 @@ pub(super) struct TransitionOptions {
     pub interpolation_kind: InterpolationKind,
 }
 
+/// Value interpolated between scene updates. Unlike layout components, that are
+/// interpolated as a whole, it is used for parameters that render nodes need to
+/// evaluate on every frame (e.g. text color or shader params).
+#[derive(Debug, Clone)]
+pub(crate) struct TransitionValue<T> {
+    start: Option<T>,
+    end: T,
+    transition: Option<TransitionState>,
+}
+
+impl<T: ContinuousValue + Clone> TransitionValue<T> {
+    /// Transition starts from the value of `previous` at `last_pts`. If `transition` is
+    /// not defined, the unfinished transition of `previous` is continued.
+    pub(super) fn new(
+        end: T,
+        transition: Option<TransitionOptions>,
+        previous: Option<&Self>,
+        last_pts: Duration,
+    ) -> Self {
+        Self {
+            start: previous.map(|previous| previous.value(last_pts)),
+            end,
+            transition: TransitionState::new(
+                transition,
+                previous.and_then(|previous| previous.transition.clone()),
+                last_pts,
+            ),
+        }
+    }
+
+    pub(crate) fn value(&self, pts: Duration) -> T {
+        let (Some(start), Some(transition)) = (&self.start, &self.transition) else {
+            return self.end.clone();
+        };
+        ContinuousValue::interpolate(start, &self.end, transition.state(pts))
+    }
+
+    pub(crate) fn end(&self) -> &T {
+        &self.end
+    }
+}
+
 impl TransitionState {
     pub fn new(
         current_transition: Option<TransitionOptions>,
@@ -76,6 +121,10 @@ impl TransitionState {
         }
     }
 
+    pub fn is_finished(&self, pts: Duration) -> bool {
+        pts >= self.start_pts + self.duration
+    }
+
     pub fn state(&self, pts: Duration) -> InterpolationState {
         // Value in range [0, 1], where 1 means end of transition.
         let progress =
@@ -93,6 +142,12 @@ impl TransitionState {
 }
 
 impl InterpolationKind {
+    /// Maps transition progress in `[0, 1]` range to the value of the easing function.
+    /// Used by the audio mixer to interpolate volume in sync with the video transitions.
+    pub fn ease(&self, t: f64) -> f64 {
+        self.state(t).0
+    }
+
     fn state(&self, t: f64) -> InterpolationState {
         match self {
             InterpolationKind::Linear => InterpolationState(t),
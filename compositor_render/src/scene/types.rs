@@ -68,7 +68,7 @@ pub enum HorizontalPosition {
     RightOffset(f32),
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum InterpolationKind {
     Linear,
     Bounce,
@@ -1,3 +1,5 @@
+use crate::scene::{BoxShadow, RGBAColor, ShaderParam, ShaderParamStructField};
+
 use super::{HorizontalPosition, VerticalPosition};
 
 #[derive(Debug, Clone, Copy)]
@@ -29,6 +31,12 @@ impl ContinuousValue for f32 {
     }
 }
 
+impl ContinuousValue for u32 {
+    fn interpolate(start: &Self, end: &Self, state: InterpolationState) -> Self {
+        interpolate_f64(*start as f64, *end as f64, state) as Self
+    }
+}
+
 impl ContinuousValue for usize {
     fn interpolate(start: &Self, end: &Self, state: InterpolationState) -> Self {
         interpolate_f64(*start as f64, *end as f64, state) as usize
@@ -44,6 +52,28 @@ impl<T: ContinuousValue + Clone> ContinuousValue for Option<T> {
     }
 }
 
+/// Lists are interpolated element-wise only if their lengths match.
+fn interpolate_list<T: ContinuousValue + Clone>(
+    start: &[T],
+    end: &[T],
+    state: InterpolationState,
+) -> Vec<T> {
+    if start.len() != end.len() {
+        return end.to_vec();
+    }
+    start
+        .iter()
+        .zip(end.iter())
+        .map(|(start, end)| ContinuousValue::interpolate(start, end, state))
+        .collect()
+}
+
+impl ContinuousValue for Vec<BoxShadow> {
+    fn interpolate(start: &Self, end: &Self, state: InterpolationState) -> Self {
+        interpolate_list(start, end, state)
+    }
+}
+
 impl From<InterpolationState> for f64 {
     fn from(value: InterpolationState) -> Self {
         value.0
@@ -77,3 +107,128 @@ impl ContinuousValue for HorizontalPosition {
         }
     }
 }
+
+impl ContinuousValue for BoxShadow {
+    fn interpolate(start: &Self, end: &Self, state: InterpolationState) -> Self {
+        Self {
+            offset_x: ContinuousValue::interpolate(&start.offset_x, &end.offset_x, state),
+            offset_y: ContinuousValue::interpolate(&start.offset_y, &end.offset_y, state),
+            blur_radius: ContinuousValue::interpolate(&start.blur_radius, &end.blur_radius, state),
+            color: ContinuousValue::interpolate(&start.color, &end.color, state),
+        }
+    }
+}
+
+/// Colors are interpolated in linear light with premultiplied alpha, so e.g. transition
+/// from a transparent color does not darken the result.
+impl ContinuousValue for RGBAColor {
+    fn interpolate(start: &Self, end: &Self, state: InterpolationState) -> Self {
+        fn srgb_to_linear(value: u8) -> f64 {
+            let value = value as f64 / 255.0;
+            match value <= 0.04045 {
+                true => value / 12.92,
+                false => ((value + 0.055) / 1.055).powf(2.4),
+            }
+        }
+        fn linear_to_srgb(value: f64) -> u8 {
+            let value = match value <= 0.0031308 {
+                true => value * 12.92,
+                false => 1.055 * value.powf(1.0 / 2.4) - 0.055,
+            };
+            (value.clamp(0.0, 1.0) * 255.0).round() as u8
+        }
+
+        let start_alpha = start.3 as f64 / 255.0;
+        let end_alpha = end.3 as f64 / 255.0;
+        let alpha = interpolate_f64(start_alpha, end_alpha, state).clamp(0.0, 1.0);
+        let channel = |start_value: u8, end_value: u8| {
+            if alpha <= 0.0 {
+                return 0;
+            }
+            let premultiplied = interpolate_f64(
+                srgb_to_linear(start_value) * start_alpha,
+                srgb_to_linear(end_value) * end_alpha,
+                state,
+            );
+            linear_to_srgb(premultiplied / alpha)
+        };
+        Self(
+            channel(start.0, end.0),
+            channel(start.1, end.1),
+            channel(start.2, end.2),
+            (alpha * 255.0).round() as u8,
+        )
+    }
+}
+
+/// Numeric values are interpolated only if both params have the same structure.
+impl ContinuousValue for ShaderParam {
+    fn interpolate(start: &Self, end: &Self, state: InterpolationState) -> Self {
+        match (start, end) {
+            (ShaderParam::F32(start), ShaderParam::F32(end)) => {
+                ShaderParam::F32(ContinuousValue::interpolate(start, end, state))
+            }
+            (ShaderParam::U32(start), ShaderParam::U32(end)) => {
+                ShaderParam::U32(ContinuousValue::interpolate(start, end, state))
+            }
+            (ShaderParam::I32(start), ShaderParam::I32(end)) => {
+                ShaderParam::I32(ContinuousValue::interpolate(start, end, state))
+            }
+            (ShaderParam::List(start), ShaderParam::List(end)) => {
+                ShaderParam::List(interpolate_list(start, end, state))
+            }
+            (ShaderParam::Struct(start_fields), ShaderParam::Struct(end_fields))
+                if start_fields.len() == end_fields.len()
+                    && start_fields
+                        .iter()
+                        .zip(end_fields.iter())
+                        .all(|(start, end)| start.field_name == end.field_name) =>
+            {
+                ShaderParam::Struct(
+                    start_fields
+                        .iter()
+                        .zip(end_fields.iter())
+                        .map(|(start, end)| ShaderParamStructField {
+                            field_name: end.field_name.clone(),
+                            value: ContinuousValue::interpolate(&start.value, &end.value, state),
+                        })
+                        .collect(),
+                )
+            }
+            (_, end) => end.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn color_interpolation_in_linear_light() {
+        let black = RGBAColor(0, 0, 0, 255);
+        let white = RGBAColor(255, 255, 255, 255);
+        assert_eq!(
+            RGBAColor::interpolate(&black, &white, InterpolationState(0.5)),
+            RGBAColor(188, 188, 188, 255)
+        );
+        assert_eq!(
+            RGBAColor::interpolate(&black, &white, InterpolationState(1.0)),
+            white
+        );
+    }
+
+    #[test]
+    fn color_interpolation_from_transparent() {
+        let transparent = RGBAColor(0, 0, 0, 0);
+        let red = RGBAColor(255, 0, 0, 255);
+        assert_eq!(
+            RGBAColor::interpolate(&transparent, &red, InterpolationState(0.5)),
+            RGBAColor(255, 0, 0, 128)
+        );
+        assert_eq!(
+            RGBAColor::interpolate(&transparent, &red, InterpolationState(0.0)),
+            transparent
+        );
+    }
+}
@@ -2,7 +2,7 @@ use std::collections::{HashMap, HashSet};
 
 use crate::{OutputId, RendererId};
 
-use super::{Component, ComponentId, OutputScene, SceneError};
+use super::{Component, ComponentId, EnterExitTransition, OutputScene, SceneError};
 
 impl Component {
     fn component_id(&self) -> Option<&ComponentId> {
@@ -30,6 +30,15 @@ impl Component {
             Component::Rescaler(rescaler) => vec![rescaler.child.as_ref()],
         }
     }
+
+    fn exit_transition(&self) -> Option<&EnterExitTransition> {
+        match self {
+            Component::View(view) => view.exit.as_ref(),
+            Component::Tiles(tiles) => tiles.exit.as_ref(),
+            Component::Rescaler(rescaler) => rescaler.exit.as_ref(),
+            _ => None,
+        }
+    }
 }
 
 pub(super) fn validate_scene_update(
@@ -42,6 +51,7 @@ pub(super) fn validate_scene_update(
 
     validate_component_ids_uniqueness(&updated_outputs)?;
     validate_web_renderer_ids_uniqueness(&updated_outputs)?;
+    validate_exit_transitions(updated_output)?;
     Ok(())
 }
 
@@ -99,3 +109,152 @@ fn validate_web_renderer_ids_uniqueness(outputs: &[&OutputScene]) -> Result<(),
         .iter()
         .try_for_each(|output| visit(&output.scene_root, &mut web_renderer_ids))
 }
+
+/// Removed components are only kept until their exit transition finishes if they
+/// are children of a `View` with an id (see `retain_exiting_components`).
+fn validate_exit_transitions(output: &OutputScene) -> Result<(), SceneError> {
+    fn visit(component: &Component, parent: Option<&Component>) -> Result<(), SceneError> {
+        if component.exit_transition().is_some() {
+            let Some(id) = component.component_id() else {
+                return Err(SceneError::ExitTransitionWithoutId);
+            };
+            let is_parent_supported = matches!(
+                parent,
+                Some(parent @ Component::View(_)) if parent.component_id().is_some()
+            );
+            if !is_parent_supported {
+                return Err(SceneError::UnsupportedExitTransitionParent(id.clone()));
+            }
+        }
+
+        component
+            .children()
+            .into_iter()
+            .try_for_each(|child| visit(child, Some(component)))
+    }
+
+    visit(&output.scene_root, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use crate::{
+        scene::{
+            EnterExitEffect, HorizontalAlign, InterpolationKind, Overflow, Position, RGBAColor,
+            RescaleMode, RescalerComponent, VerticalAlign, ViewChildrenDirection, ViewComponent,
+        },
+        Resolution,
+    };
+
+    use super::*;
+
+    fn exit() -> Option<EnterExitTransition> {
+        Some(EnterExitTransition {
+            duration: Duration::from_secs(1),
+            interpolation_kind: InterpolationKind::Linear,
+            effect: EnterExitEffect::Fade,
+        })
+    }
+
+    fn view(
+        id: Option<&str>,
+        children: Vec<Component>,
+        exit: Option<EnterExitTransition>,
+    ) -> Component {
+        Component::View(ViewComponent {
+            id: id.map(|id| ComponentId(Arc::from(id))),
+            children,
+            direction: ViewChildrenDirection::Row,
+            position: Position::Static {
+                width: None,
+                height: None,
+            },
+            transition: None,
+            enter: None,
+            exit,
+            animation: None,
+            overflow: Overflow::Hidden,
+            background_color: RGBAColor(0, 0, 0, 0),
+            opacity: 1.0,
+            border_radius: 0.0,
+            border_width: 0.0,
+            border_color: RGBAColor(0, 0, 0, 0),
+            box_shadow: vec![],
+        })
+    }
+
+    fn validate(scene_root: Component) -> Result<(), SceneError> {
+        validate_scene_update(
+            &HashMap::new(),
+            &OutputScene {
+                output_id: OutputId(Arc::from("output_1")),
+                scene_root,
+                resolution: Resolution {
+                    width: 1920,
+                    height: 1080,
+                },
+            },
+        )
+    }
+
+    #[test]
+    fn exit_transition_in_view_with_id() {
+        let root = view(
+            Some("root"),
+            vec![view(Some("child"), vec![], exit())],
+            None,
+        );
+        assert!(validate(root).is_ok());
+    }
+
+    #[test]
+    fn exit_transition_without_id() {
+        let root = view(Some("root"), vec![view(None, vec![], exit())], None);
+        assert!(matches!(
+            validate(root),
+            Err(SceneError::ExitTransitionWithoutId)
+        ));
+    }
+
+    #[test]
+    fn exit_transition_with_unsupported_parent() {
+        let root_without_id = view(None, vec![view(Some("child"), vec![], exit())], None);
+        assert!(matches!(
+            validate(root_without_id),
+            Err(SceneError::UnsupportedExitTransitionParent(_))
+        ));
+
+        let scene_root = view(Some("root"), vec![], exit());
+        assert!(matches!(
+            validate(scene_root),
+            Err(SceneError::UnsupportedExitTransitionParent(_))
+        ));
+
+        let rescaler = Component::Rescaler(RescalerComponent {
+            id: Some(ComponentId(Arc::from("rescaler"))),
+            child: Box::new(view(Some("child"), vec![], exit())),
+            position: Position::Static {
+                width: None,
+                height: None,
+            },
+            transition: None,
+            enter: None,
+            exit: None,
+            animation: None,
+            mode: RescaleMode::Fit,
+            horizontal_align: HorizontalAlign::Center,
+            vertical_align: VerticalAlign::Center,
+            opacity: 1.0,
+            border_radius: 0.0,
+            border_width: 0.0,
+            border_color: RGBAColor(0, 0, 0, 0),
+            box_shadow: vec![],
+        });
+        assert!(matches!(
+            validate(rescaler),
+            Err(SceneError::UnsupportedExitTransitionParent(_))
+        ));
+    }
+}
@@ -3,13 +3,15 @@ use std::time::Duration;
 use crate::{scene::ViewChildrenDirection, transformations::layout::NestedLayout};
 
 use super::{
+    animation::AnimationState,
     components::ViewComponent,
+    enter_exit_transition::EnterExitState,
     layout::StatefulLayoutComponent,
     scene_state::BuildStateTreeCtx,
     transition::{TransitionOptions, TransitionState},
     types::interpolation::ContinuousValue,
-    Component, ComponentId, IntermediateNode, Overflow, Position, RGBAColor, SceneError, Size,
-    StatefulComponent,
+    BoxShadow, Component, ComponentId, IntermediateNode, Overflow, Position, RGBAColor, SceneError,
+    Size, StatefulComponent,
 };
 
 mod interpolation;
@@ -17,9 +19,11 @@ mod layout;
 
 #[derive(Debug, Clone)]
 pub(super) struct StatefulViewComponent {
-    start: Option<ViewComponentParam>,
+    start: Option<Box<ViewComponentParam>>,
     end: ViewComponentParam,
     transition: Option<TransitionState>,
+    enter_exit: Box<EnterExitState>,
+    animation: Option<Box<AnimationState>>,
     children: Vec<StatefulComponent>,
 }
 
@@ -32,15 +36,28 @@ struct ViewComponentParam {
     overflow: Overflow,
 
     background_color: RGBAColor,
+    opacity: f32,
+
+    border_radius: f32,
+    border_width: f32,
+    border_color: RGBAColor,
+    box_shadow: Vec<BoxShadow>,
 }
 
 impl StatefulViewComponent {
     fn view(&self, pts: Duration) -> ViewComponentParam {
-        let (Some(transition), Some(start)) = (&self.transition, &self.start) else {
-            return self.end.clone();
+        let mut view = match (&self.transition, self.start.as_deref()) {
+            (Some(transition), Some(start)) => {
+                let interpolation_progress = transition.state(pts);
+                ContinuousValue::interpolate(start, &self.end, interpolation_progress)
+            }
+            _ => self.end.clone(),
         };
-        let interpolation_progress = transition.state(pts);
-        ContinuousValue::interpolate(start, &self.end, interpolation_progress)
+        if let Some(animation) = &self.animation {
+            view.position = animation.apply_position(view.position, pts);
+            view.opacity = animation.apply_opacity(view.opacity, pts);
+        }
+        view
     }
 
     pub(super) fn children(&self) -> Vec<&StatefulComponent> {
@@ -51,6 +68,30 @@ impl StatefulViewComponent {
         self.children.iter_mut().collect()
     }
 
+    /// Inserts a child at `index` or at the end if `index` is out of bounds.
+    pub(super) fn insert_child(&mut self, index: usize, child: StatefulComponent) {
+        self.children
+            .insert(usize::min(index, self.children.len()), child);
+    }
+
+    /// Removes children matching the predicate. Returns `true` if any child was removed.
+    pub(super) fn remove_children(
+        &mut self,
+        predicate: impl Fn(&StatefulComponent) -> bool,
+    ) -> bool {
+        let children_count = self.children.len();
+        self.children.retain(|child| !predicate(child));
+        self.children.len() != children_count
+    }
+
+    pub(super) fn enter_exit(&self) -> &EnterExitState {
+        &self.enter_exit
+    }
+
+    pub(super) fn enter_exit_mut(&mut self) -> &mut EnterExitState {
+        &mut self.enter_exit
+    }
+
     pub(super) fn position(&self, pts: Duration) -> Position {
         self.view(pts).position
     }
@@ -86,7 +127,7 @@ impl StatefulViewComponent {
 
 impl ViewComponent {
     pub(super) fn stateful_component(
-        self,
+        mut self,
         ctx: &BuildStateTreeCtx,
     ) -> Result<StatefulComponent, SceneError> {
         let previous_state = self
@@ -99,19 +140,48 @@ impl ViewComponent {
                 }
                 _ => None,
             });
+        let children = std::mem::take(&mut self.children)
+            .into_iter()
+            .map(|c| Component::stateful_component(c, ctx))
+            .collect::<Result<_, _>>()?;
 
+        Ok(StatefulComponent::Layout(StatefulLayoutComponent::View(
+            self.stateful_view(previous_state, ctx.last_render_pts, children),
+        )))
+    }
+
+    /// Builds state of the view from the state in the previous scene. Children are
+    /// converted separately, `self.children` is ignored.
+    pub(super) fn stateful_view(
+        self,
+        previous_state: Option<&StatefulViewComponent>,
+        last_render_pts: Duration,
+        children: Vec<StatefulComponent>,
+    ) -> StatefulViewComponent {
         // TODO: to handle cases like transition from top to bottom this view needs
         // to be further processed to use the same type of coordinates as end
-        let start = previous_state.map(|state| state.view(ctx.last_render_pts));
+        let start = previous_state.map(|state| Box::new(state.view(last_render_pts)));
         let transition = TransitionState::new(
             self.transition.map(|transition| TransitionOptions {
                 duration: transition.duration,
                 interpolation_kind: transition.interpolation_kind,
             }),
             previous_state.and_then(|s| s.transition.clone()),
-            ctx.last_render_pts,
+            last_render_pts,
+        );
+        let enter_exit = EnterExitState::new(
+            self.enter,
+            self.exit,
+            self.id.is_some() && previous_state.is_none_or(|s| s.enter_exit.is_exiting()),
+            last_render_pts,
         );
-        let view = StatefulViewComponent {
+        let animation = AnimationState::new(
+            self.animation,
+            previous_state.and_then(|s| s.animation.as_deref()),
+            last_render_pts,
+        )
+        .map(Box::new);
+        StatefulViewComponent {
             start,
             end: ViewComponentParam {
                 id: self.id,
@@ -119,16 +189,16 @@ impl ViewComponent {
                 position: self.position,
                 background_color: self.background_color,
                 overflow: self.overflow,
+                opacity: self.opacity,
+                border_radius: self.border_radius,
+                border_width: self.border_width,
+                border_color: self.border_color,
+                box_shadow: self.box_shadow,
             },
             transition,
-            children: self
-                .children
-                .into_iter()
-                .map(|c| Component::stateful_component(c, ctx))
-                .collect::<Result<_, _>>()?,
-        };
-        Ok(StatefulComponent::Layout(StatefulLayoutComponent::View(
-            view,
-        )))
+            enter_exit: Box::new(enter_exit),
+            animation,
+            children,
+        }
     }
 }
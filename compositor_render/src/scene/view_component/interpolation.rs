@@ -8,8 +8,29 @@ impl ContinuousValue for ViewComponentParam {
             id: end.id.clone(),
             direction: end.direction.clone(),
             position: ContinuousValue::interpolate(&start.position, &end.position, state),
-            background_color: end.background_color,
+            background_color: ContinuousValue::interpolate(
+                &start.background_color,
+                &end.background_color,
+                state,
+            ),
             overflow: end.overflow,
+            opacity: ContinuousValue::interpolate(&start.opacity, &end.opacity, state),
+            border_radius: ContinuousValue::interpolate(
+                &start.border_radius,
+                &end.border_radius,
+                state,
+            ),
+            border_width: ContinuousValue::interpolate(
+                &start.border_width,
+                &end.border_width,
+                state,
+            ),
+            border_color: ContinuousValue::interpolate(
+                &start.border_color,
+                &end.border_color,
+                state,
+            ),
+            box_shadow: ContinuousValue::interpolate(&start.box_shadow, &end.box_shadow, state),
         }
     }
 }
@@ -2,7 +2,7 @@ use std::time::Duration;
 
 use crate::{
     scene::{
-        layout::StatefulLayoutComponent, Overflow, Position, Size, StatefulComponent,
+        layout::StatefulLayoutComponent, Overflow, Position, RGBAColor, Size, StatefulComponent,
         ViewChildrenDirection,
     },
     transformations::layout::{Crop, LayoutContent, NestedLayout},
@@ -30,6 +30,38 @@ impl ViewComponentParam {
         size: Size,
         children: &mut [StatefulComponent],
         pts: Duration,
+    ) -> NestedLayout {
+        let content_size = Size {
+            width: f32::max(size.width - 2.0 * self.border_width, 0.0),
+            height: f32::max(size.height - 2.0 * self.border_width, 0.0),
+        };
+        let content_layout = self.content_layout(content_size, children, pts);
+        NestedLayout {
+            top: 0.0,
+            left: 0.0,
+            width: size.width,
+            height: size.height,
+            rotation_degrees: 0.0,
+            opacity: self.opacity,
+            border_radius: self.border_radius,
+            border_width: self.border_width,
+            border_color: self.border_color,
+            box_shadow: self.box_shadow.clone(),
+            scale_x: 1.0,
+            scale_y: 1.0,
+            crop: None,
+            content: LayoutContent::Color(self.background_color),
+            child_nodes_count: content_layout.child_nodes_count,
+            children: vec![content_layout],
+        }
+    }
+
+    /// Layout of the area inside of the border. Children are positioned relative to it.
+    fn content_layout(
+        &self,
+        size: Size,
+        children: &mut [StatefulComponent],
+        pts: Duration,
     ) -> NestedLayout {
         let static_child_size = self.static_child_size(size, children, pts);
         let (scale, crop) = match self.overflow {
@@ -63,7 +95,7 @@ impl ViewComponentParam {
                         height: non_layout_component.height(pts),
                     },
                 };
-                match position {
+                let layout = match position {
                     Position::Static { width, height } => {
                         let (layout, updated_static_offset) = self.layout_static_child(
                             child,
@@ -85,19 +117,25 @@ impl ViewComponentParam {
                             child, position, size, pts,
                         )
                     }
-                }
+                };
+                StatefulLayoutComponent::apply_enter_exit_transition(child, layout, size, pts)
             })
             .collect();
         NestedLayout {
-            top: 0.0,
-            left: 0.0,
+            top: self.border_width,
+            left: self.border_width,
             width: size.width,
             height: size.height,
             rotation_degrees: 0.0,
+            opacity: 1.0,
+            border_radius: f32::max(self.border_radius - self.border_width, 0.0),
+            border_width: 0.0,
+            border_color: RGBAColor(0, 0, 0, 0),
+            box_shadow: vec![],
             scale_x: scale,
             scale_y: scale,
             crop,
-            content: LayoutContent::Color(self.background_color),
+            content: LayoutContent::None,
             child_nodes_count: children.iter().map(|l| l.child_nodes_count).sum(),
             children,
         }
@@ -137,6 +175,11 @@ impl ViewComponentParam {
                     width,
                     height,
                     rotation_degrees: 0.0,
+                    opacity: 1.0,
+                    border_radius: 0.0,
+                    border_width: 0.0,
+                    border_color: RGBAColor(0, 0, 0, 0),
+                    box_shadow: vec![],
                     scale_x: 1.0,
                     scale_y: 1.0,
                     crop: None,
@@ -151,6 +194,11 @@ impl ViewComponentParam {
                 width,
                 height,
                 rotation_degrees: 0.0,
+                opacity: 1.0,
+                border_radius: 0.0,
+                border_width: 0.0,
+                border_color: RGBAColor(0, 0, 0, 0),
+                box_shadow: vec![],
                 scale_x: 1.0,
                 scale_y: 1.0,
                 crop: None,
@@ -1,6 +1,8 @@
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
+use log::error;
+
 use crate::error::{RegisterRendererError, UnregisterRendererError};
 
 use crate::scene::{Component, OutputScene};
@@ -214,6 +216,11 @@ impl InnerRenderer {
             .collect();
         self.scene
             .register_render_event(inputs.pts, input_resolutions);
+        for output_node in self.scene.remove_finished_exit_transitions() {
+            if let Err(err) = self.render_graph.update(ctx, output_node) {
+                error!("Failed to update scene after exit transition: {err}");
+            }
+        }
 
         let pts = inputs.pts;
         populate_inputs(ctx, &mut self.render_graph, inputs);
@@ -37,12 +37,12 @@ impl InnerRenderNode {
         pts: Duration,
     ) {
         match self {
-            InnerRenderNode::Shader(ref shader) => {
+            InnerRenderNode::Shader(shader) => {
                 shader.render(ctx.wgpu_ctx, sources, target, pts);
             }
             InnerRenderNode::Web(renderer) => renderer.render(ctx, sources, target),
             InnerRenderNode::Text(renderer) => {
-                renderer.render(ctx, target);
+                renderer.render(ctx, target, pts);
             }
             InnerRenderNode::Image(ref node) => node.render(ctx, target, pts),
             InnerRenderNode::InputStreamRef(_) => {
@@ -111,7 +111,7 @@ impl RenderNode {
         let node = InnerRenderNode::Shader(ShaderNode::new(
             ctx,
             shader,
-            &shader_params.shader_param,
+            shader_params.shader_param,
             &shader_params.size.into(),
         ));
         let mut output = NodeTexture::new();
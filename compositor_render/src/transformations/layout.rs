@@ -1,7 +1,7 @@
 use std::{sync::Arc, time::Duration};
 
 use crate::{
-    scene::{RGBAColor, Size},
+    scene::{BoxShadow, RGBAColor, Size},
     state::RenderCtx,
     wgpu::texture::NodeTexture,
     Resolution,
@@ -14,7 +14,7 @@ mod shader;
 mod transformation_matrices;
 
 use self::{
-    params::{LayoutNodeParams, ParamsBuffer},
+    params::{ContentType, LayoutNodeParams, ParamsBuffer},
     shader::LayoutShader,
 };
 
@@ -41,6 +41,17 @@ pub struct Crop {
     pub height: f32,
 }
 
+/// Area outside of which a layout is not rendered. It is defined in the output
+/// coordinates, the same as top/left/width/height of a `RenderLayout`.
+#[derive(Debug, Clone)]
+struct Mask {
+    top: f32,
+    left: f32,
+    width: f32,
+    height: f32,
+    radius: f32,
+}
+
 #[derive(Debug, Clone)]
 struct RenderLayout {
     top: f32,
@@ -48,13 +59,27 @@ struct RenderLayout {
     width: f32,
     height: f32,
     rotation_degrees: f32,
+    opacity: f32,
+    border_radius: f32,
+    border_width: f32,
+    border_color: RGBAColor,
+    /// Parent areas with rounded corners or crop that clip this layout.
+    masks: Vec<Mask>,
     content: RenderLayoutContent,
 }
 
 #[derive(Debug, Clone)]
 enum RenderLayoutContent {
     Color(RGBAColor),
-    ChildNode { index: usize, crop: Crop },
+    ChildNode {
+        index: usize,
+        crop: Crop,
+    },
+    /// Shadow of a rounded rectangle inset by `blur_radius` from the layout edges.
+    BoxShadow {
+        color: RGBAColor,
+        blur_radius: f32,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -71,6 +96,16 @@ pub struct NestedLayout {
     pub width: f32,
     pub height: f32,
     pub rotation_degrees: f32,
+    /// Opacity in the `[0, 1]` range. It also affects content/children.
+    pub opacity: f32,
+    /// Radius of the rounded corners. If `crop` is defined, children are also
+    /// clipped by the rounded corners.
+    pub border_radius: f32,
+    /// Border is drawn inside of the layout area, it does not affect children.
+    pub border_width: f32,
+    pub border_color: RGBAColor,
+    /// Shadows are rendered below the layout, they are not affected by `crop` of the layout.
+    pub box_shadow: Vec<BoxShadow>,
     /// scale will affect content/children, but not the properties of current layout like
     /// top/left/widht/height
     pub scale_x: f32,
@@ -121,18 +156,29 @@ impl LayoutNode {
         let params: Vec<LayoutNodeParams> = layouts
             .iter()
             .map(|layout| {
-                let (is_texture, background_color, input_resolution) = match layout.content {
+                let (content_type, color, blur_radius, input_resolution) = match layout.content {
                     RenderLayoutContent::ChildNode { index, .. } => (
-                        1,
+                        ContentType::Texture,
                         RGBAColor(0, 0, 0, 0),
+                        0.0,
                         *input_resolutions.get(index).unwrap_or(&None),
                     ),
-                    RenderLayoutContent::Color(color) => (0, color, None),
+                    RenderLayoutContent::Color(color) => (ContentType::Color, color, 0.0, None),
+                    RenderLayoutContent::BoxShadow { color, blur_radius } => {
+                        (ContentType::BoxShadow, color, blur_radius, None)
+                    }
                 };
 
                 LayoutNodeParams {
-                    is_texture,
-                    background_color,
+                    content_type,
+                    color,
+                    opacity: layout.opacity,
+                    size: (layout.width, layout.height),
+                    border_radius: layout.border_radius,
+                    border_width: layout.border_width,
+                    border_color: layout.border_color,
+                    blur_radius,
+                    masks: layout.masks.clone(),
                     transform_vertices_matrix: layout
                         .vertices_transformation_matrix(&output_resolution),
                     transform_texture_coords_matrix: layout
@@ -145,7 +191,7 @@ impl LayoutNode {
         let textures: Vec<Option<&NodeTexture>> = layouts
             .iter()
             .map(|layout| match layout.content {
-                RenderLayoutContent::Color(_) => None,
+                RenderLayoutContent::Color(_) | RenderLayoutContent::BoxShadow { .. } => None,
                 RenderLayoutContent::ChildNode { index, .. } => match sources.get(index) {
                     Some(node_texture) => Some(*node_texture),
                     None => {
@@ -173,6 +219,11 @@ impl NestedLayout {
             width: 0.0,
             height: 0.0,
             rotation_degrees: 0.0,
+            opacity: 1.0,
+            border_radius: 0.0,
+            border_width: 0.0,
+            border_color: RGBAColor(0, 0, 0, 0),
+            box_shadow: vec![],
             scale_x: 1.0,
             scale_y: 1.0,
             crop: None,
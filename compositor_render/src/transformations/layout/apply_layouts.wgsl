@@ -6,14 +6,28 @@ struct VertexInput {
 struct VertexOutput {
     @builtin(position) position: vec4<f32>,
     @location(0) tex_coords: vec2<f32>,
+    // (0, 0) is the top-left corner and (1, 1) is the bottom-right corner of the layout
+    @location(1) layout_coords: vec2<f32>,
 }
 
+struct Mask {
+    rect: vec4<f32>, // left, top, width, height in output pixels
+    radius: f32,
+}
 
 struct Layout {
     vertices_transformation: mat4x4<f32>,
     texture_coord_transformation: mat4x4<f32>,
-    color: vec4<f32>, // used only when is_texture == 0
-    is_texture: u32, // 0 -> color, 1 -> texture
+    color: vec4<f32>, // used only when content_type != 1
+    border_color: vec4<f32>,
+    size: vec2<f32>,
+    border_radius: f32,
+    border_width: f32,
+    blur_radius: f32, // used only when content_type == 2
+    opacity: f32,
+    content_type: u32, // 0 -> color, 1 -> texture, 2 -> box shadow
+    masks_count: u32,
+    masks: array<Mask, 4>,
 }
 
 @group(0) @binding(0) var texture: texture_2d<f32>;
@@ -22,6 +36,20 @@ struct Layout {
 
 var<push_constant> layout_id: u32;
 
+// Signed distance from the edge of a rectangle with rounded corners. Position is relative
+// to the top-left corner of the rectangle. Negative values are inside the rectangle.
+fn rounded_rect_sdf(position: vec2<f32>, size: vec2<f32>, radius: f32) -> f32 {
+    let half_size = size / 2.0;
+    let r = min(radius, min(half_size.x, half_size.y));
+    let q = abs(position - half_size) - half_size + vec2(r);
+    return length(max(q, vec2(0.0))) + min(max(q.x, q.y), 0.0) - r;
+}
+
+// Coverage of a pixel that is `distance` away from the edge.
+fn edge_alpha(distance: f32) -> f32 {
+    return clamp(0.5 - distance, 0.0, 1.0);
+}
+
 @vertex
 fn vs_main(input: VertexInput) -> VertexOutput {
     var output: VertexOutput;
@@ -31,6 +59,7 @@ fn vs_main(input: VertexInput) -> VertexOutput {
 
     output.position = vec4(input.position, 1.0) * vertices_transformation_matrix;
     output.tex_coords = (vec4(input.tex_coords, 0.0, 1.0) * texture_coord_transformation_matrix).xy;
+    output.layout_coords = input.tex_coords;
 
     return output;
 }
@@ -39,13 +68,46 @@ fn vs_main(input: VertexInput) -> VertexOutput {
 fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
     let current_layout = layouts[layout_id];
 
-    // sampling can't be conditional, so in case of plane_id == -1
-    // sample textures[0], but ignore the result.
-    if (current_layout.is_texture == 0u) {
-        return current_layout.color;
+    // sampling can't be conditional, so in case of color layouts
+    // sample the texture, but ignore the result.
+    let texture_color = textureSample(texture, sampler_, input.tex_coords);
+
+    var mask_alpha = 1.0;
+    for (var i = 0u; i < current_layout.masks_count; i++) {
+        let mask = current_layout.masks[i];
+        mask_alpha *= edge_alpha(rounded_rect_sdf(input.position.xy - mask.rect.xy, mask.rect.zw, mask.radius));
     }
-    // clamp transparent, when crop > input texture
-    let is_inside: f32 = round(f32(input.tex_coords.x < 1.0 && input.tex_coords.x > 0.0 && input.tex_coords.y > 0.0 && input.tex_coords.y < 1.0));
-    
-    return is_inside * textureSample(texture, sampler_, input.tex_coords);
+
+    let size = current_layout.size;
+    let position = input.layout_coords * size;
+
+    if (current_layout.content_type == 2u) {
+        let blur = current_layout.blur_radius;
+        // shadow shape is inset by the blur radius, blur spreads equally on both sides of its edge
+        let distance = rounded_rect_sdf(position - vec2(blur), size - vec2(2.0 * blur), current_layout.border_radius);
+        var shadow_alpha = edge_alpha(distance);
+        if (blur > 0.5) {
+            shadow_alpha = 1.0 - smoothstep(-blur, blur, distance);
+        }
+        return current_layout.color * vec4(1.0, 1.0, 1.0, shadow_alpha * mask_alpha * current_layout.opacity);
+    }
+
+    var color = current_layout.color;
+    if (current_layout.content_type == 1u) {
+        // clamp transparent, when crop > input texture
+        let is_inside: f32 = round(f32(input.tex_coords.x < 1.0 && input.tex_coords.x > 0.0 && input.tex_coords.y > 0.0 && input.tex_coords.y < 1.0));
+        color = is_inside * texture_color;
+    }
+
+    let distance = rounded_rect_sdf(position, size, current_layout.border_radius);
+    var shape_alpha = 1.0;
+    if (current_layout.border_radius > 0.0) {
+        shape_alpha = edge_alpha(distance);
+    }
+    if (current_layout.border_width > 0.0) {
+        let border = clamp(0.5 + distance + current_layout.border_width, 0.0, 1.0);
+        color = mix(color, current_layout.border_color, border);
+    }
+
+    return color * vec4(1.0, 1.0, 1.0, shape_alpha * mask_alpha * current_layout.opacity);
 }
@@ -1,6 +1,6 @@
 use crate::{scene::RGBAColor, Resolution};
 
-use super::{Crop, LayoutContent, NestedLayout, RenderLayout, RenderLayoutContent};
+use super::{Crop, LayoutContent, Mask, NestedLayout, RenderLayout, RenderLayoutContent};
 
 impl NestedLayout {
     pub(super) fn flatten(
@@ -24,6 +24,7 @@ impl NestedLayout {
             };
             child_index_offset += 1
         }
+        let shadows = self.box_shadow_layouts();
         let layout = self.render_layout();
         let children: Vec<_> = std::mem::take(&mut self.children)
             .into_iter()
@@ -35,7 +36,7 @@ impl NestedLayout {
             })
             .map(|l| self.flatten_child(l))
             .collect();
-        [vec![layout], children].concat()
+        [shadows, vec![layout], children].concat()
     }
 
     fn should_render(
@@ -45,14 +46,22 @@ impl NestedLayout {
     ) -> bool {
         if layout.width <= 0.0
             || layout.height <= 0.0
+            || layout.opacity <= 0.0
             || layout.top > resolution.height as f32
             || layout.left > resolution.width as f32
         {
             return false;
         }
         match &layout.content {
-            RenderLayoutContent::Color(RGBAColor(_, _, _, 0)) => false,
+            RenderLayoutContent::Color(RGBAColor(_, _, _, 0)) => {
+                layout.border_width > 0.0 && layout.border_color.3 > 0
+            }
             RenderLayoutContent::Color(_) => true,
+            RenderLayoutContent::BoxShadow {
+                color: RGBAColor(_, _, _, 0),
+                ..
+            } => false,
+            RenderLayoutContent::BoxShadow { .. } => true,
             RenderLayoutContent::ChildNode { crop, index } => {
                 let size = input_resolutions.get(*index).copied().flatten();
                 if let Some(size) = size {
@@ -76,7 +85,12 @@ impl NestedLayout {
                 width: layout.width * self.scale_x,
                 height: layout.height * self.scale_y,
                 rotation_degrees: layout.rotation_degrees + self.rotation_degrees, // TODO: not exactly correct
-                content: layout.content,
+                opacity: layout.opacity * self.opacity,
+                border_radius: layout.border_radius * self.min_scale(),
+                border_width: layout.border_width * self.min_scale(),
+                border_color: layout.border_color,
+                masks: self.transform_masks(layout.masks, 0.0, 0.0),
+                content: self.scale_content(layout.content),
             },
             Some(crop) => {
                 // Below values are only correct if `crop` is in the same coordinate
@@ -92,6 +106,38 @@ impl NestedLayout {
                 let cropped_right = f32::min(layout.left + layout.width - crop.left, crop.width);
                 let cropped_width = cropped_right - cropped_left;
                 let cropped_height = cropped_bottom - cropped_top;
+
+                let mut masks = self.transform_masks(layout.masks.clone(), crop.top, crop.left);
+                let crop_mask = Mask {
+                    top: self.top,
+                    left: self.left,
+                    width: crop.width * self.scale_x,
+                    height: crop.height * self.scale_y,
+                    radius: self.border_radius,
+                };
+
+                // Cropping the layout rectangle would change the shape of rounded corners,
+                // borders and shadows, so those are clipped by a mask instead.
+                if Self::has_shape(&layout) {
+                    masks.push(crop_mask);
+                    return RenderLayout {
+                        top: self.top + ((layout.top - crop.top) * self.scale_y),
+                        left: self.left + ((layout.left - crop.left) * self.scale_x),
+                        width: layout.width * self.scale_x,
+                        height: layout.height * self.scale_y,
+                        rotation_degrees: layout.rotation_degrees + self.rotation_degrees, // TODO: not exactly correct
+                        opacity: layout.opacity * self.opacity,
+                        border_radius: layout.border_radius * self.min_scale(),
+                        border_width: layout.border_width * self.min_scale(),
+                        border_color: layout.border_color,
+                        masks,
+                        content: self.scale_content(layout.content),
+                    };
+                }
+                if self.border_radius > 0.0 {
+                    masks.push(crop_mask);
+                }
+
                 match layout.content {
                     RenderLayoutContent::Color(color) => {
                         RenderLayout {
@@ -100,6 +146,11 @@ impl NestedLayout {
                             width: cropped_width * self.scale_x,
                             height: cropped_height * self.scale_y,
                             rotation_degrees: layout.rotation_degrees + self.rotation_degrees, // TODO: not exactly correct
+                            opacity: layout.opacity * self.opacity,
+                            border_radius: 0.0,
+                            border_width: 0.0,
+                            border_color: layout.border_color,
+                            masks,
                             content: RenderLayoutContent::Color(color),
                         }
                     }
@@ -131,9 +182,17 @@ impl NestedLayout {
                             width: cropped_width * self.scale_x,
                             height: cropped_height * self.scale_y,
                             rotation_degrees: layout.rotation_degrees + self.rotation_degrees, // TODO: not exactly correct
+                            opacity: layout.opacity * self.opacity,
+                            border_radius: 0.0,
+                            border_width: 0.0,
+                            border_color: layout.border_color,
+                            masks,
                             content: RenderLayoutContent::ChildNode { index, crop },
                         }
                     }
+                    RenderLayoutContent::BoxShadow { .. } => {
+                        unreachable!("Box shadow layouts are handled above.")
+                    }
                 }
             }
         }
@@ -146,6 +205,11 @@ impl NestedLayout {
             width: self.width,
             height: self.height,
             rotation_degrees: self.rotation_degrees,
+            opacity: self.opacity,
+            border_radius: self.border_radius,
+            border_width: self.border_width,
+            border_color: self.border_color,
+            masks: vec![],
             content: match self.content {
                 LayoutContent::Color(color) => RenderLayoutContent::Color(color),
                 LayoutContent::ChildNode { index, size } => RenderLayoutContent::ChildNode {
@@ -161,4 +225,65 @@ impl NestedLayout {
             },
         }
     }
+
+    /// Shadows are rendered as separate layouts, below the layout itself. Layout area
+    /// is extended by the blur radius, so the blurred edge is not clipped.
+    fn box_shadow_layouts(&self) -> Vec<RenderLayout> {
+        self.box_shadow
+            .iter()
+            .map(|shadow| RenderLayout {
+                top: self.top + shadow.offset_y - shadow.blur_radius,
+                left: self.left + shadow.offset_x - shadow.blur_radius,
+                width: self.width + 2.0 * shadow.blur_radius,
+                height: self.height + 2.0 * shadow.blur_radius,
+                rotation_degrees: self.rotation_degrees,
+                opacity: self.opacity,
+                border_radius: self.border_radius,
+                border_width: 0.0,
+                border_color: RGBAColor(0, 0, 0, 0),
+                masks: vec![],
+                content: RenderLayoutContent::BoxShadow {
+                    color: shadow.color,
+                    blur_radius: shadow.blur_radius,
+                },
+            })
+            .collect()
+    }
+
+    fn has_shape(layout: &RenderLayout) -> bool {
+        layout.border_radius > 0.0
+            || layout.border_width > 0.0
+            || matches!(layout.content, RenderLayoutContent::BoxShadow { .. })
+    }
+
+    /// Scale applied to values that can't be scaled separately in each direction
+    /// (e.g. border radius).
+    fn min_scale(&self) -> f32 {
+        f32::min(self.scale_x, self.scale_y)
+    }
+
+    fn scale_content(&self, content: RenderLayoutContent) -> RenderLayoutContent {
+        match content {
+            RenderLayoutContent::BoxShadow { color, blur_radius } => {
+                RenderLayoutContent::BoxShadow {
+                    color,
+                    blur_radius: blur_radius * self.min_scale(),
+                }
+            }
+            content => content,
+        }
+    }
+
+    fn transform_masks(&self, masks: Vec<Mask>, crop_top: f32, crop_left: f32) -> Vec<Mask> {
+        masks
+            .into_iter()
+            .map(|mask| Mask {
+                top: self.top + ((mask.top - crop_top) * self.scale_y),
+                left: self.left + ((mask.left - crop_left) * self.scale_x),
+                width: mask.width * self.scale_x,
+                height: mask.height * self.scale_y,
+                radius: mask.radius * self.min_scale(),
+            })
+            .collect()
+    }
 }
@@ -3,12 +3,34 @@ use wgpu::util::DeviceExt;
 
 use crate::{scene::RGBAColor, wgpu::WgpuCtx};
 
+use super::Mask;
+
+/// Maximal number of masks passed to the shader, must match the array size
+/// in `apply_layouts.wgsl`.
+const MAX_MASKS: usize = 4;
+
+#[derive(Debug, Clone, Copy)]
+pub(super) enum ContentType {
+    Color = 0,
+    Texture = 1,
+    BoxShadow = 2,
+}
+
 #[derive(Debug)]
 pub(super) struct LayoutNodeParams {
     pub(super) transform_vertices_matrix: Mat4,
     pub(super) transform_texture_coords_matrix: Mat4,
-    pub(super) is_texture: u32,
-    pub(super) background_color: RGBAColor,
+    pub(super) content_type: ContentType,
+    pub(super) color: RGBAColor,
+    pub(super) opacity: f32,
+    pub(super) size: (f32, f32),
+    pub(super) border_radius: f32,
+    pub(super) border_width: f32,
+    pub(super) border_color: RGBAColor,
+    pub(super) blur_radius: f32,
+    /// Masks ordered from the innermost one. Only the first `MAX_MASKS` are applied, this
+    /// limit is documented in the `View` and `Rescaler` API docs.
+    pub(super) masks: Vec<Mask>,
 }
 
 pub(super) struct ParamsBuffer {
@@ -70,37 +92,60 @@ impl ParamsBuffer {
         params
             .iter()
             .map(LayoutNodeParams::shader_buffer_content)
-            .collect::<Vec<[u8; 160]>>()
+            .collect::<Vec<[u8; 320]>>()
             .concat()
             .into()
     }
 }
 
 impl LayoutNodeParams {
-    fn shader_buffer_content(&self) -> [u8; 160] {
+    fn shader_buffer_content(&self) -> [u8; 320] {
         let Self {
             transform_vertices_matrix,
             transform_texture_coords_matrix,
-            is_texture,
-            background_color,
+            content_type,
+            color,
+            opacity,
+            size,
+            border_radius,
+            border_width,
+            border_color,
+            blur_radius,
+            masks,
         } = self;
-        let mut result = [0; 160];
-        fn from_u8_color(value: u8) -> [u8; 4] {
-            (value as f32 / 255.0).to_ne_bytes()
+        let mut result = [0; 320];
+        fn write_color(buffer: &mut [u8], color: &RGBAColor) {
+            for (index, value) in [color.0, color.1, color.2, color.3].iter().enumerate() {
+                buffer[index * 4..index * 4 + 4]
+                    .copy_from_slice(&(*value as f32 / 255.0).to_ne_bytes());
+            }
         }
 
         result[0..64].copy_from_slice(bytemuck::bytes_of(&transform_vertices_matrix.transpose()));
         result[64..128].copy_from_slice(bytemuck::bytes_of(
             &transform_texture_coords_matrix.transpose(),
         ));
-        // 12 bytes padding
-        result[128..132].copy_from_slice(&from_u8_color(background_color.0));
-        result[132..136].copy_from_slice(&from_u8_color(background_color.1));
-        result[136..140].copy_from_slice(&from_u8_color(background_color.2));
-        result[140..144].copy_from_slice(&from_u8_color(background_color.3));
-
-        result[144..148].copy_from_slice(&is_texture.to_ne_bytes());
-        // 12 bytes padding
+        write_color(&mut result[128..144], color);
+        write_color(&mut result[144..160], border_color);
+        result[160..164].copy_from_slice(&size.0.to_ne_bytes());
+        result[164..168].copy_from_slice(&size.1.to_ne_bytes());
+        result[168..172].copy_from_slice(&border_radius.to_ne_bytes());
+        result[172..176].copy_from_slice(&border_width.to_ne_bytes());
+        result[176..180].copy_from_slice(&blur_radius.to_ne_bytes());
+        result[180..184].copy_from_slice(&opacity.to_ne_bytes());
+        result[184..188].copy_from_slice(&(*content_type as u32).to_ne_bytes());
+
+        let masks_count = usize::min(masks.len(), MAX_MASKS);
+        result[188..192].copy_from_slice(&(masks_count as u32).to_ne_bytes());
+        for (index, mask) in masks.iter().take(MAX_MASKS).enumerate() {
+            let offset = 192 + index * 32;
+            result[offset..offset + 4].copy_from_slice(&mask.left.to_ne_bytes());
+            result[offset + 4..offset + 8].copy_from_slice(&mask.top.to_ne_bytes());
+            result[offset + 8..offset + 12].copy_from_slice(&mask.width.to_ne_bytes());
+            result[offset + 12..offset + 16].copy_from_slice(&mask.height.to_ne_bytes());
+            result[offset + 16..offset + 20].copy_from_slice(&mask.radius.to_ne_bytes());
+            // 12 bytes padding
+        }
 
         result
     }
@@ -29,7 +29,9 @@ impl RenderLayout {
         };
 
         match self.content {
-            super::RenderLayoutContent::Color(_) => Mat4::identity(),
+            super::RenderLayoutContent::Color(_) | super::RenderLayoutContent::BoxShadow { .. } => {
+                Mat4::identity()
+            }
             super::RenderLayoutContent::ChildNode { ref crop, .. } => {
                 let x_scale = crop.width / input_resolution.width as f32;
                 let y_scale = crop.height / input_resolution.height as f32;
@@ -3,7 +3,7 @@ use std::{sync::Arc, time::Duration};
 use wgpu::util::DeviceExt;
 
 use crate::{
-    scene::ShaderParam,
+    scene::{ShaderParam, TransitionValue},
     state::RenderCtx,
     wgpu::{texture::NodeTexture, WgpuCtx},
     Resolution,
@@ -13,7 +13,10 @@ use super::Shader;
 
 pub struct ShaderNode {
     params_bind_group: wgpu::BindGroup,
-    _custom_params_buffer: wgpu::Buffer,
+    custom_params_buffer: wgpu::Buffer,
+    shader_params: TransitionValue<Option<ShaderParam>>,
+    /// Content of `custom_params_buffer`.
+    shader_params_content: Option<bytes::Bytes>,
     shader: Arc<Shader>,
     resolution: Resolution,
 }
@@ -22,20 +25,41 @@ impl ShaderNode {
     pub fn new(
         ctx: &RenderCtx,
         shader: Arc<Shader>,
-        shader_params: &Option<ShaderParam>,
+        shader_params: TransitionValue<Option<ShaderParam>>,
         resolution: &Resolution,
     ) -> Self {
-        let custom_params_buffer = Self::new_params_buffer(ctx.wgpu_ctx, shader_params);
+        let custom_params_buffer = Self::new_params_buffer(ctx.wgpu_ctx, shader_params.end());
         let params_bind_group = Self::new_params_bind_group(ctx.wgpu_ctx, &custom_params_buffer);
 
         Self {
             params_bind_group,
-            _custom_params_buffer: custom_params_buffer,
+            custom_params_buffer,
+            shader_params_content: shader_params.end().as_ref().map(ShaderParam::to_bytes),
+            shader_params,
             shader,
             resolution: *resolution,
         }
     }
 
+    /// Writes params interpolated for `pts` if they changed. Interpolated params always
+    /// have the same structure as the final ones, so the buffer size does not change.
+    fn update_params(&mut self, wgpu_ctx: &WgpuCtx, pts: Duration) {
+        let content = self
+            .shader_params
+            .value(pts)
+            .as_ref()
+            .map(ShaderParam::to_bytes);
+        if content == self.shader_params_content {
+            return;
+        }
+        if let Some(content) = &content {
+            wgpu_ctx
+                .queue
+                .write_buffer(&self.custom_params_buffer, 0, content);
+        }
+        self.shader_params_content = content;
+    }
+
     fn new_params_buffer(ctx: &WgpuCtx, shader_params: &Option<ShaderParam>) -> wgpu::Buffer {
         match shader_params {
             Some(params) => {
@@ -43,7 +67,7 @@ impl ShaderNode {
                 ctx.device
                     .create_buffer_init(&wgpu::util::BufferInitDescriptor {
                         label: Some("shader node custom params buffer"),
-                        usage: wgpu::BufferUsages::UNIFORM,
+                        usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
                         contents: &params,
                     })
             }
@@ -69,12 +93,13 @@ impl ShaderNode {
     }
 
     pub fn render(
-        &self,
+        &mut self,
         wgpu_ctx: &Arc<WgpuCtx>,
         sources: &[&NodeTexture],
         target: &mut NodeTexture,
         pts: Duration,
     ) {
+        self.update_params(wgpu_ctx, pts);
         let target = target.ensure_size(wgpu_ctx, self.resolution);
 
         self.shader.pipeline.render(
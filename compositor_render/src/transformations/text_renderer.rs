@@ -2,6 +2,7 @@ use std::{
     cmp::max,
     fmt,
     sync::{Arc, Mutex},
+    time::Duration,
 };
 
 use glyphon::{
@@ -16,6 +17,7 @@ use wgpu::{
 use crate::{
     scene::{
         HorizontalAlign, RGBAColor, TextComponent, TextDimensions, TextStyle, TextWeight, TextWrap,
+        TransitionValue,
     },
     state::RenderCtx,
     utils::rgba_to_wgpu_color,
@@ -28,6 +30,9 @@ pub(crate) struct TextRenderParams {
     pub(crate) buffer: TextBuffer,
     pub(crate) resolution: Resolution,
     pub(crate) background_color: RGBAColor,
+    /// Text color is not part of the text buffer, so it can change without
+    /// laying out the text again.
+    pub(crate) color: TransitionValue<RGBAColor>,
 }
 
 #[derive(Clone)]
@@ -43,7 +48,9 @@ pub(crate) struct TextRendererNode {
     buffer: TextBuffer,
     resolution: Resolution,
     background_color: wgpu::Color,
-    was_rendered: bool,
+    color: TransitionValue<RGBAColor>,
+    /// Color used in the last render, `None` if text was not rendered yet.
+    rendered_color: Option<RGBAColor>,
 }
 
 impl TextRendererNode {
@@ -54,14 +61,22 @@ impl TextRendererNode {
             buffer: params.buffer,
             resolution: params.resolution,
             background_color,
-            was_rendered: false,
+            color: params.color,
+            rendered_color: None,
         }
     }
 
-    pub(crate) fn render(&mut self, renderer_ctx: &mut RenderCtx, target: &mut NodeTexture) {
-        if self.was_rendered {
+    pub(crate) fn render(
+        &mut self,
+        renderer_ctx: &mut RenderCtx,
+        target: &mut NodeTexture,
+        pts: Duration,
+    ) {
+        let color = self.color.value(pts);
+        if self.rendered_color == Some(color) {
             return;
         }
+        let RGBAColor(r, g, b, a) = color;
 
         let text_renderer = renderer_ctx.text_renderer_ctx;
         let font_system = &mut text_renderer.font_system.lock().unwrap();
@@ -101,7 +116,7 @@ impl TextRendererNode {
                         right: self.resolution.width as i32,
                         bottom: self.resolution.height as i32,
                     },
-                    default_color: Color::rgb(255, 255, 255),
+                    default_color: Color::rgba(r, g, b, a),
                 }],
                 cache,
             )
@@ -137,7 +152,7 @@ impl TextRendererNode {
         }
 
         renderer_ctx.wgpu_ctx.queue.submit(Some(encoder.finish()));
-        self.was_rendered = true;
+        self.rendered_color = Some(color);
     }
 }
 
@@ -152,9 +167,6 @@ pub(crate) struct TextParams {
 
 impl From<&TextComponent> for TextParams {
     fn from(text: &TextComponent) -> Self {
-        let RGBAColor(r, g, b, a) = text.color;
-        let color = glyphon::Color::rgba(r, g, b, a);
-
         let family = glyphon::FamilyOwned::Name(text.font_family.to_string());
 
         let style = match text.style {
@@ -187,7 +199,8 @@ impl From<&TextComponent> for TextParams {
 
         Self {
             attributes: glyphon::AttrsOwned {
-                color_opt: Some(color),
+                // color is defined when rendering
+                color_opt: None,
                 family_owned: family,
                 stretch: Default::default(),
                 style,
@@ -118,14 +118,14 @@ const sidebars: SidebarsConfig = {
           label: 'Outputs',
           collapsible: false,
           description: 'Elements that deliver generated media.',
-          items: ['api/outputs/rtp'],
+          items: ['api/outputs/rtp', 'api/outputs/mp4', 'api/outputs/rtmp', 'api/outputs/hls', 'api/outputs/whep'],
         },
         {
           type: 'category',
           label: 'Inputs',
           collapsible: false,
           description: 'Elements that deliver media from external sources.',
-          items: ['api/inputs/rtp', 'api/inputs/mp4'],
+          items: ['api/inputs/rtp', 'api/inputs/mp4', 'api/inputs/fragmented_mp4', 'api/inputs/rtmp', 'api/inputs/whip'],
         },
       ],
     },
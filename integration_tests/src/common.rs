@@ -1,9 +1,11 @@
-use anyhow::{Context, Result};
+use anyhow::{anyhow, Context, Result};
 use bytes::Bytes;
+use compositor_render::event_handler::{Event, EventValue};
+use crossbeam_channel::Receiver;
 use std::{
     fs,
     path::{Path, PathBuf},
-    time::Duration,
+    time::{Duration, Instant},
 };
 use webrtc_util::Unmarshal;
 
@@ -142,3 +144,19 @@ pub fn unmarshal_packets(data: &Bytes) -> Result<Vec<rtp::packet::Packet>> {
 
     Ok(packets)
 }
+
+/// Waits until an event of `kind` is emitted for the input. Other events are skipped.
+pub fn wait_for_input_event(events: &Receiver<Event>, kind: &str, input_id: &str) -> Result<()> {
+    let deadline = Instant::now() + Duration::from_secs(20);
+    loop {
+        let event = events
+            .recv_deadline(deadline)
+            .map_err(|_| anyhow!("Did not receive {kind} event for input {input_id}"))?;
+        let is_expected_input = event.properties.iter().any(|(key, value)| {
+            key == "input_id" && matches!(value, EventValue::String(value) if value == input_id)
+        });
+        if event.kind == kind && is_expected_input {
+            return Ok(());
+        }
+    }
+}
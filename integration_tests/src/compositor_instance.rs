@@ -79,6 +79,26 @@ impl CompositorInstance {
         Ok(())
     }
 
+    /// Sends a request with a raw binary body, e.g. fragmented MP4 segments.
+    pub fn send_bytes_request(&self, path: &str, body: Vec<u8>) -> Result<()> {
+        let resp = self
+            .http_client
+            .post(format!("http://127.0.0.1:{}/api/{}", self.api_port, path))
+            .timeout(Duration::from_secs(100))
+            .body(body)
+            .send()?;
+
+        if resp.status() >= StatusCode::BAD_REQUEST {
+            let status = resp.status();
+            let body_str = resp.text().unwrap();
+            return Err(anyhow::anyhow!(
+                "Request to {path} failed with status: {status}\nResponse: {body_str}",
+            ));
+        }
+
+        Ok(())
+    }
+
     pub fn get_port(&self) -> u16 {
         get_free_port()
     }
@@ -1,7 +1,15 @@
 mod aac;
 mod audio_mixing;
+mod fragmented_mp4;
+mod hls_output;
+mod mp4_output;
 mod muxed_video_audio;
 mod push_input_before_start;
 mod required_inputs;
+mod rtmp_input;
+mod rtmp_output;
 mod schedule_update;
 mod unregistering;
+mod video_codecs;
+mod whep_output;
+mod whip_input;
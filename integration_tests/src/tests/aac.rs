@@ -1,10 +1,11 @@
 use anyhow::Result;
+use compositor_render::event_handler;
 use serde_json::json;
 use std::time::Duration;
 
 use crate::{
-    compare_audio_dumps, input_dump_from_disk, AudioValidationConfig, CommunicationProtocol,
-    CompositorInstance, OutputReceiver, PacketSender,
+    compare_audio_dumps, input_dump_from_disk, wait_for_input_event, AudioValidationConfig,
+    CommunicationProtocol, CompositorInstance, OutputReceiver, PacketSender,
 };
 
 /// An AAC audio input stream.
@@ -87,3 +88,81 @@ pub fn aac() -> Result<()> {
 
     Ok(())
 }
+
+/// Encodes 2 seconds of AAC audio, then sends the output dump to an RTP input
+/// that decodes it with the AudioSpecificConfig of 48kHz stereo AAC-LC.
+#[test]
+pub fn aac_rtp_round_trip() -> Result<()> {
+    let instance = CompositorInstance::start();
+    let events = event_handler::subscribe();
+    let input_1_port = instance.get_port();
+    let input_2_port = instance.get_port();
+    let output_port = instance.get_port();
+
+    instance.send_request(
+        "input/input_1/register",
+        json!({
+            "type": "rtp_stream",
+            "transport_protocol": "tcp_server",
+            "port": input_1_port,
+            "audio": {
+                "decoder": "aac",
+                "audio_specific_config": "1210",
+                "rtp_mode": "high_bitrate",
+            }
+        }),
+    )?;
+    instance.send_request(
+        "output/output_1/register",
+        json!({
+            "type": "rtp_stream",
+            "transport_protocol": "tcp_server",
+            "port": output_port,
+            "audio": {
+                "initial": {
+                    "inputs": [
+                        {
+                            "input_id": "input_1",
+                        },
+                    ]
+                },
+                "encoder": {
+                    "type": "aac",
+                    "channels": "stereo",
+                }
+            },
+        }),
+    )?;
+    instance.send_request(
+        "output/output_1/unregister",
+        json!({
+            "schedule_time_ms": 2000,
+        }),
+    )?;
+
+    let output_receiver = OutputReceiver::start(output_port, CommunicationProtocol::Tcp)?;
+    let mut audio_1_sender = PacketSender::new(CommunicationProtocol::Tcp, input_1_port)?;
+    audio_1_sender.send(&input_dump_from_disk("big_buck_bunny_10s_audio_aac.rtp")?)?;
+    instance.send_request("start", json!({}))?;
+    let output_dump = output_receiver.wait_for_output()?;
+
+    instance.send_request(
+        "input/input_2/register",
+        json!({
+            "type": "rtp_stream",
+            "transport_protocol": "tcp_server",
+            "port": input_2_port,
+            "audio": {
+                "decoder": "aac",
+                "audio_specific_config": "1190",
+                "rtp_mode": "high_bitrate",
+            }
+        }),
+    )?;
+    let mut audio_2_sender = PacketSender::new(CommunicationProtocol::Tcp, input_2_port)?;
+    audio_2_sender.send(&output_dump)?;
+
+    wait_for_input_event(&events, "AUDIO_INPUT_DELIVERED", "input_2")?;
+
+    Ok(())
+}
@@ -0,0 +1,192 @@
+use std::{fs, path::Path, thread, time::Duration};
+
+use crate::{wait_for_input_event, CompositorInstance};
+use anyhow::{anyhow, Result};
+use compositor_render::event_handler;
+use ffmpeg_next as ffmpeg;
+use serde_json::json;
+
+/// Checks registration flow of fragmented MP4 input before the init segment is delivered.
+#[test]
+pub fn fragmented_mp4_registration() -> Result<()> {
+    let instance = CompositorInstance::start();
+
+    instance.send_request(
+        "input/input_1/register",
+        json!({
+            "type": "fragmented_mp4",
+        }),
+    )?;
+
+    instance
+        .send_request(
+            "input/input_1/register",
+            json!({
+                "type": "fragmented_mp4",
+            }),
+        )
+        .expect_err("Input with the same id is already registered");
+
+    instance
+        .send_request("input/input_1/fragmented_mp4/fragment", json!({}))
+        .expect_err("Fragments can not be sent before the init segment");
+
+    instance.send_request("input/input_1/unregister", json!({}))?;
+
+    instance
+        .send_request("input/input_1/fragmented_mp4/init", json!({}))
+        .expect_err("Input was unregistered");
+
+    Ok(())
+}
+
+/// Checks if video from a fragmented MP4 stream is decoded and delivered. MP4 file
+/// recorded with the MP4 output is remuxed to fragmented MP4 and sent as the init
+/// segment followed by `moof` + `mdat` fragments.
+#[test]
+pub fn fragmented_mp4_video() -> Result<()> {
+    const INPUT_ID: &str = "fragmented_mp4_input_1";
+
+    let instance = CompositorInstance::start();
+    let events = event_handler::subscribe();
+    let file_name = format!("live_compositor_fragmented_mp4_{}", rand::random::<u32>());
+    let source_path = std::env::temp_dir().join(format!("{file_name}.mp4"));
+    let fragmented_path = std::env::temp_dir().join(format!("{file_name}_fragmented.mp4"));
+
+    instance.send_request(
+        "output/output_1/register",
+        json!({
+            "type": "mp4",
+            "path": source_path,
+            "video": {
+                "resolution": {
+                    "width": 640,
+                    "height": 360,
+                },
+                "encoder": {
+                    "type": "ffmpeg_h264",
+                    "preset": "ultrafast"
+                },
+                "initial": {
+                    "root": {
+                        "type": "view",
+                        "background_color_rgba": "#FF0000FF",
+                    }
+                }
+            },
+        }),
+    )?;
+
+    instance.send_request("start", json!({}))?;
+    thread::sleep(Duration::from_secs(3));
+    instance.send_request("output/output_1/unregister", json!({}))?;
+    // Give the muxer thread time to write the trailer.
+    thread::sleep(Duration::from_secs(1));
+
+    remux_to_fragmented_mp4(&source_path, &fragmented_path)?;
+    let (init_segment, fragments) = split_fragmented_mp4(&fs::read(&fragmented_path)?)?;
+    if fragments.len() < 2 {
+        return Err(anyhow!(
+            "Expected at least 2 fragments, found {}",
+            fragments.len()
+        ));
+    }
+
+    instance.send_request(
+        &format!("input/{INPUT_ID}/register"),
+        json!({
+            "type": "fragmented_mp4",
+        }),
+    )?;
+    instance.send_bytes_request(
+        &format!("input/{INPUT_ID}/fragmented_mp4/init"),
+        init_segment,
+    )?;
+    for fragment in fragments {
+        instance.send_bytes_request(
+            &format!("input/{INPUT_ID}/fragmented_mp4/fragment"),
+            fragment,
+        )?;
+    }
+
+    wait_for_input_event(&events, "VIDEO_INPUT_DELIVERED", INPUT_ID)?;
+
+    instance.send_request(&format!("input/{INPUT_ID}/fragmented_mp4/eos"), json!({}))?;
+
+    wait_for_input_event(&events, "VIDEO_INPUT_EOS", INPUT_ID)?;
+
+    fs::remove_file(source_path)?;
+    fs::remove_file(fragmented_path)?;
+    Ok(())
+}
+
+fn remux_to_fragmented_mp4(source_path: &Path, fragmented_path: &Path) -> Result<()> {
+    let mut input_ctx = ffmpeg::format::input(&source_path)?;
+    let input_stream = input_ctx
+        .streams()
+        .best(ffmpeg::media::Type::Video)
+        .ok_or(anyhow!("No video track in MP4 file"))?;
+    let input_stream_index = input_stream.index();
+    let input_time_base = input_stream.time_base();
+
+    let mut muxer_options = ffmpeg::Dictionary::new();
+    // Sample offsets have to be relative to the `moof` box, because each fragment
+    // is read on its own. Fragments are cut every 500ms, not only on keyframes.
+    muxer_options.set("movflags", "empty_moov+default_base_moof");
+    muxer_options.set("frag_duration", "500000");
+    let mut output_ctx = ffmpeg::format::output_as_with(&fragmented_path, "mp4", muxer_options)?;
+
+    let mut output_stream =
+        output_ctx.add_stream(ffmpeg::encoder::find(ffmpeg::codec::Id::None))?;
+    output_stream.set_parameters(input_stream.parameters());
+    unsafe {
+        (*output_stream.parameters().as_mut_ptr()).codec_tag = 0;
+    }
+    output_ctx.write_header()?;
+    let output_time_base = output_ctx.stream(0).unwrap().time_base();
+
+    for (stream, mut packet) in input_ctx.packets() {
+        if stream.index() != input_stream_index {
+            continue;
+        }
+        packet.rescale_ts(input_time_base, output_time_base);
+        packet.set_stream(0);
+        packet.set_position(-1);
+        packet.write_interleaved(&mut output_ctx)?;
+    }
+    output_ctx.write_trailer()?;
+
+    Ok(())
+}
+
+/// Splits fragmented MP4 file into the init segment (`ftyp` and `moov` boxes) and
+/// fragments (`moof` box followed by `mdat`).
+fn split_fragmented_mp4(data: &[u8]) -> Result<(Vec<u8>, Vec<Vec<u8>>)> {
+    let mut init_segment = Vec::new();
+    let mut fragments: Vec<Vec<u8>> = Vec::new();
+
+    let mut offset = 0;
+    while offset < data.len() {
+        let header = data
+            .get(offset..offset + 8)
+            .ok_or(anyhow!("Truncated MP4 box header at {offset}"))?;
+        let size = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize;
+        let mp4_box = data
+            .get(offset..offset + size)
+            .filter(|_| size >= 8)
+            .ok_or(anyhow!("Invalid MP4 box size {size} at {offset}"))?;
+
+        match &header[4..8] {
+            b"moof" => fragments.push(mp4_box.to_vec()),
+            // Random access index written at the end of the file is not a part of any fragment.
+            b"mfra" => {}
+            _ => match fragments.last_mut() {
+                Some(fragment) => fragment.extend_from_slice(mp4_box),
+                None => init_segment.extend_from_slice(mp4_box),
+            },
+        }
+        offset += size;
+    }
+
+    Ok((init_segment, fragments))
+}
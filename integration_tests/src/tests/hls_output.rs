@@ -0,0 +1,69 @@
+use std::{fs, thread, time::Duration};
+
+use crate::CompositorInstance;
+use anyhow::{anyhow, Result};
+use serde_json::json;
+
+/// Checks if HLS output writes a playlist with keyframe-aligned segments.
+#[test]
+pub fn hls_output() -> Result<()> {
+    let instance = CompositorInstance::start();
+    let output_dir =
+        std::env::temp_dir().join(format!("live_compositor_hls_{}", rand::random::<u32>()));
+
+    instance.send_request(
+        "output/output_1/register",
+        json!({
+            "type": "hls",
+            "path": output_dir,
+            "segment_duration_ms": 1000,
+            "playlist_size": 0,
+            "video": {
+                "resolution": {
+                    "width": 640,
+                    "height": 360,
+                },
+                "encoder": {
+                    "type": "ffmpeg_h264",
+                    "preset": "ultrafast"
+                },
+                "initial": {
+                    "root": {
+                        "type": "view",
+                        "background_color_rgba": "#FF0000FF",
+                    }
+                }
+            },
+        }),
+    )?;
+
+    instance.send_request("start", json!({}))?;
+    thread::sleep(Duration::from_secs(4));
+    instance.send_request("output/output_1/unregister", json!({}))?;
+    // Give the writer thread time to finalize the playlist.
+    thread::sleep(Duration::from_secs(1));
+
+    let playlist = fs::read_to_string(output_dir.join("index.m3u8"))?;
+    let segments: Vec<&str> = playlist
+        .lines()
+        .filter(|line| !line.starts_with('#') && !line.is_empty())
+        .collect();
+    if segments.len() < 2 {
+        return Err(anyhow!(
+            "Expected at least 2 segments, playlist:\n{playlist}"
+        ));
+    }
+    for segment in segments {
+        if !output_dir.join(segment).exists() {
+            return Err(anyhow!(
+                "Segment {segment} listed in the playlist does not exist"
+            ));
+        }
+    }
+    if !playlist.contains("#EXT-X-ENDLIST") {
+        return Err(anyhow!("Playlist was not finalized:\n{playlist}"));
+    }
+
+    fs::remove_dir_all(output_dir)?;
+    Ok(())
+}
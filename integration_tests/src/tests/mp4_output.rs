@@ -0,0 +1,100 @@
+use std::{fs, thread, time::Duration};
+
+use crate::CompositorInstance;
+use anyhow::{anyhow, Result};
+use ffmpeg_next as ffmpeg;
+use serde_json::json;
+
+/// Checks if MP4 output writes a file with H264 video and Opus audio tracks that is
+/// finalized when the output is unregistered.
+#[test]
+pub fn mp4_output() -> Result<()> {
+    let instance = CompositorInstance::start();
+    let output_path =
+        std::env::temp_dir().join(format!("live_compositor_mp4_{}.mp4", rand::random::<u32>()));
+
+    instance.send_request(
+        "output/output_1/register",
+        json!({
+            "type": "mp4",
+            "path": output_path,
+            "video": {
+                "resolution": {
+                    "width": 640,
+                    "height": 360,
+                },
+                "encoder": {
+                    "type": "ffmpeg_h264",
+                    "preset": "ultrafast"
+                },
+                "initial": {
+                    "root": {
+                        "type": "view",
+                        "background_color_rgba": "#FF0000FF",
+                    }
+                }
+            },
+            "audio": {
+                "encoder": {
+                    "type": "opus",
+                    "channels": "stereo"
+                },
+                "initial": {
+                    "inputs": []
+                }
+            }
+        }),
+    )?;
+
+    instance.send_request("start", json!({}))?;
+    thread::sleep(Duration::from_secs(3));
+    instance.send_request("output/output_1/unregister", json!({}))?;
+    // Give the muxer thread time to write the trailer.
+    thread::sleep(Duration::from_secs(1));
+
+    let mut input_ctx = ffmpeg::format::input(&output_path)?;
+    let video_stream_index = input_ctx
+        .streams()
+        .best(ffmpeg::media::Type::Video)
+        .ok_or(anyhow!("No video track in MP4 file"))?
+        .index();
+    let audio_stream_index = input_ctx
+        .streams()
+        .best(ffmpeg::media::Type::Audio)
+        .ok_or(anyhow!("No audio track in MP4 file"))?
+        .index();
+
+    for (index, expected_codec) in [
+        (video_stream_index, ffmpeg::codec::Id::H264),
+        (audio_stream_index, ffmpeg::codec::Id::OPUS),
+    ] {
+        let codec = input_ctx.stream(index).unwrap().parameters().id();
+        if codec != expected_codec {
+            return Err(anyhow!(
+                "Expected {expected_codec:?} track, found {codec:?}"
+            ));
+        }
+    }
+
+    let duration = Duration::from_micros(input_ctx.duration().max(0) as u64);
+    if duration < Duration::from_secs(2) {
+        return Err(anyhow!("MP4 file is too short: {duration:?}"));
+    }
+
+    let (mut video_packets, mut audio_packets) = (0, 0);
+    for (stream, _packet) in input_ctx.packets() {
+        if stream.index() == video_stream_index {
+            video_packets += 1;
+        } else if stream.index() == audio_stream_index {
+            audio_packets += 1;
+        }
+    }
+    if video_packets == 0 || audio_packets == 0 {
+        return Err(anyhow!(
+            "Expected video and audio packets, found {video_packets} video and {audio_packets} audio packets"
+        ));
+    }
+
+    fs::remove_file(output_path)?;
+    Ok(())
+}
@@ -0,0 +1,57 @@
+use crate::{wait_for_input_event, CompositorInstance};
+use anyhow::Result;
+use compositor_render::event_handler;
+use serde_json::json;
+
+/// Checks if RTMP input accepts a publisher and emits EOS after it disconnects.
+///
+/// The stream is published by the RTMP output of the same compositor instance.
+#[test]
+pub fn rtmp_input() -> Result<()> {
+    let instance = CompositorInstance::start();
+    let rtmp_port = instance.get_port();
+    let events = event_handler::subscribe();
+
+    instance.send_request(
+        "input/rtmp_input_1/register",
+        json!({
+            "type": "rtmp",
+            "port": rtmp_port,
+        }),
+    )?;
+
+    instance.send_request(
+        "output/rtmp_output_1/register",
+        json!({
+            "type": "rtmp",
+            "url": format!("rtmp://127.0.0.1:{rtmp_port}/live/stream"),
+            "video": {
+                "resolution": {
+                    "width": 640,
+                    "height": 360,
+                },
+                "encoder": {
+                    "type": "ffmpeg_h264",
+                    "preset": "ultrafast"
+                },
+                "initial": {
+                    "root": {
+                        "type": "view",
+                        "background_color_rgba": "#FF0000FF",
+                    }
+                }
+            },
+        }),
+    )?;
+
+    instance.send_request("start", json!({}))?;
+
+    wait_for_input_event(&events, "VIDEO_INPUT_DELIVERED", "rtmp_input_1")?;
+
+    // Publisher disconnects when the output is unregistered.
+    instance.send_request("output/rtmp_output_1/unregister", json!({}))?;
+
+    wait_for_input_event(&events, "VIDEO_INPUT_EOS", "rtmp_input_1")?;
+
+    Ok(())
+}
@@ -0,0 +1,126 @@
+use std::{sync::mpsc, thread, time::Duration};
+
+use crate::CompositorInstance;
+use anyhow::{anyhow, Result};
+use ffmpeg_next as ffmpeg;
+use serde_json::{json, Value};
+
+const EXPECTED_VIDEO_PACKETS: usize = 10;
+
+/// Checks if RTMP output connects to the server and pushes video.
+///
+/// Local RTMP server is emulated with ffmpeg listening on the RTMP port.
+#[test]
+pub fn rtmp_output() -> Result<()> {
+    let instance = CompositorInstance::start();
+    let rtmp_port = instance.get_port();
+    let url = format!("rtmp://127.0.0.1:{rtmp_port}/live/stream");
+
+    let packets_receiver = start_rtmp_server(url.clone(), 1);
+
+    // Give the server time to start listening.
+    thread::sleep(Duration::from_millis(500));
+
+    instance.send_request("output/output_1/register", register_request(&url))?;
+    instance.send_request("start", json!({}))?;
+
+    for _ in 0..EXPECTED_VIDEO_PACKETS {
+        packets_receiver
+            .recv_timeout(Duration::from_secs(10))
+            .map_err(|_| anyhow!("RTMP server did not receive video packets"))?;
+    }
+
+    instance.send_request("output/output_1/unregister", json!({}))?;
+
+    Ok(())
+}
+
+/// Checks if RTMP output reconnects and resumes the stream after the server
+/// closes the connection.
+#[test]
+pub fn rtmp_output_reconnect() -> Result<()> {
+    let instance = CompositorInstance::start();
+    let rtmp_port = instance.get_port();
+    let url = format!("rtmp://127.0.0.1:{rtmp_port}/live/stream");
+
+    let packets_receiver = start_rtmp_server(url.clone(), 2);
+
+    // Give the server time to start listening.
+    thread::sleep(Duration::from_millis(500));
+
+    instance.send_request("output/output_1/register", register_request(&url))?;
+    instance.send_request("start", json!({}))?;
+
+    for expected_session in 0..2 {
+        for _ in 0..EXPECTED_VIDEO_PACKETS {
+            let session = packets_receiver
+                .recv_timeout(Duration::from_secs(20))
+                .map_err(|_| {
+                    anyhow!(
+                        "RTMP server did not receive video packets in session {expected_session}"
+                    )
+                })?;
+            assert_eq!(session, expected_session);
+        }
+    }
+
+    instance.send_request("output/output_1/unregister", json!({}))?;
+
+    Ok(())
+}
+
+fn register_request(url: &str) -> Value {
+    json!({
+        "type": "rtmp",
+        "url": url,
+        "video": {
+            "resolution": {
+                "width": 640,
+                "height": 360,
+            },
+            "encoder": {
+                "type": "ffmpeg_h264",
+                "preset": "ultrafast"
+            },
+            "initial": {
+                "root": {
+                    "type": "view",
+                    "background_color_rgba": "#FF0000FF",
+                }
+            }
+        },
+    })
+}
+
+/// Emulates an RTMP server with ffmpeg listening on the RTMP port. The server accepts
+/// `sessions` connections one after another, each of them is closed after
+/// `EXPECTED_VIDEO_PACKETS` video packets. Index of the session is sent for every
+/// received video packet.
+fn start_rtmp_server(url: String, sessions: usize) -> mpsc::Receiver<usize> {
+    let (packets_sender, packets_receiver) = mpsc::channel();
+    thread::spawn(move || -> Result<()> {
+        for session in 0..sessions {
+            let mut options = ffmpeg::Dictionary::new();
+            options.set("listen", "1");
+            let mut input_ctx = ffmpeg::format::input_with_dictionary(&url, options)?;
+
+            let video_stream_index = input_ctx
+                .streams()
+                .best(ffmpeg::media::Type::Video)
+                .ok_or(anyhow!("No video stream in RTMP input"))?
+                .index();
+            let video_packets = input_ctx
+                .packets()
+                .filter(|(stream, _packet)| stream.index() == video_stream_index)
+                .take(EXPECTED_VIDEO_PACKETS);
+            for _ in video_packets {
+                if packets_sender.send(session).is_err() {
+                    return Ok(());
+                }
+            }
+            // Dropping the input context closes the connection.
+        }
+        Ok(())
+    });
+    packets_receiver
+}
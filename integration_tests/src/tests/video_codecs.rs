@@ -0,0 +1,85 @@
+use crate::{
+    wait_for_input_event, CommunicationProtocol, CompositorInstance, OutputReceiver, PacketSender,
+};
+use anyhow::Result;
+use compositor_render::event_handler;
+use serde_json::json;
+
+/// Checks if VP8 output stream can be decoded by VP8 RTP input.
+#[test]
+pub fn vp8_rtp_round_trip() -> Result<()> {
+    rtp_round_trip("ffmpeg_vp8")
+}
+
+/// Checks if VP9 output stream can be decoded by VP9 RTP input.
+#[test]
+pub fn vp9_rtp_round_trip() -> Result<()> {
+    rtp_round_trip("ffmpeg_vp9")
+}
+
+/// Checks if AV1 output stream can be decoded by AV1 RTP input.
+#[test]
+pub fn av1_rtp_round_trip() -> Result<()> {
+    rtp_round_trip("ffmpeg_av1")
+}
+
+/// Encodes 2 seconds of video with the codec, then sends the output dump
+/// to an RTP input that decodes it with the same codec.
+fn rtp_round_trip(codec: &str) -> Result<()> {
+    let instance = CompositorInstance::start();
+    let events = event_handler::subscribe();
+    let input_port = instance.get_port();
+    let output_port = instance.get_port();
+
+    instance.send_request(
+        "output/output_1/register",
+        json!({
+            "type": "rtp_stream",
+            "transport_protocol": "tcp_server",
+            "port": output_port,
+            "video": {
+                "resolution": {
+                    "width": 640,
+                    "height": 360,
+                },
+                "encoder": {
+                    "type": codec,
+                },
+                "initial": {
+                    "root": {
+                        "type": "view",
+                        "background_color_rgba": "#FF0000FF",
+                    }
+                }
+            },
+        }),
+    )?;
+    instance.send_request(
+        "output/output_1/unregister",
+        json!({
+            "schedule_time_ms": 2000,
+        }),
+    )?;
+
+    let output_receiver = OutputReceiver::start(output_port, CommunicationProtocol::Tcp)?;
+    instance.send_request("start", json!({}))?;
+    let output_dump = output_receiver.wait_for_output()?;
+
+    instance.send_request(
+        "input/input_1/register",
+        json!({
+            "type": "rtp_stream",
+            "transport_protocol": "tcp_server",
+            "port": input_port,
+            "video": {
+                "decoder": codec
+            },
+        }),
+    )?;
+    let mut input_sender = PacketSender::new(CommunicationProtocol::Tcp, input_port)?;
+    input_sender.send(&output_dump)?;
+
+    wait_for_input_event(&events, "VIDEO_INPUT_DELIVERED", "input_1")?;
+
+    Ok(())
+}
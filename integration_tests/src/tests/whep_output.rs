@@ -0,0 +1,142 @@
+use std::{sync::Arc, time::Duration};
+
+use crate::CompositorInstance;
+use anyhow::{anyhow, Result};
+use reqwest::{header, StatusCode};
+use serde_json::json;
+use webrtc::{
+    api::{
+        interceptor_registry::register_default_interceptors, media_engine::MediaEngine, APIBuilder,
+    },
+    interceptor::registry::Registry,
+    peer_connection::{
+        configuration::RTCConfiguration, sdp::session_description::RTCSessionDescription,
+        RTCPeerConnection,
+    },
+    rtp_transceiver::{
+        rtp_codec::RTPCodecType, rtp_transceiver_direction::RTCRtpTransceiverDirection,
+        RTCRtpTransceiverInit,
+    },
+};
+
+/// Checks if WHEP output negotiates a session with an in-process WebRTC client,
+/// sends video to it and ends the session after it is deleted.
+#[test]
+pub fn whep_output() -> Result<()> {
+    let instance = CompositorInstance::start();
+    let runtime = tokio::runtime::Runtime::new()?;
+    let api_url = format!("http://127.0.0.1:{}", instance.api_port);
+
+    instance.send_request(
+        "output/whep_output_1/register",
+        json!({
+            "type": "whep",
+            "video": {
+                "resolution": {
+                    "width": 640,
+                    "height": 360,
+                },
+                "encoder": {
+                    "type": "ffmpeg_h264",
+                    "preset": "ultrafast"
+                },
+                "initial": {
+                    "root": {
+                        "type": "view",
+                        "background_color_rgba": "#FF0000FF",
+                    }
+                }
+            },
+        }),
+    )?;
+    instance.send_request("start", json!({}))?;
+
+    let peer_connection = runtime.block_on(create_client())?;
+    let (packet_sender, packet_receiver) = crossbeam_channel::bounded(1);
+    peer_connection.on_track(Box::new(move |track, _, _| {
+        let packet_sender = packet_sender.clone();
+        Box::pin(async move {
+            if let Ok((packet, _)) = track.read_rtp().await {
+                let _ = packet_sender.try_send(packet);
+            }
+        })
+    }));
+    let offer = runtime.block_on(create_offer(&peer_connection))?;
+
+    let response = instance
+        .http_client
+        .post(format!("{api_url}/api/output/whep_output_1/whep"))
+        .header("Content-Type", "application/sdp")
+        .body(offer)
+        .send()?;
+    if response.status() != StatusCode::CREATED {
+        return Err(anyhow!("WHEP offer failed: {}", response.text()?));
+    }
+    let session_url = response
+        .headers()
+        .get(header::LOCATION)
+        .and_then(|location| location.to_str().ok())
+        .map(|location| format!("{api_url}{location}"))
+        .ok_or(anyhow!("Missing Location header"))?;
+    let answer = response.text()?;
+    runtime
+        .block_on(peer_connection.set_remote_description(RTCSessionDescription::answer(answer)?))?;
+
+    let packet = packet_receiver
+        .recv_timeout(Duration::from_secs(10))
+        .map_err(|_| anyhow!("No RTP packets were received"))?;
+    if packet.payload.is_empty() {
+        return Err(anyhow!("Received RTP packet with empty payload"));
+    }
+
+    let response = instance.http_client.delete(&session_url).send()?;
+    if response.status() != StatusCode::OK {
+        return Err(anyhow!("WHEP session delete failed: {}", response.text()?));
+    }
+    let response = instance.http_client.delete(&session_url).send()?;
+    if response.status() != StatusCode::NOT_FOUND {
+        return Err(anyhow!("Deleted WHEP session still exists"));
+    }
+
+    instance.send_request("output/whep_output_1/unregister", json!({}))?;
+    runtime.block_on(peer_connection.close())?;
+
+    Ok(())
+}
+
+async fn create_client() -> Result<Arc<RTCPeerConnection>> {
+    let mut media_engine = MediaEngine::default();
+    media_engine.register_default_codecs()?;
+    let registry = register_default_interceptors(Registry::new(), &mut media_engine)?;
+    let api = APIBuilder::new()
+        .with_media_engine(media_engine)
+        .with_interceptor_registry(registry)
+        .build();
+
+    let peer_connection = Arc::new(api.new_peer_connection(RTCConfiguration::default()).await?);
+    peer_connection
+        .add_transceiver_from_kind(
+            RTPCodecType::Video,
+            Some(RTCRtpTransceiverInit {
+                direction: RTCRtpTransceiverDirection::Recvonly,
+                send_encodings: vec![],
+            }),
+        )
+        .await?;
+
+    Ok(peer_connection)
+}
+
+/// WHEP does not use trickle ICE here, so the offer is sent after gathering is complete.
+async fn create_offer(peer_connection: &RTCPeerConnection) -> Result<String> {
+    let offer = peer_connection.create_offer(None).await?;
+    let mut gathering_complete = peer_connection.gathering_complete_promise().await;
+    peer_connection.set_local_description(offer).await?;
+    let _ = gathering_complete.recv().await;
+
+    peer_connection
+        .local_description()
+        .await
+        .map(|offer| offer.sdp)
+        .ok_or(anyhow!("Missing local description"))
+}
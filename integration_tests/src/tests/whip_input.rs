@@ -0,0 +1,133 @@
+use std::{sync::Arc, time::Duration};
+
+use crate::{input_dump_from_disk, unmarshal_packets, wait_for_input_event, CompositorInstance};
+use anyhow::{anyhow, Result};
+use compositor_render::event_handler;
+use reqwest::StatusCode;
+use serde_json::json;
+use webrtc::{
+    api::{
+        interceptor_registry::register_default_interceptors,
+        media_engine::{MediaEngine, MIME_TYPE_H264},
+        APIBuilder,
+    },
+    interceptor::registry::Registry,
+    peer_connection::{
+        configuration::RTCConfiguration, peer_connection_state::RTCPeerConnectionState,
+        sdp::session_description::RTCSessionDescription, RTCPeerConnection,
+    },
+    rtp_transceiver::rtp_codec::RTCRtpCodecCapability,
+    track::track_local::{track_local_static_rtp::TrackLocalStaticRTP, TrackLocalWriter},
+};
+
+/// Checks if WHIP input negotiates a session with an in-process WebRTC client,
+/// receives video and ends the stream after the session is deleted.
+#[test]
+pub fn whip_input() -> Result<()> {
+    const VIDEO_PACKETS: usize = 300;
+
+    let instance = CompositorInstance::start();
+    let events = event_handler::subscribe();
+    let runtime = tokio::runtime::Runtime::new()?;
+    let whip_url = format!(
+        "http://127.0.0.1:{}/api/input/whip_input_1/whip",
+        instance.api_port
+    );
+
+    instance.send_request(
+        "input/whip_input_1/register",
+        json!({
+            "type": "whip",
+            "video": {
+                "decoder": "ffmpeg_h264"
+            },
+        }),
+    )?;
+    instance.send_request("start", json!({}))?;
+
+    let (peer_connection, track) = runtime.block_on(create_client())?;
+    let (connected_sender, connected_receiver) = crossbeam_channel::bounded(1);
+    peer_connection.on_peer_connection_state_change(Box::new(move |state| {
+        if state == RTCPeerConnectionState::Connected {
+            let _ = connected_sender.try_send(());
+        }
+        Box::pin(async {})
+    }));
+    let offer = runtime.block_on(create_offer(&peer_connection))?;
+
+    let response = instance
+        .http_client
+        .post(&whip_url)
+        .header("Content-Type", "application/sdp")
+        .body(offer)
+        .send()?;
+    if response.status() != StatusCode::CREATED {
+        return Err(anyhow!("WHIP offer failed: {}", response.text()?));
+    }
+    let answer = response.text()?;
+    runtime
+        .block_on(peer_connection.set_remote_description(RTCSessionDescription::answer(answer)?))?;
+    connected_receiver
+        .recv_timeout(Duration::from_secs(10))
+        .map_err(|_| anyhow!("WebRTC connection was not established"))?;
+
+    let packets = unmarshal_packets(&input_dump_from_disk("8_colors_input_video.rtp")?)?;
+    runtime.block_on(async {
+        for packet in packets.iter().take(VIDEO_PACKETS) {
+            track.write_rtp(packet).await?;
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        Ok::<_, anyhow::Error>(())
+    })?;
+
+    wait_for_input_event(&events, "VIDEO_INPUT_DELIVERED", "whip_input_1")?;
+
+    let response = instance.http_client.delete(&whip_url).send()?;
+    if response.status() != StatusCode::OK {
+        return Err(anyhow!("WHIP session delete failed: {}", response.text()?));
+    }
+
+    wait_for_input_event(&events, "VIDEO_INPUT_EOS", "whip_input_1")?;
+
+    runtime.block_on(peer_connection.close())?;
+
+    Ok(())
+}
+
+async fn create_client() -> Result<(Arc<RTCPeerConnection>, Arc<TrackLocalStaticRTP>)> {
+    let mut media_engine = MediaEngine::default();
+    media_engine.register_default_codecs()?;
+    let registry = register_default_interceptors(Registry::new(), &mut media_engine)?;
+    let api = APIBuilder::new()
+        .with_media_engine(media_engine)
+        .with_interceptor_registry(registry)
+        .build();
+
+    let peer_connection = Arc::new(api.new_peer_connection(RTCConfiguration::default()).await?);
+    let track = Arc::new(TrackLocalStaticRTP::new(
+        RTCRtpCodecCapability {
+            mime_type: MIME_TYPE_H264.to_owned(),
+            clock_rate: 90000,
+            ..Default::default()
+        },
+        "video".to_owned(),
+        "whip_test".to_owned(),
+    ));
+    peer_connection.add_track(track.clone()).await?;
+
+    Ok((peer_connection, track))
+}
+
+/// WHIP does not use trickle ICE here, so the offer is sent after gathering is complete.
+async fn create_offer(peer_connection: &RTCPeerConnection) -> Result<String> {
+    let offer = peer_connection.create_offer(None).await?;
+    let mut gathering_complete = peer_connection.gathering_complete_promise().await;
+    peer_connection.set_local_description(offer).await?;
+    let _ = gathering_complete.recv().await;
+
+    peer_connection
+        .local_description()
+        .await
+        .map(|offer| offer.sdp)
+        .ok_or(anyhow!("Missing local description"))
+}
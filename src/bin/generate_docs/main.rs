@@ -1,8 +1,9 @@
 use docs_config::DocsConfig;
 use document::generate;
 use live_compositor::types::{
-    Image, ImageSpec, InputStream, Mp4, Rescaler, RtpInputStream, RtpOutputStream, Shader,
-    ShaderSpec, Text, Tiles, View, WebRendererSpec, WebView,
+    FragmentedMp4, HlsOutput, Image, ImageSpec, InputStream, Mp4, Mp4Output, Rescaler,
+    RtmpInputStream, RtmpOutput, RtpInputStream, RtpOutputStream, Shader, ShaderSpec, Text, Tiles,
+    View, WebRendererSpec, WebView, WhepOutput, WhipInput,
 };
 use markdown::overrides;
 use std::{fs, path::PathBuf};
@@ -34,6 +35,9 @@ fn main() {
         generate::<WebRendererSpec>("WebRenderer", &config),
         generate::<RtpInputStream>("RtpInputStream", &config),
         generate::<Mp4>("Mp4", &config),
+        generate::<FragmentedMp4>("FragmentedMp4", &config),
+        generate::<RtmpInputStream>("RtmpInputStream", &config),
+        generate::<WhipInput>("WhipInput", &config),
     ];
 
     let component_pages = [
@@ -47,7 +51,13 @@ fn main() {
         generate::<Rescaler>("Rescaler", &config),
     ];
 
-    let output_pages = [generate::<RtpOutputStream>("OutputStream", &config)];
+    let output_pages = [
+        generate::<RtpOutputStream>("OutputStream", &config),
+        generate::<Mp4Output>("Mp4Output", &config),
+        generate::<RtmpOutput>("RtmpOutput", &config),
+        generate::<HlsOutput>("HlsOutput", &config),
+        generate::<WhepOutput>("WhepOutput", &config),
+    ];
 
     for page in renderer_pages {
         fs::write(
@@ -17,6 +17,7 @@ pub struct Config {
     pub download_root: PathBuf,
     pub queue_options: QueueOptions,
     pub output_sample_rate: u32,
+    pub audio_levels_interval: Option<Duration>,
     pub required_wgpu_features: WgpuFeatures,
 }
 
@@ -88,6 +89,16 @@ fn try_read_config() -> Result<Config, String> {
         Err(_) => DEFAULT_OUTPUT_SAMPLE_RATE,
     };
 
+    let audio_levels_interval = match env::var("LIVE_COMPOSITOR_AUDIO_LEVELS_INTERVAL_MS") {
+        Ok(interval_ms) => {
+            let interval_ms: u64 = interval_ms
+                .parse()
+                .map_err(|_| "LIVE_COMPOSITOR_AUDIO_LEVELS_INTERVAL_MS has to be a valid number")?;
+            Some(Duration::from_millis(interval_ms))
+        }
+        Err(_) => None,
+    };
+
     let force_gpu = match env::var("LIVE_COMPOSITOR_FORCE_GPU") {
         Ok(enable) => bool_env_from_str(&enable).unwrap_or(false),
         Err(_) => false,
@@ -193,6 +204,7 @@ fn try_read_config() -> Result<Config, String> {
         },
         download_root,
         output_sample_rate,
+        audio_levels_interval,
         required_wgpu_features,
     };
     Ok(config)
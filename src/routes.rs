@@ -1,9 +1,12 @@
 use axum::{
     async_trait,
-    extract::{rejection::JsonRejection, ws::WebSocketUpgrade, FromRequest, Request, State},
+    extract::{
+        rejection::JsonRejection, ws::WebSocketUpgrade, DefaultBodyLimit, FromRequest, Request,
+        State,
+    },
     http::StatusCode,
     response::IntoResponse,
-    routing::{get, post},
+    routing::{delete, get, post},
     Router,
 };
 use compositor_pipeline::Pipeline;
@@ -16,9 +19,12 @@ use crate::{
 
 use self::{update_output::handle_output_update, ws::handle_ws_upgrade};
 
+mod fragmented_mp4;
 mod register_request;
 mod unregister_request;
 mod update_output;
+mod whep;
+mod whip;
 mod ws;
 
 #[allow(unused_imports)]
@@ -29,12 +35,28 @@ pub use unregister_request::{UnregisterInput, UnregisterOutput};
 pub fn routes(state: ApiState) -> Router {
     let inputs = Router::new()
         .route("/:id/register", post(register_request::handle_input))
-        .route("/:id/unregister", post(unregister_request::handle_input));
+        .route("/:id/unregister", post(unregister_request::handle_input))
+        // Segments can be larger than the default body limit.
+        .route(
+            "/:id/fragmented_mp4/init",
+            post(fragmented_mp4::handle_init_segment).layer(DefaultBodyLimit::disable()),
+        )
+        .route(
+            "/:id/fragmented_mp4/fragment",
+            post(fragmented_mp4::handle_fragment).layer(DefaultBodyLimit::disable()),
+        )
+        .route("/:id/fragmented_mp4/eos", post(fragmented_mp4::handle_eos))
+        .route(
+            "/:id/whip",
+            post(whip::handle_offer).delete(whip::handle_terminate),
+        );
 
     let outputs = Router::new()
         .route("/:id/register", post(register_request::handle_output))
         .route("/:id/unregister", post(unregister_request::handle_output))
-        .route("/:id/update", post(handle_output_update));
+        .route("/:id/update", post(handle_output_update))
+        .route("/:id/whep", post(whep::handle_offer))
+        .route("/:id/whep/:session_id", delete(whep::handle_terminate));
 
     let image = Router::new()
         .route("/:id/register", post(register_request::handle_image))
@@ -0,0 +1,141 @@
+use axum::{
+    body::Bytes,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use compositor_pipeline::pipeline::input::{
+    mp4::{Mp4Error, Mp4FragmentSender},
+    Input,
+};
+use compositor_render::error::ErrorStack;
+
+use crate::{
+    error::ApiError,
+    state::{ApiState, Pipeline, Response},
+    types::{FragmentedMp4, InputId},
+};
+
+/// Fragmented MP4 input is registered in the pipeline only after the init segment
+/// is delivered, because tracks and decoder configuration are read from it.
+pub(super) fn register_pending_input(
+    api: &ApiState,
+    input_id: compositor_render::InputId,
+    request: FragmentedMp4,
+) -> Result<(), ApiError> {
+    let mut pending_inputs = api.pending_fragmented_mp4_inputs.lock().unwrap();
+    let is_registered = api.pipeline().inputs().any(|(id, _)| *id == input_id);
+    if is_registered || pending_inputs.contains_key(&input_id) {
+        return Err(
+            compositor_pipeline::error::RegisterInputError::AlreadyRegistered(input_id).into(),
+        );
+    }
+    pending_inputs.insert(input_id, request);
+    Ok(())
+}
+
+pub(super) async fn handle_init_segment(
+    State(api): State<ApiState>,
+    Path(input_id): Path<InputId>,
+    init_segment: Bytes,
+) -> Result<Response, ApiError> {
+    let input_id: compositor_render::InputId = input_id.into();
+    let request = api
+        .pending_fragmented_mp4_inputs
+        .lock()
+        .unwrap()
+        .remove(&input_id)
+        .ok_or_else(|| {
+            ApiError::new(
+                "INPUT_STREAM_NOT_FOUND",
+                format!("Fragmented MP4 input \"{input_id}\" is not registered or already received the init segment."),
+                StatusCode::NOT_FOUND,
+            )
+        })?;
+
+    tokio::task::spawn_blocking(move || {
+        let register = || -> Result<(), ApiError> {
+            let options = (request.clone(), init_segment).try_into()?;
+            Pipeline::register_input(&api.pipeline, input_id.clone(), options)?;
+            Ok(())
+        };
+        if let Err(err) = register() {
+            // Input stays pending, so the init segment can be sent again.
+            api.pending_fragmented_mp4_inputs
+                .lock()
+                .unwrap()
+                .insert(input_id, request);
+            return Err(err);
+        }
+        Ok(Response::Ok {})
+    })
+    .await
+    // `unwrap()` panics only when the task panicked or `response.abort()` was called
+    .unwrap()
+}
+
+pub(super) async fn handle_fragment(
+    State(api): State<ApiState>,
+    Path(input_id): Path<InputId>,
+    fragment: Bytes,
+) -> Result<Response, ApiError> {
+    let sender = fragment_sender(&api, input_id.into())?;
+    // Sending blocks if the input did not process previous fragments yet.
+    tokio::task::spawn_blocking(move || sender.send_fragment(fragment))
+        .await
+        .unwrap()
+        .map_err(fragment_error)?;
+    Ok(Response::Ok {})
+}
+
+pub(super) async fn handle_eos(
+    State(api): State<ApiState>,
+    Path(input_id): Path<InputId>,
+) -> Result<Response, ApiError> {
+    let sender = fragment_sender(&api, input_id.into())?;
+    tokio::task::spawn_blocking(move || sender.send_eos())
+        .await
+        .unwrap()
+        .map_err(fragment_error)?;
+    Ok(Response::Ok {})
+}
+
+fn fragment_sender(
+    api: &ApiState,
+    input_id: compositor_render::InputId,
+) -> Result<Mp4FragmentSender, ApiError> {
+    let pipeline = api.pipeline();
+    let input = pipeline
+        .inputs()
+        .find(|(id, _)| **id == input_id)
+        .map(|(_, input)| &input.input);
+
+    match input {
+        Some(Input::Mp4(mp4)) => mp4.fragment_sender().map_err(fragment_error),
+        Some(_) => Err(ApiError::new(
+            "INPUT_STREAM_NOT_FRAGMENTED_MP4",
+            format!("Input \"{input_id}\" is not a fragmented MP4 input."),
+            StatusCode::BAD_REQUEST,
+        )),
+        None => Err(ApiError::new(
+            "INPUT_STREAM_NOT_FOUND",
+            format!("Input \"{input_id}\" does not exist or did not receive the init segment."),
+            StatusCode::NOT_FOUND,
+        )),
+    }
+}
+
+fn fragment_error(err: Mp4Error) -> ApiError {
+    let (error_code, http_status_code) = match err {
+        Mp4Error::NotFragmented => ("INPUT_STREAM_NOT_FRAGMENTED_MP4", StatusCode::BAD_REQUEST),
+        Mp4Error::InputClosed => ("INPUT_STREAM_CLOSED", StatusCode::BAD_REQUEST),
+        _ => (
+            "INPUT_STREAM_INPUT_ERROR",
+            StatusCode::INTERNAL_SERVER_ERROR,
+        ),
+    };
+    ApiError::new(
+        error_code,
+        ErrorStack::new(&err).into_string(),
+        http_status_code,
+    )
+}
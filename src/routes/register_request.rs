@@ -8,24 +8,32 @@ use crate::{
     routes::Json,
     state::{Pipeline, Response},
     types::{
-        ImageSpec, InputId, Mp4, OutputId, RendererId, RtpInputStream, RtpOutputStream, ShaderSpec,
-        WebRendererSpec,
+        FragmentedMp4, HlsOutput, ImageSpec, InputId, Mp4, Mp4Output, OutputId, RendererId,
+        RtmpInputStream, RtmpOutput, RtpInputStream, RtpOutputStream, ShaderSpec, WebRendererSpec,
+        WhepOutput, WhipInput,
     },
 };
 
-use super::ApiState;
+use super::{fragmented_mp4, ApiState};
 
 #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum RegisterInput {
     RtpStream(RtpInputStream),
     Mp4(Mp4),
+    FragmentedMp4(FragmentedMp4),
+    Rtmp(RtmpInputStream),
+    Whip(WhipInput),
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum RegisterOutput {
     RtpStream(RtpOutputStream),
+    Mp4(Mp4Output),
+    Rtmp(RtmpOutput),
+    Hls(HlsOutput),
+    Whep(WhepOutput),
 }
 
 pub(super) async fn handle_input(
@@ -42,6 +50,16 @@ pub(super) async fn handle_input(
             RegisterInput::Mp4(mp4) => {
                 Pipeline::register_input(&api.pipeline, input_id.into(), mp4.try_into()?)?
             }
+            RegisterInput::FragmentedMp4(fragmented_mp4) => {
+                fragmented_mp4::register_pending_input(&api, input_id.into(), fragmented_mp4)?;
+                None
+            }
+            RegisterInput::Rtmp(rtmp) => {
+                Pipeline::register_input(&api.pipeline, input_id.into(), rtmp.try_into()?)?
+            }
+            RegisterInput::Whip(whip) => {
+                Pipeline::register_input(&api.pipeline, input_id.into(), whip.try_into()?)?
+            }
         };
         match response {
             Some(Port(port)) => Ok(Response::RegisteredPort { port }),
@@ -64,6 +82,18 @@ pub(super) async fn handle_output(
             RegisterOutput::RtpStream(rtp) => {
                 Pipeline::register_output(&mut api.pipeline(), output_id.into(), rtp.try_into()?)?
             }
+            RegisterOutput::Mp4(mp4) => {
+                Pipeline::register_output(&mut api.pipeline(), output_id.into(), mp4.try_into()?)?
+            }
+            RegisterOutput::Rtmp(rtmp) => {
+                Pipeline::register_output(&mut api.pipeline(), output_id.into(), rtmp.try_into()?)?
+            }
+            RegisterOutput::Hls(hls) => {
+                Pipeline::register_output(&mut api.pipeline(), output_id.into(), hls.try_into()?)?
+            }
+            RegisterOutput::Whep(whep) => {
+                Pipeline::register_output(&mut api.pipeline(), output_id.into(), whep.try_into()?)?
+            }
         };
         match response {
             Some(Port(port)) => Ok(Response::RegisteredPort { port }),
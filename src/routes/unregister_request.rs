@@ -58,7 +58,16 @@ pub(super) async fn handle_input(
             );
         }
         None => {
-            api.pipeline().unregister_input(&input_id.into())?;
+            let input_id = input_id.into();
+            // Input that did not receive the init segment yet is not registered in the pipeline.
+            let pending_input = api
+                .pending_fragmented_mp4_inputs
+                .lock()
+                .unwrap()
+                .remove(&input_id);
+            if pending_input.is_none() {
+                api.pipeline().unregister_input(&input_id)?;
+            }
         }
     }
     Ok(Response::Ok {})
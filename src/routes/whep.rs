@@ -0,0 +1,103 @@
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap, StatusCode},
+    response::IntoResponse,
+};
+use compositor_pipeline::pipeline::output::{
+    whep::{WhepSessionError, WhepViewers},
+    Output,
+};
+use compositor_render::error::ErrorStack;
+
+use crate::{
+    error::ApiError,
+    state::{ApiState, Response},
+    types::OutputId,
+};
+
+use super::whip::validate_sdp_content_type;
+
+/// Handles the SDP offer of a new viewer as described in
+/// [WHEP, section 4.1](https://datatracker.ietf.org/doc/html/draft-murillo-whep#section-4.1).
+/// The answer includes all ICE candidates, so trickle ICE (PATCH requests) is not supported.
+pub(super) async fn handle_offer(
+    State(api): State<ApiState>,
+    Path(output_id): Path<OutputId>,
+    headers: HeaderMap,
+    offer: String,
+) -> Result<impl IntoResponse, ApiError> {
+    validate_sdp_content_type(&headers)?;
+
+    let output_id: compositor_render::OutputId = output_id.into();
+    let viewers = whep_viewers(&api, &output_id)?;
+    let (session_id, answer) = viewers
+        .add_viewer(&output_id, offer)
+        .await
+        .map_err(session_error)?;
+
+    Ok((
+        StatusCode::CREATED,
+        [
+            (header::CONTENT_TYPE, "application/sdp".to_string()),
+            (
+                header::LOCATION,
+                format!("/api/output/{output_id}/whep/{session_id}"),
+            ),
+        ],
+        answer,
+    ))
+}
+
+pub(super) async fn handle_terminate(
+    State(api): State<ApiState>,
+    Path((output_id, session_id)): Path<(OutputId, String)>,
+) -> Result<Response, ApiError> {
+    let viewers = whep_viewers(&api, &output_id.into())?;
+    viewers
+        .remove_viewer(&session_id)
+        .await
+        .map_err(session_error)?;
+    Ok(Response::Ok {})
+}
+
+fn whep_viewers(
+    api: &ApiState,
+    output_id: &compositor_render::OutputId,
+) -> Result<WhepViewers, ApiError> {
+    let pipeline = api.pipeline();
+    let output = pipeline
+        .outputs()
+        .find(|(id, _)| *id == output_id)
+        .map(|(_, output)| &output.output);
+
+    match output {
+        Some(Output::Whep(whep)) => Ok(whep.viewers()),
+        Some(_) => Err(ApiError::new(
+            "OUTPUT_STREAM_NOT_WHEP",
+            format!("Output \"{output_id}\" is not a WHEP output."),
+            StatusCode::BAD_REQUEST,
+        )),
+        None => Err(ApiError::new(
+            "OUTPUT_STREAM_NOT_FOUND",
+            format!("Output \"{output_id}\" does not exist."),
+            StatusCode::NOT_FOUND,
+        )),
+    }
+}
+
+fn session_error(err: WhepSessionError) -> ApiError {
+    let (error_code, http_status_code) = match err {
+        WhepSessionError::OutputEnded => ("WHEP_OUTPUT_ENDED", StatusCode::BAD_REQUEST),
+        WhepSessionError::SessionNotFound(_) => ("WHEP_SESSION_NOT_FOUND", StatusCode::NOT_FOUND),
+        WhepSessionError::InvalidOffer(_) => ("WHEP_INVALID_OFFER", StatusCode::BAD_REQUEST),
+        WhepSessionError::PeerConnection(_) | WhepSessionError::MissingAnswer => (
+            "WHEP_PEER_CONNECTION_ERROR",
+            StatusCode::INTERNAL_SERVER_ERROR,
+        ),
+    };
+    ApiError::new(
+        error_code,
+        ErrorStack::new(&err).into_string(),
+        http_status_code,
+    )
+}
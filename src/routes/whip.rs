@@ -0,0 +1,140 @@
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap, StatusCode},
+    response::IntoResponse,
+};
+use compositor_pipeline::pipeline::input::{
+    whip::{WhipSession, WhipSessionError},
+    Input,
+};
+use compositor_render::error::ErrorStack;
+
+use crate::{
+    error::ApiError,
+    state::{ApiState, Response},
+    types::InputId,
+};
+
+/// Handles the SDP offer as described in
+/// [WHIP, section 4.2](https://datatracker.ietf.org/doc/html/draft-ietf-wish-whip#section-4.2).
+/// The answer includes all ICE candidates, so trickle ICE (PATCH requests) is not supported.
+pub(super) async fn handle_offer(
+    State(api): State<ApiState>,
+    Path(input_id): Path<InputId>,
+    headers: HeaderMap,
+    offer: String,
+) -> Result<impl IntoResponse, ApiError> {
+    validate_sdp_content_type(&headers)?;
+
+    let input_id: compositor_render::InputId = input_id.into();
+    let session = whip_session(&api, &input_id)?;
+    let answer = session
+        .negotiate(&input_id, offer)
+        .await
+        .map_err(session_error)?;
+
+    Ok((
+        StatusCode::CREATED,
+        [
+            (header::CONTENT_TYPE, "application/sdp".to_string()),
+            (header::LOCATION, format!("/api/input/{input_id}/whip")),
+        ],
+        answer,
+    ))
+}
+
+pub(super) async fn handle_terminate(
+    State(api): State<ApiState>,
+    Path(input_id): Path<InputId>,
+) -> Result<Response, ApiError> {
+    let session = whip_session(&api, &input_id.into())?;
+    session.terminate().await.map_err(session_error)?;
+    Ok(Response::Ok {})
+}
+
+/// Validates that the request body is an SDP offer. Shared with WHEP endpoints.
+/// Media type parameters (e.g. `charset`) are ignored.
+pub(super) fn validate_sdp_content_type(headers: &HeaderMap) -> Result<(), ApiError> {
+    let media_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(';').next())
+        .map(str::trim);
+    let is_sdp =
+        media_type.is_some_and(|media_type| media_type.eq_ignore_ascii_case("application/sdp"));
+    if !is_sdp {
+        return Err(ApiError::new(
+            "UNSUPPORTED_CONTENT_TYPE",
+            "SDP offer has to be sent with \"Content-Type: application/sdp\" header.".to_string(),
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+        ));
+    }
+    Ok(())
+}
+
+fn whip_session(
+    api: &ApiState,
+    input_id: &compositor_render::InputId,
+) -> Result<WhipSession, ApiError> {
+    let pipeline = api.pipeline();
+    let input = pipeline
+        .inputs()
+        .find(|(id, _)| *id == input_id)
+        .map(|(_, input)| &input.input);
+
+    match input {
+        Some(Input::Whip(whip)) => Ok(whip.session()),
+        Some(_) => Err(ApiError::new(
+            "INPUT_STREAM_NOT_WHIP",
+            format!("Input \"{input_id}\" is not a WHIP input."),
+            StatusCode::BAD_REQUEST,
+        )),
+        None => Err(ApiError::new(
+            "INPUT_STREAM_NOT_FOUND",
+            format!("Input \"{input_id}\" does not exist."),
+            StatusCode::NOT_FOUND,
+        )),
+    }
+}
+
+fn session_error(err: WhipSessionError) -> ApiError {
+    let (error_code, http_status_code) = match err {
+        WhipSessionError::SessionAlreadyStarted => {
+            ("WHIP_SESSION_ALREADY_STARTED", StatusCode::CONFLICT)
+        }
+        WhipSessionError::SessionEnded => ("WHIP_SESSION_ENDED", StatusCode::BAD_REQUEST),
+        WhipSessionError::InvalidOffer(_) => ("WHIP_INVALID_OFFER", StatusCode::BAD_REQUEST),
+        WhipSessionError::PeerConnection(_) | WhipSessionError::MissingAnswer => (
+            "WHIP_PEER_CONNECTION_ERROR",
+            StatusCode::INTERNAL_SERVER_ERROR,
+        ),
+    };
+    ApiError::new(
+        error_code,
+        ErrorStack::new(&err).into_string(),
+        http_status_code,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+
+    use super::*;
+
+    fn headers(content_type: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::CONTENT_TYPE, HeaderValue::from_static(content_type));
+        headers
+    }
+
+    #[test]
+    fn sdp_content_type() {
+        assert!(validate_sdp_content_type(&headers("application/sdp")).is_ok());
+        assert!(validate_sdp_content_type(&headers("application/sdp; charset=utf-8")).is_ok());
+        assert!(validate_sdp_content_type(&headers("Application/SDP")).is_ok());
+        assert!(validate_sdp_content_type(&headers("application/json")).is_err());
+        assert!(validate_sdp_content_type(&headers("application/sdpx")).is_err());
+        assert!(validate_sdp_content_type(&HeaderMap::new()).is_err());
+    }
+}
@@ -1,7 +1,7 @@
 use std::thread;
 
 use axum::extract::ws::{Message, WebSocket};
-use compositor_render::event_handler::{subscribe, Event};
+use compositor_render::event_handler::{subscribe, Event, EventValue};
 use futures_util::{SinkExt, StreamExt};
 use tokio::sync::mpsc::channel;
 use tracing::debug;
@@ -80,7 +80,11 @@ fn event_to_json(event: Event) -> serde_json::Value {
     let mut map = serde_json::Map::new();
     map.insert("type".to_string(), event.kind.into());
     for (key, value) in event.properties {
-        map.insert(key, value.into());
+        let value = match value {
+            EventValue::String(value) => value.into(),
+            EventValue::NumberList(values) => values.into(),
+        };
+        map.insert(key, value);
     }
     map.into()
 }
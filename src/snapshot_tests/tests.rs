@@ -19,6 +19,7 @@ pub fn snapshot_tests() -> Vec<TestCase> {
     tests.append(&mut base_snapshot_tests());
     tests.append(&mut view_snapshot_tests());
     tests.append(&mut transition_snapshot_tests());
+    tests.append(&mut enter_exit_snapshot_tests());
     tests.append(&mut image_snapshot_tests());
     tests.append(&mut text_snapshot_tests());
     tests.append(&mut tiles_snapshot_tests());
@@ -1237,6 +1238,50 @@ fn image_snapshot_tests() -> Vec<TestCase> {
     ])
 }
 
+fn enter_exit_snapshot_tests() -> Vec<TestCase> {
+    Vec::from([
+        TestCase {
+            name: "enter_exit/view_enter",
+            scene_updates: Updates::Scenes(vec![
+                (
+                    include_str!("../../snapshot_tests/enter_exit/view_enter_start.scene.json"),
+                    DEFAULT_RESOLUTION,
+                ),
+                (
+                    include_str!("../../snapshot_tests/enter_exit/view_enter_end.scene.json"),
+                    DEFAULT_RESOLUTION,
+                ),
+            ]),
+            timestamps: vec![
+                Duration::from_millis(0),
+                Duration::from_millis(500),
+                Duration::from_millis(1000),
+            ],
+            ..Default::default()
+        },
+        TestCase {
+            name: "enter_exit/view_exit",
+            scene_updates: Updates::Scenes(vec![
+                (
+                    include_str!("../../snapshot_tests/enter_exit/view_exit_start.scene.json"),
+                    DEFAULT_RESOLUTION,
+                ),
+                (
+                    include_str!("../../snapshot_tests/enter_exit/view_exit_end.scene.json"),
+                    DEFAULT_RESOLUTION,
+                ),
+            ]),
+            timestamps: vec![
+                Duration::from_millis(0),
+                Duration::from_millis(500),
+                // Removed components are no longer rendered after the exit animation.
+                Duration::from_millis(1000),
+            ],
+            ..Default::default()
+        },
+    ])
+}
+
 fn transition_snapshot_tests() -> Vec<TestCase> {
     Vec::from([
         TestCase {
@@ -1444,6 +1489,44 @@ fn transition_snapshot_tests() -> Vec<TestCase> {
             ],
             ..Default::default()
         },
+        TestCase {
+            name: "transition/change_view_style",
+            scene_updates: Updates::Scenes(vec![
+                (
+                    include_str!("../../snapshot_tests/transition/change_view_style_start.scene.json"),
+                    DEFAULT_RESOLUTION,
+                ),
+                (
+                    include_str!("../../snapshot_tests/transition/change_view_style_end.scene.json"),
+                    DEFAULT_RESOLUTION,
+                ),
+            ]),
+            timestamps: vec![
+                Duration::from_secs(0),
+                Duration::from_secs(5),
+                Duration::from_secs(10),
+            ],
+            ..Default::default()
+        },
+        TestCase {
+            name: "transition/change_text_color",
+            scene_updates: Updates::Scenes(vec![
+                (
+                    include_str!("../../snapshot_tests/transition/change_text_color_start.scene.json"),
+                    DEFAULT_RESOLUTION,
+                ),
+                (
+                    include_str!("../../snapshot_tests/transition/change_text_color_end.scene.json"),
+                    DEFAULT_RESOLUTION,
+                ),
+            ]),
+            timestamps: vec![
+                Duration::from_secs(0),
+                Duration::from_secs(5),
+                Duration::from_secs(10),
+            ],
+            ..Default::default()
+        },
     ])
 }
 
@@ -1565,7 +1648,52 @@ fn view_snapshot_tests() -> Vec<TestCase> {
             ),
             inputs: vec![TestInput::new(1)],
             ..Default::default()
-        }
+        },
+        TestCase {
+            name: "view/view_with_opacity",
+            scene_updates: Updates::Scene(
+                    include_str!("../../snapshot_tests/view/view_with_opacity.scene.json"),
+                    DEFAULT_RESOLUTION,
+            ),
+            inputs: vec![TestInput::new(1)],
+            ..Default::default()
+        },
+        TestCase {
+            name: "view/view_with_border_radius",
+            scene_updates: Updates::Scene(
+                    include_str!("../../snapshot_tests/view/view_with_border_radius.scene.json"),
+                    DEFAULT_RESOLUTION,
+            ),
+            inputs: vec![TestInput::new(1)],
+            ..Default::default()
+        },
+        TestCase {
+            name: "view/view_with_border",
+            scene_updates: Updates::Scene(
+                    include_str!("../../snapshot_tests/view/view_with_border.scene.json"),
+                    DEFAULT_RESOLUTION,
+            ),
+            inputs: vec![TestInput::new(1)],
+            ..Default::default()
+        },
+        TestCase {
+            name: "view/view_with_box_shadow",
+            scene_updates: Updates::Scene(
+                    include_str!("../../snapshot_tests/view/view_with_box_shadow.scene.json"),
+                    DEFAULT_RESOLUTION,
+            ),
+            inputs: vec![TestInput::new(1)],
+            ..Default::default()
+        },
+        TestCase {
+            name: "view/overflow_hidden_with_border_radius",
+            scene_updates: Updates::Scene(
+                    include_str!("../../snapshot_tests/view/overflow_hidden_with_border_radius.scene.json"),
+                    DEFAULT_RESOLUTION,
+            ),
+            inputs: vec![TestInput::new(1)],
+            ..Default::default()
+        },
     ])
 }
 
@@ -1,12 +1,15 @@
-use std::sync::{Arc, Mutex, MutexGuard};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, MutexGuard},
+};
 
 use axum::response::IntoResponse;
 use compositor_pipeline::pipeline::{self};
-use compositor_render::{error::InitPipelineError, EventLoop};
+use compositor_render::{error::InitPipelineError, EventLoop, InputId};
 
 use serde::Serialize;
 
-use crate::config::Config;
+use crate::{config::Config, types::FragmentedMp4};
 
 pub type Pipeline = compositor_pipeline::Pipeline;
 
@@ -27,6 +30,9 @@ impl IntoResponse for Response {
 pub struct ApiState {
     pub pipeline: Arc<Mutex<Pipeline>>,
     pub config: Config,
+    /// Fragmented MP4 inputs that are registered, but did not receive
+    /// the init segment yet.
+    pub pending_fragmented_mp4_inputs: Arc<Mutex<HashMap<InputId, FragmentedMp4>>>,
 }
 
 impl ApiState {
@@ -38,6 +44,7 @@ impl ApiState {
             force_gpu,
             download_root,
             output_sample_rate,
+            audio_levels_interval,
             required_wgpu_features,
             ..
         } = config.clone();
@@ -48,12 +55,14 @@ impl ApiState {
             force_gpu,
             download_root,
             output_sample_rate,
+            audio_levels_interval,
             wgpu_features: required_wgpu_features,
         })?;
         Ok((
             ApiState {
                 pipeline: Mutex::new(pipeline).into(),
                 config,
+                pending_fragmented_mp4_inputs: Default::default(),
             },
             event_loop,
         ))
@@ -41,13 +41,27 @@ pub use component::View;
 #[allow(unused_imports)]
 pub use component::WebView;
 
+#[allow(unused_imports)]
+pub use register_input::FragmentedMp4;
 #[allow(unused_imports)]
 pub use register_input::Mp4;
 #[allow(unused_imports)]
+pub use register_output::HlsOutput;
+#[allow(unused_imports)]
+pub use register_output::Mp4Output;
+#[allow(unused_imports)]
+pub use register_output::RtmpOutput;
+#[allow(unused_imports)]
 pub use register_output::RtpOutputStream;
+#[allow(unused_imports)]
+pub use register_output::WhepOutput;
 
+#[allow(unused_imports)]
+pub use register_input::RtmpInputStream;
 #[allow(unused_imports)]
 pub use register_input::RtpInputStream;
+#[allow(unused_imports)]
+pub use register_input::WhipInput;
 
 #[allow(unused_imports)]
 pub use renderer::ImageSpec;
@@ -1,12 +1,37 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use super::InputId;
+use super::{util::Transition, InputId};
 
 #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Audio {
     pub inputs: Vec<InputAudio>,
+    /// Defines how the volume of the inputs should change after the update. Volume is
+    /// interpolated from the current value to the new one for each sample. Inputs added
+    /// by the update fade in from silence.
+    pub transition: Option<Transition>,
+    /// (**default=`[]`**) Rules that lower the volume of some inputs while another input is active,
+    /// e.g. background music while a commentator speaks.
+    pub ducking: Option<Vec<DuckingRule>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct DuckingRule {
+    /// Input that triggers the ducking. It does not have to be mixed into the output.
+    pub trigger_input_id: InputId,
+    /// Inputs which volume is lowered while the trigger input is active.
+    pub ducked_input_ids: Vec<InputId>,
+    /// (**default=`-40.0`**) Level in dBFS above which the trigger input is considered active.
+    pub threshold_db: Option<f32>,
+    /// (**default=`12.0`**) Volume reduction of the ducked inputs in dB.
+    pub amount_db: Option<f32>,
+    /// (**default=`50`**) Time in milliseconds it takes to lower the volume.
+    pub attack_ms: Option<f64>,
+    /// (**default=`500`**) Time in milliseconds it takes to restore the volume after
+    /// the trigger input becomes inactive.
+    pub release_ms: Option<f64>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
@@ -15,16 +40,106 @@ pub struct InputAudio {
     pub input_id: InputId,
     /// (**default=`1.0`**) float in `[0, 1]` range representing input volume
     pub volume: Option<f32>,
+    /// (**default=`0.0`**) float in `[-1, 1]` range representing stereo position of the input,
+    /// `-1.0` is full left and `1.0` is full right. Uses constant-power panning.
+    pub pan: Option<f32>,
+    /// (**default=`"stereo"`**) Channels of the input that should be mixed.
+    pub channel_routing: Option<ChannelRouting>,
+    /// (**default=`[]`**) Audio effects applied to the input, in order, before mixing.
+    pub effects: Option<Vec<AudioEffect>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 #[serde(rename_all = "snake_case")]
+pub enum ChannelRouting {
+    /// Both channels of the input are mixed.
+    Stereo,
+    /// Only the left channel of the input is mixed, as a mono source.
+    Left,
+    /// Only the right channel of the input is mixed, as a mono source.
+    Right,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum AudioEffect {
+    /// Removes frequencies below the cutoff frequency, e.g. rumble or microphone handling noise.
+    HighPassFilter {
+        /// Cutoff frequency in Hz.
+        cutoff_frequency_hz: f32,
+    },
+    /// Boosts or attenuates selected frequency bands.
+    ParametricEq { bands: Vec<EqBand> },
+    /// Reduces the dynamic range of the input by attenuating signal above the threshold.
+    Compressor {
+        /// Level in dBFS above which the signal is attenuated.
+        threshold_db: f32,
+        /// Compression ratio, has to be greater or equal to `1.0`. For example, with ratio `4.0`
+        /// the signal that is 8 dB above the threshold is reduced to 2 dB above the threshold.
+        ratio: f32,
+        /// (**default=`10`**) Time in milliseconds it takes to react to signal above the threshold.
+        attack_ms: Option<f64>,
+        /// (**default=`100`**) Time in milliseconds it takes to stop attenuating after the signal
+        /// drops below the threshold.
+        release_ms: Option<f64>,
+        /// (**default=`0.0`**) Gain in dB applied after compression.
+        makeup_gain_db: Option<f32>,
+    },
+    /// Mutes the input when its level is below the threshold.
+    NoiseGate {
+        /// Level in dBFS below which the input is muted.
+        threshold_db: f32,
+        /// (**default=`1`**) Time in milliseconds it takes to open the gate.
+        attack_ms: Option<f64>,
+        /// (**default=`50`**) Time in milliseconds the gate stays open after the signal
+        /// drops below the threshold.
+        hold_ms: Option<f64>,
+        /// (**default=`100`**) Time in milliseconds it takes to close the gate.
+        release_ms: Option<f64>,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct EqBand {
+    #[serde(rename = "type")]
+    pub kind: EqBandType,
+    /// Center frequency (for `peaking`) or corner frequency (for shelves) in Hz.
+    pub frequency_hz: f32,
+    /// Gain in dB, negative values attenuate the band.
+    pub gain_db: f32,
+    /// (**default=`0.707`**) Quality factor. Higher values result in a narrower band.
+    pub q: Option<f32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EqBandType {
+    /// Boosts or attenuates frequencies around the center frequency.
+    Peaking,
+    /// Boosts or attenuates frequencies below the corner frequency.
+    LowShelf,
+    /// Boosts or attenuates frequencies above the corner frequency.
+    HighShelf,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
 pub enum MixingStrategy {
     /// Firstly, input samples are summed. If the result is outside the i16 PCM range, it gets clipped.
     SumClip,
     /// Firstly, input samples are summed. If the result is outside the i16 PCM range,
     /// nearby summed samples are scaled down by factor, such that the summed wave is in the i16 PCM range.
     SumScale,
+    /// Firstly, input samples are summed. Then the gain is smoothly adjusted, so the loudness of the
+    /// output (measured according to ITU-R BS.1770) stays close to the target. A true-peak limiter
+    /// is applied at the end.
+    LoudnessNormalization {
+        /// (**default=`-23.0`**) Target loudness in LUFS. The default value follows EBU R128.
+        target_lufs: Option<f64>,
+        /// (**default=`-1.0`**) Maximal true-peak level of the output in dBTP.
+        true_peak_limit_dbtp: Option<f64>,
+    },
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
@@ -34,6 +149,13 @@ pub enum AudioChannels {
     Mono,
     /// Stereo audio (two channels).
     Stereo,
+    /// 5.1 surround audio (six channels). Mono and stereo inputs are mixed into the front
+    /// channels. 5.1 inputs mixed into mono or stereo outputs are downmixed. 5.1 inputs
+    /// that use `pan`, `channel_routing` or `effects` are also downmixed to stereo and mixed
+    /// into the front channels. Not supported in WHEP outputs and with the Opus encoder
+    /// in RTP outputs.
+    #[serde(rename = "surround_5_1")]
+    Surround51,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
@@ -75,11 +75,52 @@ pub struct View {
     /// effect if the previous scene already contained a View component with the same id.
     pub transition: Option<Transition>,
 
+    /// Animation played when the component is added to the scene. It requires the `id`
+    /// field, the animation is played if the previous scene did not contain a component
+    /// with the same id.
+    pub enter: Option<EnterExitTransition>,
+    /// Animation played when the component is removed from the scene. It requires the `id`
+    /// field and a parent `View` component with an `id`, otherwise the scene is rejected.
+    /// The component is rendered until the animation finishes only if its parent is still
+    /// present in the new scene.
+    pub exit: Option<EnterExitTransition>,
+    /// Keyframe animation that changes the size, position, rotation or opacity of the component
+    /// on every frame, e.g. a looping animation requires only one scene update. Position and
+    /// rotation can only be animated if the component is absolutely positioned.
+    pub animation: Option<Animation>,
+
     /// (**default=`"hidden"`**) Controls what happens to content that is too big to fit into an area.
     pub overflow: Option<Overflow>,
 
     /// (**default=`"#00000000"`**) Background color in a `"#RRGGBBAA"` format.
     pub background_color_rgba: Option<RGBAColor>,
+
+    /// (**default=`1.0`**) Opacity of the component and its children, in the `[0, 1]` range.
+    pub opacity: Option<f32>,
+
+    /// (**default=`0.0`**) Radius of the rounded corners in pixels. If `overflow` is set to
+    /// `"hidden"`, children are also clipped by the rounded corners.
+    pub border_radius: Option<f32>,
+    /// (**default=`0.0`**) Border width in pixels. The border is drawn inside the component
+    /// area, children are positioned inside of it.
+    pub border_width: Option<f32>,
+    /// (**default=`"#00000000"`**) Border color in a `"#RRGGBBAA"` format.
+    pub border_color_rgba: Option<RGBAColor>,
+    /// List of shadows rendered below the component.
+    pub box_shadow: Option<Vec<BoxShadow>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct BoxShadow {
+    /// (**default=`0.0`**) Horizontal offset of the shadow in pixels.
+    pub offset_x: Option<f32>,
+    /// (**default=`0.0`**) Vertical offset of the shadow in pixels.
+    pub offset_y: Option<f32>,
+    /// (**default=`"#000000FF"`**) Color of the shadow in a `"#RRGGBBAA"` format.
+    pub color_rgba: Option<RGBAColor>,
+    /// (**default=`0.0`**) Blur radius of the shadow in pixels.
+    pub blur_radius: Option<f32>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
@@ -125,6 +166,19 @@ pub struct Rescaler {
     pub horizontal_align: Option<HorizontalAlign>,
     /// (**default=`"center"`**) Vertical alignment.
     pub vertical_align: Option<VerticalAlign>,
+    /// (**default=`1.0`**) Opacity of the component and its child, in the `[0, 1]` range.
+    pub opacity: Option<f32>,
+
+    /// (**default=`0.0`**) Radius of the rounded corners in pixels. The child is clipped by
+    /// the rounded corners.
+    pub border_radius: Option<f32>,
+    /// (**default=`0.0`**) Border width in pixels. The border is drawn inside the component
+    /// area, the child is rescaled to fit inside of it.
+    pub border_width: Option<f32>,
+    /// (**default=`"#00000000"`**) Border color in a `"#RRGGBBAA"` format.
+    pub border_color_rgba: Option<RGBAColor>,
+    /// List of shadows rendered below the component.
+    pub box_shadow: Option<Vec<BoxShadow>>,
 
     /// Width of a component in pixels. Exact behavior might be different based on the parent
     /// component:
@@ -161,6 +215,20 @@ pub struct Rescaler {
     /// Defines how this component will behave during a scene update. This will only have an
     /// effect if the previous scene already contained a View component with the same id.
     pub transition: Option<Transition>,
+
+    /// Animation played when the component is added to the scene. It requires the `id`
+    /// field, the animation is played if the previous scene did not contain a component
+    /// with the same id.
+    pub enter: Option<EnterExitTransition>,
+    /// Animation played when the component is removed from the scene. It requires the `id`
+    /// field and a parent `View` component with an `id`, otherwise the scene is rejected.
+    /// The component is rendered until the animation finishes only if its parent is still
+    /// present in the new scene.
+    pub exit: Option<EnterExitTransition>,
+    /// Keyframe animation that changes the size, position, rotation or opacity of the component
+    /// on every frame, e.g. a looping animation requires only one scene update. Position and
+    /// rotation can only be animated if the component is absolutely positioned.
+    pub animation: Option<Animation>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
@@ -223,6 +291,10 @@ pub struct Shader {
     pub shader_param: Option<ShaderParam>,
     /// Resolution of a texture where shader will be executed.
     pub resolution: Resolution,
+    /// Defines how `shader_param` will be interpolated during a scene update. This will only
+    /// have an effect if the previous scene already contained a `Shader` component with the
+    /// same id that uses the same shader and the structure of the params did not change.
+    pub transition: Option<Transition>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
@@ -289,6 +361,9 @@ pub struct Text {
     pub wrap: Option<TextWrapMode>,
     /// (**default=`"normal"`**) Font weight. The selected font needs to support the specified weight.
     pub weight: Option<TextWeight>,
+    /// Defines how `color_rgba` will be interpolated during a scene update. This will only have
+    /// an effect if the previous scene already contained a `Text` component with the same id.
+    pub transition: Option<Transition>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
@@ -374,8 +449,23 @@ pub struct Tiles {
     pub horizontal_align: Option<HorizontalAlign>,
     /// (**default=`"center"`**) Vertical alignment of tiles.
     pub vertical_align: Option<VerticalAlign>,
+    /// (**default=`1.0`**) Opacity of the component and its children, in the `[0, 1]` range.
+    pub opacity: Option<f32>,
 
     /// Defines how this component will behave during a scene update. This will only have an
     /// effect if the previous scene already contained a `Tiles` component with the same id.
     pub transition: Option<Transition>,
+
+    /// Animation played when the component is added to the scene. It requires the `id`
+    /// field, the animation is played if the previous scene did not contain a component
+    /// with the same id.
+    pub enter: Option<EnterExitTransition>,
+    /// Animation played when the component is removed from the scene. It requires the `id`
+    /// field and a parent `View` component with an `id`, otherwise the scene is rejected.
+    /// The component is rendered until the animation finishes only if its parent is still
+    /// present in the new scene.
+    pub exit: Option<EnterExitTransition>,
+    /// Keyframe animation that changes the size or opacity of the component on every frame,
+    /// e.g. a looping animation requires only one scene update.
+    pub animation: Option<Animation>,
 }
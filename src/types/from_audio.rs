@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use compositor_pipeline::{audio_mixer, pipeline};
 
 use super::audio::*;
@@ -12,7 +14,19 @@ impl TryFrom<Audio> for compositor_pipeline::audio_mixer::AudioMixingParams {
             inputs.push(input.try_into()?);
         }
 
-        Ok(Self { inputs })
+        let transition = value.transition.map(TryInto::try_into).transpose()?;
+        let ducking = value
+            .ducking
+            .unwrap_or_default()
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            inputs,
+            transition,
+            ducking,
+        })
     }
 }
 
@@ -25,19 +39,177 @@ impl TryFrom<InputAudio> for compositor_pipeline::audio_mixer::InputParams {
                 return Err(TypeError::new("Input volume has to be in [0, 1] range."));
             }
         }
+        if let Some(pan) = value.pan {
+            if !(-1.0..=1.0).contains(&pan) {
+                return Err(TypeError::new("Input pan has to be in [-1, 1] range."));
+            }
+        }
+        let effects = value
+            .effects
+            .unwrap_or_default()
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<_, _>>()?;
         Ok(Self {
             input_id: value.input_id.into(),
             volume: value.volume.unwrap_or(1.0),
+            pan: value.pan.unwrap_or(0.0),
+            channel_routing: value
+                .channel_routing
+                .map(Into::into)
+                .unwrap_or(audio_mixer::ChannelRouting::Stereo),
+            effects,
         })
     }
 }
 
-impl From<MixingStrategy> for compositor_pipeline::audio_mixer::MixingStrategy {
-    fn from(value: MixingStrategy) -> Self {
+impl From<ChannelRouting> for audio_mixer::ChannelRouting {
+    fn from(value: ChannelRouting) -> Self {
         match value {
+            ChannelRouting::Stereo => audio_mixer::ChannelRouting::Stereo,
+            ChannelRouting::Left => audio_mixer::ChannelRouting::Left,
+            ChannelRouting::Right => audio_mixer::ChannelRouting::Right,
+        }
+    }
+}
+
+impl TryFrom<AudioEffect> for audio_mixer::AudioEffect {
+    type Error = TypeError;
+
+    fn try_from(value: AudioEffect) -> Result<Self, Self::Error> {
+        let effect = match value {
+            AudioEffect::HighPassFilter {
+                cutoff_frequency_hz,
+            } => {
+                if cutoff_frequency_hz <= 0.0 {
+                    return Err(TypeError::new(
+                        "High-pass filter cutoff frequency has to be a positive number.",
+                    ));
+                }
+                audio_mixer::AudioEffect::HighPassFilter {
+                    cutoff_frequency: cutoff_frequency_hz,
+                }
+            }
+            AudioEffect::ParametricEq { bands } => audio_mixer::AudioEffect::ParametricEq {
+                bands: bands
+                    .into_iter()
+                    .map(TryInto::try_into)
+                    .collect::<Result<_, _>>()?,
+            },
+            AudioEffect::Compressor {
+                threshold_db,
+                ratio,
+                attack_ms,
+                release_ms,
+                makeup_gain_db,
+            } => {
+                if ratio < 1.0 {
+                    return Err(TypeError::new(
+                        "Compressor ratio has to be greater or equal to 1.0.",
+                    ));
+                }
+                audio_mixer::AudioEffect::Compressor {
+                    threshold_db,
+                    ratio,
+                    attack: duration("attack_ms", attack_ms, 10.0)?,
+                    release: duration("release_ms", release_ms, 100.0)?,
+                    makeup_gain_db: makeup_gain_db.unwrap_or(0.0),
+                }
+            }
+            AudioEffect::NoiseGate {
+                threshold_db,
+                attack_ms,
+                hold_ms,
+                release_ms,
+            } => audio_mixer::AudioEffect::NoiseGate {
+                threshold_db,
+                attack: duration("attack_ms", attack_ms, 1.0)?,
+                hold: duration("hold_ms", hold_ms, 50.0)?,
+                release: duration("release_ms", release_ms, 100.0)?,
+            },
+        };
+        Ok(effect)
+    }
+}
+
+impl TryFrom<DuckingRule> for audio_mixer::DuckingRule {
+    type Error = TypeError;
+
+    fn try_from(value: DuckingRule) -> Result<Self, Self::Error> {
+        let amount_db = value.amount_db.unwrap_or(12.0);
+        if amount_db < 0.0 {
+            return Err(TypeError::new(
+                "Ducking amount has to be a non-negative number.",
+            ));
+        }
+        Ok(Self {
+            trigger_input_id: value.trigger_input_id.into(),
+            ducked_input_ids: value.ducked_input_ids.into_iter().map(Into::into).collect(),
+            threshold_db: value.threshold_db.unwrap_or(-40.0),
+            amount_db,
+            attack: duration("attack_ms", value.attack_ms, 50.0)?,
+            release: duration("release_ms", value.release_ms, 500.0)?,
+        })
+    }
+}
+
+impl TryFrom<EqBand> for audio_mixer::EqBand {
+    type Error = TypeError;
+
+    fn try_from(value: EqBand) -> Result<Self, Self::Error> {
+        let q = value.q.unwrap_or(std::f32::consts::FRAC_1_SQRT_2);
+        if value.frequency_hz <= 0.0 {
+            return Err(TypeError::new(
+                "EQ band frequency has to be a positive number.",
+            ));
+        }
+        if q <= 0.0 {
+            return Err(TypeError::new("EQ band \"q\" has to be a positive number."));
+        }
+        let kind = match value.kind {
+            EqBandType::Peaking => audio_mixer::EqBandKind::Peaking,
+            EqBandType::LowShelf => audio_mixer::EqBandKind::LowShelf,
+            EqBandType::HighShelf => audio_mixer::EqBandKind::HighShelf,
+        };
+        Ok(Self {
+            kind,
+            frequency: value.frequency_hz,
+            gain_db: value.gain_db,
+            q,
+        })
+    }
+}
+
+impl TryFrom<MixingStrategy> for compositor_pipeline::audio_mixer::MixingStrategy {
+    type Error = TypeError;
+
+    fn try_from(value: MixingStrategy) -> Result<Self, Self::Error> {
+        let strategy = match value {
             MixingStrategy::SumClip => compositor_pipeline::audio_mixer::MixingStrategy::SumClip,
             MixingStrategy::SumScale => compositor_pipeline::audio_mixer::MixingStrategy::SumScale,
-        }
+            MixingStrategy::LoudnessNormalization {
+                target_lufs,
+                true_peak_limit_dbtp,
+            } => {
+                let target_lufs = target_lufs.unwrap_or(-23.0);
+                if !(-70.0..=0.0).contains(&target_lufs) {
+                    return Err(TypeError::new(
+                        "Target loudness has to be in [-70, 0] LUFS range.",
+                    ));
+                }
+                let true_peak_limit_dbtp = true_peak_limit_dbtp.unwrap_or(-1.0);
+                if true_peak_limit_dbtp > 0.0 {
+                    return Err(TypeError::new(
+                        "True-peak limit can not be higher than 0 dBTP.",
+                    ));
+                }
+                compositor_pipeline::audio_mixer::MixingStrategy::LoudnessNormalization {
+                    target_lufs,
+                    true_peak_limit_dbtp,
+                }
+            }
+        };
+        Ok(strategy)
     }
 }
 
@@ -54,6 +226,17 @@ impl From<AudioChannels> for audio_mixer::AudioChannels {
         match value {
             AudioChannels::Mono => audio_mixer::AudioChannels::Mono,
             AudioChannels::Stereo => audio_mixer::AudioChannels::Stereo,
+            AudioChannels::Surround51 => audio_mixer::AudioChannels::Surround51,
         }
     }
 }
+
+fn duration(name: &str, value_ms: Option<f64>, default_ms: f64) -> Result<Duration, TypeError> {
+    let value_ms = value_ms.unwrap_or(default_ms);
+    if value_ms < 0.0 {
+        return Err(TypeError::new(format!(
+            "\"{name}\" has to be a non-negative number."
+        )));
+    }
+    Ok(Duration::from_secs_f64(value_ms / 1000.0))
+}
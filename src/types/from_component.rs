@@ -100,7 +100,39 @@ impl TryFrom<View> for scene::ViewComponent {
                 .background_color_rgba
                 .map(TryInto::try_into)
                 .unwrap_or(Ok(scene::RGBAColor(0, 0, 0, 0)))?,
+            opacity: opacity(view.opacity)?,
+            border_radius: non_negative(view.border_radius, "border_radius")?,
+            border_width: non_negative(view.border_width, "border_width")?,
+            border_color: view
+                .border_color_rgba
+                .map(TryInto::try_into)
+                .unwrap_or(Ok(scene::RGBAColor(0, 0, 0, 0)))?,
+            box_shadow: view
+                .box_shadow
+                .unwrap_or_default()
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<Vec<_>, _>>()?,
             transition: view.transition.map(TryInto::try_into).transpose()?,
+            enter: view.enter.map(TryInto::try_into).transpose()?,
+            exit: view.exit.map(TryInto::try_into).transpose()?,
+            animation: animation(view.animation, is_absolute_position, "View")?,
+        })
+    }
+}
+
+impl TryFrom<BoxShadow> for scene::BoxShadow {
+    type Error = TypeError;
+
+    fn try_from(shadow: BoxShadow) -> Result<Self, Self::Error> {
+        Ok(Self {
+            offset_x: shadow.offset_x.unwrap_or(0.0),
+            offset_y: shadow.offset_y.unwrap_or(0.0),
+            blur_radius: non_negative(shadow.blur_radius, "blur_radius")?,
+            color: shadow
+                .color_rgba
+                .map(TryInto::try_into)
+                .unwrap_or(Ok(scene::RGBAColor(0, 0, 0, 255)))?,
         })
     }
 }
@@ -164,7 +196,23 @@ impl TryFrom<Rescaler> for scene::RescalerComponent {
                 .vertical_align
                 .unwrap_or(VerticalAlign::Center)
                 .into(),
+            opacity: opacity(rescaler.opacity)?,
+            border_radius: non_negative(rescaler.border_radius, "border_radius")?,
+            border_width: non_negative(rescaler.border_width, "border_width")?,
+            border_color: rescaler
+                .border_color_rgba
+                .map(TryInto::try_into)
+                .unwrap_or(Ok(scene::RGBAColor(0, 0, 0, 0)))?,
+            box_shadow: rescaler
+                .box_shadow
+                .unwrap_or_default()
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<Vec<_>, _>>()?,
             transition: rescaler.transition.map(TryInto::try_into).transpose()?,
+            enter: rescaler.enter.map(TryInto::try_into).transpose()?,
+            exit: rescaler.exit.map(TryInto::try_into).transpose()?,
+            animation: animation(rescaler.animation, is_absolute_position, "Rescaler")?,
         })
     }
 }
@@ -178,6 +226,7 @@ impl TryFrom<Shader> for scene::ShaderComponent {
             shader_id: shader.shader_id.into(),
             shader_param: shader.shader_param.map(Into::into),
             size: shader.resolution.into(),
+            transition: shader.transition.map(TryInto::try_into).transpose()?,
             children: shader
                 .children
                 .unwrap_or_default()
@@ -282,6 +331,7 @@ impl TryFrom<Text> for scene::TextComponent {
                 .background_color_rgba
                 .map(TryInto::try_into)
                 .unwrap_or(Ok(scene::RGBAColor(0, 0, 0, 0)))?,
+            transition: text.transition.map(TryInto::try_into).transpose()?,
         };
         Ok(text)
     }
@@ -334,8 +384,53 @@ impl TryFrom<Tiles> for scene::TilesComponent {
                 .unwrap_or(HorizontalAlign::Center)
                 .into(),
             vertical_align: tiles.vertical_align.unwrap_or(VerticalAlign::Center).into(),
+            opacity: opacity(tiles.opacity)?,
             transition: tiles.transition.map(TryInto::try_into).transpose()?,
+            enter: tiles.enter.map(TryInto::try_into).transpose()?,
+            exit: tiles.exit.map(TryInto::try_into).transpose()?,
+            animation: animation(tiles.animation, false, "Tiles")?,
         };
         Ok(result)
     }
 }
+
+fn opacity(opacity: Option<f32>) -> Result<f32, TypeError> {
+    match opacity {
+        Some(opacity) if !(0.0..=1.0).contains(&opacity) => Err(TypeError::new(
+            "Component opacity has to be in [0, 1] range.",
+        )),
+        opacity => Ok(opacity.unwrap_or(1.0)),
+    }
+}
+
+fn animation(
+    animation: Option<Animation>,
+    is_absolute_position: bool,
+    component: &str,
+) -> Result<Option<scene::Animation>, TypeError> {
+    let Some(animation) = animation else {
+        return Ok(None);
+    };
+    let changes_position = animation.keyframes.iter().any(|keyframe| {
+        keyframe.top.is_some()
+            || keyframe.bottom.is_some()
+            || keyframe.left.is_some()
+            || keyframe.right.is_some()
+            || keyframe.rotation.is_some()
+    });
+    if changes_position && !is_absolute_position {
+        return Err(TypeError::new(format!(
+            "Animation of a statically positioned \"{component}\" component can't change \"top\", \"bottom\", \"left\", \"right\" or \"rotation\" fields."
+        )));
+    }
+    animation.try_into().map(Some)
+}
+
+fn non_negative(value: Option<f32>, field: &str) -> Result<f32, TypeError> {
+    match value {
+        Some(value) if value < 0.0 => Err(TypeError::new(format!(
+            "Field \"{field}\" can't be negative."
+        ))),
+        value => Ok(value.unwrap_or(0.0)),
+    }
+}
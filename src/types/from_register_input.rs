@@ -81,6 +81,19 @@ impl TryFrom<InputRtpAudioOptions> for InputAudioStream {
     }
 }
 
+impl From<&InputRtpVideoOptions> for decoder::VideoDecoderOptions {
+    fn from(value: &InputRtpVideoOptions) -> Self {
+        let codec = match value {
+            InputRtpVideoOptions::FfmepgH264 => pipeline::VideoCodec::H264,
+            InputRtpVideoOptions::FfmpegH265 => pipeline::VideoCodec::H265,
+            InputRtpVideoOptions::FfmpegVp8 => pipeline::VideoCodec::Vp8,
+            InputRtpVideoOptions::FfmpegVp9 => pipeline::VideoCodec::Vp9,
+            InputRtpVideoOptions::FfmpegAv1 => pipeline::VideoCodec::Av1,
+        };
+        decoder::VideoDecoderOptions { codec }
+    }
+}
+
 impl TryFrom<RtpInputStream> for pipeline::RegisterInputOptions {
     type Error = TypeError;
 
@@ -103,11 +116,7 @@ impl TryFrom<RtpInputStream> for pipeline::RegisterInputOptions {
 
         let rtp_stream = input::rtp::RtpStream {
             video: video.as_ref().map(|video| input::rtp::InputVideoStream {
-                options: match video {
-                    InputRtpVideoOptions::FfmepgH264 => decoder::VideoDecoderOptions {
-                        codec: pipeline::VideoCodec::H264,
-                    },
-                },
+                options: video.into(),
             }),
             audio: audio.map(TryFrom::try_from).transpose()?,
         };
@@ -130,6 +139,75 @@ impl TryFrom<RtpInputStream> for pipeline::RegisterInputOptions {
     }
 }
 
+impl TryFrom<RtmpInputStream> for pipeline::RegisterInputOptions {
+    type Error = TypeError;
+
+    fn try_from(value: RtmpInputStream) -> Result<Self, Self::Error> {
+        let RtmpInputStream {
+            port,
+            required,
+            offset_ms,
+        } = value;
+
+        let queue_options = queue::InputOptions {
+            required: required.unwrap_or(false),
+            offset: offset_ms.map(|offset_ms| Duration::from_secs_f64(offset_ms / 1000.0)),
+        };
+
+        Ok(pipeline::RegisterInputOptions {
+            input_options: input::InputOptions::Rtmp(input::rtmp::RtmpReceiverOptions {
+                port: port.try_into()?,
+            }),
+            queue_options,
+        })
+    }
+}
+
+impl TryFrom<WhipInput> for pipeline::RegisterInputOptions {
+    type Error = TypeError;
+
+    fn try_from(value: WhipInput) -> Result<Self, Self::Error> {
+        let WhipInput {
+            video,
+            audio,
+            required,
+            offset_ms,
+        } = value;
+
+        const NO_VIDEO_AUDIO_SPEC: &str =
+            "At least one of `video` and `audio` has to be specified in `register_input` request.";
+        const AAC_NOT_SUPPORTED: &str = "AAC audio is not supported by the WHIP input.";
+        const VIDEO_CODEC_NOT_SUPPORTED: &str = "Only H264 video is supported by the WHIP input.";
+
+        if video.is_none() && audio.is_none() {
+            return Err(TypeError::new(NO_VIDEO_AUDIO_SPEC));
+        }
+        if let Some(InputRtpAudioOptions::Aac { .. }) = audio {
+            return Err(TypeError::new(AAC_NOT_SUPPORTED));
+        }
+        if !matches!(video, None | Some(InputRtpVideoOptions::FfmepgH264)) {
+            return Err(TypeError::new(VIDEO_CODEC_NOT_SUPPORTED));
+        }
+
+        let input_options = input::InputOptions::Whip(input::whip::WhipReceiverOptions {
+            video: video.as_ref().map(|video| input::rtp::InputVideoStream {
+                options: video.into(),
+            }),
+            audio: audio.map(TryFrom::try_from).transpose()?,
+        });
+
+        let queue_options = queue::InputOptions {
+            required: required.unwrap_or(false),
+            offset: offset_ms.map(|offset_ms| Duration::from_secs_f64(offset_ms / 1000.0)),
+        };
+
+        Ok(pipeline::RegisterInputOptions {
+            input_options,
+            queue_options,
+        })
+    }
+}
+
 impl TryFrom<Mp4> for pipeline::RegisterInputOptions {
     type Error = TypeError;
 
@@ -164,3 +242,30 @@ impl TryFrom<Mp4> for pipeline::RegisterInputOptions {
         })
     }
 }
+
+impl TryFrom<(FragmentedMp4, Bytes)> for pipeline::RegisterInputOptions {
+    type Error = TypeError;
+
+    fn try_from((value, init_segment): (FragmentedMp4, Bytes)) -> Result<Self, Self::Error> {
+        let FragmentedMp4 {
+            required,
+            offset_ms,
+        } = value;
+
+        if init_segment.is_empty() {
+            return Err(TypeError::new("Init segment can not be empty."));
+        }
+
+        let queue_options = queue::InputOptions {
+            required: required.unwrap_or(false),
+            offset: offset_ms.map(|offset_ms| Duration::from_secs_f64(offset_ms / 1000.0)),
+        };
+
+        Ok(pipeline::RegisterInputOptions {
+            input_options: input::InputOptions::Mp4(input::mp4::Mp4Options {
+                source: input::mp4::Source::Fragmented { init_segment },
+            }),
+            queue_options,
+        })
+    }
+}
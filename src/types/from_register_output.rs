@@ -1,11 +1,17 @@
-use compositor_pipeline::pipeline::{
-    self,
-    encoder::{
+use std::time::Duration;
+
+use compositor_pipeline::{
+    audio_mixer,
+    pipeline::{
         self,
-        ffmpeg_h264::{self, Options},
+        encoder::{
+            self, fdk_aac, ffmpeg_av1,
+            ffmpeg_h264::{self, Options},
+            ffmpeg_vpx, opus,
+        },
+        output::{self, rtp::RtpSenderOptions},
+        rtp,
     },
-    output::{self, rtp::RtpSenderOptions},
-    rtp,
 };
 
 use super::register_output::*;
@@ -31,48 +37,33 @@ impl TryFrom<RtpOutputStream> for pipeline::RegisterOutputOptions {
         }
 
         let output_video_options = match video.clone() {
-            Some(v) => {
-                if v.resolution.width % 2 != 0 || v.resolution.height % 2 != 0 {
-                    return Err(TypeError::new(
-                        "Output video width and height has to be divisible by 2",
-                    ));
-                };
-
-                let VideoEncoderOptions::FfmpegH264 {
-                    preset,
-                    ffmpeg_options,
-                } = v.encoder;
-
-                Some(pipeline::OutputVideoOptions {
-                    initial: v.initial.try_into()?,
-                    encoder_opts: pipeline::encoder::VideoEncoderOptions::H264(Options {
-                        preset: preset.into(),
-                        resolution: v.resolution.into(),
-                        raw_options: ffmpeg_options.unwrap_or_default().into_iter().collect(),
-                    }),
-                    end_condition: v.send_eos_when.unwrap_or_default().try_into()?,
-                })
-            }
+            Some(v) => Some(output_video_options(
+                v.resolution,
+                v.encoder,
+                v.initial,
+                v.send_eos_when,
+            )?),
             None => None,
         };
 
+        if let Some(AudioEncoderOptions::Opus {
+            channels: AudioChannels::Surround51,
+            ..
+        }) = audio.as_ref().map(|a| &a.encoder)
+        {
+            // Opus RTP payload format supports only mono and stereo streams.
+            return Err(TypeError::new(
+                "5.1 Opus audio is not supported in RTP outputs.",
+            ));
+        }
+
         let output_audio_options = match audio.clone() {
-            Some(a) => {
-                let AudioEncoderOptions::Opus {
-                    channels,
-                    preset,
-                    forward_error_correction,
-                } = a.encoder;
-
-                Some(pipeline::OutputAudioOptions {
-                    initial: a.initial.try_into()?,
-                    channels: channels.into(),
-                    forward_error_correction: forward_error_correction.unwrap_or(false),
-                    encoder_preset: preset.unwrap_or(OpusEncoderPreset::Voip).into(),
-                    end_condition: a.send_eos_when.unwrap_or_default().try_into()?,
-                    mixing_strategy: a.mixing_strategy.unwrap_or(MixingStrategy::SumClip).into(),
-                })
-            }
+            Some(a) => Some(output_audio_options(
+                a.encoder,
+                a.initial,
+                a.mixing_strategy,
+                a.send_eos_when,
+            )?),
             None => None,
         };
 
@@ -108,8 +99,120 @@ impl TryFrom<RtpOutputStream> for pipeline::RegisterOutputOptions {
 
         let output_options = output::OutputOptions::Rtp(RtpSenderOptions {
             connection_options,
-            video: video.map(|_| pipeline::VideoCodec::H264),
-            audio: audio.map(|_| pipeline::AudioCodec::Opus),
+            video: video.map(|v| (&v.encoder).into()),
+            audio: audio.map(|a| (&a.encoder).into()),
+        });
+
+        Ok(Self {
+            output_options,
+            video: output_video_options,
+            audio: output_audio_options,
+        })
+    }
+}
+
+impl TryFrom<Mp4Output> for pipeline::RegisterOutputOptions {
+    type Error = TypeError;
+
+    fn try_from(request: Mp4Output) -> Result<Self, Self::Error> {
+        let Mp4Output { path, video, audio } = request;
+
+        if video.is_none() && audio.is_none() {
+            return Err(TypeError::new(
+                "At least one of \"video\" and \"audio\" fields have to be specified.",
+            ));
+        }
+
+        let mp4_video = video.as_ref().map(|v| output::mp4::Mp4VideoTrack {
+            codec: (&v.encoder).into(),
+            width: v.resolution.width as u32,
+            height: v.resolution.height as u32,
+        });
+        let mp4_audio = audio.as_ref().map(|a| output::mp4::Mp4AudioTrack {
+            codec: (&a.encoder).into(),
+            channels: a.encoder.channels().into(),
+        });
+
+        let output_video_options = match video {
+            Some(v) => Some(output_video_options(
+                v.resolution,
+                v.encoder,
+                v.initial,
+                v.send_eos_when,
+            )?),
+            None => None,
+        };
+
+        let output_audio_options = match audio {
+            Some(a) => Some(output_audio_options(
+                a.encoder,
+                a.initial,
+                a.mixing_strategy,
+                a.send_eos_when,
+            )?),
+            None => None,
+        };
+
+        let output_options = output::OutputOptions::Mp4(output::mp4::Mp4OutputOptions {
+            output_path: path.into(),
+            video: mp4_video,
+            audio: mp4_audio,
+        });
+
+        Ok(Self {
+            output_options,
+            video: output_video_options,
+            audio: output_audio_options,
+        })
+    }
+}
+
+impl TryFrom<RtmpOutput> for pipeline::RegisterOutputOptions {
+    type Error = TypeError;
+
+    fn try_from(request: RtmpOutput) -> Result<Self, Self::Error> {
+        let RtmpOutput { url, video, audio } = request;
+
+        if video.is_none() && audio.is_none() {
+            return Err(TypeError::new(
+                "At least one of \"video\" and \"audio\" fields have to be specified.",
+            ));
+        }
+
+        let rtmp_video = video.as_ref().map(|v| output::rtmp::RtmpVideoTrack {
+            codec: (&v.encoder).into(),
+            width: v.resolution.width as u32,
+            height: v.resolution.height as u32,
+        });
+        let rtmp_audio = audio.as_ref().map(|a| output::rtmp::RtmpAudioTrack {
+            codec: (&a.encoder).into(),
+            channels: a.encoder.channels().into(),
+        });
+
+        let output_video_options = match video {
+            Some(v) => Some(output_video_options(
+                v.resolution,
+                v.encoder,
+                v.initial,
+                v.send_eos_when,
+            )?),
+            None => None,
+        };
+
+        let output_audio_options = match audio {
+            Some(a) => Some(output_audio_options(
+                a.encoder,
+                a.initial,
+                a.mixing_strategy,
+                a.send_eos_when,
+            )?),
+            None => None,
+        };
+
+        let output_options = output::OutputOptions::Rtmp(output::rtmp::RtmpSenderOptions {
+            url,
+            video: rtmp_video,
+            audio: rtmp_audio,
         });
 
         Ok(Self {
@@ -120,6 +223,276 @@ impl TryFrom<RtpOutputStream> for pipeline::RegisterOutputOptions {
     }
 }
 
+impl TryFrom<HlsOutput> for pipeline::RegisterOutputOptions {
+    type Error = TypeError;
+
+    fn try_from(request: HlsOutput) -> Result<Self, Self::Error> {
+        const DEFAULT_SEGMENT_DURATION_MS: f64 = 2000.0;
+        const DEFAULT_PLAYLIST_SIZE: u32 = 6;
+
+        let HlsOutput {
+            path,
+            segment_duration_ms,
+            playlist_size,
+            delete_old_segments,
+            segment_format,
+            video,
+            audio,
+        } = request;
+
+        if video.is_none() && audio.is_none() {
+            return Err(TypeError::new(
+                "At least one of \"video\" and \"audio\" fields have to be specified.",
+            ));
+        }
+
+        let segment_duration_ms = segment_duration_ms.unwrap_or(DEFAULT_SEGMENT_DURATION_MS);
+        if segment_duration_ms <= 0.0 || !segment_duration_ms.is_finite() {
+            return Err(TypeError::new(
+                "\"segment_duration_ms\" has to be a positive number.",
+            ));
+        }
+        let segment_duration = Duration::from_secs_f64(segment_duration_ms / 1000.0);
+
+        let hls_video = video.as_ref().map(|v| output::hls::HlsVideoTrack {
+            codec: (&v.encoder).into(),
+            width: v.resolution.width as u32,
+            height: v.resolution.height as u32,
+        });
+        let is_opus = matches!(
+            audio.as_ref().map(|a| &a.encoder),
+            Some(AudioEncoderOptions::Opus { .. })
+        );
+        let hls_audio = audio.as_ref().map(|a| output::hls::HlsAudioTrack {
+            codec: (&a.encoder).into(),
+            channels: a.encoder.channels().into(),
+        });
+
+        let output_video_options = match video {
+            Some(v) => {
+                let mut video_options =
+                    output_video_options(v.resolution, v.encoder, v.initial, v.send_eos_when)?;
+                // Segments are split on keyframes, so encoder has to produce them at
+                // the segment boundaries.
+                match video_options.encoder_opts {
+                    encoder::VideoEncoderOptions::H264(ref mut options) => {
+                        options.keyframe_interval = Some(segment_duration)
+                    }
+                    encoder::VideoEncoderOptions::Vp8(ref mut options)
+                    | encoder::VideoEncoderOptions::Vp9(ref mut options) => {
+                        options.keyframe_interval = Some(segment_duration)
+                    }
+                    encoder::VideoEncoderOptions::Av1(ref mut options) => {
+                        options.keyframe_interval = Some(segment_duration)
+                    }
+                }
+                Some(video_options)
+            }
+            None => None,
+        };
+
+        let output_audio_options = match audio {
+            Some(a) => Some(output_audio_options(
+                a.encoder,
+                a.initial,
+                a.mixing_strategy,
+                a.send_eos_when,
+            )?),
+            None => None,
+        };
+
+        let default_segment_format = if is_opus {
+            HlsSegmentFormat::Fmp4
+        } else {
+            HlsSegmentFormat::MpegTs
+        };
+        let segment_format = match segment_format.unwrap_or(default_segment_format) {
+            HlsSegmentFormat::MpegTs => output::hls::HlsSegmentFormat::MpegTs,
+            HlsSegmentFormat::Fmp4 => output::hls::HlsSegmentFormat::Fmp4,
+        };
+
+        let output_options = output::OutputOptions::Hls(output::hls::HlsOutputOptions {
+            directory: path.into(),
+            segment_duration,
+            playlist_size: playlist_size.unwrap_or(DEFAULT_PLAYLIST_SIZE) as usize,
+            delete_old_segments: delete_old_segments.unwrap_or(false),
+            segment_format,
+            video: hls_video,
+            audio: hls_audio,
+        });
+
+        Ok(Self {
+            output_options,
+            video: output_video_options,
+            audio: output_audio_options,
+        })
+    }
+}
+
+impl TryFrom<WhepOutput> for pipeline::RegisterOutputOptions {
+    type Error = TypeError;
+
+    fn try_from(request: WhepOutput) -> Result<Self, Self::Error> {
+        // Viewers can join at any time and have to wait for a keyframe before
+        // the video is displayed.
+        const KEYFRAME_INTERVAL: Duration = Duration::from_secs(1);
+
+        let WhepOutput { video, audio } = request;
+
+        if video.is_none() && audio.is_none() {
+            return Err(TypeError::new(
+                "At least one of \"video\" and \"audio\" fields have to be specified.",
+            ));
+        }
+
+        let output_video_options = match video.clone() {
+            Some(v) => {
+                let mut video_options =
+                    output_video_options(v.resolution, v.encoder, v.initial, v.send_eos_when)?;
+                match video_options.encoder_opts {
+                    encoder::VideoEncoderOptions::H264(ref mut options) => {
+                        options.keyframe_interval = Some(KEYFRAME_INTERVAL)
+                    }
+                    encoder::VideoEncoderOptions::Vp8(ref mut options)
+                    | encoder::VideoEncoderOptions::Vp9(ref mut options) => {
+                        options.keyframe_interval = Some(KEYFRAME_INTERVAL)
+                    }
+                    encoder::VideoEncoderOptions::Av1(ref mut options) => {
+                        options.keyframe_interval = Some(KEYFRAME_INTERVAL)
+                    }
+                }
+                Some(video_options)
+            }
+            None => None,
+        };
+
+        if let Some(a) = &audio {
+            if matches!(a.encoder.channels(), AudioChannels::Surround51) {
+                return Err(TypeError::new(
+                    "5.1 audio is not supported in WHEP outputs.",
+                ));
+            }
+        }
+
+        let output_audio_options = match audio.clone() {
+            Some(a) => Some(output_audio_options(
+                a.encoder,
+                a.initial,
+                a.mixing_strategy,
+                a.send_eos_when,
+            )?),
+            None => None,
+        };
+
+        let output_options = output::OutputOptions::Whep(output::whep::WhepSenderOptions {
+            video: video.map(|v| (&v.encoder).into()),
+            audio: audio.map(|a| (&a.encoder).into()),
+        });
+
+        Ok(Self {
+            output_options,
+            video: output_video_options,
+            audio: output_audio_options,
+        })
+    }
+}
+
+fn output_video_options(
+    resolution: Resolution,
+    encoder: VideoEncoderOptions,
+    initial: Video,
+    send_eos_when: Option<OutputEndCondition>,
+) -> Result<pipeline::OutputVideoOptions, TypeError> {
+    if resolution.width % 2 != 0 || resolution.height % 2 != 0 {
+        return Err(TypeError::new(
+            "Output video width and height has to be divisible by 2",
+        ));
+    };
+
+    let encoder_opts = match encoder {
+        VideoEncoderOptions::FfmpegH264 {
+            preset,
+            ffmpeg_options,
+        } => pipeline::encoder::VideoEncoderOptions::H264(Options {
+            preset: preset.into(),
+            resolution: resolution.into(),
+            raw_options: ffmpeg_options.unwrap_or_default().into_iter().collect(),
+            keyframe_interval: None,
+        }),
+        VideoEncoderOptions::FfmpegVp8 { ffmpeg_options } => {
+            pipeline::encoder::VideoEncoderOptions::Vp8(ffmpeg_vpx::Options {
+                resolution: resolution.into(),
+                raw_options: ffmpeg_options.unwrap_or_default().into_iter().collect(),
+                keyframe_interval: None,
+            })
+        }
+        VideoEncoderOptions::FfmpegVp9 { ffmpeg_options } => {
+            pipeline::encoder::VideoEncoderOptions::Vp9(ffmpeg_vpx::Options {
+                resolution: resolution.into(),
+                raw_options: ffmpeg_options.unwrap_or_default().into_iter().collect(),
+                keyframe_interval: None,
+            })
+        }
+        VideoEncoderOptions::FfmpegAv1 { ffmpeg_options } => {
+            pipeline::encoder::VideoEncoderOptions::Av1(ffmpeg_av1::Options {
+                resolution: resolution.into(),
+                raw_options: ffmpeg_options.unwrap_or_default().into_iter().collect(),
+                keyframe_interval: None,
+            })
+        }
+    };
+
+    Ok(pipeline::OutputVideoOptions {
+        initial: initial.try_into()?,
+        encoder_opts,
+        end_condition: send_eos_when.unwrap_or_default().try_into()?,
+    })
+}
+
+fn output_audio_options(
+    encoder: AudioEncoderOptions,
+    initial: Audio,
+    mixing_strategy: Option<MixingStrategy>,
+    send_eos_when: Option<OutputEndCondition>,
+) -> Result<pipeline::OutputAudioOptions, TypeError> {
+    let encoder_opts = match encoder {
+        AudioEncoderOptions::Opus {
+            channels,
+            preset,
+            forward_error_correction: _,
+        } => pipeline::encoder::AudioEncoderOptions::Opus(opus::Options {
+            channels: channels.into(),
+            preset: preset.unwrap_or(OpusEncoderPreset::Voip).into(),
+        }),
+        AudioEncoderOptions::Aac { channels, dither } => {
+            pipeline::encoder::AudioEncoderOptions::Aac(fdk_aac::Options {
+                channels: channels.into(),
+                dither: dither.unwrap_or(false),
+            })
+        }
+    };
+
+    let mixing_strategy = mixing_strategy.unwrap_or(MixingStrategy::SumClip);
+    if matches!(
+        encoder_opts.channels(),
+        audio_mixer::AudioChannels::Surround51
+    ) && matches!(
+        mixing_strategy,
+        MixingStrategy::LoudnessNormalization { .. }
+    ) {
+        return Err(TypeError::new(
+            "Loudness normalization is not supported for 5.1 audio.",
+        ));
+    }
+
+    Ok(pipeline::OutputAudioOptions {
+        initial: initial.try_into()?,
+        encoder_opts,
+        end_condition: send_eos_when.unwrap_or_default().try_into()?,
+        mixing_strategy: mixing_strategy.try_into()?,
+    })
+}
+
 impl TryFrom<OutputEndCondition> for pipeline::PipelineOutputEndCondition {
     type Error = TypeError;
 
@@ -166,6 +539,35 @@ impl TryFrom<OutputEndCondition> for pipeline::PipelineOutputEndCondition {
     }
 }
 
+impl From<&VideoEncoderOptions> for pipeline::VideoCodec {
+    fn from(value: &VideoEncoderOptions) -> Self {
+        match value {
+            VideoEncoderOptions::FfmpegH264 { .. } => pipeline::VideoCodec::H264,
+            VideoEncoderOptions::FfmpegVp8 { .. } => pipeline::VideoCodec::Vp8,
+            VideoEncoderOptions::FfmpegVp9 { .. } => pipeline::VideoCodec::Vp9,
+            VideoEncoderOptions::FfmpegAv1 { .. } => pipeline::VideoCodec::Av1,
+        }
+    }
+}
+
+impl From<&AudioEncoderOptions> for pipeline::AudioCodec {
+    fn from(value: &AudioEncoderOptions) -> Self {
+        match value {
+            AudioEncoderOptions::Opus { .. } => pipeline::AudioCodec::Opus,
+            AudioEncoderOptions::Aac { .. } => pipeline::AudioCodec::Aac,
+        }
+    }
+}
+
+impl AudioEncoderOptions {
+    fn channels(&self) -> AudioChannels {
+        match self {
+            AudioEncoderOptions::Opus { channels, .. }
+            | AudioEncoderOptions::Aac { channels, .. } => channels.clone(),
+        }
+    }
+}
+
 impl From<H264EncoderPreset> for encoder::ffmpeg_h264::EncoderPreset {
     fn from(value: H264EncoderPreset) -> Self {
         match value {
@@ -27,38 +27,158 @@ impl TryFrom<Transition> for scene::Transition {
     type Error = TypeError;
 
     fn try_from(transition: Transition) -> Result<Self, Self::Error> {
-        let interpolation_kind = match transition.easing_function.unwrap_or(EasingFunction::Linear)
+        Ok(Self {
+            duration: Duration::from_secs_f64(transition.duration_ms / 1000.0),
+            interpolation_kind: interpolation_kind(transition.easing_function)?,
+        })
+    }
+}
+
+impl TryFrom<EnterExitTransition> for scene::EnterExitTransition {
+    type Error = TypeError;
+
+    fn try_from(transition: EnterExitTransition) -> Result<Self, Self::Error> {
+        let effect = match transition.effect {
+            EnterExitEffect::Fade => scene::EnterExitEffect::Fade,
+            EnterExitEffect::Slide { edge } => scene::EnterExitEffect::Slide(match edge {
+                Edge::Top => scene::Edge::Top,
+                Edge::Right => scene::Edge::Right,
+                Edge::Bottom => scene::Edge::Bottom,
+                Edge::Left => scene::Edge::Left,
+            }),
+            EnterExitEffect::Scale => scene::EnterExitEffect::Scale,
+        };
+        Ok(Self {
+            duration: Duration::from_secs_f64(transition.duration_ms / 1000.0),
+            interpolation_kind: interpolation_kind(transition.easing_function)?,
+            effect,
+        })
+    }
+}
+
+impl TryFrom<Animation> for scene::Animation {
+    type Error = TypeError;
+
+    fn try_from(animation: Animation) -> Result<Self, Self::Error> {
+        if animation.duration_ms <= 0.0 {
+            return Err(TypeError::new("Animation duration has to be positive."));
+        }
+        if animation.keyframes.is_empty() {
+            return Err(TypeError::new("Animation requires at least one keyframe."));
+        }
+        if !animation
+            .keyframes
+            .windows(2)
+            .all(|pair| pair[0].offset <= pair[1].offset)
         {
-            EasingFunction::Linear => scene::InterpolationKind::Linear,
-            EasingFunction::Bounce => scene::InterpolationKind::Bounce,
-            EasingFunction::CubicBezier { points } => {
-                if points[0] < 0.0 || points[0] > 1.0 {
-                    return Err(TypeError::new(
-                        "Control point x1 has to be in the range [0, 1].",
-                    ));
-                }
-                if points[2] < 0.0 || points[2] > 1.0 {
-                    return Err(TypeError::new(
-                        "Control point x2 has to be in the range [0, 1].",
-                    ));
-                }
+            return Err(TypeError::new(
+                "Animation keyframes have to be sorted by \"offset\".",
+            ));
+        }
+        let uses = |get: fn(&Keyframe) -> Option<f32>| {
+            animation
+                .keyframes
+                .iter()
+                .any(|keyframe| get(keyframe).is_some())
+        };
+        if uses(|k: &Keyframe| k.top) && uses(|k: &Keyframe| k.bottom) {
+            return Err(TypeError::new(
+                "Fields \"top\" and \"bottom\" are mutually exclusive, an animation can only change one of them.",
+            ));
+        }
+        if uses(|k: &Keyframe| k.left) && uses(|k: &Keyframe| k.right) {
+            return Err(TypeError::new(
+                "Fields \"left\" and \"right\" are mutually exclusive, an animation can only change one of them.",
+            ));
+        }
 
-                scene::InterpolationKind::CubicBezier {
-                    x1: points[0],
-                    y1: points[1],
-                    x2: points[2],
-                    y2: points[3],
-                }
-            }
+        let repeat = match animation.repeat {
+            Some(AnimationRepeat::None) | None => scene::AnimationRepeat::None,
+            Some(AnimationRepeat::Loop) => scene::AnimationRepeat::Loop,
+            Some(AnimationRepeat::PingPong) => scene::AnimationRepeat::PingPong,
         };
+        Ok(Self {
+            duration: Duration::from_secs_f64(animation.duration_ms / 1000.0),
+            keyframes: animation
+                .keyframes
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<Vec<_>, _>>()?,
+            repeat,
+        })
+    }
+}
 
+impl TryFrom<Keyframe> for scene::Keyframe {
+    type Error = TypeError;
+
+    fn try_from(keyframe: Keyframe) -> Result<Self, Self::Error> {
+        if !(0.0..=1.0).contains(&keyframe.offset) {
+            return Err(TypeError::new(
+                "Keyframe \"offset\" has to be in [0, 1] range.",
+            ));
+        }
+        if keyframe
+            .opacity
+            .is_some_and(|opacity| !(0.0..=1.0).contains(&opacity))
+        {
+            return Err(TypeError::new(
+                "Keyframe \"opacity\" has to be in [0, 1] range.",
+            ));
+        }
+        if keyframe.width.is_some_and(|width| width < 0.0)
+            || keyframe.height.is_some_and(|height| height < 0.0)
+        {
+            return Err(TypeError::new(
+                "Keyframe \"width\" and \"height\" can't be negative.",
+            ));
+        }
         Ok(Self {
-            duration: Duration::from_secs_f64(transition.duration_ms / 1000.0),
-            interpolation_kind,
+            offset: keyframe.offset,
+            interpolation_kind: interpolation_kind(keyframe.easing_function)?,
+            values: scene::KeyframeValues {
+                width: keyframe.width,
+                height: keyframe.height,
+                top: keyframe.top,
+                left: keyframe.left,
+                bottom: keyframe.bottom,
+                right: keyframe.right,
+                rotation_degrees: keyframe.rotation,
+                opacity: keyframe.opacity,
+            },
         })
     }
 }
 
+fn interpolation_kind(
+    easing_function: Option<EasingFunction>,
+) -> Result<scene::InterpolationKind, TypeError> {
+    let interpolation_kind = match easing_function.unwrap_or(EasingFunction::Linear) {
+        EasingFunction::Linear => scene::InterpolationKind::Linear,
+        EasingFunction::Bounce => scene::InterpolationKind::Bounce,
+        EasingFunction::CubicBezier { points } => {
+            if points[0] < 0.0 || points[0] > 1.0 {
+                return Err(TypeError::new(
+                    "Control point x1 has to be in the range [0, 1].",
+                ));
+            }
+            if points[2] < 0.0 || points[2] > 1.0 {
+                return Err(TypeError::new(
+                    "Control point x2 has to be in the range [0, 1].",
+                ));
+            }
+
+            scene::InterpolationKind::CubicBezier {
+                x1: points[0],
+                y1: points[1],
+                x2: points[2],
+                y2: points[3],
+            }
+        }
+    };
+    Ok(interpolation_kind)
+}
+
 impl From<HorizontalAlign> for scene::HorizontalAlign {
     fn from(alignment: HorizontalAlign) -> Self {
         match alignment {
@@ -27,6 +27,41 @@ pub struct RtpInputStream {
     pub offset_ms: Option<f64>,
 }
 
+/// Input stream published over RTMP, e.g. from OBS. The compositor acts as an RTMP
+/// server and accepts a single publisher. Video has to be encoded with H264 and audio with AAC.
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct RtmpInputStream {
+    /// TCP port or port range on which the compositor should listen for the publisher.
+    pub port: PortOrPortRange,
+    /// (**default=`false`**) If input is required and the stream is not delivered
+    /// on time, then LiveCompositor will delay producing output frames.
+    pub required: Option<bool>,
+    /// Offset in milliseconds relative to the pipeline start (start request). If the offset is
+    /// not defined then the stream will be synchronized based on the delivery time of the initial
+    /// frames.
+    pub offset_ms: Option<f64>,
+}
+
+/// Input stream sent from a WebRTC peer (e.g. a browser) using the
+/// [WHIP](https://datatracker.ietf.org/doc/html/draft-ietf-wish-whip) protocol.
+/// At least one of `video` and `audio` has to be defined.
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct WhipInput {
+    /// Parameters of a video source. Only H264 is supported.
+    pub video: Option<InputRtpVideoOptions>,
+    /// Parameters of an audio source. Only Opus is supported.
+    pub audio: Option<InputRtpAudioOptions>,
+    /// (**default=`false`**) If input is required and the stream is not delivered
+    /// on time, then LiveCompositor will delay producing output frames.
+    pub required: Option<bool>,
+    /// Offset in milliseconds relative to the pipeline start (start request). If the offset is
+    /// not defined then the stream will be synchronized based on the delivery time of the initial
+    /// frames.
+    pub offset_ms: Option<f64>,
+}
+
 /// Input stream from MP4 file.
 /// Exactly one of `url` and `path` has to be defined.
 #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
@@ -44,6 +79,20 @@ pub struct Mp4 {
     pub offset_ms: Option<f64>,
 }
 
+/// Input stream from fragmented MP4 segments pushed over HTTP.
+/// The input is created when the init segment is delivered with the
+/// `/api/input/:input_id/fragmented_mp4/init` request.
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct FragmentedMp4 {
+    /// (**default=`false`**) If input is required and frames are not processed
+    /// on time, then LiveCompositor will delay producing output frames.
+    pub required: Option<bool>,
+    /// Offset in milliseconds relative to the pipeline start (start request). If offset is
+    /// not defined then stream is synchronized based on the first frames delivery time.
+    pub offset_ms: Option<f64>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum AacRtpMode {
@@ -88,4 +137,12 @@ pub enum InputRtpAudioOptions {
 pub enum InputRtpVideoOptions {
     #[serde(rename = "ffmpeg_h264")]
     FfmepgH264,
+    #[serde(rename = "ffmpeg_h265")]
+    FfmpegH265,
+    #[serde(rename = "ffmpeg_vp8")]
+    FfmpegVp8,
+    #[serde(rename = "ffmpeg_vp9")]
+    FfmpegVp9,
+    #[serde(rename = "ffmpeg_av1")]
+    FfmpegAv1,
 }
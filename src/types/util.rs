@@ -19,6 +19,86 @@ pub struct Transition {
     pub easing_function: Option<EasingFunction>,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct EnterExitTransition {
+    /// Duration of an animation in milliseconds.
+    pub duration_ms: f64,
+    /// (**default=`"linear"`**) Easing function to be used for the animation.
+    pub easing_function: Option<EasingFunction>,
+    /// Animation effect.
+    pub effect: EnterExitEffect,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum EnterExitEffect {
+    /// Change opacity of the component between 0 and 1.
+    Fade,
+    /// Move the component from/to the outside of its parent through the specified edge.
+    Slide { edge: Edge },
+    /// Scale the component from/to 0 relative to its center.
+    Scale,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Edge {
+    Top,
+    Right,
+    Bottom,
+    Left,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Animation {
+    /// Duration of a single iteration of the animation in milliseconds.
+    pub duration_ms: f64,
+    /// List of keyframes sorted by `offset`.
+    pub keyframes: Vec<Keyframe>,
+    /// (**default=`"none"`**) Defines what happens after the last keyframe.
+    pub repeat: Option<AnimationRepeat>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Keyframe {
+    /// Position of the keyframe within a single iteration of the animation. Value in
+    /// the `[0, 1]` range, where `0` is the start and `1` is the end of the iteration.
+    pub offset: f64,
+    /// (**default=`"linear"`**) Easing function used between this keyframe and the next
+    /// one that defines the same value.
+    pub easing_function: Option<EasingFunction>,
+    /// Width of a component in pixels.
+    pub width: Option<f32>,
+    /// Height of a component in pixels.
+    pub height: Option<f32>,
+    /// Distance in pixels between this component's top edge and its parent's top edge.
+    pub top: Option<f32>,
+    /// Distance in pixels between this component's left edge and its parent's left edge.
+    pub left: Option<f32>,
+    /// Distance in pixels between the bottom edge of this component and the bottom edge of its parent.
+    pub bottom: Option<f32>,
+    /// Distance in pixels between this component's right edge and its parent's right edge.
+    pub right: Option<f32>,
+    /// Rotation of a component in degrees.
+    pub rotation: Option<f32>,
+    /// Opacity in the `[0, 1]` range.
+    pub opacity: Option<f32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AnimationRepeat {
+    /// Play the animation once and keep the values from the last keyframe.
+    None,
+    /// Restart the animation from the first keyframe.
+    Loop,
+    /// Play the animation alternately forwards and backwards.
+    PingPong,
+}
+
 /// Easing functions are used to interpolate between two values over time.
 ///
 /// Custom easing functions can be implemented with cubic Bézier.
@@ -8,6 +8,14 @@ use super::Component;
 pub enum VideoCodec {
     /// H264 video.
     H264,
+    /// H265 video.
+    H265,
+    /// VP8 video.
+    Vp8,
+    /// VP9 video.
+    Vp9,
+    /// AV1 video.
+    Av1,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]